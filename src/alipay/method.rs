@@ -76,4 +76,8 @@ impl RequestMethod for AlipayMethod {
             AlipayMethod::Custom{ ref response_key, .. } => response_key.to_string()
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        matches!(self, AlipayMethod::QueryOrder | AlipayMethod::QueryRefund | AlipayMethod::DownloadAlipayCert)
+    }
 }
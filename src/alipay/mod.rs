@@ -1,11 +1,13 @@
+use std::fmt;
 use chrono::Local;
-use crate::{client::{APIClient}, request::{RequestType, Method, LabraRequest}, errors::LabraError, session::{SimpleStorage, SessionStore}, RequestMethod, LabradorResult, get_nonce_str, RequestParametersHolder, cfg_if};
+use crate::{client::{APIClient}, request::{LabraRequest}, errors::LabraError, session::{SimpleStorage, AsyncSessionStore}, RequestMethod, LabradorResult, get_nonce_str, RequestParametersHolder, cfg_if, LabraHttpClient, LabraHttpConfig, HttpTransport, Middleware, LabraLogConfig, RetryPolicy, RateLimiter, TenantClient};
+use crate::config::{Proxy, Secret};
 
 use std::collections::{BTreeMap};
 use std::fs;
 use std::sync::Arc;
 use dashmap::{DashMap};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::alipay::method::AlipayMethod;
 
@@ -35,7 +37,7 @@ use crate::md5::md5;
 use crate::prp::PrpCrypto;
 
 #[derive(Debug, Clone)]
-pub struct AlipayClient<T: SessionStore> {
+pub struct AlipayClient<T: AsyncSessionStore> {
     api_client: APIClient<T>,
     /// 加密类型
     encrypt_type: String,
@@ -63,6 +65,87 @@ pub struct AlipayClient<T: SessionStore> {
 
 }
 
+/// 支付宝应用配置
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct AlipayConfig {
+    pub app_id: String,
+    /// 是否使用沙箱环境
+    pub sandbox: bool,
+    /// 应用私钥
+    pub private_key: Option<String>,
+    /// 应用私钥文件路径，未设置`private_key`时读取
+    pub private_key_path: Option<String>,
+    /// 支付宝公钥，公钥模式使用
+    pub alipay_public_key: Option<String>,
+    /// 应用公钥证书，证书模式使用
+    pub app_cert: Option<String>,
+    /// 应用公钥证书文件路径
+    pub app_cert_path: Option<String>,
+    /// 支付宝公钥证书
+    pub alipay_public_cert: Option<String>,
+    /// 支付宝公钥证书文件路径
+    pub alipay_public_cert_path: Option<String>,
+    /// 支付宝根证书
+    pub alipay_root_cert: Option<String>,
+    /// 支付宝根证书文件路径
+    pub alipay_root_cert_path: Option<String>,
+    /// 签名类型，默认RSA2
+    pub sign_type: Option<String>,
+    /// 接口内容加密密钥
+    pub encrypt_key: Option<String>,
+    /// 接口地址，用于测试环境或本地模拟服务
+    pub api_path: Option<String>,
+    /// 代理地址，如：http://127.0.0.1:8080
+    pub proxy: Option<String>,
+}
+
+impl fmt::Debug for AlipayConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlipayConfig")
+            .field("app_id", &self.app_id)
+            .field("sandbox", &self.sandbox)
+            .field("private_key", &Secret::option(&self.private_key))
+            .field("private_key_path", &self.private_key_path)
+            .field("alipay_public_key", &self.alipay_public_key)
+            .field("app_cert", &self.app_cert)
+            .field("app_cert_path", &self.app_cert_path)
+            .field("alipay_public_cert", &self.alipay_public_cert)
+            .field("alipay_public_cert_path", &self.alipay_public_cert_path)
+            .field("alipay_root_cert", &self.alipay_root_cert)
+            .field("alipay_root_cert_path", &self.alipay_root_cert_path)
+            .field("sign_type", &self.sign_type)
+            .field("encrypt_key", &Secret::option(&self.encrypt_key))
+            .field("api_path", &self.api_path)
+            .field("proxy", &Proxy::option(&self.proxy))
+            .finish()
+    }
+}
+
+impl AlipayConfig {
+    pub fn new<S: Into<String>>(app_id: S) -> Self {
+        Self { app_id: app_id.into(), ..Default::default() }
+    }
+}
+
+impl<T: AsyncSessionStore> TenantClient<T> for AlipayClient<T> {
+    type Config = AlipayConfig;
+
+    fn tenant_id(config: &Self::Config) -> String {
+        config.app_id.to_string()
+    }
+
+    fn build(config: &Self::Config, session: T, transport: Arc<dyn HttpTransport>) -> LabradorResult<Self> {
+        // 配置了代理时在共用传输层的HTTP配置上生成带代理的客户端
+        let mut config = config.clone();
+        let proxy = config.proxy.take();
+        let client = Self::from_config(&config, session)?.set_transport(transport);
+        match proxy {
+            Some(proxy) => client.set_proxy(&proxy),
+            None => Ok(client),
+        }
+    }
+}
+
 
 pub trait AlipayResponse {
 
@@ -189,7 +272,7 @@ pub trait AlipayRequest<T: Serialize> {
 /// ```
 /// 
 #[allow(unused)]
-impl <T: SessionStore> AlipayClient<T> {
+impl <T: AsyncSessionStore> AlipayClient<T> {
 
     pub fn new<Q: Into<String> + Clone>(app_key: Q, sandbox: bool) -> AlipayClient<SimpleStorage> {
         let url = if sandbox {
@@ -197,7 +280,7 @@ impl <T: SessionStore> AlipayClient<T> {
         } else {
             "https://openapi.alipay.com/gateway.do"
         };
-        let api_client = APIClient::<SimpleStorage>::new::<Q, String, String>(app_key.clone(), "".to_string(), url.to_owned());
+        let api_client = APIClient::<SimpleStorage>::new::<Q, String, String>(app_key.clone(), "".to_string(), url.to_owned()).platform("alipay");
         AlipayClient {
             api_client,
             encrypt_type: ENCRYPT_TYPE_AES.to_string(),
@@ -222,7 +305,7 @@ impl <T: SessionStore> AlipayClient<T> {
             "https://openapi.alipay.com/gateway.do"
         };
         AlipayClient {
-            api_client: APIClient::from_session(app_key.clone(), "", url.to_string(), session),
+            api_client: APIClient::from_session(app_key.clone(), "", url.to_string(), session).platform("alipay"),
             encrypt_type: ENCRYPT_TYPE_AES.to_string(),
             charset: constants::CHARSET_UTF8.to_string(),
             sign_type: SIGN_TYPE_RSA2.to_string(),
@@ -238,6 +321,47 @@ impl <T: SessionStore> AlipayClient<T> {
         }
     }
 
+    /// 根据配置创建客户端
+    pub fn from_config(config: &AlipayConfig, session: T) -> LabradorResult<AlipayClient<T>> {
+        let mut client = Self::from_session(config.app_id.as_str(), session, config.sandbox);
+        if let Some(private_key) = config.private_key.as_ref() {
+            client = client.set_private_key(private_key)?;
+        } else if let Some(private_key_path) = config.private_key_path.as_ref() {
+            client = client.set_private_key_path(private_key_path)?;
+        }
+        if let Some(alipay_public_key) = config.alipay_public_key.as_ref() {
+            client = client.set_alipay_public_key(alipay_public_key);
+        }
+        if let Some(app_cert) = config.app_cert.as_ref() {
+            client = client.set_app_cert(app_cert);
+        } else if let Some(app_cert_path) = config.app_cert_path.as_ref() {
+            client = client.set_app_cert_path(app_cert_path)?;
+        }
+        if let Some(alipay_public_cert) = config.alipay_public_cert.as_ref() {
+            client = client.set_alipay_public_cert(alipay_public_cert);
+        } else if let Some(alipay_public_cert_path) = config.alipay_public_cert_path.as_ref() {
+            client = client.set_alipay_public_cert_path(alipay_public_cert_path)?;
+        }
+        if let Some(alipay_root_cert) = config.alipay_root_cert.as_ref() {
+            client = client.set_alipay_root_cert(alipay_root_cert);
+        } else if let Some(alipay_root_cert_path) = config.alipay_root_cert_path.as_ref() {
+            client = client.set_alipay_root_cert_path(alipay_root_cert_path)?;
+        }
+        if let Some(encrypt_key) = config.encrypt_key.as_ref() {
+            client = client.set_encrypt_key(encrypt_key);
+        }
+        if let Some(sign_type) = config.sign_type.as_ref() {
+            client = client.set_sign_type(sign_type);
+        }
+        if let Some(api_path) = config.api_path.as_ref() {
+            client = client.set_api_path(api_path.as_str());
+        }
+        if let Some(proxy) = config.proxy.as_ref() {
+            client = client.set_proxy(proxy)?;
+        }
+        Ok(client)
+    }
+
     /// 获取应用证书SN
     pub fn get_app_cert_sn(&self) -> LabradorResult<String> {
         let pem = self.app_cert.to_owned().unwrap_or_default();
@@ -495,6 +619,67 @@ impl <T: SessionStore> AlipayClient<T> {
         self
     }
 
+    /// 设置HTTP客户端，多个客户端可共用同一个连接池
+    pub fn set_http_client(mut self, http_client: LabraHttpClient) -> Self {
+        self.api_client = self.api_client.http_client(http_client);
+        self
+    }
+
+    /// 根据配置生成独立的HTTP客户端，可设置代理、超时等
+    pub fn set_http_config(mut self, config: LabraHttpConfig) -> LabradorResult<Self> {
+        self.api_client = self.api_client.http_config(config)?;
+        Ok(self)
+    }
+
+    /// 设置代理，如：http://127.0.0.1:8080，开启`socks`特性后支持socks5
+    pub fn set_proxy(mut self, proxy: &str) -> LabradorResult<Self> {
+        self.api_client = self.api_client.proxy(proxy)?;
+        Ok(self)
+    }
+
+    /// 设置接口地址，可指向测试环境或本地的模拟服务
+    pub fn set_api_path<S: Into<String>>(mut self, api_path: S) -> Self {
+        self.api_client = self.api_client.api_path(api_path);
+        self
+    }
+
+    /// 设置传输层，测试时可替换为`MockTransport`
+    pub fn set_transport<H: HttpTransport + 'static>(mut self, transport: H) -> Self {
+        self.api_client = self.api_client.transport(transport);
+        self
+    }
+
+    /// 同步客户端使用默认的传输层时改用专用的连接池
+    #[cfg(feature = "blocking")]
+    pub(crate) fn blocking_transport(mut self) -> Self {
+        self.api_client = self.api_client.blocking_transport();
+        self
+    }
+
+    /// 添加中间件
+    pub fn set_middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.api_client = self.api_client.middleware(middleware);
+        self
+    }
+
+    /// 设置请求日志配置
+    pub fn set_log_config(mut self, log_config: LabraLogConfig) -> Self {
+        self.api_client = self.api_client.log_config(log_config);
+        self
+    }
+
+    /// 设置重试策略，交易查询和退款查询任何失败都可以重试；支付、下单、退款、关闭和撤销交易只会在连接阶段失败时重试，避免重复扣款或退款
+    pub fn retry_policy<P: RetryPolicy + 'static>(mut self, retry_policy: P) -> Self {
+        self.api_client = self.api_client.retry_policy(retry_policy);
+        self
+    }
+
+    /// 设置限流器，按接口方法限流并统计每日调用次数
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.api_client = self.api_client.rate_limiter(rate_limiter);
+        self
+    }
+
 
     /// 签名
     fn sign(&self, params: &str) -> LabradorResult<String> {
@@ -687,9 +872,9 @@ impl <T: SessionStore> AlipayClient<T> {
             let method = req.get_api_method_name();
             let holder = self.get_request_holder_with_sign(req, None, None, None)?;
             let url = self.get_request_url(&holder)?;
-            let req = LabraRequest::new().url(url).method(Method::Get).form(&holder.application_params).req_type(RequestType::Form);
-            let result = self.api_client.request(req).await?.text()?;
-            match AlipayBaseResponse::parse(&result, method) {
+            let req = LabraRequest::get(url).api_method(method.get_name()).params(holder.application_params.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect());
+            let response = self.api_client.request(req).await?;
+            match AlipayBaseResponse::from_response(&response, method) {
                 Ok(mut resp) => {
                     let response = resp.get_biz_model::<AlipayOpenAppAlipaycertDownloadResponse>()?;
                     let content = response.alipay_cert_content;
@@ -743,9 +928,9 @@ impl <T: SessionStore> AlipayClient<T> {
         let method = request.get_api_method_name();
         let holder = self.get_request_holder_with_sign(request, access_token, app_auth_token, target_app_id)?;
         let url = self.get_request_url(&holder)?;
-        let req = LabraRequest::new().url(url).method(Method::Post).form(&holder.application_params).req_type(RequestType::Form);
-        let result = self.api_client.request(req).await?.text()?;
-        match AlipayBaseResponse::parse(&result, method) {
+        let req = LabraRequest::post_form(url, &holder.application_params).api_method(method.get_name()).idempotent(method.is_idempotent());
+        let response = self.api_client.request(req).await?;
+        match AlipayBaseResponse::from_response(&response, method) {
             Ok(mut resp) => {
                 let sign = resp.get_sign();
                 // 验签请求返回原始串
@@ -768,9 +953,9 @@ impl <T: SessionStore> AlipayClient<T> {
         let method = request.get_api_method_name();
         let holder = self.get_request_holder_with_sign(request, access_token, app_auth_token, target_app_id)?;
         let url = self.get_request_url(&holder)?;
-        let req = LabraRequest::new().url(url).method(Method::Post).form(&holder.application_params).req_type(RequestType::Form);
-        let result = self.api_client.request(req).await?.text()?;
-        match AlipayBaseResponse::parse(&result, method) {
+        let req = LabraRequest::post_form(url, &holder.application_params).api_method(method.get_name()).idempotent(method.is_idempotent());
+        let response = self.api_client.request(req).await?;
+        match AlipayBaseResponse::from_response(&response, method) {
             Ok(mut resp) => {
                 let sign = resp.get_sign();
                 // 验签请求返回原始串
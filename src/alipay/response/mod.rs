@@ -3,7 +3,7 @@ use json::JsonValue;
 use serde::{Deserialize, Serialize};
 use serde::de::{DeserializeOwned};
 
-use crate::{errors::LabraError, AlipayResponse, LabradorResult, RequestMethod};
+use crate::{errors::{LabraError, Platform, PlatformError}, AlipayResponse, LabradorResult, LabraResponse, RequestMethod};
 use crate::alipay::constants::{ALIPAY_CERT_SN, ERROR_RESPONSE_KEY, SIGN};
 
 //----------------------------------------------------------------------------------------------------------------------------
@@ -24,6 +24,12 @@ pub struct AlipayBaseResponse {
     pub sign: Option<String>,
     pub alipay_cert_sn: Option<String>,
     pub params: Option<BTreeMap<String, String>>,
+    /// 响应的HTTP状态码
+    #[serde(skip)]
+    pub http_status: Option<u16>,
+    /// 响应头中的链路ID
+    #[serde(skip)]
+    pub trace_id: Option<String>,
 }
 
 
@@ -61,6 +67,8 @@ impl AlipayBaseResponse {
             sign: None,
             alipay_cert_sn: None,
             params: None,
+            http_status: None,
+            trace_id: None,
         }
     }
 
@@ -72,7 +80,9 @@ impl AlipayBaseResponse {
         let err= &v[ERROR_RESPONSE_KEY];
         if !err.is_empty() && !err.is_null() {
             let resp = serde_json::from_str::<Self>(&err.to_string()).unwrap_or(AlipayBaseResponse::new());
-            Err(LabraError::ClientError {errcode: resp.code.to_owned().unwrap_or_default(), errmsg: resp.sub_msg.to_owned().unwrap_or_default()})
+            Err(PlatformError::from_code_str(Platform::Alipay, &resp.code.unwrap_or_default(), resp.sub_msg.or(resp.msg).unwrap_or_default())
+                .sub_code(resp.sub_code.unwrap_or_default())
+                .into())
         } else {
             let response = &v[&method.get_response_key()];
             if !response.is_empty() && !response.is_null() {
@@ -96,6 +106,14 @@ impl AlipayBaseResponse {
 
     }
 
+    /// 解析响应，并记录HTTP状态码与响应头中的链路ID
+    pub fn from_response(response: &LabraResponse, method: impl RequestMethod) -> LabradorResult<Self> {
+        let mut resp = Self::parse(&response.text()?, method).map_err(|err| err.with_response(response))?;
+        resp.http_status = response.status().as_u16().into();
+        resp.trace_id = response.request_id();
+        Ok(resp)
+    }
+
     pub fn is_success(&self) -> bool {
         self.code.to_owned().unwrap_or_default().eq("10000")
     }
@@ -104,7 +122,11 @@ impl AlipayBaseResponse {
         if self.is_success() {
             serde_json::from_str::<T>(&self.body.to_owned().unwrap_or_default()).map_err(LabraError::from)
         } else {
-            Err(LabraError::ClientError { errcode: self.code.to_owned().unwrap_or_default(), errmsg: self.sub_msg.to_owned().unwrap_or_default() })
+            let mut err = PlatformError::from_code_str(Platform::Alipay, &self.code.to_owned().unwrap_or_default(), self.sub_msg.to_owned().unwrap_or_default())
+                .sub_code(self.sub_code.to_owned().unwrap_or_default())
+                .request_id(self.trace_id.to_owned().unwrap_or_default());
+            err.http_status = self.http_status;
+            Err(err.into())
         }
     }
 
@@ -0,0 +1,56 @@
+use tokio::runtime::Handle;
+
+use crate::{AsyncSessionStore, LabradorResult, AlipayCancelOrderResponse, AlipayCloseOrderResponse, AlipayCreateUnifiedOrderResponse, AlipayCycleOrderPayModel, AlipayCycleOrderPayResponse, AlipayFaceOrderPayModel, AlipayFaceOrderPayResponse, AlipayOpenAuthTokenAppModel, AlipayOpenAuthTokenAppRequest, AlipayOpenAuthTokenAppResponse, AlipayPreAuthOnlinePayModel, AlipayPreAuthOnlinePayResponse, AlipayPreOrderResponse, AlipayQueryOrderResponse, AlipayRefundOrderResponse, AlipayRefundQueryResponse, AlipaySystemOauthTokenRequest, AlipaySystemOauthTokenResponse, AlipayTradeCancelModel, AlipayTradeCancelRequest, AlipayTradeCloseModel, AlipayTradeCloseRequest, AlipayTradeCreateModel, AlipayTradeCreateRequest, AlipayTradeFastpayRefundQueryModel, AlipayTradeFastpayRefundQueryRequest, AlipayTradePayModel, AlipayTradePayRequest, AlipayTradePrecreateModel, AlipayTradePrecreateRequest, AlipayTradeQueryModel, AlipayTradeQueryRequest, AlipayTradeRefundModel, AlipayTradeRefundRequest, AlipayUnifiedOrderPayResponse};
+
+/// 支付宝同步客户端，页面支付等不发送请求的接口可通过`get_ref`直接调用
+#[derive(Debug)]
+pub struct AlipayClient<T: AsyncSessionStore> {
+    inner: crate::AlipayClient<T>,
+    runtime: Handle,
+}
+
+#[allow(unused)]
+impl<T: AsyncSessionStore> AlipayClient<T> {
+    /// 使用配置好的异步客户端创建同步客户端
+    pub fn new(inner: crate::AlipayClient<T>) -> LabradorResult<Self> {
+        Ok(Self { inner: inner.blocking_transport(), runtime: super::runtime()? })
+    }
+
+    /// 内部的异步客户端
+    pub fn get_ref(&self) -> &crate::AlipayClient<T> {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> crate::AlipayClient<T> {
+        self.inner
+    }
+
+    blocking_methods! {
+        /// 统一收单交易支付接口
+        fn unified_order_pay(&self, req: AlipayTradePayRequest<AlipayTradePayModel>) -> LabradorResult<AlipayUnifiedOrderPayResponse>;
+        /// 刷脸支付
+        fn face_pay(&self, req: AlipayTradePayRequest<AlipayFaceOrderPayModel>) -> LabradorResult<AlipayFaceOrderPayResponse>;
+        /// 周期扣款
+        fn cycle_pay(&self, req: AlipayTradePayRequest<AlipayCycleOrderPayModel>) -> LabradorResult<AlipayCycleOrderPayResponse>;
+        /// 预授权支付
+        fn pre_auth_online_pay(&self, req: AlipayTradePayRequest<AlipayPreAuthOnlinePayModel>) -> LabradorResult<AlipayPreAuthOnlinePayResponse>;
+        /// 统一收单线下交易预创建
+        fn create_pre_order(&self, req: AlipayTradePrecreateRequest<AlipayTradePrecreateModel>) -> LabradorResult<AlipayPreOrderResponse>;
+        /// 统一收单交易创建接口
+        fn create_unified_order(&self, req: AlipayTradeCreateRequest<AlipayTradeCreateModel>) -> LabradorResult<AlipayCreateUnifiedOrderResponse>;
+        /// 统一收单线下交易查询
+        fn query_order(&self, req: AlipayTradeQueryRequest<AlipayTradeQueryModel>) -> LabradorResult<AlipayQueryOrderResponse>;
+        /// 统一收单交易关闭接口
+        fn close_order(&self, req: AlipayTradeCloseRequest<AlipayTradeCloseModel>) -> LabradorResult<AlipayCloseOrderResponse>;
+        /// 统一收单交易退款接口
+        fn refund_order(&self, req: AlipayTradeRefundRequest<AlipayTradeRefundModel>) -> LabradorResult<AlipayRefundOrderResponse>;
+        /// 统一收单交易退款查询
+        fn query_refund_order(&self, req: AlipayTradeFastpayRefundQueryRequest<AlipayTradeFastpayRefundQueryModel>) -> LabradorResult<AlipayRefundQueryResponse>;
+        /// 统一收单交易撤销接口
+        fn cancel_order(&self, req: AlipayTradeCancelRequest<AlipayTradeCancelModel>) -> LabradorResult<AlipayCancelOrderResponse>;
+        /// 换取授权访问令牌
+        fn system_oauth_token(&self, req: AlipaySystemOauthTokenRequest) -> LabradorResult<AlipaySystemOauthTokenResponse>;
+        /// 换取应用授权令牌
+        fn open_auth_token_app(&self, req: AlipayOpenAuthTokenAppRequest<AlipayOpenAuthTokenAppModel>) -> LabradorResult<AlipayOpenAuthTokenAppResponse>;
+    }
+}
@@ -0,0 +1,44 @@
+use tokio::runtime::Handle;
+
+use crate::{AsyncSessionStore, LabradorResult, JdCommonResponse, JdGoodsInfoQuery, JdGoodsInfoQueryRequest, JdJFGoodsParam, JdJFGoodsSelect, JdOrderQueryResponse, JdOrderRawQueryParam, JdPromotionBySubUnionIdGetParam, JdPromotionCodeGetParam, JdPromotionCodeGetResponse, JdPromotionUrlGenerateParam, JdPromotionUrlGenerateResponse, SellingGoodsQueryParam, SellingGoodsQueryResponse};
+
+/// 京东联盟同步客户端
+#[derive(Debug)]
+pub struct JDClient<T: AsyncSessionStore> {
+    inner: crate::JDClient<T>,
+    runtime: Handle,
+}
+
+#[allow(unused)]
+impl<T: AsyncSessionStore> JDClient<T> {
+    /// 使用配置好的异步客户端创建同步客户端
+    pub fn new(inner: crate::JDClient<T>) -> LabradorResult<Self> {
+        Ok(Self { inner: inner.blocking_transport(), runtime: super::runtime()? })
+    }
+
+    /// 内部的异步客户端
+    pub fn get_ref(&self) -> &crate::JDClient<T> {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> crate::JDClient<T> {
+        self.inner
+    }
+
+    blocking_methods! {
+        /// 京粉精选商品查询
+        fn get_jf_select(&self, request: JdJFGoodsParam) -> LabradorResult<JdCommonResponse<Vec<JdJFGoodsSelect>>>;
+        /// 根据skuid查询商品信息
+        fn get_goods_detail(&self, request: JdGoodsInfoQueryRequest) -> LabradorResult<JdCommonResponse<Vec<JdGoodsInfoQuery>>>;
+        /// 网站/APP获取推广链接接口
+        fn generate_promotion_url(&self, request: JdPromotionUrlGenerateParam) -> LabradorResult<JdCommonResponse<JdPromotionUrlGenerateResponse>>;
+        /// 订单行查询
+        fn query_raw_order(&self, request: JdOrderRawQueryParam) -> LabradorResult<JdCommonResponse<Vec<JdOrderQueryResponse>>>;
+        /// 转链获取接口
+        fn get_promotion_code(&self, request: JdPromotionCodeGetParam) -> LabradorResult<JdCommonResponse<JdPromotionCodeGetResponse>>;
+        /// 社交媒体获取推广链接接口
+        fn get_promotion_code_by_sub_unionid(&self, request: JdPromotionBySubUnionIdGetParam) -> LabradorResult<JdCommonResponse<JdPromotionCodeGetResponse>>;
+        /// 商羚商品查询接口
+        fn get_selling_goods_query(&self, request: SellingGoodsQueryParam) -> LabradorResult<JdCommonResponse<Vec<SellingGoodsQueryResponse>>>;
+    }
+}
@@ -0,0 +1,205 @@
+//! 同步客户端
+//!
+//! 目前提供淘宝客、京东联盟、拼多多、支付宝与微信支付的同步客户端，方法与异步客户端一一对应，
+//! 签名、请求构建与响应解析都由内部的异步客户端完成。公众号、企业微信、小程序与七牛云暂未提供同步版本。
+//!
+//! 所有同步客户端共用一个后台线程上的运行时驱动IO与定时器，请求在调用线程上等待完成，
+//! 因此也可以在异步运行时中调用，但会阻塞当前线程，异步代码中应直接使用异步客户端。
+//! 内部的异步客户端使用默认连接池时会改用同步客户端专用的连接池，连接只在后台运行时上使用。
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(feature = "taobao")]
+//! use labrador::{blocking, SimpleStorage, TaobaoClient, TbItemDetailRequest};
+//!
+//! # #[cfg(feature = "taobao")]
+//! fn main() -> labrador::LabradorResult<()> {
+//!     let client = blocking::TaobaoClient::new(TaobaoClient::<SimpleStorage>::new("appkey", "secret"))?;
+//!     let result = client.get_item_detail(TbItemDetailRequest {
+//!         num_iids: Some("597649283190".to_string()),
+//!         platform: None,
+//!         ip: None,
+//!     })?;
+//!     println!("{:?}", result);
+//!     Ok(())
+//! }
+//! # #[cfg(not(feature = "taobao"))]
+//! # fn main() {}
+//! ```
+//!
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use once_cell::sync::{Lazy, OnceCell};
+use tokio::runtime::{Builder, Handle};
+
+use crate::{HttpTransport, LabraHttpClient, LabraHttpConfig, LabradorResult};
+
+/// 同步客户端共用的运行时
+static RUNTIME: OnceCell<Handle> = OnceCell::new();
+
+/// 同步客户端专用的连接池
+static HTTP_CLIENT: Lazy<Arc<dyn HttpTransport>> = Lazy::new(|| {
+    Arc::new(LabraHttpClient::new(LabraHttpConfig::default()).expect("can not build the blocking http client"))
+});
+
+/// 生成同步方法，在共用的运行时上执行异步客户端的同名方法
+#[allow(unused_macros)]
+macro_rules! blocking_methods {
+    ($($(#[$meta:meta])* fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(&self $(, $arg: $ty)*) -> $ret {
+                $crate::blocking::block_on(&self.runtime, self.inner.$name($($arg),*))
+            }
+        )*
+    };
+    ($service:ident => $($(#[$meta:meta])* fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(&self $(, $arg: $ty)*) -> $ret {
+                $crate::blocking::block_on(&self.client.runtime, self.client.inner.$service().$name($($arg),*))
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "taobao")]
+mod taobao;
+#[cfg(feature = "taobao")]
+pub use self::taobao::*;
+#[cfg(feature = "jd")]
+mod jd;
+#[cfg(feature = "jd")]
+pub use self::jd::*;
+#[cfg(feature = "pdd")]
+mod pdd;
+#[cfg(feature = "pdd")]
+pub use self::pdd::*;
+#[cfg(feature = "alipay")]
+mod alipay;
+#[cfg(feature = "alipay")]
+pub use self::alipay::*;
+#[cfg(feature = "wechat")]
+mod pay;
+#[cfg(feature = "wechat")]
+pub use self::pay::*;
+
+/// 获取同步客户端共用的运行时，第一次调用时启动后台线程
+#[allow(unused)]
+fn runtime() -> LabradorResult<Handle> {
+    RUNTIME.get_or_try_init(|| -> LabradorResult<Handle> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        let handle = runtime.handle().clone();
+        // 后台线程一直运行，驱动IO、定时器以及连接池的后台任务
+        thread::Builder::new()
+            .name("labrador-blocking".to_string())
+            .spawn(move || runtime.block_on(std::future::pending::<()>()))?;
+        Ok(handle)
+    }).cloned()
+}
+
+/// 同步客户端使用的传输层，使用默认连接池时改用同步客户端专用的连接池
+#[allow(unused)]
+pub(crate) fn transport() -> Arc<dyn HttpTransport> {
+    HTTP_CLIENT.clone()
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// 在调用线程上等待异步请求完成，期间进入共用运行时的上下文，IO与定时器由后台线程驱动
+#[allow(unused)]
+pub(crate) fn block_on<F: Future>(runtime: &Handle, future: F) -> F::Output {
+    let _guard = runtime.enter();
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    // 在异步任务中调用时不受所在任务的协作调度预算限制
+    let mut future = Box::pin(tokio::task::unconstrained(future));
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+#[cfg(all(test, feature = "taobao"))]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use crate::{MockResponse, MockTransport, SimpleStorage, TaobaoClient, TbItemDetailRequest};
+
+    const ITEM_DETAIL: &str = r#"{"tbk_item_info_get_response":{"results":{"n_tbk_item":[{"num_iid":597649283190,"title":"labrador"}]}}}"#;
+
+    fn request() -> TbItemDetailRequest {
+        TbItemDetailRequest { num_iids: Some("597649283190".to_string()), platform: None, ip: None }
+    }
+
+    /// 本地HTTP服务，同一个连接上可以处理多个请求，返回服务地址与连接数
+    fn serve(content: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    loop {
+                        let mut length = 0;
+                        loop {
+                            let mut line = String::new();
+                            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                                return;
+                            }
+                            if line == "\r\n" {
+                                break;
+                            }
+                            if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                                length = v.trim().parse().unwrap();
+                            }
+                        }
+                        reader.read_exact(&mut vec![0; length]).unwrap();
+                        write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}", content.len(), content).unwrap();
+                    }
+                });
+            }
+        });
+        (format!("http://{}", addr), connections)
+    }
+
+    #[test]
+    fn test_shared_runtime() {
+        let (url, connections) = serve(ITEM_DETAIL);
+        // 前一个同步客户端释放后，连接池中的连接仍然可用
+        for _ in 0..2 {
+            let client = super::TaobaoClient::new(TaobaoClient::<SimpleStorage>::new("appkey", "secret").api_path(url.as_str())).unwrap();
+            let items = client.get_item_detail(request()).unwrap().n_tbk_item.unwrap_or_default();
+            assert_eq!(Some(597649283190), items[0].num_iid);
+        }
+        assert_eq!(1, connections.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_inside_runtime() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, ITEM_DETAIL));
+        let client = super::TaobaoClient::new(TaobaoClient::<SimpleStorage>::new("appkey", "secret").transport(transport)).unwrap();
+        // 在异步运行时中调用不会panic
+        let items = client.get_item_detail(request()).unwrap().n_tbk_item.unwrap_or_default();
+        assert_eq!(Some(597649283190), items[0].num_iid);
+    }
+}
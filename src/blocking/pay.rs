@@ -0,0 +1,228 @@
+use serde_json::Value;
+use tokio::runtime::Handle;
+
+use crate::{AsyncSessionStore, LabradorResult, LabraResponse, IsvWechatPayRequestV3, PlatformCertificateResponse, SignatureHeader, TradeType, WechatBillResponseV3, WechatCloseOrderRequest, WechatCloseOrderRequestV3, WechatCloseOrderResponse, WechatFundFlowBillRequestV3, WechatFundFlowBillV3, WechatOrderReverseRequest, WechatOrderReverseResponse, WechatPayNotifyResponseV3, WechatPayRequest, WechatPayRequestV3, WechatPayResponse, WechatPayResponseV3, WechatProfitSharingAmountResponseV3, WechatProfitSharingNotifyResponseV3, WechatProfitSharingOrderRequestV3, WechatProfitSharingOrderResponseV3, WechatProfitSharingReceiverRequestV3, WechatProfitSharingReceiverResponseV3, WechatProfitSharingRemoveReceiverRequestV3, WechatProfitSharingReturnRequestV3, WechatProfitSharingReturnResponseV3, WechatProfitSharingUnfreezeRequestV3, WechatQueryOrderRequest, WechatQueryOrderRequestV3, WechatQueryOrderResponse, WechatQueryOrderResponseV3, WechatQueryProfitSharingOrderRequestV3, WechatQueryProfitSharingReturnRequestV3, WechatQueryRefundOrderRequest, WechatQueryRefundResponse, WechatQueryRefundResponseV3, WechatQueryTransferBatchRequestV3, WechatQueryTransferBatchResponseV3, WechatQueryTransferDetailResponseV3, WechatQueryTransferRequest, WechatQueryTransferResponse, WechatRefundNotifyResponseV3, WechatRefundRequest, WechatRefundRequestV3, WechatRefundResponse, WechatRefundResponseV3, WechatSubMerchantFundFlowBillRequestV3, WechatSubMerchantFundFlowBillResponseV3, WechatTradeBillRequestV3, WechatTradeBillV3, WechatTransferBatchRequestV3, WechatTransferBatchResponseV3, WechatTransferBillReceiptResponseV3, WechatTransferDetailReceiptRequestV3, WechatTransferDetailReceiptResponseV3, WechatTransferRequest, WechatTransferResponse, WxPayShortUrlResponse, WxPayShorturlRequest};
+
+/// 微信支付同步客户端
+#[derive(Debug)]
+pub struct WechatPayClient<T: AsyncSessionStore> {
+    inner: crate::WechatPayClient<T>,
+    runtime: Handle,
+}
+
+#[allow(unused)]
+impl<T: AsyncSessionStore> WechatPayClient<T> {
+    /// 使用配置好的异步客户端创建同步客户端
+    pub fn new(inner: crate::WechatPayClient<T>) -> LabradorResult<Self> {
+        Ok(Self { inner: inner.blocking_transport(), runtime: super::runtime()? })
+    }
+
+    /// 内部的异步客户端
+    pub fn get_ref(&self) -> &crate::WechatPayClient<T> {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> crate::WechatPayClient<T> {
+        self.inner
+    }
+
+    /// 普通支付
+    pub fn wxpay(&self) -> WxPay<'_, T> {
+        WxPay { client: self }
+    }
+
+    /// 企业付款
+    pub fn entpay(&self) -> EntPay<'_, T> {
+        EntPay { client: self }
+    }
+
+    /// 分账
+    pub fn profit_sharing(&self) -> ProfitSharing<'_, T> {
+        ProfitSharing { client: self }
+    }
+
+    blocking_methods! {
+        /// 获取沙箱环境的签名密钥
+        fn get_sandbox_sign_key(&self) -> LabradorResult<String>;
+        /// 使用当前最新的平台证书加密敏感信息，返回密文与平台证书序列号
+        fn encrypt_sensitive(&self, content: &str) -> LabradorResult<(String, String)>;
+        /// 重新下载平台证书
+        fn refresh_certs(&self) -> LabradorResult<()>;
+        /// 获取平台证书 - V3版本
+        fn get_certificates(&self) -> LabradorResult<Vec<PlatformCertificateResponse>>;
+    }
+}
+
+/// 普通支付
+#[derive(Debug)]
+pub struct WxPay<'a, T: AsyncSessionStore> {
+    client: &'a WechatPayClient<T>,
+}
+
+#[allow(unused)]
+impl<'a, T: AsyncSessionStore> WxPay<'a, T> {
+    blocking_methods! { wxpay =>
+        /// 统一下单
+        fn unified_order(&self, params: WechatPayRequest) -> LabradorResult<WechatPayResponse>;
+        /// 统一下单 - V3版本
+        fn unified_order_v3(&self, trade_type: TradeType, params: WechatPayRequestV3) -> LabradorResult<WechatPayResponseV3>;
+        /// 服务商统一下单 - V3版本
+        fn isv_unified_order_v3(&self, trade_type: TradeType, params: IsvWechatPayRequestV3) -> LabradorResult<WechatPayResponseV3>;
+        /// 调用统一下单接口，并组装生成支付所需参数对象
+        fn create_order_v3(&self, trade_type: TradeType, params: WechatPayRequestV3) -> LabradorResult<Value>;
+        /// 服务商调用统一下单接口，并组装生成支付所需参数对象
+        fn isv_create_order_v3(&self, trade_type: TradeType, params: IsvWechatPayRequestV3) -> LabradorResult<Value>;
+        /// 关闭订单
+        fn close_order(&self, params: WechatCloseOrderRequest) -> LabradorResult<WechatCloseOrderResponse>;
+        /// 关闭订单 - V3版本
+        fn close_order_v3(&self, params: WechatCloseOrderRequestV3) -> LabradorResult<()>;
+        /// 查询订单
+        fn query_order(&self, params: WechatQueryOrderRequest) -> LabradorResult<WechatQueryOrderResponse>;
+        /// 查询订单 - V3版本
+        fn query_order_v3(&self, params: WechatQueryOrderRequestV3) -> LabradorResult<WechatQueryOrderResponseV3>;
+        /// 查询退款
+        fn query_refund_order(&self, params: WechatQueryRefundOrderRequest) -> LabradorResult<WechatQueryRefundResponse>;
+        /// 查询退款 - V2版本
+        fn query_refund_order_v2(&self, params: WechatQueryRefundOrderRequest) -> LabradorResult<WechatQueryRefundResponse>;
+        /// 查询退款 - V3版本
+        fn query_refund_order_v3(&self, out_refund_no: String) -> LabradorResult<WechatQueryRefundResponseV3>;
+        /// 服务商查询退款 - V3版本
+        fn isv_query_refund_order_v3(&self, out_refund_no: String, sub_mch_id: String) -> LabradorResult<WechatQueryRefundResponseV3>;
+        /// 解析支付结果通知 - V3版本
+        fn parse_order_notify_v3(&self, notify_data: &str, header: Option<SignatureHeader>) -> LabradorResult<WechatPayNotifyResponseV3>;
+        /// 解析退款结果通知 - V3版本
+        fn parse_refund_notify_v3(&self, notify_data: &str, header: &Option<SignatureHeader>) -> LabradorResult<WechatRefundNotifyResponseV3>;
+        /// 付款码支付
+        fn micro_pay(&self, pay_params: WechatPayRequest) -> LabradorResult<WechatPayResponse>;
+        /// JSAPI支付
+        fn jsapi_pay(&self, pay_params: WechatPayRequest) -> LabradorResult<WechatPayResponse>;
+        /// APP支付
+        fn app_pay(&self, pay_params: WechatPayRequest) -> LabradorResult<WechatPayResponse>;
+        /// 申请退款
+        fn refund(&self, params: WechatRefundRequest) -> LabradorResult<WechatRefundResponse>;
+        /// 撤销订单
+        fn reverse_order(&self, params: WechatOrderReverseRequest) -> LabradorResult<WechatOrderReverseResponse>;
+        /// 转换短链接
+        fn short_url(&self, params: WxPayShorturlRequest) -> LabradorResult<WxPayShortUrlResponse>;
+        /// 申请退款 - V3版本
+        fn refund_v3(&self, params: WechatRefundRequestV3) -> LabradorResult<WechatRefundResponseV3>;
+        /// 申请交易账单 - V3版本
+        fn apply_trade_bill_v3(&self, params: &WechatTradeBillRequestV3) -> LabradorResult<WechatBillResponseV3>;
+        /// 申请资金账单 - V3版本
+        fn apply_fund_flow_bill_v3(&self, params: &WechatFundFlowBillRequestV3) -> LabradorResult<WechatBillResponseV3>;
+        /// 申请单个子商户资金账单 - V3版本
+        fn apply_sub_merchant_fund_flow_bill_v3(&self, params: &WechatSubMerchantFundFlowBillRequestV3) -> LabradorResult<WechatSubMerchantFundFlowBillResponseV3>;
+        /// 下载账单 - V3版本
+        fn download_bill_v3(&self, bill: &WechatBillResponseV3) -> LabradorResult<String>;
+        /// 下载子商户资金账单 - V3版本
+        fn download_sub_merchant_bill_v3(&self, bill: &WechatSubMerchantFundFlowBillResponseV3) -> LabradorResult<String>;
+        /// 申请并下载交易账单，解析为明细数据与汇总数据
+        fn trade_bill_v3(&self, params: &WechatTradeBillRequestV3) -> LabradorResult<WechatTradeBillV3>;
+        /// 申请并下载资金账单，解析为明细数据与汇总数据
+        fn fund_flow_bill_v3(&self, params: &WechatFundFlowBillRequestV3) -> LabradorResult<WechatFundFlowBillV3>;
+        /// 申请、下载并解密子商户资金账单，解析为明细数据与汇总数据
+        fn sub_merchant_fund_flow_bill_v3(&self, params: &WechatSubMerchantFundFlowBillRequestV3) -> LabradorResult<WechatFundFlowBillV3>;
+    }
+}
+
+/// 企业付款
+#[derive(Debug)]
+pub struct EntPay<'a, T: AsyncSessionStore> {
+    client: &'a WechatPayClient<T>,
+}
+
+#[allow(unused)]
+impl<'a, T: AsyncSessionStore> EntPay<'a, T> {
+    blocking_methods! { entpay =>
+        /// 企业付款到零钱
+        fn transfer(&self, params: WechatTransferRequest) -> LabradorResult<WechatTransferResponse>;
+        /// 查询企业付款到零钱
+        fn query_transfer(&self, params: WechatQueryTransferRequest) -> LabradorResult<WechatQueryTransferResponse>;
+        /// 发起商家转账
+        fn transfer_batches_v3(&self, params: WechatTransferBatchRequestV3) -> LabradorResult<WechatTransferBatchResponseV3>;
+        /// 微信批次单号查询批次单
+        fn query_transfer_batch_by_id_v3(&self, batch_id: &str, params: &WechatQueryTransferBatchRequestV3) -> LabradorResult<WechatQueryTransferBatchResponseV3>;
+        /// 商家批次单号查询批次单
+        fn query_transfer_batch_by_out_no_v3(&self, out_batch_no: &str, params: &WechatQueryTransferBatchRequestV3) -> LabradorResult<WechatQueryTransferBatchResponseV3>;
+        /// 微信明细单号查询明细单
+        fn query_transfer_detail_by_id_v3(&self, batch_id: &str, detail_id: &str) -> LabradorResult<WechatQueryTransferDetailResponseV3>;
+        /// 商家明细单号查询明细单
+        fn query_transfer_detail_by_out_no_v3(&self, out_batch_no: &str, out_detail_no: &str) -> LabradorResult<WechatQueryTransferDetailResponseV3>;
+        /// 转账账单电子回单申请受理
+        fn apply_bill_receipt_v3(&self, out_batch_no: &str) -> LabradorResult<WechatTransferBillReceiptResponseV3>;
+        /// 查询转账账单电子回单
+        fn query_bill_receipt_v3(&self, out_batch_no: &str) -> LabradorResult<WechatTransferBillReceiptResponseV3>;
+        /// 转账明细电子回单受理
+        fn apply_detail_receipt_v3(&self, params: &WechatTransferDetailReceiptRequestV3) -> LabradorResult<WechatTransferDetailReceiptResponseV3>;
+        /// 查询转账明细电子回单受理结果
+        fn query_detail_receipt_v3(&self, params: &WechatTransferDetailReceiptRequestV3) -> LabradorResult<WechatTransferDetailReceiptResponseV3>;
+    }
+
+    /// 下载电子回单，读取完整的回单内容后返回
+    pub fn download_receipt_v3(&self, download_url: &str) -> LabradorResult<LabraResponse> {
+        super::block_on(&self.client.runtime, async {
+            self.client.inner.entpay().download_receipt_v3(download_url).await?.into_response().await
+        })
+    }
+}
+
+/// 分账
+#[derive(Debug)]
+pub struct ProfitSharing<'a, T: AsyncSessionStore> {
+    client: &'a WechatPayClient<T>,
+}
+
+#[allow(unused)]
+impl<'a, T: AsyncSessionStore> ProfitSharing<'a, T> {
+    blocking_methods! { profit_sharing =>
+        /// 添加分账接收方
+        fn add_receiver_v3(&self, params: WechatProfitSharingReceiverRequestV3) -> LabradorResult<WechatProfitSharingReceiverResponseV3>;
+        /// 删除分账接收方
+        fn remove_receiver_v3(&self, params: WechatProfitSharingRemoveReceiverRequestV3) -> LabradorResult<WechatProfitSharingReceiverResponseV3>;
+        /// 请求分账
+        fn create_order_v3(&self, params: WechatProfitSharingOrderRequestV3) -> LabradorResult<WechatProfitSharingOrderResponseV3>;
+        /// 查询分账结果
+        fn query_order_v3(&self, params: &WechatQueryProfitSharingOrderRequestV3) -> LabradorResult<WechatProfitSharingOrderResponseV3>;
+        /// 解冻剩余资金
+        fn unfreeze_v3(&self, params: WechatProfitSharingUnfreezeRequestV3) -> LabradorResult<WechatProfitSharingOrderResponseV3>;
+        /// 请求分账回退
+        fn create_return_order_v3(&self, params: WechatProfitSharingReturnRequestV3) -> LabradorResult<WechatProfitSharingReturnResponseV3>;
+        /// 查询分账回退结果
+        fn query_return_order_v3(&self, params: &WechatQueryProfitSharingReturnRequestV3) -> LabradorResult<WechatProfitSharingReturnResponseV3>;
+        /// 查询剩余待分金额
+        fn query_amounts_v3(&self, transaction_id: &str) -> LabradorResult<WechatProfitSharingAmountResponseV3>;
+        /// 解析分账动账通知
+        fn parse_notify_v3(&self, notify_data: &str, header: &Option<SignatureHeader>) -> LabradorResult<WechatProfitSharingNotifyResponseV3>;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MockResponse, MockTransport, SimpleStorage, WechatTransferRequest};
+
+    #[test]
+    fn test_transfer() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, "<xml><return_code>SUCCESS</return_code><return_msg></return_msg><mch_appid>appid</mch_appid><mchid>10000100</mchid><nonce_str>nonce</nonce_str><result_code>SUCCESS</result_code><partner_trade_no>T001</partner_trade_no><payment_no>1000018301201505190181489473</payment_no><payment_time>2024-01-01 12:00:00</payment_time></xml>"));
+        let client = crate::WechatPayClient::<SimpleStorage>::new("appid", "secret")
+            .mch_id("10000100".to_string())
+            .key("key".to_string())
+            .transport(transport.clone());
+        let client = super::WechatPayClient::new(client).unwrap();
+        let params = WechatTransferRequest {
+            partner_trade_no: "T001".to_string(),
+            openid: "openid".to_string(),
+            check_name: "NO_CHECK".to_string(),
+            amount: 100,
+            desc: "佣金".to_string(),
+            ..Default::default()
+        };
+        let response = client.entpay().transfer(params).unwrap();
+        assert_eq!("1000018301201505190181489473", response.payment_no);
+        // 请求由异步客户端签名
+        let requests = transport.requests();
+        assert_eq!("https://api.mch.weixin.qq.com/mmpaymkttransfers/promotion/transfers", requests[0].url.as_str());
+        assert!(requests[0].body_text().contains("<mch_appid>appid</mch_appid>"));
+        assert!(requests[0].body_text().contains("<sign>"));
+    }
+}
@@ -0,0 +1,60 @@
+use tokio::runtime::Handle;
+
+use crate::{AsyncSessionStore, LabradorResult, PddAuthorityQueryParam, PddAuthorityQueryResponse, PddCmsUrlGenerateParam, PddCmsUrlGenerateResponse, PddGoodsDetailParam, PddGoodsDetailResponse, PddGoodsRecommendParam, PddGoodsRecommendResponse, PddGoodsSearchParam, PddGoodsSearchResponse, PddGoodsTopParam, PddGoodsTopResponse, PddOrderDetail, PddOrderDetailParam, PddOrderIncrementQueryParam, PddOrderIncrementQueryResponse, PddOrderRangeQueryParam, PddOrderRangeQueryResponse, PddPidBindMediaParam, PddPidBindMediaResponse, PddPidGenerateParam, PddPidGenerateResponse, PddPidQueryParam, PddPidQueryResponse, PddPromoteUrlGenerateParam, PddPromotionUrlGenerateResponse, PddRpUrlGenerateParam, PddRpUrlGenerateResponse, PddZsUrlGenerateParam, PddZsUrlGenerateResponse};
+
+/// 多多进宝同步客户端
+#[derive(Debug)]
+pub struct PDDClient<T: AsyncSessionStore> {
+    inner: crate::PDDClient<T>,
+    runtime: Handle,
+}
+
+#[allow(unused)]
+impl<T: AsyncSessionStore> PDDClient<T> {
+    /// 使用配置好的异步客户端创建同步客户端
+    pub fn new(inner: crate::PDDClient<T>) -> LabradorResult<Self> {
+        Ok(Self { inner: inner.blocking_transport(), runtime: super::runtime()? })
+    }
+
+    /// 内部的异步客户端
+    pub fn get_ref(&self) -> &crate::PDDClient<T> {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> crate::PDDClient<T> {
+        self.inner
+    }
+
+    blocking_methods! {
+        /// 获取多多客推荐的商品
+        fn get_goods_recommend(&self, param: PddGoodsRecommendParam) -> LabradorResult<PddGoodsRecommendResponse>;
+        /// 多多客获取爆款排行商品接口
+        fn get_goods_top(&self, param: PddGoodsTopParam) -> LabradorResult<PddGoodsTopResponse>;
+        /// 多多客商品搜索
+        fn search_goods(&self, param: PddGoodsSearchParam) -> LabradorResult<PddGoodsSearchResponse>;
+        /// 拼多多备案查询
+        fn query_authority(&self, param: PddAuthorityQueryParam) -> LabradorResult<PddAuthorityQueryResponse>;
+        /// 多多进宝推广链接生成
+        fn generate_promote_url(&self, param: PddPromoteUrlGenerateParam) -> LabradorResult<PddPromotionUrlGenerateResponse>;
+        /// 生成营销工具推广链接
+        fn generate_rp_promote_url(&self, param: PddRpUrlGenerateParam) -> LabradorResult<PddRpUrlGenerateResponse>;
+        /// 多多进宝商品详情查询
+        fn get_goods_detail(&self, param: PddGoodsDetailParam) -> LabradorResult<PddGoodsDetailResponse>;
+        /// 多多进宝转链
+        fn get_zs_url_generate(&self, param: PddZsUrlGenerateParam) -> LabradorResult<PddZsUrlGenerateResponse>;
+        /// 生成商城-频道推广链接
+        fn get_cms_url_generate(&self, param: PddCmsUrlGenerateParam) -> LabradorResult<PddCmsUrlGenerateResponse>;
+        /// 用时间段查询推广订单
+        fn get_order_list(&self, param: PddOrderRangeQueryParam) -> LabradorResult<PddOrderRangeQueryResponse>;
+        /// 最后更新时间段增量同步推广订单信息
+        fn get_increment_order_list(&self, param: PddOrderIncrementQueryParam) -> LabradorResult<PddOrderIncrementQueryResponse>;
+        /// 查询订单详情
+        fn get_order_detail(&self, param: PddOrderDetailParam) -> LabradorResult<PddOrderDetail>;
+        /// 创建多多进宝推广位
+        fn pid_generate(&self, param: PddPidGenerateParam) -> LabradorResult<PddPidGenerateResponse>;
+        /// 查询已经生成的推广位信息
+        fn pid_query(&self, param: PddPidQueryParam) -> LabradorResult<PddPidQueryResponse>;
+        /// 批量绑定推广位的媒体id
+        fn pid_bind_media(&self, param: PddPidBindMediaParam) -> LabradorResult<PddPidBindMediaResponse>;
+    }
+}
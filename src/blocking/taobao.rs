@@ -0,0 +1,69 @@
+use tokio::runtime::Handle;
+
+use crate::{AsyncSessionStore, LabradorResult, TbCouponDetailRequest, TbCouponDetailResponse, TbCreateTPwdRequest, TbCreateTPwdResponse, TbGetActivityInfoRequest, TbGetActivityInfoResponse, TbItemDetailRequest, TbItemDetailResponse, TbMaterialSearchRequest, TbMaterialSearchResponse, TbMaterialSelectRequest, TbMaterialSelectResponse, TbSpreadGetRequest, TbSpreadGetResponse, TbTPwdReportGetRequest, TbTPwdReportGetResponse};
+
+/// 淘宝客同步客户端
+#[derive(Debug)]
+pub struct TaobaoClient<T: AsyncSessionStore> {
+    inner: crate::TaobaoClient<T>,
+    runtime: Handle,
+}
+
+#[allow(unused)]
+impl<T: AsyncSessionStore> TaobaoClient<T> {
+    /// 使用配置好的异步客户端创建同步客户端
+    pub fn new(inner: crate::TaobaoClient<T>) -> LabradorResult<Self> {
+        Ok(Self { inner: inner.blocking_transport(), runtime: super::runtime()? })
+    }
+
+    /// 内部的异步客户端
+    pub fn get_ref(&self) -> &crate::TaobaoClient<T> {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> crate::TaobaoClient<T> {
+        self.inner
+    }
+
+    blocking_methods! {
+        /// 获取淘宝客物料精选
+        fn get_material_selected(&self, request: TbMaterialSelectRequest) -> LabradorResult<TbMaterialSelectResponse>;
+        /// 淘宝客商品详情查询(简版)
+        fn get_item_detail(&self, request: TbItemDetailRequest) -> LabradorResult<TbItemDetailResponse>;
+        /// 淘宝客阿里妈妈推广券详情查询
+        fn get_coupon_detail(&self, request: TbCouponDetailRequest) -> LabradorResult<TbCouponDetailResponse>;
+        /// 淘宝客淘口令生成
+        fn create_tpwd(&self, request: TbCreateTPwdRequest) -> LabradorResult<TbCreateTPwdResponse>;
+        /// 淘宝客-公用-长链转短链
+        fn get_spread(&self, request: TbSpreadGetRequest) -> LabradorResult<TbSpreadGetResponse>;
+        /// 淘宝客-推广者-淘口令回流数据查询
+        fn get_tpwd_report(&self, request: TbTPwdReportGetRequest) -> LabradorResult<TbTPwdReportGetResponse>;
+        /// 淘宝客物料搜索
+        fn search_material(&self, request: TbMaterialSearchRequest) -> LabradorResult<TbMaterialSearchResponse>;
+        /// 淘宝客-推广者-官方活动转链
+        fn get_activity_info(&self, request: TbGetActivityInfoRequest) -> LabradorResult<TbGetActivityInfoResponse>;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MockResponse, MockTransport, SimpleStorage, TbItemDetailRequest};
+
+    #[test]
+    fn test_get_item_detail() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, r#"{"tbk_item_info_get_response":{"results":{"n_tbk_item":[{"num_iid":597649283190,"title":"labrador"}]}}}"#))
+            .push_response(MockResponse::new(200, r#"{"error_response":{"code":15,"msg":"Remote service error","sub_code":"isv.item-not-exist","sub_msg":"商品不存在"}}"#));
+        let client = super::TaobaoClient::new(crate::TaobaoClient::<SimpleStorage>::new("appkey", "secret").transport(transport.clone())).unwrap();
+        let request = || TbItemDetailRequest { num_iids: Some("597649283190".to_string()), platform: None, ip: None };
+        let items = client.get_item_detail(request()).unwrap().n_tbk_item.unwrap_or_default();
+        assert_eq!(Some(597649283190), items[0].num_iid);
+        let err = client.get_item_detail(request()).unwrap_err();
+        assert_eq!("isv.item-not-exist", err.platform_error().unwrap().sub_code.as_deref().unwrap_or_default());
+        // 请求由异步客户端签名
+        let requests = transport.requests();
+        assert_eq!(2, requests.len());
+        assert!(requests[0].url.as_str().contains("method=taobao.tbk.item.info.get"));
+        assert!(requests[0].url.query_pairs().any(|(k, _)| k == "sign"));
+    }
+}
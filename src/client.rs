@@ -1,14 +1,32 @@
+use std::sync::Arc;
+use std::time::Instant;
+use reqwest::multipart;
 use serde::Serialize;
+use tracing::Instrument;
 
-use crate::{request::{LabraResponse, LabraRequest}, session::{SessionStore, SimpleStorage}, LabradorResult, RequestMethod, RequestType, Method};
+use crate::{request::{LabraResponse, LabraRequest}, stream::LabraStreamResponse, session::{AsyncSessionStore, LockOptions, SimpleStorage}, transport::{HttpTransport, LabraHttpClient, LabraHttpConfig, DEFAULT_TRANSPORT}, retry::{NoRetry, RetryPolicy}, middleware::Middleware, logging::LabraLogConfig, ratelimit::RateLimiter, telemetry, LabradorResult, RequestMethod};
 
 /// API請求
 #[derive(Debug, Clone)]
-pub struct APIClient<T: SessionStore> {
+pub struct APIClient<T: AsyncSessionStore> {
     pub app_key: String,
     pub secret: String,
     pub api_path: String,
     pub session: T,
+    /// 传输层，默认为共用连接池的HTTP客户端
+    pub transport: Arc<dyn HttpTransport>,
+    /// 重试策略，默认不重试
+    pub retry_policy: Arc<dyn RetryPolicy>,
+    /// 中间件，按注册顺序调用
+    pub middlewares: Vec<Arc<dyn Middleware>>,
+    /// 平台名称，用于日志
+    pub platform: &'static str,
+    /// 请求日志配置
+    pub log_config: LabraLogConfig,
+    /// 凭证刷新锁配置
+    pub lock_options: LockOptions,
+    /// 限流器，同时统计每个方法的调用次数
+    pub rate_limiter: RateLimiter,
 }
 
 /// APIClient
@@ -17,16 +35,20 @@ pub struct APIClient<T: SessionStore> {
 /// 
 /// # Examples
 /// ```no_run
-/// use labrador::{APIClient, SessionStore};
-/// struct DemoClient<T: SessionStore> {
+/// use labrador::{APIClient, AsyncSessionStore, LabraRequest, LabraResponse, LabradorResult};
+/// struct DemoClient<T: AsyncSessionStore> {
 ///     api_client: APIClient<T>,
 /// }
 ///
-/// api_client.request()
+/// impl<T: AsyncSessionStore> DemoClient<T> {
+///     async fn ping(&self) -> LabradorResult<LabraResponse> {
+///         self.api_client.request(LabraRequest::get("/ping")).await
+///     }
+/// }
 /// ```
 /// 
 #[allow(unused)]
-impl<T: SessionStore> APIClient<T> {
+impl<T: AsyncSessionStore> APIClient<T> {
 
     /// # Init the client
     /// 
@@ -40,7 +62,14 @@ impl<T: SessionStore> APIClient<T> {
             app_key: app_key.into(),
             secret: secret.into(),
             api_path: api_path.into(),
-            session: SimpleStorage::new()
+            session: SimpleStorage::new(),
+            transport: DEFAULT_TRANSPORT.clone(),
+            retry_policy: Arc::new(NoRetry),
+            middlewares: vec![],
+            platform: "",
+            log_config: LabraLogConfig::default(),
+            lock_options: LockOptions::default(),
+            rate_limiter: RateLimiter::default(),
         }
     }
 
@@ -50,10 +79,92 @@ impl<T: SessionStore> APIClient<T> {
             app_key: app_key.into(),
             secret: secret.into(),
             api_path: api_path.into(),
-            session: session,
+            session,
+            transport: DEFAULT_TRANSPORT.clone(),
+            retry_policy: Arc::new(NoRetry),
+            middlewares: vec![],
+            platform: "",
+            log_config: LabraLogConfig::default(),
+            lock_options: LockOptions::default(),
+            rate_limiter: RateLimiter::default(),
         }
     }
 
+    /// 设置HTTP客户端，多个客户端可共用同一个连接池
+    pub fn http_client(mut self, http_client: LabraHttpClient) -> Self {
+        self.transport = Arc::new(http_client);
+        self
+    }
+
+    /// 设置传输层，测试时可替换为`MockTransport`
+    pub fn transport<H: HttpTransport + 'static>(mut self, transport: H) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// 使用默认的传输层时改用同步客户端专用的连接池
+    #[cfg(feature = "blocking")]
+    pub(crate) fn blocking_transport(mut self) -> Self {
+        if Arc::ptr_eq(&self.transport, &DEFAULT_TRANSPORT) {
+            self.transport = crate::blocking::transport();
+        }
+        self
+    }
+
+    /// 设置重试策略，GET请求和标记为幂等的请求任何失败都可以重试，其他请求只会在连接阶段失败时重试
+    pub fn retry_policy<P: RetryPolicy + 'static>(mut self, retry_policy: P) -> Self {
+        self.retry_policy = Arc::new(retry_policy);
+        self
+    }
+
+    /// 添加中间件
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// 设置平台名称
+    pub fn platform(mut self, platform: &'static str) -> Self {
+        self.platform = platform;
+        self
+    }
+
+    /// 设置请求日志配置
+    pub fn log_config(mut self, log_config: LabraLogConfig) -> Self {
+        self.log_config = log_config;
+        self
+    }
+
+    /// 设置凭证刷新锁配置
+    pub fn lock_options(mut self, lock_options: LockOptions) -> Self {
+        self.lock_options = lock_options;
+        self
+    }
+
+    /// 设置限流器
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// 根据配置生成独立的HTTP客户端
+    pub fn http_config(mut self, config: LabraHttpConfig) -> LabradorResult<Self> {
+        self.transport = Arc::new(LabraHttpClient::new(config)?);
+        Ok(self)
+    }
+
+    /// 在当前HTTP配置的基础上设置代理，生成独立的HTTP客户端
+    pub fn proxy(self, proxy: &str) -> LabradorResult<Self> {
+        let config = self.transport.http_config().cloned().unwrap_or_default().proxy(proxy);
+        self.http_config(config)
+    }
+
+    /// 设置接口地址
+    pub fn api_path<S: Into<String>>(mut self, api_path: S) -> Self {
+        self.api_path = api_path.into();
+        self
+    }
+
     pub fn session(&self) -> &T {
         &self.session
     }
@@ -63,18 +174,18 @@ impl<T: SessionStore> APIClient<T> {
     /// # Examples
     ///
     /// ```no_run
-    /// use labrador::APIClient;
+    /// use labrador::{APIClient, SimpleStorage};
     /// use serde_json::json;
-    /// use labrador::Method;
     /// use labrador::{LabraRequest, LabradorResult};
-    /// use std::error::Error;
-    /// async fn main() -> LabradorResult<(), Error> {
-    ///     let api = APIClient::new("appkey", "secret", "http_url");
+    /// # #[tokio::main]
+    /// async fn main() -> LabradorResult<()> {
+    ///     let api = APIClient::<SimpleStorage>::new("appkey", "secret", "http_url");
     ///     let params = vec![(String::from("key"), String::from("value"))];
     ///     let data = json!({
     ///         "key": "value"
     ///     });
-    ///     api.request(LabraRequest::new().method(Method::Post).data(data).req_type(request_type).params(params)).await?;
+    ///     api.request(LabraRequest::post_json("/cgi-bin/test", data).params(params)).await?;
+    ///     Ok(())
     /// }
     /// ```
     ///
@@ -82,23 +193,59 @@ impl<T: SessionStore> APIClient<T> {
     pub async fn request<D: Serialize>(&self, mut req: LabraRequest<D>) -> LabradorResult<LabraResponse> {
         let mut api_path = self.api_path.to_owned();
         let LabraRequest { url, ..} = req;
+        let api_method = req.api_method.clone().unwrap_or_else(|| url.split('?').next().unwrap_or_default().to_string());
         if url.starts_with("http") {
             req.url = url;
         } else {
             req.url = api_path + &url;
         }
-        req.request().await
+        let span = tracing::info_span!("labrador", platform = self.platform);
+        self.rate_limiter.acquire(&self.session, &self.app_key, &api_method).instrument(span.clone()).await?;
+        let started = Instant::now();
+        let result = req.request_with(self.transport.as_ref(), self.retry_policy.as_ref(), &self.middlewares, &self.log_config)
+            .instrument(span)
+            .await;
+        telemetry::record_request(self.platform, &api_method, started.elapsed(), &result);
+        result
+    }
+
+    /// 发送请求并流式读取响应，用于下载文件
+    pub async fn request_stream<D: Serialize>(&self, mut req: LabraRequest<D>) -> LabradorResult<LabraStreamResponse> {
+        let api_method = req.api_method.clone().unwrap_or_else(|| req.url.split('?').next().unwrap_or_default().to_string());
+        if !req.url.starts_with("http") {
+            req.url = self.api_path.to_owned() + &req.url;
+        }
+        let span = tracing::info_span!("labrador", platform = self.platform);
+        self.rate_limiter.acquire(&self.session, &self.app_key, &api_method).instrument(span.clone()).await?;
+        let started = Instant::now();
+        let result = req.request_stream_with(self.transport.as_ref(), &self.middlewares, &self.log_config)
+            .instrument(span)
+            .await;
+        telemetry::record_stream_request(self.platform, &api_method, started.elapsed(), &result);
+        result
+    }
+
+    /// 发送POST请求，请求体编码为JSON
+    pub async fn post<D: Serialize, R: RequestMethod>(&self, method: R, querys: Vec<(String, String)>, data: D) -> LabradorResult<LabraResponse> {
+        let req = LabraRequest::post_json(method.get_method(), data).api_method(method.get_name()).params(querys).idempotent(method.is_idempotent());
+        self.request(req).await
+    }
+
+    /// 发送POST请求，请求体编码为JSON，流式读取响应
+    pub async fn post_stream<D: Serialize, R: RequestMethod>(&self, method: R, querys: Vec<(String, String)>, data: D) -> LabradorResult<LabraStreamResponse> {
+        let req = LabraRequest::post_json(method.get_method(), data).api_method(method.get_name()).params(querys).idempotent(method.is_idempotent());
+        self.request_stream(req).await
     }
 
-    /// 发送POST请求
-    pub async fn post<D: Serialize, R: RequestMethod>(&self, method: R, mut querys: Vec<(String, String)>, data: D, request_type: RequestType) -> LabradorResult<LabraResponse> {
-        let req = LabraRequest::new().url(method.get_method()).params(querys).method(Method::Post).json(data).req_type(request_type);
+    /// 上传multipart表单
+    pub async fn post_multipart<R: RequestMethod>(&self, method: R, querys: Vec<(String, String)>, form: multipart::Form) -> LabradorResult<LabraResponse> {
+        let req = LabraRequest::post_multipart(method.get_method(), form).api_method(method.get_name()).params(querys);
         self.request(req).await
     }
 
     /// 发送GET请求
-    pub async fn get<R: RequestMethod>(&self, method: R, params: Vec<(String, String)>, request_type: RequestType) -> LabradorResult<LabraResponse> {
-        let req = LabraRequest::<String>::new().url(method.get_method()).params(params).method(Method::Get).req_type(request_type);
+    pub async fn get<R: RequestMethod>(&self, method: R, params: Vec<(String, String)>) -> LabradorResult<LabraResponse> {
+        let req = LabraRequest::get(method.get_method()).api_method(method.get_name()).params(params);
         self.request(req).await
     }
 }
@@ -9,17 +9,39 @@ use serde_json::{ error::Error as JsonError};
 use tracing::error;
 use x509_parser::der_parser::asn1_rs::SerializeError;
 
+use crate::request::LabraResponse;
+use crate::transport::TransportError;
+
 #[allow(unused)]
 #[derive(Debug)]
 pub enum LabraError {
     InvalidSignature(String),
     ApiError(String),
     InvalidAppId,
-    ClientError { errcode: String, errmsg: String },
+    /// 平台返回的业务错误
+    ClientError(PlatformError),
     IOError(io::Error),
     MissingField(String),
     RedundantField(String),
     RequestError(String),
+    /// HTTP请求错误
+    HttpError(reqwest::Error),
+    /// 传输层错误
+    TransportError(TransportError),
+    /// JSON解析错误
+    JsonError(JsonError),
+    /// XML解析错误
+    XmlError(serde_xml_rs::Error),
+    /// redis错误
+    RedisError(RedisError),
+    /// redis连接池错误
+    PoolError(r2d2::Error),
+    /// 触发本地限流或超过每日调用上限
+    RateLimited(String),
+    /// 配置文件解析错误
+    ConfigError(String),
+    /// 密钥与证书解析、加解密错误
+    CryptoError(Box<dyn std::error::Error + Send + Sync>),
     Unknown,
 }
 
@@ -28,38 +50,115 @@ impl fmt::Display for LabraError {
         match self {
             LabraError::InvalidSignature(ref err) => write!(f, "Invalid signature: {}", err),
             LabraError::InvalidAppId => write!(f, "Invalid app_id"),
-            LabraError::ClientError { errcode, ref errmsg } => write!(f, "Client error code: {}, message: {}", errcode, errmsg),
+            LabraError::ClientError(ref err) => err.fmt(f),
             LabraError::IOError(ref err) => err.fmt(f),
             LabraError::MissingField(ref err) => write!(f, "Client MissingField message: {}", err),
             LabraError::RedundantField(ref err) => write!(f, "Client RedundantField , message: {}", err),
             LabraError::ApiError(ref err) => write!(f, "Client ApiError , message: {}", err),
             LabraError::RequestError(ref err) => write!(f, "Request Error {}", err),
+            LabraError::HttpError(ref err) => write!(f, "Request Error {}", err),
+            LabraError::TransportError(ref err) => write!(f, "Request Error {}", err),
+            LabraError::JsonError(ref err) => write!(f, "Json Error {}", err),
+            LabraError::XmlError(ref err) => write!(f, "Xml Error {}", err),
+            LabraError::RedisError(ref err) => write!(f, "Redis Error {}", err),
+            LabraError::PoolError(ref err) => write!(f, "Redis Pool Error {}", err),
+            LabraError::RateLimited(ref err) => write!(f, "Rate Limited {}", err),
+            LabraError::ConfigError(ref err) => write!(f, "Config Error {}", err),
+            LabraError::CryptoError(ref err) => write!(f, "Crypto Error {}", err),
             LabraError::Unknown => write!(f, "Unknown Error")
         }
     }
 }
 
+#[allow(unused)]
+impl LabraError {
+    /// 平台返回的业务错误
+    pub fn platform_error(&self) -> Option<&PlatformError> {
+        match self {
+            LabraError::ClientError(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// 是否可以重试：平台繁忙类的业务错误，以及超时、连接失败等网络错误
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            LabraError::ClientError(err) => err.retryable,
+            LabraError::HttpError(err) => err.is_connect() || err.is_timeout(),
+            LabraError::TransportError(TransportError::Connect(_)) | LabraError::TransportError(TransportError::Timeout(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// 是否为授权失效类错误，需要重新获取凭证
+    pub fn is_auth_error(&self) -> bool {
+        self.platform_error().map(|v| v.is_auth_error()).unwrap_or(false)
+    }
+
+    /// 使用响应补充平台错误的HTTP状态码与请求ID
+    pub fn with_response(self, response: &LabraResponse) -> Self {
+        match self {
+            LabraError::ClientError(err) => LabraError::ClientError(err.with_response(response)),
+            err => err,
+        }
+    }
+}
+
 #[allow(deprecated, deprecated_in_future)]
 impl std::error::Error for LabraError {
     fn description(&self) -> &str {
         match *self {
             LabraError::InvalidSignature(ref err) => err,
             LabraError::InvalidAppId => "Invalid app_id",
-            LabraError::ClientError { ref errmsg, .. } => errmsg,
+            LabraError::ClientError(ref err) => &err.message,
             LabraError::IOError(ref err) => err.description(),
             LabraError::MissingField(ref err) => err,
             LabraError::RedundantField(ref err) => err,
             LabraError::ApiError(ref err) => err,
             LabraError::RequestError(ref err) => err,
+            LabraError::RateLimited(ref err) => err,
+            LabraError::ConfigError(ref err) => err,
+            LabraError::HttpError(_) | LabraError::TransportError(_) => "Request Error",
+            LabraError::JsonError(_) => "Json Error",
+            LabraError::XmlError(_) => "Xml Error",
+            LabraError::RedisError(_) | LabraError::PoolError(_) => "Redis Error",
+            LabraError::CryptoError(_) => "Crypto Error",
             LabraError::Unknown => "Request Error"
         }
     }
+
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LabraError::IOError(err) => Some(err),
+            LabraError::HttpError(err) => Some(err),
+            LabraError::TransportError(err) => Some(err),
+            LabraError::JsonError(err) => Some(err),
+            LabraError::XmlError(err) => Some(err),
+            LabraError::RedisError(err) => Some(err),
+            LabraError::PoolError(err) => Some(err),
+            LabraError::CryptoError(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<PlatformError> for LabraError {
+    fn from(err: PlatformError) -> Self {
+        LabraError::ClientError(err)
+    }
 }
 
 impl From<reqwest::Error> for LabraError {
-    fn from(_err: reqwest::Error) -> Self {
-        error!("error to request:{:?}", _err);
-        LabraError::RequestError(_err.to_string())
+    fn from(err: reqwest::Error) -> Self {
+        error!("error to request:{:?}", err);
+        LabraError::HttpError(err)
+    }
+}
+
+impl From<TransportError> for LabraError {
+    fn from(err: TransportError) -> Self {
+        error!("error to request:{:?}", err);
+        LabraError::TransportError(err)
     }
 }
 
@@ -70,17 +169,17 @@ impl From<io::Error> for LabraError {
 }
 
 impl From<JsonError> for LabraError {
-    fn from(_err: JsonError) -> Self {
-        error!("error to parse json:{:?}", _err);
-        LabraError::RedundantField(_err.to_string())
+    fn from(err: JsonError) -> Self {
+        error!("error to parse json:{:?}", err);
+        LabraError::JsonError(err)
     }
 }
 
 
 impl From<serde_xml_rs::Error> for LabraError {
-    fn from(_err: serde_xml_rs::Error) -> Self {
-        error!("error to parse xml:{:?}", _err);
-        LabraError::RedundantField(_err.to_string())
+    fn from(err: serde_xml_rs::Error) -> Self {
+        error!("error to parse xml:{:?}", err);
+        LabraError::XmlError(err)
     }
 }
 
@@ -95,59 +194,59 @@ impl From<openssl::error::ErrorStack> for LabraError {
 #[cfg(not(feature = "openssl-crypto"))]
 impl From<block_modes::InvalidKeyIvLength> for LabraError {
     fn from(err: block_modes::InvalidKeyIvLength) -> Self {
-        LabraError::InvalidSignature(format!("加解密出错：{}", err.to_string()))
+        LabraError::InvalidSignature(format!("加解密出错：{}", err))
     }
 }
 
 #[cfg(not(feature = "openssl-crypto"))]
 impl From<block_modes::BlockModeError> for LabraError {
     fn from(err: block_modes::BlockModeError) -> Self {
-        LabraError::InvalidSignature(format!("加解密出错：{}", err.to_string()))
+        LabraError::InvalidSignature(format!("加解密出错：{}", err))
     }
 }
 
 #[cfg(not(feature = "openssl-crypto"))]
 impl From<hmac::digest::InvalidLength> for LabraError {
     fn from(err: hmac::digest::InvalidLength) -> Self {
-        LabraError::InvalidSignature(format!("加解密出错：{}", err.to_string()))
+        LabraError::InvalidSignature(format!("加解密出错：{}", err))
     }
 }
 
 #[cfg(not(feature = "openssl-crypto"))]
 impl From<aes_gcm::Error> for LabraError {
     fn from(err: aes_gcm::Error) -> Self {
-        LabraError::InvalidSignature(format!("加解密出错：{}", err.to_string()))
+        LabraError::InvalidSignature(format!("加解密出错：{}", err))
     }
 }
 
 impl From<FromUtf8Error> for LabraError {
     fn from(err: FromUtf8Error) -> Self {
-        LabraError::InvalidSignature(format!("字符转换错误：{}", err.to_string()))
+        LabraError::InvalidSignature(format!("字符转换错误：{}", err))
     }
 }
 
 
 impl From<InvalidHeaderValue> for LabraError {
     fn from(err: InvalidHeaderValue) -> Self {
-        LabraError::RequestError(format!("请求头转换出错：{}", err.to_string()))
+        LabraError::RequestError(format!("请求头转换出错：{}", err))
     }
 }
 
 impl From<hex::FromHexError> for LabraError {
     fn from(err: hex::FromHexError) -> Self {
-        LabraError::InvalidSignature(format!("字符转码出错：{}", err.to_string()))
+        LabraError::InvalidSignature(format!("字符转码出错：{}", err))
     }
 }
 
 impl From<serde_urlencoded::ser::Error> for LabraError {
     fn from(err: serde_urlencoded::ser::Error) -> Self {
-        LabraError::InvalidSignature(format!("URL转码：{}", err.to_string()))
+        LabraError::InvalidSignature(format!("URL转码：{}", err))
     }
 }
 
 impl From<serde_urlencoded::de::Error> for LabraError {
     fn from(err: serde_urlencoded::de::Error) -> Self {
-        LabraError::InvalidSignature(format!("URL转码：{}", err.to_string()))
+        LabraError::InvalidSignature(format!("URL转码：{}", err))
     }
 }
 
@@ -161,39 +260,39 @@ impl From<serde_urlencoded::de::Error> for LabraError {
 
 impl From<DecodeError> for LabraError {
     fn from(err: DecodeError) -> Self {
-        LabraError::InvalidSignature(format!("字符编码出错：{}", err.to_string()))
+        LabraError::InvalidSignature(format!("字符编码出错：{}", err))
     }
 }
 impl From<r2d2::Error> for LabraError {
     fn from(err: r2d2::Error) -> Self {
-        LabraError::RequestError(format!("redis连接错误：{}", err.to_string()))
+        LabraError::PoolError(err)
     }
 }
 
 impl From<RedisError> for LabraError {
     fn from(err: RedisError) -> Self {
-        LabraError::RequestError(format!("redis错误：{}", err.to_string()))
+        LabraError::RedisError(err)
     }
 }
 
 
 impl From<rsa::pkcs8::Error> for LabraError {
     fn from(err: rsa::pkcs8::Error) -> Self {
-        LabraError::RequestError(err.to_string())
+        LabraError::CryptoError(Box::new(err))
     }
 }
 
 
 impl From<rsa::pkcs1::Error> for LabraError {
     fn from(err: rsa::pkcs1::Error) -> Self {
-        LabraError::RequestError(err.to_string())
+        LabraError::CryptoError(Box::new(err))
     }
 }
 
 
 impl From<rsa::pkcs8::spki::Error> for LabraError {
     fn from(err: rsa::pkcs8::spki::Error) -> Self {
-        LabraError::RequestError(err.to_string())
+        LabraError::CryptoError(Box::new(err))
     }
 }
 
@@ -207,22 +306,351 @@ impl From<Infallible> for LabraError {
 
 impl From<rsa::errors::Error> for LabraError {
     fn from(err: rsa::errors::Error) -> Self {
-        LabraError::RequestError(err.to_string())
+        LabraError::CryptoError(Box::new(err))
     }
 }
 impl From<x509_parser::nom::Err<x509_parser::prelude::PEMError>> for LabraError {
     fn from(err: x509_parser::nom::Err<x509_parser::prelude::PEMError>) -> Self {
-        LabraError::RequestError(err.to_string())
+        LabraError::CryptoError(Box::new(err))
     }
 }
 
 impl From<x509_parser::nom::Err<x509_parser::prelude::X509Error>> for LabraError {
     fn from(err: x509_parser::nom::Err<x509_parser::prelude::X509Error>) -> Self {
-        LabraError::RequestError(err.to_string())
+        LabraError::CryptoError(Box::new(err))
     }
 }
 impl From<SerializeError> for LabraError {
     fn from(err: SerializeError) -> Self {
-        LabraError::RequestError(err.to_string())
+        LabraError::CryptoError(Box::new(err))
+    }
+}
+/// 错误来源的平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Platform {
+    Wechat,
+    WechatPay,
+    Alipay,
+    Taobao,
+    Jd,
+    Pdd,
+    Qiniu,
+}
+
+impl Platform {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Platform::Wechat => "wechat",
+            Platform::WechatPay => "wechat_pay",
+            Platform::Alipay => "alipay",
+            Platform::Taobao => "taobao",
+            Platform::Jd => "jd",
+            Platform::Pdd => "pdd",
+            Platform::Qiniu => "qiniu",
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 平台返回的业务错误
+///
+/// `code`为平台的数字错误码（微信的`errcode`、支付宝与淘宝的`code`等），无法转换为数字时为`-1`；
+/// `sub_code`为明细错误码，如支付宝的`ACQ.TRADE_NOT_EXIST`、微信支付的`SYSTEMERROR`。
+/// `retryable`根据已知错误码推断，可通过`retryable()`覆盖。
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlatformError {
+    pub platform: Platform,
+    pub code: i64,
+    pub sub_code: Option<String>,
+    pub message: String,
+    pub request_id: Option<String>,
+    pub http_status: Option<u16>,
+    pub retryable: bool,
+}
+
+#[allow(unused)]
+impl PlatformError {
+    pub fn new<S: Into<String>>(platform: Platform, code: i64, message: S) -> Self {
+        Self {
+            platform,
+            code,
+            sub_code: None,
+            message: message.into(),
+            request_id: None,
+            http_status: None,
+            retryable: is_retryable_code(platform, code, None),
+        }
+    }
+
+    /// 由字符串错误码创建，无法转换为数字时错误码为`-1`
+    pub fn from_code_str<S: Into<String>>(platform: Platform, code: &str, message: S) -> Self {
+        Self::new(platform, code.trim().parse::<i64>().unwrap_or(-1), message)
+    }
+
+    pub fn sub_code<S: Into<String>>(mut self, sub_code: S) -> Self {
+        let sub_code = sub_code.into();
+        if !sub_code.is_empty() {
+            self.retryable = is_retryable_code(self.platform, self.code, Some(&sub_code));
+            self.sub_code = sub_code.into();
+        }
+        self
     }
-}
\ No newline at end of file
+
+    pub fn request_id<S: Into<String>>(mut self, request_id: S) -> Self {
+        let request_id = request_id.into();
+        if !request_id.is_empty() {
+            self.request_id = request_id.into();
+        }
+        self
+    }
+
+    pub fn http_status(mut self, http_status: u16) -> Self {
+        self.http_status = http_status.into();
+        self
+    }
+
+    pub fn retryable(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// 微信的业务错误，错误信息中带有`rid`时作为请求ID
+    pub fn wechat<S: Into<String>>(code: i64, message: S) -> Self {
+        let message = message.into();
+        let request_id = message.split("rid:").nth(1)
+            .and_then(|v| v.split_whitespace().next())
+            .unwrap_or_default()
+            .to_string();
+        Self::new(Platform::Wechat, code, message).request_id(request_id)
+    }
+
+    /// 使用响应补充HTTP状态码，错误中没有请求ID时从响应头读取
+    pub fn with_response(mut self, response: &LabraResponse) -> Self {
+        self.http_status = response.status().as_u16().into();
+        if self.request_id.is_none() {
+            self.request_id = response.request_id();
+        }
+        self
+    }
+
+    /// 微信的已知错误码
+    pub fn wechat_code(&self) -> Option<WechatErrorCode> {
+        match self.platform {
+            Platform::Wechat => WechatErrorCode::from_code(self.code),
+            _ => None,
+        }
+    }
+
+    /// 支付宝的已知错误码
+    pub fn alipay_code(&self) -> Option<AlipayErrorCode> {
+        match self.platform {
+            Platform::Alipay => AlipayErrorCode::from_code(self.code),
+            _ => None,
+        }
+    }
+
+    /// 是否为授权失效类错误，需要重新获取凭证
+    pub fn is_auth_error(&self) -> bool {
+        match self.platform {
+            Platform::Wechat => self.wechat_code().map(|v| v.is_invalid_token()).unwrap_or(false),
+            Platform::Alipay => self.alipay_code() == Some(AlipayErrorCode::InsufficientAuth)
+                || self.sub_code.as_deref().map(|v| v.starts_with("aop.invalid-auth-token") || v.starts_with("aop.auth-token-time-out")).unwrap_or(false),
+            Platform::Taobao => self.code == 27 || self.sub_code.as_deref().map(|v| v.starts_with("invalid-sessionkey")).unwrap_or(false),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for PlatformError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Client error platform: {}, code: {}", self.platform, self.code)?;
+        if let Some(sub_code) = self.sub_code.as_ref() {
+            write!(f, ", sub_code: {}", sub_code)?;
+        }
+        write!(f, ", message: {}", self.message)?;
+        if let Some(request_id) = self.request_id.as_ref() {
+            write!(f, ", request_id: {}", request_id)?;
+        }
+        Ok(())
+    }
+}
+
+fn is_retryable_code(platform: Platform, code: i64, sub_code: Option<&str>) -> bool {
+    match platform {
+        Platform::Wechat => WechatErrorCode::from_code(code).map(|v| v.is_retryable()).unwrap_or(false),
+        Platform::WechatPay => matches!(sub_code, Some("SYSTEMERROR") | Some("BANKERROR") | Some("FREQUENCY_LIMITED")),
+        Platform::Alipay => AlipayErrorCode::from_code(code).map(|v| v.is_retryable()).unwrap_or(false)
+            || matches!(sub_code, Some("ACQ.SYSTEM_ERROR") | Some("aop.ACQ.SYSTEM_ERROR") | Some("isp.unknow-error")),
+        Platform::Taobao => sub_code.map(|v| v.starts_with("isp.")).unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// 微信常见错误码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WechatErrorCode {
+    /// 系统繁忙
+    SystemBusy,
+    /// 获取access_token时AppSecret错误，或者access_token无效
+    InvalidCredential,
+    /// 不合法的AppID
+    InvalidAppId,
+    /// 不合法的access_token
+    InvalidAccessToken,
+    /// 不合法的oauth_code
+    InvalidOauthCode,
+    /// 调用接口的IP地址不在白名单中
+    InvalidIp,
+    /// access_token超时
+    AccessTokenExpired,
+    /// 接口调用超过限制
+    ApiFreqOutOfLimit,
+    /// 调用分钟频率受限
+    ApiMinuteQuotaReachLimit,
+    /// api功能未授权
+    ApiUnauthorized,
+    /// 用户未授权该api
+    UserUnauthorized,
+}
+
+impl WechatErrorCode {
+    pub fn from_code(code: i64) -> Option<Self> {
+        let v = match code {
+            -1 => WechatErrorCode::SystemBusy,
+            40001 => WechatErrorCode::InvalidCredential,
+            40013 => WechatErrorCode::InvalidAppId,
+            40014 => WechatErrorCode::InvalidAccessToken,
+            40029 => WechatErrorCode::InvalidOauthCode,
+            40164 => WechatErrorCode::InvalidIp,
+            42001 => WechatErrorCode::AccessTokenExpired,
+            45009 => WechatErrorCode::ApiFreqOutOfLimit,
+            45011 => WechatErrorCode::ApiMinuteQuotaReachLimit,
+            48001 => WechatErrorCode::ApiUnauthorized,
+            50001 => WechatErrorCode::UserUnauthorized,
+            _ => return None,
+        };
+        Some(v)
+    }
+
+    pub fn code(&self) -> i64 {
+        match self {
+            WechatErrorCode::SystemBusy => -1,
+            WechatErrorCode::InvalidCredential => 40001,
+            WechatErrorCode::InvalidAppId => 40013,
+            WechatErrorCode::InvalidAccessToken => 40014,
+            WechatErrorCode::InvalidOauthCode => 40029,
+            WechatErrorCode::InvalidIp => 40164,
+            WechatErrorCode::AccessTokenExpired => 42001,
+            WechatErrorCode::ApiFreqOutOfLimit => 45009,
+            WechatErrorCode::ApiMinuteQuotaReachLimit => 45011,
+            WechatErrorCode::ApiUnauthorized => 48001,
+            WechatErrorCode::UserUnauthorized => 50001,
+        }
+    }
+
+    /// access_token无效或过期，需要强制刷新
+    pub fn is_invalid_token(&self) -> bool {
+        matches!(self, WechatErrorCode::InvalidCredential | WechatErrorCode::InvalidAccessToken | WechatErrorCode::AccessTokenExpired)
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, WechatErrorCode::SystemBusy)
+    }
+}
+
+/// 支付宝公共错误码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlipayErrorCode {
+    /// 接口调用成功
+    Success,
+    /// 服务不可用
+    ServiceUnavailable,
+    /// 授权权限不足
+    InsufficientAuth,
+    /// 缺少必选参数
+    MissingParameter,
+    /// 非法的参数
+    InvalidParameter,
+    /// 业务处理失败
+    BusinessFailed,
+    /// 权限不足
+    InsufficientPermission,
+}
+
+impl AlipayErrorCode {
+    pub fn from_code(code: i64) -> Option<Self> {
+        let v = match code {
+            10000 => AlipayErrorCode::Success,
+            20000 => AlipayErrorCode::ServiceUnavailable,
+            20001 => AlipayErrorCode::InsufficientAuth,
+            40001 => AlipayErrorCode::MissingParameter,
+            40002 => AlipayErrorCode::InvalidParameter,
+            40004 => AlipayErrorCode::BusinessFailed,
+            40006 => AlipayErrorCode::InsufficientPermission,
+            _ => return None,
+        };
+        Some(v)
+    }
+
+    pub fn code(&self) -> i64 {
+        match self {
+            AlipayErrorCode::Success => 10000,
+            AlipayErrorCode::ServiceUnavailable => 20000,
+            AlipayErrorCode::InsufficientAuth => 20001,
+            AlipayErrorCode::MissingParameter => 40001,
+            AlipayErrorCode::InvalidParameter => 40002,
+            AlipayErrorCode::BusinessFailed => 40004,
+            AlipayErrorCode::InsufficientPermission => 40006,
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, AlipayErrorCode::ServiceUnavailable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use bytes::Bytes;
+    use reqwest::{StatusCode, Url};
+    use reqwest::header::HeaderMap;
+    use crate::LabraResponse;
+    use super::{AlipayErrorCode, LabraError, Platform, PlatformError, WechatErrorCode};
+
+    #[test]
+    fn test_platform_error() {
+        let err = LabraError::from(PlatformError::wechat(42001, "access_token expired"));
+        assert!(err.is_auth_error());
+        assert!(!err.is_retryable());
+        assert_eq!(Some(WechatErrorCode::AccessTokenExpired), err.platform_error().and_then(|v| v.wechat_code()));
+
+        let err = PlatformError::from_code_str(Platform::Alipay, "20000", "Service Currently Unavailable").sub_code("isp.unknow-error");
+        assert_eq!(Some(AlipayErrorCode::ServiceUnavailable), err.alipay_code());
+        assert!(err.retryable);
+        let err = PlatformError::from_code_str(Platform::Alipay, "40004", "Business Failed").sub_code("ACQ.TRADE_NOT_EXIST");
+        assert!(!err.retryable);
+        assert_eq!("Client error platform: alipay, code: 40004, sub_code: ACQ.TRADE_NOT_EXIST, message: Business Failed", err.to_string());
+
+        let err = LabraError::from(serde_json::from_str::<serde_json::Value>("{").unwrap_err());
+        assert!(err.source().is_some());
+        let err = LabraError::from(x509_parser::pem::parse_x509_pem(b"not a pem").unwrap_err());
+        assert!(matches!(err, LabraError::CryptoError(_)));
+        assert!(err.source().is_some());
+
+        // 微信错误信息中的rid作为请求ID，状态码与响应头中的请求ID从响应补充
+        let err = PlatformError::wechat(40013, "invalid appid rid: 63f2b0d4-1a2b3c4d-5e6f7a8b");
+        assert_eq!(Some("63f2b0d4-1a2b3c4d-5e6f7a8b"), err.request_id.as_deref());
+        let mut headers = HeaderMap::new();
+        headers.insert("Request-ID", "08F4A8E5B3".parse().unwrap());
+        let response = LabraResponse::new(Url::parse("https://api.mch.weixin.qq.com/v3/pay").unwrap(), StatusCode::BAD_REQUEST, None, headers, Bytes::new());
+        let err = LabraError::from(PlatformError::new(Platform::WechatPay, -1, "参数错误")).with_response(&response);
+        assert_eq!(Some(400), err.platform_error().unwrap().http_status);
+        assert_eq!(Some("08F4A8E5B3"), err.platform_error().unwrap().request_id.as_deref());
+    }
+}
@@ -51,4 +51,8 @@ impl RequestMethod for JDMethod {
             JDMethod::SellingGoodsQuery => String::from("jd_union_open_selling_goods_query_response"),
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        matches!(self, JDMethod::FanGoodsSelect | JDMethod::GoodsInfoQuery | JDMethod::OrderRecentQuery | JDMethod::OrderRawQuery | JDMethod::SellingGoodsQuery)
+    }
 }
\ No newline at end of file
@@ -1,5 +1,7 @@
+use std::fmt;
 use chrono::Local;
-use crate::{client::{APIClient}, request::{RequestType, Method, LabraRequest, RequestMethod}, session::{SessionStore, SimpleStorage}, LabradorResult, RequestParametersHolder, md5};
+use crate::{client::{APIClient}, request::{LabraRequest, RequestMethod}, session::{AsyncSessionStore, SimpleStorage}, LabradorResult, RequestParametersHolder, md5, LabraHttpClient, LabraHttpConfig, HttpTransport, Middleware, LabraLogConfig, RetryPolicy, RateLimiter, Page, Pagination, paginate_pages};
+use crate::config::{Proxy, Secret};
 use crate::jd::constants::{RESPONSE_GETRESULT, RESPONSE_QUERYRESULT, SIGN_TYPE_MD5, VERSION_1};
 
 mod method;
@@ -9,16 +11,45 @@ mod response;
 mod constants;
 
 use std::collections::BTreeMap;
-use serde::Serialize;
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
 pub use request::*;
 pub use response::*;
 use crate::jd::method::JDMethod;
 
 #[derive(Debug, Clone)]
-pub struct JDClient <T: SessionStore> {
+pub struct JDClient <T: AsyncSessionStore> {
     api_client: APIClient<T>,
 }
 
+/// 京东联盟配置
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct JDConfig {
+    pub app_key: String,
+    pub secret: String,
+    /// 接口地址，用于测试环境或本地模拟服务
+    pub api_path: Option<String>,
+    /// 代理地址，如：http://127.0.0.1:8080
+    pub proxy: Option<String>,
+}
+
+impl fmt::Debug for JDConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JDConfig")
+            .field("app_key", &self.app_key)
+            .field("secret", &Secret(&self.secret))
+            .field("api_path", &self.api_path)
+            .field("proxy", &Proxy::option(&self.proxy))
+            .finish()
+    }
+}
+
+impl JDConfig {
+    pub fn new<S: Into<String>>(app_key: S, secret: S) -> Self {
+        Self { app_key: app_key.into(), secret: secret.into(), api_path: None, proxy: None }
+    }
+}
+
 
 pub trait JDRequest {
 
@@ -71,19 +102,92 @@ pub trait JDRequest {
 /// ```
 /// 
 #[allow(unused)]
-impl <T: SessionStore> JDClient<T> {
+impl <T: AsyncSessionStore> JDClient<T> {
 
     pub fn new<Q: Into<String>, S: Into<String>>(app_key: Q, secret: S) -> JDClient<SimpleStorage> {
         JDClient {
-            api_client: APIClient::<SimpleStorage>::new::<Q, String, S>(app_key, secret, "https://api.jd.com/routerjson".to_owned()),
+            api_client: APIClient::<SimpleStorage>::new::<Q, String, S>(app_key, secret, "https://api.jd.com/routerjson".to_owned()).platform("jd"),
         }
     }
 
 
     pub fn from_session<Q: Into<String>, S: Into<String>>(app_key: Q, secret: S, session: T) -> JDClient<T> {
         JDClient {
-            api_client: APIClient::from_session(app_key, secret, String::from("https://api.jd.com/routerjson"), session),
+            api_client: APIClient::from_session(app_key, secret, String::from("https://api.jd.com/routerjson"), session).platform("jd"),
+        }
+    }
+
+    /// 根据配置创建客户端
+    pub fn from_config(config: &JDConfig, session: T) -> LabradorResult<JDClient<T>> {
+        let mut client = Self::from_session(config.app_key.as_str(), config.secret.as_str(), session);
+        if let Some(api_path) = config.api_path.as_ref() {
+            client = client.api_path(api_path.as_str());
+        }
+        if let Some(proxy) = config.proxy.as_ref() {
+            client = client.proxy(proxy)?;
         }
+        Ok(client)
+    }
+
+    /// 设置HTTP客户端，多个客户端可共用同一个连接池
+    pub fn http_client(mut self, http_client: LabraHttpClient) -> Self {
+        self.api_client = self.api_client.http_client(http_client);
+        self
+    }
+
+    /// 根据配置生成独立的HTTP客户端，可设置代理、超时等
+    pub fn http_config(mut self, config: LabraHttpConfig) -> LabradorResult<Self> {
+        self.api_client = self.api_client.http_config(config)?;
+        Ok(self)
+    }
+
+    /// 设置代理，如：http://127.0.0.1:8080，开启`socks`特性后支持socks5
+    pub fn proxy(mut self, proxy: &str) -> LabradorResult<Self> {
+        self.api_client = self.api_client.proxy(proxy)?;
+        Ok(self)
+    }
+
+    /// 设置接口地址，可指向测试环境或本地的模拟服务
+    pub fn api_path<S: Into<String>>(mut self, api_path: S) -> Self {
+        self.api_client = self.api_client.api_path(api_path);
+        self
+    }
+
+    /// 设置传输层，测试时可替换为`MockTransport`
+    pub fn transport<H: HttpTransport + 'static>(mut self, transport: H) -> Self {
+        self.api_client = self.api_client.transport(transport);
+        self
+    }
+
+    /// 同步客户端使用默认的传输层时改用专用的连接池
+    #[cfg(feature = "blocking")]
+    pub(crate) fn blocking_transport(mut self) -> Self {
+        self.api_client = self.api_client.blocking_transport();
+        self
+    }
+
+    /// 添加中间件
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.api_client = self.api_client.middleware(middleware);
+        self
+    }
+
+    /// 设置请求日志配置
+    pub fn log_config(mut self, log_config: LabraLogConfig) -> Self {
+        self.api_client = self.api_client.log_config(log_config);
+        self
+    }
+
+    /// 设置重试策略，商品和订单查询接口任何失败都可以重试；推广链接生成接口只会在连接阶段失败时重试
+    pub fn retry_policy<P: RetryPolicy + 'static>(mut self, retry_policy: P) -> Self {
+        self.api_client = self.api_client.retry_policy(retry_policy);
+        self
+    }
+
+    /// 设置限流器，按接口方法限流并统计每日调用次数
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.api_client = self.api_client.rate_limiter(rate_limiter);
+        self
     }
 
     /// 签名
@@ -137,9 +241,11 @@ impl <T: SessionStore> JDClient<T> {
         let holder = self.get_request_holder_with_sign(&request)?;
         let url = self.get_request_url(&holder)?;
         let data = holder.get_sorted_map();
-        let req = LabraRequest::new().url(url).method(Method::Post).form(data).req_type(RequestType::Form);
-        let result = self.api_client.request(req).await?.text()?;
-        JDResponse::parse(&result, method)
+        let req = LabraRequest::post_form(url, data).api_method(method.get_name()).idempotent(method.is_idempotent());
+        let response = self.api_client.request(req).await?;
+        let mut result = JDResponse::parse(&response.text()?, method).map_err(|err| err.with_response(&response))?;
+        result.http_status = response.status().as_u16().into();
+        Ok(result)
     }
 
     /// 京粉精选商品查询
@@ -315,6 +421,23 @@ impl <T: SessionStore> JDClient<T> {
         self.excute(JdOrderRawRequest { order_req: request}).await?.get_biz_model::<JdCommonResponse<Vec<JdOrderQueryResponse>>>(RESPONSE_QUERYRESULT.into())
     }
 
+    /// 逐条获取订单行，从`page_index`（默认1）开始按页码翻页直到`has_more`为false
+    ///
+    /// `pagination`可设置每页条数（上限500）与同时请求的页数。
+    pub fn query_raw_order_stream(&self, request: JdOrderRawQueryParam, pagination: Pagination) -> impl Stream<Item = LabradorResult<JdOrderQueryResponse>> + '_ {
+        let page_size = pagination.page_size.or(request.page_size).unwrap_or(20);
+        paginate_pages(request.page_index.unwrap_or(1), pagination.concurrency, move |page_index| {
+            let request = JdOrderRawQueryParam { page_index: page_index.into(), page_size: page_size.into(), ..request.clone() };
+            async move {
+                let res = self.query_raw_order(request).await?;
+                let has_more = res.has_more.unwrap_or_default();
+                let items = res.data.unwrap_or_default();
+                let next = Some(page_index + 1).filter(|_| has_more && !items.is_empty());
+                Ok(Page::new(items, next))
+            }
+        })
+    }
+
     /// 转链获取接口
     ///
     /// 转链获取，支持工具商
@@ -565,4 +688,41 @@ mod tests {
         rt.block_on(r);
     }
 
+    #[tokio::test]
+    async fn test_business_error() {
+        let transport = crate::MockTransport::new();
+        transport.push_response(crate::MockResponse::new(200, r#"{"jd_union_open_goods_jingfen_query_responce":{"code":"0","queryResult":"{\"code\":403,\"message\":\"无访问权限\",\"requestId\":\"o_0b1a2c3d\"}"}}"#));
+        let client = JDClient::<SimpleStorage>::new("appkey", "secret").transport(transport);
+        let param = JdJFGoodsParam { elite_id: 1, page_index: None, page_size: None, sort_name: None, sort: None, pid: None, fields: None, forbid_types: None };
+        let err = client.get_jf_select(param).await.unwrap_err();
+        let err = err.platform_error().unwrap();
+        assert_eq!(403, err.code);
+        assert_eq!(Some("o_0b1a2c3d"), err.request_id.as_deref());
+        assert_eq!(Some(200), err.http_status);
+    }
+
+    #[tokio::test]
+    async fn test_query_raw_order_stream() {
+        use futures_util::StreamExt;
+        use crate::{JdOrderQueryResponse, LabradorResult, Pagination};
+        let transport = crate::MockTransport::new();
+        transport.push_response(crate::MockResponse::new(200, r#"{"jd_union_open_order_row_query_responce":{"code":"0","queryResult":"{\"code\":200,\"message\":\"success\",\"hasMore\":true,\"data\":[{\"id\":\"1\"},{\"id\":\"2\"}]}"}}"#))
+            .push_response(crate::MockResponse::new(200, r#"{"jd_union_open_order_row_query_responce":{"code":"0","queryResult":"{\"code\":200,\"message\":\"success\",\"hasMore\":false,\"data\":[{\"id\":\"3\"}]}"}}"#));
+        let client = JDClient::<SimpleStorage>::new("appkey", "secret").transport(transport.clone());
+        let param = JdOrderRawQueryParam { page_index: None, page_size: None, bill_type: 1, start_time: "2024-01-01 00:00:00".to_string(), end_time: "2024-01-01 01:00:00".to_string(), child_union_id: None, key: None, fields: None };
+        let orders = client.query_raw_order_stream(param, Pagination::new().page_size(2)).collect::<Vec<LabradorResult<JdOrderQueryResponse>>>().await;
+        assert_eq!(3, orders.into_iter().filter(|v| v.is_ok()).count());
+        // hasMore为false时结束，页码从1开始递增
+        let requests = transport.requests();
+        assert_eq!(2, requests.len());
+        let page = |index: usize| {
+            let form = serde_urlencoded::from_str::<Vec<(String, String)>>(&requests[index].body_text()).unwrap();
+            let param = form.into_iter().find(|(k, _)| k == "360buy_param_json").unwrap().1;
+            let param = serde_json::from_str::<Value>(&param).unwrap();
+            (param["orderReq"]["pageIndex"].as_u64().unwrap(), param["orderReq"]["pageSize"].as_u64().unwrap())
+        };
+        assert_eq!((1, 2), page(0));
+        assert_eq!((2, 2), page(1));
+    }
+
 }
@@ -159,7 +159,7 @@ impl JDRequest for JdOrderRequest {
 //----------------------------------------------------------------------------------------------------------------------------
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JdOrderRawQueryParam {
     /// 页码
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use serde::de::DeserializeOwned;
 use serde_json::{Value as JsonValue};
 
-use crate::{errors::LabraError, LabradorResult, request::Response, RequestMethod};
+use crate::{errors::{LabraError, Platform, PlatformError}, LabradorResult, request::Response, RequestMethod};
 use crate::jd::constants::ERROR_RESPONSE_KEY;
 
 
@@ -14,6 +14,9 @@ pub struct JDResponse {
     pub code: Option<String>,
     pub message: Option<String>,
     pub body: Option<String>,
+    /// 响应的HTTP状态码
+    #[serde(skip)]
+    pub http_status: Option<u16>,
 }
 
 
@@ -23,6 +26,7 @@ impl JDResponse {
             code: Some("0".to_string()),
             message: None,
             body: None,
+            http_status: None,
         }
     }
 
@@ -32,7 +36,7 @@ impl JDResponse {
         let err= &v[ERROR_RESPONSE_KEY];
         if !err.is_null() {
             let resp = serde_json::from_str::<Self>(&err.to_string()).unwrap_or(JDResponse::new());
-            Err(LabraError::ClientError {errcode: resp.code.to_owned().unwrap_or_default().to_string(), errmsg: resp.message.to_owned().unwrap_or_default()})
+            Err(PlatformError::from_code_str(Platform::Jd, &resp.code.to_owned().unwrap_or_default(), resp.message.to_owned().unwrap_or_default()).into())
         } else {
             let response = &v[&method.get_response_key()];
             if !response.is_null() {
@@ -58,17 +62,33 @@ impl JDResponse {
             if let Some(key) = key {
                 let v = serde_json::from_str::<JsonValue>(&self.body.to_owned().unwrap_or_default())?;
                 let result = &v[key];
-                if result.is_string() {
-                    serde_json::from_str::<T>(result.as_str().unwrap_or_default()).map_err(LabraError::from)
+                let result = if result.is_string() {
+                    serde_json::from_str::<JsonValue>(result.as_str().unwrap_or_default())?
                 } else {
-                    serde_json::from_value::<T>(v[key].to_owned()).map_err(LabraError::from)
-                }
+                    result.to_owned()
+                };
+                self.check_biz_result(&result)?;
+                serde_json::from_value::<T>(result).map_err(LabraError::from)
             } else {
                 serde_json::from_str::<T>(&self.body.to_owned().unwrap_or_default()).map_err(LabraError::from)
             }
         } else {
-            Err(LabraError::ClientError { errcode: self.code.to_owned().unwrap_or_default().to_string(), errmsg: self.message.to_owned().unwrap_or_default() })
+            let mut err = PlatformError::from_code_str(Platform::Jd, &self.code.to_owned().unwrap_or_default(), self.message.to_owned().unwrap_or_default());
+            err.http_status = self.http_status;
+            Err(err.into())
+        }
+    }
+
+    /// 业务结果中的`code`不是200且没有返回数据时转换为错误，`requestId`作为请求ID
+    fn check_biz_result(&self, result: &JsonValue) -> LabradorResult<()> {
+        let code = result["code"].as_i64().unwrap_or(200);
+        if code == 200 || !result["data"].is_null() {
+            return Ok(());
         }
+        let mut err = PlatformError::new(Platform::Jd, code, result["message"].as_str().unwrap_or_default())
+            .request_id(result["requestId"].as_str().unwrap_or_default());
+        err.http_status = self.http_status;
+        Err(err.into())
     }
 
 }
@@ -18,6 +18,11 @@
 //! *   ```pdd``` - Pinduoduo related services
 //! *   ```jd``` - Jingdong related services
 //! *   ```wechat``` - Wechat related services
+//! *   ```file-store``` - File-backed persistent session storage
+//! *   ```blocking``` - Synchronous clients
+//! *   ```config``` - TOML/YAML configuration files
+//! *   ```metrics``` - Request, token and certificate metrics via the `metrics` facade
+//! *   ```socks``` - SOCKS5 proxy support
 //!
 //! ## Installation
 //!
@@ -34,13 +39,15 @@
 //!
 //! ### With Wechat（微信开放平台、包含微信支付）
 //!
-//!  ```rust
+//!  ```no_run
+//! # #[cfg(feature = "wechat")]
 //! use labrador::{WechatPayClient, SimpleStorage, TradeType, WechatPayRequestV3, Amount, Payer};
 //! use chrono::{Local, SecondsFormat};
 //!
+//! # #[cfg(feature = "wechat")]
 //!  #[tokio::main]
 //!  async fn main() {
-//!      let c =  WechatPayClient::new("appid", "secret");
+//!      let c =  WechatPayClient::<SimpleStorage>::new("appid", "secret");
 //!      let mut client =c.wxpay();
 //!      let date = Local::now().to_rfc3339_opts(SecondsFormat::Secs, false);
 //!      let result = client.unified_order_v3(TradeType::Jsapi, WechatPayRequestV3 {
@@ -69,33 +76,37 @@
 //!          Err(err) => {}
 //!      }
 //!  }
+//! # #[cfg(not(feature = "wechat"))]
+//! # fn main() {}
 //!  ```
 //!
 //! ### With Alipay（支付宝）
 //!
-//!  ```rust
-//! use labrador::{AlipayTradeWapPayRequest, AlipayClient};
+//!  ```no_run
+//! # #[cfg(feature = "alipay")]
+//! use labrador::{AlipayTradeWapPayRequest, AlipayClient, SimpleStorage};
 //!
+//! # #[cfg(feature = "alipay")]
 //!  #[tokio::main]
 //!  async fn main() {
 //!      let param = AlipayTradeWapPayRequest::default();
-//!      let client = AlipayClient::new("appKey", false);
-//!      match client.wap_pay("POST".into(), param).await {
-//!          Ok(res) => {}
-//!          Err(err) => {}
-//!      }
-//!      match result.await {
+//!      let client = AlipayClient::<SimpleStorage>::new("appKey", false);
+//!      match client.wap_pay("POST".into(), param) {
 //!          Ok(res) => {}
 //!          Err(err) => {}
 //!      }
 //!  }
+//! # #[cfg(not(feature = "alipay"))]
+//! # fn main() {}
 //!  ```
 //!
 //! ### With Taobao（淘宝客相关）
 //!
-//!  ```rust
+//!  ```no_run
+//! # #[cfg(feature = "taobao")]
 //! use labrador::{TbItemDetailRequest, TaobaoClient, SimpleStorage};
 //!
+//! # #[cfg(feature = "taobao")]
 //!  #[tokio::main]
 //!  async fn main() {
 //!      let client =  TaobaoClient::<SimpleStorage>::new("appkey", "secret");
@@ -112,15 +123,19 @@
 //!          }
 //!      }
 //!  }
+//! # #[cfg(not(feature = "taobao"))]
+//! # fn main() {}
 //!  ```
 //!
 //!
 //! ### With JD（京东，目前暂时只支持联盟相关）
 //!
-//!  ```rust
+//!  ```no_run
+//! # #[cfg(feature = "jd")]
 //! use labrador::{JDClient, JdOrderRawQueryParam, SimpleStorage};
 //! use chrono::{Local, SecondsFormat};
 //!
+//! # #[cfg(feature = "jd")]
 //!  #[tokio::main]
 //!  async fn main() {
 //!      let client =  JDClient::<SimpleStorage>::new("appkey", "secert");
@@ -142,6 +157,8 @@
 //!          }
 //!      }
 //!  }
+//! # #[cfg(not(feature = "jd"))]
+//! # fn main() {}
 //!  ```
 //!
 //! ### With Custom Request
@@ -162,6 +179,16 @@ mod session;
 mod request;
 mod errors;
 mod client;
+mod transport;
+mod retry;
+mod middleware;
+mod logging;
+mod ratelimit;
+mod registry;
+mod paginate;
+mod telemetry;
+mod stream;
+mod config;
 mod util;
 #[cfg(feature = "jd")]
 mod jd;
@@ -182,21 +209,34 @@ pub use wechat::*;
 
 pub type LabradorResult<T, E = LabraError> = Result<T, E>;
 
-#[cfg(all(feature = "alipay"))]
+#[cfg(feature = "alipay")]
 mod alipay;
 
-#[cfg(all(feature = "alipay"))]
+#[cfg(feature = "alipay")]
 pub use alipay::*;
 
-#[cfg(all(feature = "qiniu"))]
+#[cfg(feature = "qiniu")]
 mod qiniu;
-#[cfg(all(feature = "qiniu"))]
+#[cfg(feature = "qiniu")]
 pub use qiniu::*;
 
-pub use errors::LabraError;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+pub use errors::{LabraError, Platform, PlatformError, WechatErrorCode, AlipayErrorCode};
 pub use session::*;
 pub use util::*;
 pub use client::APIClient;
+pub use transport::*;
+pub use retry::*;
+pub use middleware::*;
+pub use logging::*;
+pub use ratelimit::*;
+pub use registry::*;
+pub use paginate::*;
+pub use telemetry::*;
+pub use stream::*;
+pub use config::*;
 pub use request::*;
 pub use reqwest::multipart::{Form, Part};
 
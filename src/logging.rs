@@ -0,0 +1,182 @@
+use reqwest::Url;
+use serde_json::Value;
+
+/// 默认脱敏的字段
+pub const DEFAULT_REDACT_KEYS: &[&str] = &[
+    "access_token", "refresh_token", "sign", "paysign", "signature", "secret", "app_secret", "corpsecret",
+    "password", "token", "ticket", "mobile", "phone", "id_card", "idcard", "bank_card", "openid", "unionid",
+    "session_key", "encrypted_data", "js_code", "auth_code",
+];
+
+const MASK: &str = "***";
+
+/// 请求日志配置
+///
+/// 请求完成后在`labra_request`跨度中输出平台、请求方法、脱敏后的地址、状态码、耗时与错误码。
+/// 请求体与响应体默认不输出，开启`log_body`后以debug级别输出脱敏后的内容。
+#[derive(Debug, Clone)]
+pub struct LabraLogConfig {
+    /// 是否输出请求体与响应体
+    pub log_body: bool,
+    /// 需要脱敏的字段，不区分大小写，同时匹配以`_字段`结尾的字段，如`suite_access_token`
+    pub redact_keys: Vec<String>,
+}
+
+impl Default for LabraLogConfig {
+    fn default() -> Self {
+        Self {
+            log_body: false,
+            redact_keys: DEFAULT_REDACT_KEYS.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+}
+
+#[allow(unused)]
+impl LabraLogConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn log_body(mut self, log_body: bool) -> Self {
+        self.log_body = log_body;
+        self
+    }
+
+    pub fn redact_keys(mut self, redact_keys: Vec<String>) -> Self {
+        self.redact_keys = redact_keys;
+        self
+    }
+
+    /// 追加需要脱敏的字段
+    pub fn redact_key(mut self, key: &str) -> Self {
+        self.redact_keys.push(key.to_string());
+        self
+    }
+
+    /// 字段是否需要脱敏
+    pub fn is_sensitive(&self, key: &str) -> bool {
+        let key = key.to_lowercase();
+        self.redact_keys.iter().any(|v| {
+            let v = v.to_lowercase();
+            key == v || key.ends_with(&format!("_{}", v))
+        })
+    }
+
+    /// 脱敏请求地址中的查询参数
+    pub fn redact_url(&self, url: &Url) -> String {
+        if url.query().is_none() {
+            return url.to_string();
+        }
+        let pairs = url.query_pairs()
+            .map(|(k, v)| {
+                let v = if self.is_sensitive(&k) { MASK.to_string() } else { v.to_string() };
+                (k.to_string(), v)
+            })
+            .collect::<Vec<_>>();
+        let mut url = url.clone();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+        url.to_string()
+    }
+
+    /// 脱敏请求体或响应体，支持JSON、XML与表单格式，其他格式原样返回
+    pub fn redact_body(&self, body: &str) -> String {
+        let trimmed = body.trim_start();
+        if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            if let Ok(mut value) = serde_json::from_str::<Value>(body) {
+                self.redact_json(&mut value);
+                return value.to_string();
+            }
+        }
+        if trimmed.starts_with('<') {
+            return self.redact_xml(body);
+        }
+        if body.contains('=') && !body.contains(char::is_whitespace) {
+            if let Ok(pairs) = serde_urlencoded::from_str::<Vec<(String, String)>>(body) {
+                let pairs = pairs.into_iter()
+                    .map(|(k, v)| if self.is_sensitive(&k) { (k, MASK.to_string()) } else { (k, v) })
+                    .collect::<Vec<_>>();
+                return serde_urlencoded::to_string(pairs).unwrap_or_default();
+            }
+        }
+        body.to_string()
+    }
+
+    /// 响应中的错误码，依次读取`errcode`、`code`、`error_code`字段
+    pub fn errcode(body: &[u8]) -> Option<String> {
+        if !body.starts_with(b"{") {
+            return None;
+        }
+        let value = serde_json::from_slice::<Value>(body).ok()?;
+        ["errcode", "code", "error_code"].iter()
+            .filter_map(|k| value.get(*k))
+            .find_map(|v| match v {
+                Value::String(v) => Some(v.to_string()),
+                Value::Number(v) => Some(v.to_string()),
+                _ => None,
+            })
+    }
+
+    fn redact_json(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (k, v) in map.iter_mut() {
+                    if self.is_sensitive(k) && !v.is_object() && !v.is_array() {
+                        *v = Value::String(MASK.to_string());
+                    } else {
+                        self.redact_json(v);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|v| self.redact_json(v)),
+            _ => {}
+        }
+    }
+
+    /// 标签名与JSON字段一样不区分大小写匹配
+    fn redact_xml(&self, body: &str) -> String {
+        let mut result = String::with_capacity(body.len());
+        let mut rest = body;
+        while let Some(start) = rest.find('<') {
+            let end = match rest[start..].find('>') {
+                Some(i) => start + i + 1,
+                None => break,
+            };
+            let tag = &rest[start..end];
+            result.push_str(&rest[..end]);
+            rest = &rest[end..];
+            let name = tag[1..tag.len() - 1].split_whitespace().next().unwrap_or_default();
+            if name.is_empty() || name.starts_with(['/', '!', '?']) || tag.ends_with("/>") || !self.is_sensitive(name) {
+                continue;
+            }
+            // ASCII小写不改变字节偏移
+            let close = format!("</{}>", name.to_ascii_lowercase());
+            if let Some(i) = rest.to_ascii_lowercase().find(&close) {
+                result.push_str(MASK);
+                rest = &rest[i..];
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+    use super::LabraLogConfig;
+
+    #[test]
+    fn test_redact() {
+        let config = LabraLogConfig::new();
+        let url = Url::parse("https://api.weixin.qq.com/cgi-bin/user/info?access_token=abc&lang=zh_CN").unwrap();
+        assert_eq!("https://api.weixin.qq.com/cgi-bin/user/info?access_token=***&lang=zh_CN", config.redact_url(&url));
+        assert_eq!(r#"{"openid":"***","user":{"mobile":"***","name":"labrador"}}"#, config.redact_body(r#"{"openid":"o123","user":{"mobile":"13800000000","name":"labrador"}}"#));
+        assert_eq!("<xml><appid>wx1</appid><sign>***</sign></xml>", config.redact_body("<xml><appid>wx1</appid><sign>ABCDEF</sign></xml>"));
+        assert_eq!("<xml><Sign>***</Sign><SIGN>***</SIGN><pay_sign>***</pay_sign><Design>a</Design></xml>", config.redact_body("<xml><Sign>A</Sign><SIGN><![CDATA[B]]></SIGN><pay_sign>C</pay_sign><Design>a</Design></xml>"));
+        assert_eq!("app_key=k&sign=***", config.redact_body("app_key=k&sign=ABCDEF"));
+        assert!(config.is_sensitive("suite_access_token"));
+        assert!(!config.is_sensitive("design"));
+        assert_eq!(Some("40001".to_string()), LabraLogConfig::errcode(br#"{"errcode":40001,"errmsg":"invalid credential"}"#));
+        assert_eq!(None, LabraLogConfig::errcode(b"<xml></xml>"));
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+use reqwest::header::{HeaderName, HeaderValue};
+
+use crate::{HttpRequest, LabraError, LabraResponse, LabradorResult};
+
+/// 请求中间件
+///
+/// 注册在`APIClient`上，所有平台客户端的请求都会经过中间件。
+/// `before_request`在请求体编码完成后、发送之前按注册顺序调用，重试时不会重复调用；
+/// `after_response`在收到最终响应后按注册顺序调用。任一钩子返回错误都会中断请求。
+///
+/// # Examples
+///
+/// ```no_run
+/// use labrador::{APIClient, HttpRequest, LabradorResult, Middleware, SimpleStorage};
+///
+/// #[derive(Debug)]
+/// struct Audit;
+///
+/// impl Middleware for Audit {
+///     fn before_request(&self, request: &mut HttpRequest) -> LabradorResult<()> {
+///         println!("request: {} {}", request.method, request.url);
+///         Ok(())
+///     }
+/// }
+///
+/// let client = APIClient::<SimpleStorage>::new("appkey", "secret", "https://api.weixin.qq.com").middleware(Audit);
+/// ```
+///
+pub trait Middleware: fmt::Debug + Send + Sync {
+    /// 发送请求之前调用，可以修改请求头、请求体等
+    fn before_request(&self, _request: &mut HttpRequest) -> LabradorResult<()> {
+        Ok(())
+    }
+
+    /// 收到响应之后调用
+    fn after_response(&self, _response: &LabraResponse) -> LabradorResult<()> {
+        Ok(())
+    }
+}
+
+/// 为所有请求添加固定的请求头
+#[derive(Debug, Clone, Default)]
+pub struct HeaderMiddleware {
+    headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+#[allow(unused)]
+impl HeaderMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn header(mut self, key: &str, value: &str) -> LabradorResult<Self> {
+        let key = HeaderName::from_bytes(key.as_bytes()).map_err(|e| LabraError::RequestError(format!("请求头转换出错：{}", e)))?;
+        self.headers.push((key, HeaderValue::from_str(value)?));
+        Ok(self)
+    }
+}
+
+impl Middleware for HeaderMiddleware {
+    fn before_request(&self, request: &mut HttpRequest) -> LabradorResult<()> {
+        for (k, v) in self.headers.iter() {
+            request.headers.insert(k.clone(), v.clone());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::{APIClient, HttpRequest, LabraError, LabraRequest, LabraResponse, LabradorResult, MockResponse, MockTransport, SimpleStorage};
+    use super::{HeaderMiddleware, Middleware};
+
+    #[derive(Debug, Default)]
+    struct Counter {
+        requests: Arc<AtomicUsize>,
+        responses: Arc<AtomicUsize>,
+    }
+
+    impl Middleware for Counter {
+        fn before_request(&self, _request: &mut HttpRequest) -> LabradorResult<()> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn after_response(&self, _response: &LabraResponse) -> LabradorResult<()> {
+            self.responses.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct Reject;
+
+    impl Middleware for Reject {
+        fn before_request(&self, _request: &mut HttpRequest) -> LabradorResult<()> {
+            Err(LabraError::RequestError("rejected".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_chain() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, "ok"));
+        let counter = Counter::default();
+        let (requests, responses) = (counter.requests.clone(), counter.responses.clone());
+        let client = APIClient::<SimpleStorage>::new("appkey", "secret", "https://api.weixin.qq.com")
+            .transport(transport.clone())
+            .middleware(HeaderMiddleware::new().header("X-Request-Source", "labrador").unwrap())
+            .middleware(counter);
+        client.request(LabraRequest::get("/cgi-bin/test")).await.unwrap();
+        assert_eq!(1, requests.load(Ordering::SeqCst));
+        assert_eq!(1, responses.load(Ordering::SeqCst));
+        assert_eq!("labrador", transport.requests()[0].headers["x-request-source"]);
+
+        let client = client.middleware(Reject);
+        assert!(client.request(LabraRequest::get("/cgi-bin/test")).await.is_err());
+        // 中间件拒绝的请求不会发送
+        assert_eq!(1, transport.requests().len());
+    }
+}
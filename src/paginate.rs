@@ -0,0 +1,153 @@
+use std::future::Future;
+use futures_util::future::join_all;
+use futures_util::stream::{self, Stream, StreamExt};
+
+use crate::LabradorResult;
+
+/// 一页数据
+#[derive(Debug, Clone)]
+pub struct Page<T, C> {
+    pub items: Vec<T>,
+    /// 下一页的游标或页码，`None`表示已经是最后一页
+    pub next: Option<C>,
+}
+
+#[allow(unused)]
+impl<T, C> Page<T, C> {
+    pub fn new(items: Vec<T>, next: Option<C>) -> Self {
+        Self { items, next }
+    }
+
+    /// 最后一页
+    pub fn last(items: Vec<T>) -> Self {
+        Self { items, next: None }
+    }
+}
+
+/// 分页配置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pagination {
+    /// 每页条数，不设置时使用请求参数中的值或接口默认值
+    pub page_size: Option<u64>,
+    /// 同时请求的页数，只对按页码分页的接口生效，游标分页只能逐页请求
+    pub concurrency: usize,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            page_size: None,
+            concurrency: 1,
+        }
+    }
+}
+
+#[allow(unused)]
+impl Pagination {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn page_size(mut self, page_size: u64) -> Self {
+        self.page_size = page_size.into();
+        self
+    }
+
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+}
+
+/// 按游标分页，依次请求直到没有下一页，逐条返回数据
+///
+/// 请求出错时返回错误并结束。
+///
+/// # Examples
+///
+/// ```ignore
+/// use futures_util::StreamExt;
+///
+/// let mut openids = client.user().get_followers_stream();
+/// while let Some(openid) = openids.next().await {
+///     let openid = openid?;
+/// }
+/// ```
+///
+pub fn paginate<'a, T, C, F, Fut>(start: Option<C>, fetch: F) -> impl Stream<Item = LabradorResult<T>> + 'a
+    where T: 'a, C: 'a, F: FnMut(Option<C>) -> Fut + 'a, Fut: Future<Output = LabradorResult<Page<T, C>>> + 'a {
+    stream::unfold((Some(start), fetch), |(cursor, mut fetch)| async move {
+        let cursor = cursor?;
+        let (items, next) = match fetch(cursor).await {
+            Ok(page) => (page.items.into_iter().map(Ok).collect::<Vec<_>>(), page.next.map(Some)),
+            Err(err) => (vec![Err(err)], None),
+        };
+        Some((stream::iter(items), (next, fetch)))
+    }).flatten()
+}
+
+/// 按页码分页，每次同时请求`concurrency`页，按页码顺序逐条返回数据
+///
+/// 某一页没有下一页时结束，同一批中后面的页会被丢弃。
+pub fn paginate_pages<'a, T, F, Fut>(start_page: u64, concurrency: usize, fetch: F) -> impl Stream<Item = LabradorResult<T>> + 'a
+    where T: 'a, F: Fn(u64) -> Fut + 'a, Fut: Future<Output = LabradorResult<Page<T, u64>>> + 'a {
+    let concurrency = concurrency.max(1) as u64;
+    stream::unfold((Some(start_page), fetch), move |(page, fetch)| async move {
+        let page = page?;
+        let pages = join_all((page..page + concurrency).map(&fetch)).await;
+        let mut items = Vec::new();
+        let mut next = Some(page + concurrency);
+        for result in pages {
+            match result {
+                Ok(page) => {
+                    items.extend(page.items.into_iter().map(Ok));
+                    if page.next.is_none() {
+                        next = None;
+                        break;
+                    }
+                }
+                Err(err) => {
+                    items.push(Err(err));
+                    next = None;
+                    break;
+                }
+            }
+        }
+        Some((stream::iter(items), (next, fetch)))
+    }).flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use futures_util::StreamExt;
+    use crate::{LabraError, LabradorResult};
+    use super::{paginate, paginate_pages, Page};
+
+    #[tokio::test]
+    async fn test_paginate() {
+        let items = paginate(None, |cursor: Option<String>| async move {
+            Ok(match cursor.as_deref() {
+                None => Page::new(vec![1, 2], Some("next".to_string())),
+                Some("next") => Page::last(vec![3]),
+                _ => unreachable!(),
+            })
+        }).collect::<Vec<LabradorResult<i32>>>().await;
+        assert_eq!(vec![1, 2, 3], items.into_iter().map(|v| v.unwrap()).collect::<Vec<_>>());
+
+        let requests = AtomicU64::new(0);
+        let items = paginate_pages(1, 3, |page| {
+            requests.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if page == 5 {
+                    return Err(LabraError::ApiError("error".to_string()));
+                }
+                Ok(Page::new(vec![page], Some(page + 1)))
+            }
+        }).collect::<Vec<LabradorResult<u64>>>().await;
+        assert_eq!(5, items.len());
+        assert_eq!(vec![1, 2, 3, 4], items[..4].iter().map(|v| *v.as_ref().unwrap()).collect::<Vec<_>>());
+        assert!(items[4].is_err());
+        assert_eq!(6, requests.load(Ordering::SeqCst));
+    }
+}
@@ -75,4 +75,9 @@ impl RequestMethod for PDDMethod {
             PDDMethod::PidBindMedia => String::from("p_id_bind_response"),
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        matches!(self, PDDMethod::GoodsRecommend | PDDMethod::GoodsSearch | PDDMethod::AuthorityQuery | PDDMethod::GoodsTop | PDDMethod::GoodsDetail
+            | PDDMethod::OrderRangeQuery | PDDMethod::OrderIncrementQuery | PDDMethod::OrderDetail | PDDMethod::PidQuery)
+    }
 }
\ No newline at end of file
@@ -1,20 +1,54 @@
+use std::fmt;
 use serde_json::{Value as JsonValue};
 use std::collections::BTreeMap;
-use serde::Serialize;
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
 
-use crate::{client::APIClient, util::{get_timestamp, get_sign}, request::{Params, RequestType, Method, Response, LabraRequest, RequestMethod}, errors::LabraError, session::{SessionStore, SimpleStorage}, LabradorResult};
+use crate::{client::APIClient, util::{get_timestamp, get_sign}, request::{Params, Response, LabraRequest, RequestMethod}, errors::{Platform, PlatformError}, session::{AsyncSessionStore, SimpleStorage}, LabradorResult, LabraHttpClient, LabraHttpConfig, HttpTransport, Middleware, LabraLogConfig, RetryPolicy, RateLimiter, Page, Pagination, paginate_pages};
+use crate::config::{Proxy, Secret};
 
-use self::{method::PDDMethod, request::{PddPidQueryParam, PddPidBindMediaParam, PddPidGenerateParam, PddOrderDetailParam, PddOrderIncrementQueryParam, PddOrderRangeQueryParam, PddCmsUrlGenerateParam, PddZsUrlGenerateParam, PddGoodsDetailParam, PddRpUrlGenerateParam, PddPromoteUrlGenerateParam, PddAuthorityQueryParam, PddGoodsSearchParam, PddGoodsTopParam, PddGoodsRecommendParam}, response::{PddPidQueryResponse, PddPidBindMediaResponse, PddPidGenerateResponse, PddOrderDetail, PddOrderIncrementQueryResponse, PddOrderRangeQueryResponse, PddCmsUrlGenerateResponse, PddZsUrlGenerateResponse, PddGoodsDetailResponse, PddRpUrlGenerateResponse, PddPromotionUrlGenerateResponse, PddAuthorityQueryResponse, PddGoodsSearchResponse, PddGoodsTopResponse, PddGoodsRecommendResponse}};
+use self::method::PDDMethod;
 
 mod request;
 mod response;
 mod method;
 
+pub use request::*;
+pub use response::*;
+
 #[derive(Debug, Clone)]
-pub struct PDDClient<T: SessionStore> {
+pub struct PDDClient<T: AsyncSessionStore> {
     api_client: APIClient<T>
 }
 
+/// 多多进宝配置
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct PDDConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    /// 接口地址，用于测试环境或本地模拟服务
+    pub api_path: Option<String>,
+    /// 代理地址，如：http://127.0.0.1:8080
+    pub proxy: Option<String>,
+}
+
+impl fmt::Debug for PDDConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PDDConfig")
+            .field("client_id", &self.client_id)
+            .field("client_secret", &Secret(&self.client_secret))
+            .field("api_path", &self.api_path)
+            .field("proxy", &Proxy::option(&self.proxy))
+            .finish()
+    }
+}
+
+impl PDDConfig {
+    pub fn new<S: Into<String>>(client_id: S, client_secret: S) -> Self {
+        Self { client_id: client_id.into(), client_secret: client_secret.into(), api_path: None, proxy: None }
+    }
+}
+
 /// PDDClient
 /// 
 /// 
@@ -30,18 +64,91 @@ pub struct PDDClient<T: SessionStore> {
 /// ```
 /// 
 #[allow(unused)]
-impl <T: SessionStore> PDDClient<T> {
+impl <T: AsyncSessionStore> PDDClient<T> {
 
     pub fn new<Q: Into<String>, S: Into<String>>(app_key: Q, secret: S) -> PDDClient<SimpleStorage> {
         PDDClient {
-            api_client: APIClient::<SimpleStorage>::new::<Q, String, S>(app_key, secret, String::from("https://gw-api.pinduoduo.com/api/router"))
+            api_client: APIClient::<SimpleStorage>::new::<Q, String, S>(app_key, secret, String::from("https://gw-api.pinduoduo.com/api/router")).platform("pdd")
         }
     }
 
     pub fn from_session<Q: Into<String>, S: Into<String>>(app_key: Q, secret: S, session: T) -> PDDClient<T> {
         PDDClient {
-            api_client: APIClient::from_session(app_key, secret, String::from("https://gw-api.pinduoduo.com/api/router"), session)
+            api_client: APIClient::from_session(app_key, secret, String::from("https://gw-api.pinduoduo.com/api/router"), session).platform("pdd")
+        }
+    }
+
+    /// 根据配置创建客户端
+    pub fn from_config(config: &PDDConfig, session: T) -> LabradorResult<PDDClient<T>> {
+        let mut client = Self::from_session(config.client_id.as_str(), config.client_secret.as_str(), session);
+        if let Some(api_path) = config.api_path.as_ref() {
+            client = client.api_path(api_path.as_str());
+        }
+        if let Some(proxy) = config.proxy.as_ref() {
+            client = client.proxy(proxy)?;
         }
+        Ok(client)
+    }
+
+    /// 设置HTTP客户端，多个客户端可共用同一个连接池
+    pub fn http_client(mut self, http_client: LabraHttpClient) -> Self {
+        self.api_client = self.api_client.http_client(http_client);
+        self
+    }
+
+    /// 根据配置生成独立的HTTP客户端，可设置代理、超时等
+    pub fn http_config(mut self, config: LabraHttpConfig) -> LabradorResult<Self> {
+        self.api_client = self.api_client.http_config(config)?;
+        Ok(self)
+    }
+
+    /// 设置代理，如：http://127.0.0.1:8080，开启`socks`特性后支持socks5
+    pub fn proxy(mut self, proxy: &str) -> LabradorResult<Self> {
+        self.api_client = self.api_client.proxy(proxy)?;
+        Ok(self)
+    }
+
+    /// 设置接口地址，可指向测试环境或本地的模拟服务
+    pub fn api_path<S: Into<String>>(mut self, api_path: S) -> Self {
+        self.api_client = self.api_client.api_path(api_path);
+        self
+    }
+
+    /// 设置传输层，测试时可替换为`MockTransport`
+    pub fn transport<H: HttpTransport + 'static>(mut self, transport: H) -> Self {
+        self.api_client = self.api_client.transport(transport);
+        self
+    }
+
+    /// 同步客户端使用默认的传输层时改用专用的连接池
+    #[cfg(feature = "blocking")]
+    pub(crate) fn blocking_transport(mut self) -> Self {
+        self.api_client = self.api_client.blocking_transport();
+        self
+    }
+
+    /// 添加中间件
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.api_client = self.api_client.middleware(middleware);
+        self
+    }
+
+    /// 设置请求日志配置
+    pub fn log_config(mut self, log_config: LabraLogConfig) -> Self {
+        self.api_client = self.api_client.log_config(log_config);
+        self
+    }
+
+    /// 设置重试策略，商品、订单和推广位查询接口任何失败都可以重试；生成推广链接、创建和绑定推广位只会在连接阶段失败时重试
+    pub fn retry_policy<P: RetryPolicy + 'static>(mut self, retry_policy: P) -> Self {
+        self.api_client = self.api_client.retry_policy(retry_policy);
+        self
+    }
+
+    /// 设置限流器，按接口方法限流并统计每日调用次数
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.api_client = self.api_client.rate_limiter(rate_limiter);
+        self
     }
 
     #[inline]
@@ -60,7 +167,6 @@ impl <T: SessionStore> PDDClient<T> {
         let mut params = self.build_common_params();
         let method_name_str = method.get_method();
         params.push(("type".to_owned(), method_name_str));
-        let request_type = RequestType::Json;
         params.extend_from_slice(data.get_params().as_slice());
         // build sign
         let mut pairs = BTreeMap::new();
@@ -69,8 +175,8 @@ impl <T: SessionStore> PDDClient<T> {
         }
         let sign = get_sign(&pairs, self.api_client.secret.to_owned().as_str());
         params.push(("sign".to_owned(), sign));
-        let result = self.api_client.request(LabraRequest::new().method(Method::Post).json(data).req_type(request_type).params(params)).await?.json::<serde_json::Value>()?;
-        self.json_decode(result, &method.get_response_key())
+        let response = self.api_client.request(LabraRequest::post_json("", data).api_method(method.get_name()).idempotent(method.is_idempotent()).params(params)).await?;
+        self.json_decode(response.json::<serde_json::Value>()?, &method.get_response_key()).map_err(|err| err.with_response(&response))
     }
 
 
@@ -84,11 +190,11 @@ impl <T: SessionStore> PDDClient<T> {
                     0
                 };
                 if errcode != 0 {
-                    let errmsg = match error_response.get("sub_msg") {
-                        Some(msg) => msg.as_str().unwrap_or_default().to_owned(),
-                        None => "".to_string()
-                    };
-                    return Err(LabraError::ClientError { errcode: errcode.to_string(), errmsg: errmsg.to_owned() });
+                    let field = |key: &str| error_response.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_owned();
+                    return Err(PlatformError::new(Platform::Pdd, errcode as i64, field("sub_msg"))
+                        .sub_code(field("sub_code"))
+                        .request_id(field("request_id"))
+                        .into());
                 }
             },
             None => {},
@@ -99,7 +205,7 @@ impl <T: SessionStore> PDDClient<T> {
                 Ok(response.to_owned())
             },
             None => {
-                Err(LabraError::ClientError { errcode: "-3".to_string(), errmsg: format!("Response decode error") })
+                Err(PlatformError::new(Platform::Pdd, -3, "Response decode error").into())
             }
         }
     }
@@ -503,7 +609,22 @@ impl <T: SessionStore> PDDClient<T> {
     /// ```
     /// 
     pub async fn get_increment_order_list(&self, param: PddOrderIncrementQueryParam) -> LabradorResult<PddOrderIncrementQueryResponse> {
-        self.send(PDDMethod::OrderRangeQuery, param).await?.parse_result()
+        self.send(PDDMethod::OrderIncrementQuery, param).await?.parse_result()
+    }
+
+    /// 逐条获取增量订单，从`page`（默认1）开始按页码翻页直到返回的订单数不足一页
+    ///
+    /// `pagination`可设置每页条数（10到100）与同时请求的页数。
+    pub fn get_increment_order_list_stream(&self, param: PddOrderIncrementQueryParam, pagination: Pagination) -> impl Stream<Item = LabradorResult<PddOrderDetail>> + '_ {
+        let page_size = pagination.page_size.or(param.page_size).unwrap_or(100);
+        paginate_pages(param.page.unwrap_or(1), pagination.concurrency, move |page| {
+            let param = PddOrderIncrementQueryParam { page: page.into(), page_size: page_size.into(), ..param.clone() };
+            async move {
+                let items = self.get_increment_order_list(param).await?.order_list.unwrap_or_default();
+                let next = Some(page + 1).filter(|_| items.len() as u64 >= page_size);
+                Ok(Page::new(items, next))
+            }
+        })
     }
 
     /// 查询订单详情
@@ -627,4 +748,47 @@ impl <T: SessionStore> PDDClient<T> {
     pub async fn pid_bind_media(&self, param: PddPidBindMediaParam) -> LabradorResult<PddPidBindMediaResponse> {
         self.send(PDDMethod::PidBindMedia, param).await?.parse_result()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use futures_util::StreamExt;
+    use crate::{ExponentialBackoff, LabradorResult, MockResponse, MockTransport, Pagination, PDDClient, SimpleStorage};
+    use super::request::{PddOrderDetailParam, PddOrderIncrementQueryParam, PddPidGenerateParam};
+    use super::response::PddOrderDetail;
+
+    #[tokio::test]
+    async fn test_get_increment_order_list_stream() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, r#"{"order_list_get_response":{"total_count":3,"order_list":[{"order_sn":"1"},{"order_sn":"2"}]}}"#))
+            .push_response(MockResponse::new(200, r#"{"order_list_get_response":{"total_count":3,"order_list":[{"order_sn":"3"}]}}"#));
+        let client = PDDClient::<SimpleStorage>::new("client_id", "client_secret").transport(transport.clone());
+        let param = PddOrderIncrementQueryParam { end_update_time: 1704085200, start_update_time: 1704081600, return_count: None, page_size: None, page: None, query_order_type: None };
+        let orders = client.get_increment_order_list_stream(param, Pagination::new().page_size(2)).collect::<Vec<LabradorResult<PddOrderDetail>>>().await;
+        assert_eq!(3, orders.into_iter().filter(|v| v.is_ok()).count());
+        // 返回的订单数不足一页时结束
+        let requests = transport.requests();
+        assert_eq!(2, requests.len());
+        assert!(requests[0].url.as_str().contains("type=pdd.ddk.order.list.increment.get"));
+        assert!(requests[0].url.as_str().contains("page=1&"));
+        assert!(requests[1].url.as_str().contains("page=2&"));
+        assert!(requests[1].url.as_str().contains("page_size=2"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_idempotent_method() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(503, ""))
+            .push_response(MockResponse::new(200, r#"{"order_detail_response":{"order_sn":"1"}}"#))
+            .push_response(MockResponse::new(503, ""));
+        let policy = ExponentialBackoff::new(3).base_delay(Duration::from_millis(0)).jitter(false);
+        let client = PDDClient::<SimpleStorage>::new("client_id", "client_secret").transport(transport.clone()).retry_policy(policy);
+        // 订单详情是只读接口，服务端错误时重试
+        let order = client.get_order_detail(PddOrderDetailParam { order_sn: "1".to_string(), query_order_type: None }).await.unwrap();
+        assert_eq!(Some("1".to_string()), order.order_sn);
+        assert_eq!(2, transport.requests().len());
+        // 创建推广位不是幂等的，服务端错误时不重试
+        assert!(client.pid_generate(PddPidGenerateParam { number: 1, p_id_name_list: None, media_id: None }).await.is_err());
+        assert_eq!(3, transport.requests().len());
+    }
+}
@@ -1,15 +1,22 @@
+use std::fmt;
 use std::borrow::Cow;
+use std::path::Path;
 use chrono::Local;
 
 use bytes::Bytes;
-use log::{debug, info};
 use reqwest::header::HeaderMap;
 use reqwest::multipart::Part;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use crate::{LabradorResult, LabraError};
+use tokio::io::AsyncRead;
+use crate::{LabradorResult, LabraError, LabraHttpClient, file_part, stream_part};
+use crate::config::{Proxy, Secret};
 use crate::prp::PrpCrypto;
 
 
+/// 默认的上传请求Host
+const DEFAULT_UPLOAD_HOST: &str = "up-z2.qiniup.com";
+
 #[allow(unused)]
 #[derive(Debug, Clone)]
 pub struct Qiniu<'a> {
@@ -17,8 +24,36 @@ pub struct Qiniu<'a> {
     secret_key: Cow<'a, str>,
     endpoint: Cow<'a, str>,
     bucket: Cow<'a, str>,
+    /// 上传请求的Host，默认为up-z2.qiniup.com，为空时使用endpoint的Host
+    upload_host: Option<Cow<'a, str>>,
 
-    pub(crate) http_client: reqwest::Client,
+    pub(crate) http_client: LabraHttpClient,
+}
+
+/// 七牛云配置
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct QiniuConfig {
+    pub access_key: String,
+    pub secret_key: String,
+    pub endpoint: String,
+    pub bucket: String,
+    /// 上传请求的Host，默认为up-z2.qiniup.com，通过IP或其他域名访问上传接口时设置，设为空字符串时使用endpoint的Host
+    pub upload_host: Option<String>,
+    /// 代理地址，如：http://127.0.0.1:8080
+    pub proxy: Option<String>,
+}
+
+impl fmt::Debug for QiniuConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QiniuConfig")
+            .field("access_key", &self.access_key)
+            .field("secret_key", &Secret(&self.secret_key))
+            .field("endpoint", &self.endpoint)
+            .field("bucket", &self.bucket)
+            .field("upload_host", &self.upload_host)
+            .field("proxy", &Proxy::option(&self.proxy))
+            .finish()
+    }
 }
 
 
@@ -42,17 +77,48 @@ impl<'a> Qiniu<'a> {
         where
             S: Into<Cow<'a, str>>,
     {
-        let http_client = reqwest::Client::new();
+        let http_client = LabraHttpClient::default();
 
         Qiniu {
             access_key: access_key.into(),
             secret_key: secret_key.into(),
             endpoint: endpoint.into(),
             bucket: bucket.into(),
+            upload_host: Some(Cow::Borrowed(DEFAULT_UPLOAD_HOST)),
             http_client,
         }
     }
 
+    /// 根据配置创建客户端
+    pub fn from_config(config: &QiniuConfig) -> LabradorResult<Qiniu<'static>> {
+        let mut client = Qiniu::new(config.access_key.to_string(), config.secret_key.to_string(), config.endpoint.to_string(), config.bucket.to_string());
+        if let Some(upload_host) = config.upload_host.as_ref() {
+            client = client.upload_host(Some(upload_host.to_string()).filter(|v| !v.is_empty()));
+        }
+        if let Some(proxy) = config.proxy.as_ref() {
+            client = client.proxy(proxy)?;
+        }
+        Ok(client)
+    }
+
+    /// 设置HTTP客户端，多个客户端可共用同一个连接池
+    pub fn http_client(mut self, http_client: LabraHttpClient) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// 设置代理，如：http://127.0.0.1:8080，开启`socks`特性后支持socks5
+    pub fn proxy(mut self, proxy: &str) -> LabradorResult<Self> {
+        self.http_client = LabraHttpClient::new(self.http_client.config().clone().proxy(proxy))?;
+        Ok(self)
+    }
+
+    /// 设置上传请求的Host，通过IP或其他域名访问上传接口时使用，默认为up-z2.qiniup.com，设为None时使用endpoint的Host
+    pub fn upload_host<S: Into<Cow<'a, str>>>(mut self, upload_host: Option<S>) -> Self {
+        self.upload_host = upload_host.map(Into::into);
+        self
+    }
+
     pub fn bucket(&self) -> &str {
         &self.bucket
     }
@@ -83,17 +149,15 @@ impl<'a> Qiniu<'a> {
             "deadline": Local::now().timestamp() + 172000,
             "returnBody":"{\"name\":$(fname),\"size\":$(fsize),\"w\":$(imageInfo.width),\"h\":$(imageInfo.height),\"hash\":$(etag),\"key\":$(key)}"
         });
-        debug!("上传七牛云参数policy: {}", &put_policy.to_string());
+        tracing::debug!(scope = %scope, "[七牛云上传策略]");
         // 3.对 JSON 编码的上传策略进行URL 安全的 Base64 编码，得到待签名字符串
         let encoded_put_policy = safe_base64(put_policy.to_string().as_bytes());//base64::encode(put_policy.to_string().as_bytes());
-        debug!("上传七牛云参数encodedPutPolicy: {}", &encoded_put_policy.to_string());
         // 4.使用访问密钥（AK/SK）对上一步生成的待签名字符串计算HMAC-SHA1签名
         let prp = PrpCrypto::new(secret_key.as_bytes().to_vec());
         let result = prp.hmac_sha1_sign(&encoded_put_policy).unwrap_or_default();
         // 5.对签名进行URL安全的Base64编码
         let encoded_sign = safe_base64(result);//base64::encode(result.code());
         let data = format!("{}:{}:{}",access_key, encoded_sign, encoded_put_policy);
-        debug!("上传七牛云参数token: {}", &data);
         data
     }
 
@@ -118,23 +182,42 @@ impl<'a> Qiniu<'a> {
     }
 
     pub async fn upload(&self, file: Bytes, filename: String) -> LabradorResult<String> {
+        let part = Part::stream(file).file_name(filename.to_owned());
+        self.upload_part(part, filename).await
+    }
+
+    /// 从数据流上传，`length`为数据长度，不知道长度时使用分块传输
+    pub async fn upload_stream<R: AsyncRead + Send + Sync + 'static>(&self, reader: R, length: Option<u64>, filename: String) -> LabradorResult<String> {
+        let part = stream_part(reader, filename.to_owned(), length);
+        self.upload_part(part, filename).await
+    }
+
+    /// 上传本地文件，不会将整个文件读入内存
+    pub async fn upload_file<P: AsRef<Path>>(&self, path: P, filename: String) -> LabradorResult<String> {
+        let part = file_part(path).await?.file_name(filename.to_owned());
+        self.upload_part(part, filename).await
+    }
+
+    async fn upload_part(&self, part: Part, filename: String) -> LabradorResult<String> {
         let upload_token = self.get_access_token(filename.as_str());
         let mut headers = HeaderMap::new();
-        headers.insert("Host", "up-z2.qiniup.com".parse().unwrap());
-        let client = reqwest::Client::new();
-        let part = Part::stream(file).file_name(filename.to_owned());
+        if let Some(upload_host) = self.upload_host.as_ref() {
+            headers.insert("Host", upload_host.parse().map_err(|_| LabraError::RequestError(format!("Host有误：{}", upload_host)))?);
+        }
+        let client = self.http_client.client();
         let form =reqwest::multipart::Form::new().part("file", part)
             .part("key", Part::text(filename.to_owned()))
             .part("token", Part::text(upload_token.to_owned()))
             .part("fileName", Part::text(filename.to_owned()))
             .part("resource_key",  Part::text(filename.to_owned()));
-        info!("七牛云上传参数：url:{}, upload_token: {}, filename: {}", self.endpoint(), &upload_token, &filename);
-        let result = client
+        let span = tracing::info_span!("labra_request", platform = "qiniu", url = self.endpoint(), filename = %filename, status = tracing::field::Empty);
+        let response = client
             .post(self.endpoint())
             .multipart(form)
-            .headers(headers.to_owned()).send().await.map_err(|err| LabraError::ApiError(err.to_string()))?
-            .text().await.map_err(|err| LabraError::ApiError(err.to_string()))?;
-        info!("请求七牛云返回结果：{}", result);
+            .headers(headers.to_owned()).send().await.map_err(|err| LabraError::ApiError(err.to_string()))?;
+        span.record("status", response.status().as_u16());
+        let result = response.text().await.map_err(|err| LabraError::ApiError(err.to_string()))?;
+        tracing::info!(parent: &span, "[七牛云上传完成]");
         Ok(result)
     }
 
@@ -143,4 +226,26 @@ impl<'a> Qiniu<'a> {
 
 pub fn safe_base64<T: AsRef<[u8]>>(encode_data: T) -> String {
     base64::encode(encode_data).replace("+", "-").replace("/", "_")
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::{LabraHttpClient, LabraHttpConfig};
+    use super::{Qiniu, QiniuConfig};
+
+    #[test]
+    fn test_qiniu_http_settings() {
+        let client = Qiniu::new("access_key", "secret_key", "https://upload.example.com", "bucket");
+        assert_eq!(Some("up-z2.qiniup.com"), client.upload_host.as_deref());
+        assert_eq!(None, client.clone().upload_host(None::<&str>).upload_host);
+        let client = client.upload_host(Some("up.example.com"))
+            .http_client(LabraHttpClient::new(LabraHttpConfig::new().timeout(Duration::from_secs(3))).unwrap())
+            .proxy("http://127.0.0.1:8080").unwrap();
+        assert_eq!(Some("up.example.com"), client.upload_host.as_deref());
+        assert_eq!(Some(Duration::from_secs(3)), client.http_client.config().timeout);
+        assert_eq!(Some("http://127.0.0.1:8080"), client.http_client.config().proxy.as_deref());
+        let config = QiniuConfig { upload_host: Some(String::new()), ..Default::default() };
+        assert_eq!(None, Qiniu::from_config(&config).unwrap().upload_host);
+        assert_eq!(Some("up-z2.qiniup.com"), Qiniu::from_config(&QiniuConfig::default()).unwrap().upload_host.as_deref());
+    }
+}
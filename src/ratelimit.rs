@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use chrono::Local;
+use dashmap::DashMap;
+
+use crate::{get_nonce_str, AsyncSessionStore, LabraError, LabradorResult};
+
+/// 令牌桶限流配置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// 每秒生成的令牌数
+    pub qps: f64,
+    /// 桶容量，允许的突发请求数
+    pub burst: u32,
+    /// 每日调用上限
+    pub daily_quota: Option<u64>,
+}
+
+#[allow(unused)]
+impl RateLimit {
+    pub fn new(qps: f64) -> Self {
+        Self {
+            qps,
+            burst: qps.ceil().max(1.0) as u32,
+            daily_quota: None,
+        }
+    }
+
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    pub fn daily_quota(mut self, daily_quota: u64) -> Self {
+        self.daily_quota = daily_quota.into();
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// 限流器
+///
+/// 按应用与接口方法名（`RequestMethod::get_name`）限流，未单独配置的方法使用默认配置。
+/// 令牌不足时等待，等待时间超过`max_wait`返回`LabraError::RateLimited`。
+/// 开启`shared`后令牌桶与每日调用次数保存在`SessionStore`中，多个实例共用同一个限额。
+/// 无论是否配置限流，都会统计当天每个应用每个方法的调用次数。
+///
+/// # Examples
+///
+/// ```no_run
+/// use labrador::{APIClient, RateLimit, RateLimiter, SimpleStorage};
+///
+/// let limiter = RateLimiter::new()
+///     .limit(RateLimit::new(100.0))
+///     .method_limit("taobao.tbk.dg.material.optional", RateLimit::new(10.0).daily_quota(100000));
+/// let client = APIClient::<SimpleStorage>::new("appkey", "secret", "https://eco.taobao.com/router/rest").rate_limiter(limiter);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    default_limit: Option<RateLimit>,
+    method_limits: HashMap<String, RateLimit>,
+    shared: bool,
+    max_wait: Duration,
+    buckets: Arc<DashMap<String, Bucket>>,
+    counters: Arc<Mutex<(String, HashMap<String, u64>)>>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            default_limit: None,
+            method_limits: HashMap::new(),
+            shared: false,
+            max_wait: Duration::from_secs(5),
+            buckets: Arc::new(DashMap::new()),
+            counters: Arc::new(Mutex::new((today(), HashMap::new()))),
+        }
+    }
+}
+
+#[allow(unused)]
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 默认限流配置
+    pub fn limit(mut self, limit: RateLimit) -> Self {
+        self.default_limit = limit.into();
+        self
+    }
+
+    /// 单个方法的限流配置
+    pub fn method_limit<S: Into<String>>(mut self, method: S, limit: RateLimit) -> Self {
+        self.method_limits.insert(method.into(), limit);
+        self
+    }
+
+    /// 是否通过`SessionStore`在多个实例间共用限额
+    pub fn shared(mut self, shared: bool) -> Self {
+        self.shared = shared;
+        self
+    }
+
+    /// 令牌不足时的最长等待时间
+    pub fn max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
+    /// 当天某个应用某个方法的调用次数（本实例）
+    pub fn calls(&self, app_key: &str, method: &str) -> u64 {
+        self.counters().1.get(&limit_key(app_key, method)).copied().unwrap_or_default()
+    }
+
+    /// 当天所有方法的调用次数（本实例），键为`{app_key}_{method}`
+    pub fn daily_calls(&self) -> HashMap<String, u64> {
+        self.counters().1.clone()
+    }
+
+    /// 获取令牌，令牌不足时等待
+    pub async fn acquire<S: AsyncSessionStore>(&self, session: &S, app_key: &str, method: &str) -> LabradorResult<()> {
+        let limit = match self.method_limits.get(method).or(self.default_limit.as_ref()) {
+            Some(limit) => *limit,
+            None => {
+                self.record(app_key, method);
+                return Ok(());
+            }
+        };
+        let deadline = Instant::now() + self.max_wait;
+        loop {
+            let wait = if self.shared {
+                self.try_acquire_shared(session, app_key, method, &limit, deadline).await?
+            } else {
+                self.try_acquire_local(app_key, method, &limit)?
+            };
+            match wait {
+                None => return Ok(()),
+                Some(wait) if Instant::now() + wait <= deadline => tokio::time::sleep(wait).await,
+                Some(_) => return Err(LabraError::RateLimited(format!("{} 请求过于频繁", method))),
+            }
+        }
+    }
+
+    /// 当天的调用次数，锁中毒时继续使用其中的数据，跨天时清零
+    fn counters(&self) -> MutexGuard<'_, (String, HashMap<String, u64>)> {
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        let date = today();
+        if counters.0 != date {
+            *counters = (date, HashMap::new());
+        }
+        counters
+    }
+
+    fn record(&self, app_key: &str, method: &str) {
+        *self.counters().1.entry(limit_key(app_key, method)).or_default() += 1;
+    }
+
+    fn try_acquire_local(&self, app_key: &str, method: &str, limit: &RateLimit) -> LabradorResult<Option<Duration>> {
+        // 额度检查与计数在同一把锁内完成，避免并发请求超出每日上限
+        let mut counters = self.counters();
+        let key = limit_key(app_key, method);
+        let calls = counters.1.entry(key.to_owned()).or_default();
+        if let Some(quota) = limit.daily_quota {
+            if *calls >= quota {
+                return Err(quota_exceeded(method, quota));
+            }
+        }
+        let now = Instant::now();
+        let mut bucket = self.buckets.entry(key)
+            .or_insert(Bucket { tokens: limit.burst as f64, updated_at: now });
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        let (tokens, wait) = take_token(bucket.tokens + elapsed * limit.qps, limit);
+        *bucket = Bucket { tokens, updated_at: now };
+        if wait.is_none() {
+            *calls += 1;
+        }
+        Ok(wait)
+    }
+
+    async fn try_acquire_shared<S: AsyncSessionStore>(&self, session: &S, app_key: &str, method: &str, limit: &RateLimit, deadline: Instant) -> LabradorResult<Option<Duration>> {
+        let bucket_key = format!("{}_ratelimit_{}", app_key, method);
+        let quota_key = format!("{}_quota_{}_{}", app_key, method, today());
+        let lock_key = format!("{}_lock", bucket_key);
+        let holder = get_nonce_str();
+        while !session.try_lock(&lock_key, &holder, Duration::from_secs(1)).await? {
+            if Instant::now() >= deadline {
+                return Err(LabraError::RateLimited(format!("{} 等待限流锁超时", method)));
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let result = async {
+            let calls: u64 = session.get(&quota_key, Some(0)).await?.unwrap_or_default();
+            if let Some(quota) = limit.daily_quota {
+                if calls >= quota {
+                    return Err(quota_exceeded(method, quota));
+                }
+            }
+            let now = Local::now().timestamp_millis();
+            let state: String = session.get(&bucket_key, Some(String::default())).await?.unwrap_or_default();
+            let (tokens, updated_at) = state.split_once(',')
+                .and_then(|(tokens, updated_at)| Some((tokens.parse::<f64>().ok()?, updated_at.parse::<i64>().ok()?)))
+                .unwrap_or((limit.burst as f64, now));
+            let elapsed = (now - updated_at).max(0) as f64 / 1000.0;
+            let (tokens, wait) = take_token(tokens + elapsed * limit.qps, limit);
+            session.set(&bucket_key, format!("{},{}", tokens, now), Some(60)).await?;
+            if wait.is_none() {
+                session.set(&quota_key, calls + 1, Some(86400)).await?;
+                self.record(app_key, method);
+            }
+            Ok(wait)
+        }.await;
+        if let Err(err) = session.unlock(&lock_key, &holder).await {
+            tracing::warn!(lock_key = lock_key.as_str(), error = %err, "[释放限流锁失败]");
+        }
+        result
+    }
+}
+
+/// 取出一个令牌，返回剩余令牌数与令牌不足时需要等待的时间
+fn take_token(tokens: f64, limit: &RateLimit) -> (f64, Option<Duration>) {
+    let tokens = tokens.min(limit.burst as f64);
+    if tokens >= 1.0 {
+        (tokens - 1.0, None)
+    } else {
+        (tokens, Some(Duration::from_secs_f64((1.0 - tokens) / limit.qps)))
+    }
+}
+
+/// 令牌桶与调用次数的键
+fn limit_key(app_key: &str, method: &str) -> String {
+    format!("{}_{}", app_key, method)
+}
+
+fn quota_exceeded(method: &str, quota: u64) -> LabraError {
+    LabraError::RateLimited(format!("{} 已超过每日调用上限{}", method, quota))
+}
+
+fn today() -> String {
+    Local::now().format("%Y%m%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use crate::{LabraError, SimpleStorage};
+    use super::{RateLimit, RateLimiter};
+
+    #[tokio::test]
+    async fn test_rate_limiter() {
+        let session = SimpleStorage::new();
+        let limiter = RateLimiter::new()
+            .limit(RateLimit::new(20.0).burst(2))
+            .method_limit("quota", RateLimit::new(100.0).daily_quota(2));
+        let start = Instant::now();
+        for _ in 0..4 {
+            limiter.acquire(&session, "appkey", "method").await.unwrap();
+        }
+        // 突发2次后按每秒20次发放令牌
+        assert!(start.elapsed() >= Duration::from_millis(90));
+        assert_eq!(4, limiter.calls("appkey", "method"));
+
+        limiter.acquire(&session, "appkey", "quota").await.unwrap();
+        limiter.acquire(&session, "appkey", "quota").await.unwrap();
+        assert!(matches!(limiter.acquire(&session, "appkey", "quota").await, Err(LabraError::RateLimited(_))));
+        // 每日上限按应用分别计算
+        limiter.acquire(&session, "other", "quota").await.unwrap();
+        assert_eq!(1, limiter.calls("other", "quota"));
+
+        // 共用同一个SessionStore的实例共享令牌桶与每日调用次数
+        let limit = RateLimit::new(1.0).burst(1).daily_quota(10);
+        let first = RateLimiter::new().limit(limit).shared(true).max_wait(Duration::from_millis(0));
+        let second = RateLimiter::new().limit(limit).shared(true).max_wait(Duration::from_millis(0));
+        first.acquire(&session, "appkey", "shared").await.unwrap();
+        assert!(matches!(second.acquire(&session, "appkey", "shared").await, Err(LabraError::RateLimited(_))));
+        assert_eq!(2, limiter.calls("appkey", "quota"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_rate_limiter_concurrent_quota() {
+        let session = SimpleStorage::new();
+        let limiter = RateLimiter::new().limit(RateLimit::new(100000.0).burst(1000).daily_quota(20));
+        let tasks = (0..8).map(|_| {
+            let (limiter, session) = (limiter.clone(), session.clone());
+            tokio::spawn(async move {
+                let mut passed = 0;
+                for _ in 0..10 {
+                    if limiter.acquire(&session, "appkey", "method").await.is_ok() {
+                        passed += 1;
+                    }
+                }
+                passed
+            })
+        }).collect::<Vec<_>>();
+        let mut passed = 0;
+        for task in tasks {
+            passed += task.await.unwrap();
+        }
+        assert_eq!(20, passed);
+        assert_eq!(20, limiter.calls("appkey", "method"));
+
+        // 持有计数锁的线程panic后仍可继续使用
+        let poisoned = limiter.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoned.counters.lock().unwrap();
+            panic!("poison");
+        }).join();
+        assert!(limiter.counters.is_poisoned());
+        assert_eq!(20, limiter.calls("appkey", "method"));
+        assert!(matches!(limiter.acquire(&session, "appkey", "method").await, Err(LabraError::RateLimited(_))));
+    }
+}
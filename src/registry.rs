@@ -0,0 +1,244 @@
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use async_trait::async_trait;
+use dashmap::DashMap;
+
+use crate::{AsyncSessionStore, HttpTransport, LabraError, LabraHttpClient, LabradorResult};
+
+/// 可以根据租户配置构建的客户端
+pub trait TenantClient<T: AsyncSessionStore>: Sized + Send + Sync {
+    /// 租户配置
+    type Config: Clone + Send + Sync;
+
+    /// 租户标识，如appid、mch_id、corp_id
+    fn tenant_id(config: &Self::Config) -> String;
+
+    /// 根据配置构建客户端，所有租户共用会话存储与传输层
+    fn build(config: &Self::Config, session: T, transport: Arc<dyn HttpTransport>) -> LabradorResult<Self>;
+}
+
+/// 租户配置来源，注册中心中不存在的租户会从这里加载，如数据库、配置中心
+#[async_trait]
+pub trait TenantSource<C>: Send + Sync {
+    async fn load(&self, tenant_id: &str) -> LabradorResult<Option<C>>;
+}
+
+/// 多租户客户端注册中心
+///
+/// 按租户标识（appid、mch_id、corp_id）缓存客户端，首次获取时根据配置构建。
+/// 所有客户端共用同一个会话存储与传输层，租户可以在运行时添加、更新与移除。
+///
+/// # Examples
+///
+/// ```ignore
+/// use labrador::{LabradorRegistry, SimpleStorage, WechatMpClient, WechatMpConfig};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let registry = LabradorRegistry::<WechatMpClient<SimpleStorage>, SimpleStorage>::new(SimpleStorage::new());
+///     registry.add_tenant(WechatMpConfig::new("appid", "secret"));
+///     let client = registry.get("appid").await.unwrap();
+///     let token = client.access_token(false).await.unwrap();
+/// }
+/// ```
+///
+pub struct LabradorRegistry<C: TenantClient<T>, T: AsyncSessionStore> {
+    session: T,
+    transport: Arc<dyn HttpTransport>,
+    /// 租户配置与版本号，配置更新时版本号递增
+    configs: Arc<DashMap<String, (u64, C::Config)>>,
+    clients: Arc<DashMap<String, Arc<C>>>,
+    generation: Arc<AtomicU64>,
+    source: Option<Arc<dyn TenantSource<C::Config>>>,
+}
+
+impl<C: TenantClient<T>, T: AsyncSessionStore> Clone for LabradorRegistry<C, T> {
+    fn clone(&self) -> Self {
+        Self {
+            session: self.session.clone(),
+            transport: self.transport.clone(),
+            configs: self.configs.clone(),
+            clients: self.clients.clone(),
+            generation: self.generation.clone(),
+            source: self.source.clone(),
+        }
+    }
+}
+
+impl<C: TenantClient<T>, T: AsyncSessionStore> fmt::Debug for LabradorRegistry<C, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LabradorRegistry")
+            .field("tenants", &self.tenants())
+            .field("transport", &self.transport)
+            .finish()
+    }
+}
+
+impl<C: TenantClient<T>, T: AsyncSessionStore> LabradorRegistry<C, T> {
+    pub fn new(session: T) -> Self {
+        Self {
+            session,
+            transport: Arc::new(LabraHttpClient::default()),
+            configs: Arc::new(DashMap::new()),
+            clients: Arc::new(DashMap::new()),
+            generation: Arc::new(AtomicU64::new(0)),
+            source: None,
+        }
+    }
+
+    /// 设置所有租户共用的传输层
+    pub fn transport<H: HttpTransport + 'static>(mut self, transport: H) -> Self {
+        self.transport = Arc::new(transport);
+        self.clients.clear();
+        self
+    }
+
+    /// 设置租户配置来源
+    pub fn source<S: TenantSource<C::Config> + 'static>(mut self, source: S) -> Self {
+        self.source = Some(Arc::new(source));
+        self
+    }
+
+    pub fn session(&self) -> &T {
+        &self.session
+    }
+
+    /// 添加或更新租户，已构建的客户端会在下次获取时重新构建
+    pub fn add_tenant(&self, config: C::Config) {
+        let tenant_id = C::tenant_id(&config);
+        self.configs.insert(tenant_id.to_owned(), (self.next_generation(), config));
+        self.clients.remove(&tenant_id);
+    }
+
+    /// 移除租户
+    pub fn remove_tenant(&self, tenant_id: &str) -> Option<C::Config> {
+        self.clients.remove(tenant_id);
+        self.configs.remove(tenant_id).map(|(_, (_, config))| config)
+    }
+
+    /// 清除已构建的客户端，保留租户配置
+    pub fn invalidate(&self, tenant_id: &str) {
+        self.clients.remove(tenant_id);
+    }
+
+    pub fn contains(&self, tenant_id: &str) -> bool {
+        self.configs.contains_key(tenant_id)
+    }
+
+    /// 已注册的租户
+    pub fn tenants(&self) -> Vec<String> {
+        self.configs.iter().map(|v| v.key().to_string()).collect()
+    }
+
+    /// 获取租户的客户端，首次获取时构建；注册中心中不存在的租户从配置来源加载
+    pub async fn get(&self, tenant_id: &str) -> LabradorResult<Arc<C>> {
+        loop {
+            if let Some(client) = self.clients.get(tenant_id) {
+                return Ok(client.clone());
+            }
+            let (generation, config) = match self.configs.get(tenant_id).map(|v| v.clone()) {
+                Some(config) => config,
+                None => {
+                    let config = match self.source.as_ref() {
+                        Some(source) => source.load(tenant_id).await?,
+                        None => None,
+                    };
+                    let config = config.ok_or_else(|| LabraError::ApiError(format!("租户不存在：{}", tenant_id)))?;
+                    let generation = self.next_generation();
+                    self.configs.entry(tenant_id.to_string()).or_insert((generation, config)).clone()
+                }
+            };
+            let client = Arc::new(C::build(&config, self.session.clone(), self.transport.clone())?);
+            // 持有配置的读锁写入客户端，构建期间租户被更新或移除时使用最新配置重新构建
+            let current = self.configs.get(tenant_id);
+            if current.as_ref().map(|v| v.0) == Some(generation) {
+                return Ok(self.clients.entry(tenant_id.to_string()).or_insert(client).clone());
+            }
+        }
+    }
+
+    fn next_generation(&self) -> u64 {
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::sync::Arc;
+    use async_trait::async_trait;
+    use crate::{APIClient, HttpTransport, LabraRequest, LabradorResult, MockResponse, MockTransport, SimpleStorage};
+    use super::{LabradorRegistry, TenantClient, TenantSource};
+
+    struct DemoClient {
+        api_client: APIClient<SimpleStorage>,
+    }
+
+    thread_local! {
+        /// 构建客户端时执行，用于模拟构建期间租户被更新
+        static BUILD_HOOK: RefCell<Option<Box<dyn FnOnce()>>> = RefCell::new(None);
+    }
+
+    impl TenantClient<SimpleStorage> for DemoClient {
+        type Config = (String, String);
+
+        fn tenant_id(config: &Self::Config) -> String {
+            config.0.to_string()
+        }
+
+        fn build(config: &Self::Config, session: SimpleStorage, transport: Arc<dyn HttpTransport>) -> LabradorResult<Self> {
+            if let Some(hook) = BUILD_HOOK.with(|v| v.borrow_mut().take()) {
+                hook();
+            }
+            let api_client = APIClient::from_session(config.0.to_string(), config.1.to_string(), "https://api.weixin.qq.com", session).transport(transport);
+            Ok(DemoClient { api_client })
+        }
+    }
+
+    struct DemoSource;
+
+    #[async_trait]
+    impl TenantSource<(String, String)> for DemoSource {
+        async fn load(&self, tenant_id: &str) -> LabradorResult<Option<(String, String)>> {
+            Ok(tenant_id.starts_with("wx").then(|| (tenant_id.to_string(), "secret".to_string())))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, "ok")).push_response(MockResponse::new(200, "ok"));
+        let registry = LabradorRegistry::<DemoClient, SimpleStorage>::new(SimpleStorage::new())
+            .transport(transport.clone())
+            .source(DemoSource);
+        registry.add_tenant(("app1".to_string(), "secret1".to_string()));
+        let client = registry.get("app1").await.unwrap();
+        assert!(Arc::ptr_eq(&client, &registry.get("app1").await.unwrap()));
+        assert_eq!("secret1", client.api_client.secret);
+
+        // 从配置来源加载
+        let loaded = registry.get("wx123").await.unwrap();
+        assert_eq!("wx123", loaded.api_client.app_key);
+        assert!(registry.get("unknown").await.is_err());
+
+        // 共用同一个传输层
+        client.api_client.request(LabraRequest::get("/cgi-bin/test")).await.unwrap();
+        loaded.api_client.request(LabraRequest::get("/cgi-bin/test")).await.unwrap();
+        assert_eq!(2, transport.requests().len());
+
+        // 更新与移除租户
+        registry.add_tenant(("app1".to_string(), "secret2".to_string()));
+        assert_eq!("secret2", registry.get("app1").await.unwrap().api_client.secret);
+        registry.remove_tenant("app1");
+        assert!(!registry.contains("app1"));
+        assert!(registry.get("app1").await.is_err());
+
+        // 构建期间租户被更新，不缓存使用旧配置构建的客户端
+        registry.add_tenant(("app2".to_string(), "secret1".to_string()));
+        let updater = registry.clone();
+        BUILD_HOOK.with(|v| *v.borrow_mut() = Some(Box::new(move || updater.add_tenant(("app2".to_string(), "secret2".to_string())))));
+        assert_eq!("secret2", registry.get("app2").await.unwrap().api_client.secret);
+        assert_eq!("secret2", registry.get("app2").await.unwrap().api_client.secret);
+    }
+}
@@ -1,11 +1,19 @@
+use std::fmt;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
 use bytes::Bytes;
 use reqwest::{self, multipart, StatusCode, Url};
-use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use crate::errors::LabraError;
-use crate::LabradorResult;
+use crate::{LabradorResult, LabraStreamResponse};
+use crate::transport::{HttpBody, HttpRequest, HttpTransport, LabraHttpClient, TransportError, DEFAULT_BLOCKING_CLIENT};
+use crate::retry::{AttemptOutcome, NoRetry, RetryContext, RetryPolicy};
+use crate::middleware::Middleware;
+use crate::logging::LabraLogConfig;
+use tracing::Instrument;
 
 
 /// Parse Data For Response
@@ -13,9 +21,6 @@ pub trait Response <T> where T: Serialize {
     fn parse_result(&self) -> LabradorResult<T>;
 }
 
-const APP_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; WOW64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/63.0.3239.132 Safari/537.36";
-
-
 /// Common Params Format
 /// If custom paramters.
 pub trait Params{
@@ -29,9 +34,19 @@ pub trait Params{
 pub trait RequestMethod {
     fn get_method(&self) -> String ;
 
+    /// 接口名称，用于指标标签与限流，路径中的参数以占位符表示
+    fn get_name(&self) -> String {
+        self.get_method()
+    }
+
     fn get_response_key(&self) -> String {
         String::default()
     }
+
+    /// 是否只读接口，只读接口重复调用没有副作用，任何失败都可以重试
+    fn is_idempotent(&self) -> bool {
+        false
+    }
 }
 
 #[allow(unused)]
@@ -64,19 +79,27 @@ impl From<Method> for reqwest::Method {
     }
 }
 
-impl ToString for Method {
-    fn to_string(&self) -> String {
-        match self {
-            Method::Options => String::from("OPTIONS"),
-            Method::Get => String::from("GET"),
-            Method::Post => String::from("POST"),
-            Method::Put => String::from("PUT"),
-            Method::Delete => String::from("DELETE"),
-            Method::Head => String::from("HEAD"),
-            Method::Trace => String::from("TRACE"),
-            Method::Connect => String::from("CONNECT"),
-            Method::Patch => String::from("PATCH"),
-        }
+impl Method {
+    /// 按照HTTP语义，该方法的请求是否幂等
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(self, Method::Post | Method::Patch | Method::Connect)
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let method = match self {
+            Method::Options => "OPTIONS",
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+            Method::Head => "HEAD",
+            Method::Trace => "TRACE",
+            Method::Connect => "CONNECT",
+            Method::Patch => "PATCH",
+        };
+        f.write_str(method)
     }
 }
 
@@ -91,21 +114,59 @@ pub enum RequestBody<T: Serialize> {
     Null
 }
 
+impl <T: Serialize> fmt::Display for RequestBody<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RequestBody::Multipart(v) => f.write_str(v.boundary()),
+            RequestBody::Raw(_v) => f.write_str("bytes"),
+            _ => f.write_str(&self.payload().unwrap_or_default()),
+        }
+    }
+}
+
+#[allow(unused)]
 impl <T: Serialize> RequestBody<T> {
+    /// 请求体类型，文本、字节与空请求体没有固定类型
+    pub fn request_type(&self) -> Option<RequestType> {
+        match self {
+            RequestBody::Json(_) => Some(RequestType::Json),
+            RequestBody::Form(_) => Some(RequestType::Form),
+            RequestBody::Multipart(_) => Some(RequestType::Multipart),
+            RequestBody::Xml(_) => Some(RequestType::Xml),
+            RequestBody::Text(_) | RequestBody::Raw(_) | RequestBody::Null => None,
+        }
+    }
 
-    pub fn to_string(&self) -> String {
+    /// 实际发送的请求体内容，签名时使用，multipart请求体为空字符串
+    pub fn payload(&self) -> LabradorResult<String> {
         match self {
-            RequestBody::Json(v) => serde_json::to_string(&v).unwrap_or_default(),
-            RequestBody::Form(v) => serde_json::to_string(&v).unwrap_or_default(),
-            RequestBody::Multipart(v) => {
-                v.boundary().to_string()
-            }
-            RequestBody::Xml(v) => v.to_string(),
-            RequestBody::Text(v) => v.to_string(),
-            RequestBody::Raw(_v) => String::from("bytes"),
-            RequestBody::Null => String::default(),
+            RequestBody::Json(v) => Ok(serde_json::to_string(v)?),
+            RequestBody::Form(v) => Ok(serde_urlencoded::to_string(v)?),
+            RequestBody::Xml(v) | RequestBody::Text(v) => Ok(v.to_string()),
+            RequestBody::Raw(v) => Ok(String::from_utf8_lossy(v).to_string()),
+            RequestBody::Multipart(_) | RequestBody::Null => Ok(String::default()),
         }
     }
+
+    /// 请求体是否为空
+    pub fn is_empty(&self) -> bool {
+        matches!(self, RequestBody::Null)
+    }
+
+    /// 编码请求体，Content-Type由请求体类型决定，multipart由传输层生成
+    fn encode(self) -> LabradorResult<(Option<HeaderValue>, HttpBody)> {
+        let content_type = match self.request_type() {
+            Some(RequestType::Multipart) | None => None,
+            Some(request_type) => Some(HeaderValue::from_str(&request_type.get_content_type())?),
+        };
+        let body = match self {
+            RequestBody::Multipart(v) => HttpBody::Multipart(v),
+            RequestBody::Raw(v) => HttpBody::Bytes(v),
+            RequestBody::Null => HttpBody::Empty,
+            body => HttpBody::Bytes(body.payload()?.into()),
+        };
+        Ok((content_type, body))
+    }
 }
 
 impl <T: Serialize> From<multipart::Form> for RequestBody<T> {
@@ -154,40 +215,46 @@ pub enum RequestType {
 
 /// LabraRequest
 ///
+/// 请求方法与请求体只能通过构造方法一起设置，GET、DELETE请求没有请求体，
+/// POST请求的编码方式由`post_json`、`post_form`、`post_xml`、`post_multipart`决定，
+/// 发送的请求体、Content-Type与签名使用的内容都来自同一个请求体。
+///
 /// # Examples
 ///
 /// ```no_run
-/// use labrador::APIClient;
 /// use serde_json::json;
-/// use labrador::Method;
-/// use labrador::{LabraRequest,LabradorResult};
-/// use std::error::Error;
-/// fn main() -> LabradorResult<(), Error> {
-///     let api = APIClient::new("appkey", "secret", "http_url");
+/// use labrador::{LabraHttpClient, LabraHttpConfig, LabraLogConfig, LabraRequest, LabradorResult, NoRetry};
+/// # #[tokio::main]
+/// async fn main() -> LabradorResult<()> {
+///     let transport = LabraHttpClient::new(LabraHttpConfig::default())?;
 ///     let params = vec![(String::from("key"), String::from("value"))];
 ///     let data = json!({
 ///         "key": "value"
 ///     });
-///     let req = LabraRequest::new().method(Method::Post).data(data).req_type(request_type).params(params);
-///     let _ = req.request().await?;
-///     let _ = req.request_blocking().await?;
+///     let req = LabraRequest::post_json("http_url", data).params(params);
+///     let _ = req.request_with(&transport, &NoRetry, &[], &LabraLogConfig::default()).await?;
+///     Ok(())
 /// }
 /// ```
 ///
 #[allow(unused)]
 #[derive(Debug)]
-pub struct LabraRequest <T> where T: Serialize {
+pub struct LabraRequest <T = ()> where T: Serialize {
     pub url: String,
-    pub method: Method,
-    pub req_type : RequestType,
+    pub(crate) method: Method,
     pub identity: Option<LabraIdentity>,
     pub cert: Option<LabraCertificate>,
     pub params: Option<Vec<(String, String)>>,
     pub headers: Option<Vec<(String, String)>>,
-    pub body: RequestBody<T>
+    pub(crate) body: RequestBody<T>,
+    /// 是否幂等，未设置时根据请求方法判断
+    pub idempotent: Option<bool>,
+    /// 接口方法名，用于限流与调用统计，未设置时使用请求路径
+    pub api_method: Option<String>,
 }
 
 #[allow(unused)]
+#[derive(Debug, Clone)]
 pub struct LabraResponse {
     url: Url,
     status: StatusCode,
@@ -197,7 +264,7 @@ pub struct LabraResponse {
 }
 
 impl LabraResponse {
-    fn new(url: Url, status: StatusCode, remote_addr: Option<SocketAddr>, headers: HeaderMap, body: Bytes) -> LabraResponse {
+    pub fn new(url: Url, status: StatusCode, remote_addr: Option<SocketAddr>, headers: HeaderMap, body: Bytes) -> LabraResponse {
         LabraResponse {
             url,
             headers,
@@ -223,6 +290,15 @@ impl LabraResponse {
         &self.headers
     }
 
+    /// 响应头中的请求ID，如微信支付的`Request-ID`、支付宝的`alipay-trace-id`
+    pub fn request_id(&self) -> Option<String> {
+        ["Request-ID", "alipay-trace-id", "X-Request-Id"].iter()
+            .filter_map(|name| self.headers.get(*name))
+            .filter_map(|v| v.to_str().ok())
+            .find(|v| !v.is_empty())
+            .map(|v| v.to_string())
+    }
+
     pub fn json<T: DeserializeOwned>(&self) -> LabradorResult<T> {
         serde_json::from_slice(&self.body).map_err(LabraError::from)
     }
@@ -240,25 +316,53 @@ impl LabraResponse {
     }
 }
 
+#[allow(unused)]
+impl LabraRequest {
+    /// GET请求，没有请求体
+    pub fn get<S: Into<String>>(url: S) -> Self {
+        Self::with_body(Method::Get, url, RequestBody::Null)
+    }
+
+    /// DELETE请求，没有请求体
+    pub fn delete<S: Into<String>>(url: S) -> Self {
+        Self::with_body(Method::Delete, url, RequestBody::Null)
+    }
+
+    /// POST请求，请求体为拼装好的XML
+    pub fn post_xml<S: Into<String>>(url: S, xml: String) -> Self {
+        Self::with_body(Method::Post, url, RequestBody::Xml(xml))
+    }
+
+    /// POST请求，请求体为multipart表单
+    pub fn post_multipart<S: Into<String>>(url: S, form: multipart::Form) -> Self {
+        Self::with_body(Method::Post, url, RequestBody::Multipart(form))
+    }
+}
+
 #[allow(unused)]
 impl <T> LabraRequest <T> where T: Serialize {
-    pub fn new() -> Self {
-        LabraRequest { url: String::default(), method: Method::Post, req_type: RequestType::Json, identity: None, cert: None, params: None, headers: None, body: RequestBody::Null }
+    fn with_body<S: Into<String>>(method: Method, url: S, body: RequestBody<T>) -> Self {
+        LabraRequest { url: url.into(), method, identity: None, cert: None, params: None, headers: None, body, idempotent: None, api_method: None }
     }
 
-    pub fn url(mut self, url: String) -> Self {
-        self.url = url;
-        self
+    /// POST请求，请求体编码为JSON
+    pub fn post_json<S: Into<String>>(url: S, data: T) -> Self {
+        Self::with_body(Method::Post, url, RequestBody::Json(data))
     }
 
-    pub fn method(mut self, method: Method) -> Self {
-        self.method = method;
-        self
+    /// POST请求，请求体编码为表单
+    pub fn post_form<S: Into<String>>(url: S, data: T) -> Self {
+        Self::with_body(Method::Post, url, RequestBody::Form(data))
     }
 
-    pub fn req_type(mut self, req_type: RequestType) -> Self {
-        self.req_type = req_type;
-        self
+    /// 请求方法
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// 请求体
+    pub fn body(&self) -> &RequestBody<T> {
+        &self.body
     }
 
     pub fn headers(mut self, headers: Vec<(String, String)>) -> Self {
@@ -281,93 +385,168 @@ impl <T> LabraRequest <T> where T: Serialize {
         self
     }
 
-    pub fn body(mut self, body: RequestBody<T>) -> Self {
-        self.body = body.into();
-        self
-    }
-
-    pub fn json(mut self, data: T) -> Self {
-        self.body = RequestBody::Json(data);
+    /// 标记请求是否幂等，非幂等的请求只会在连接阶段失败时重试
+    pub fn idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent.into();
         self
     }
 
-    pub fn form(mut self, data: T) -> Self {
-        self.body = RequestBody::Form(data);
+    /// 设置接口方法名
+    pub fn api_method<S: Into<String>>(mut self, api_method: S) -> Self {
+        self.api_method = api_method.into().into();
         self
     }
 
-    pub fn multipart_form(mut self, data: multipart::Form) -> Self {
-        self.body = RequestBody::Multipart(data);
-        self
+    /// 请求是否幂等
+    pub fn is_idempotent(&self) -> bool {
+        self.idempotent.unwrap_or_else(|| self.method.is_idempotent())
     }
 
-    pub fn bytes(mut self, bytes: Bytes) -> Self {
-        self.body = bytes.into();
-        self
+    #[inline]
+    pub async fn request(self) -> LabradorResult<LabraResponse> {
+        self.request_with(&LabraHttpClient::default(), &NoRetry, &[], &LabraLogConfig::default()).await
     }
 
-    pub fn text(mut self, data: &str) -> Self {
-        self.body = data.into();
-        self
+    /// 编码请求体，生成发送给传输层的请求
+    pub fn build(self) -> LabradorResult<HttpRequest> {
+        let mut http_url = Url::parse(&self.url).map_err(|e| LabraError::RequestError(e.to_string()))?;
+        // 没有参数时不添加`?`，避免与V3接口签名的地址不一致
+        if let Some(params) = self.params.as_ref().filter(|v| !v.is_empty()) {
+            http_url.query_pairs_mut().extend_pairs(params);
+        }
+        let mut headers = HeaderMap::new();
+        let (content_type, body) = self.body.encode()?;
+        if let Some(content_type) = content_type {
+            headers.insert(CONTENT_TYPE, content_type);
+        }
+        if let Some(custom) = &self.headers {
+            for (k, v) in custom.iter() {
+                let name = HeaderName::from_bytes(k.as_bytes()).map_err(|e| LabraError::RequestError(e.to_string()))?;
+                // 自定义的Content-Type覆盖默认值
+                if name == CONTENT_TYPE {
+                    headers.insert(name, HeaderValue::from_str(v)?);
+                } else {
+                    headers.append(name, HeaderValue::from_str(v)?);
+                }
+            }
+        }
+        Ok(HttpRequest {
+            method: self.method,
+            url: http_url,
+            headers,
+            body,
+            identity: self.identity,
+            cert: self.cert,
+        })
     }
 
+    /// 使用指定的传输层发送请求，经过中间件处理，并按照重试策略重试
     #[inline]
-    pub async fn request(self) -> LabradorResult<LabraResponse> {
-        let mut http_url = Url::parse(&self.url).map_err(|e| LabraError::RequestError(e.to_string()))?;
-        if let Some(params) = &self.params {
-            http_url.query_pairs_mut().extend_pairs(params.into_iter());
-        }
-        let mut client = reqwest::Client::builder().user_agent(APP_USER_AGENT);
-        if let Some(identity) = &self.identity {
-            client = client.identity(identity.identity());
+    pub async fn request_with(self, transport: &dyn HttpTransport, retry_policy: &dyn RetryPolicy, middlewares: &[Arc<dyn Middleware>], log: &LabraLogConfig) -> LabradorResult<LabraResponse> {
+        let idempotent = self.is_idempotent();
+        let data = if log.log_body { self.body.to_string() } else { String::default() };
+        let mut request = self.build()?;
+        for middleware in middlewares {
+            middleware.before_request(&mut request)?;
         }
-        if let Some(cert) = &self.cert {
-            client = client.add_root_certificate(cert.reqwest_cert()?);
+        let span = tracing::info_span!("labra_request",
+            method = %request.method,
+            url = %log.redact_url(&request.url),
+            status = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+            errcode = tracing::field::Empty,
+        );
+        if log.log_body {
+            tracing::debug!(parent: &span, body = %log.redact_body(&data), "[请求第三方接口参数]");
         }
-        let client = client.build()?;
-        let mut request = client.request(self.method.clone().into(), http_url.to_owned());
-        let mut data = &self.body.to_string();
-        match self.body {
-            RequestBody::Json(v) => {
-                request = request.json(&v);
-            }
-            RequestBody::Form(v) => {
-                request = request.form(&v);
-            }
-            RequestBody::Multipart(v) => {
-                request = request.multipart(v);
-            }
-            RequestBody::Xml(v) => {
-                request = request.body(v);
+        let started = Instant::now();
+        let result = async {
+            let mut attempt = 0;
+            loop {
+                attempt += 1;
+                // multipart等流式请求体无法复制，只能请求一次
+                let next = if attempt < retry_policy.max_attempts() { request.try_clone() } else { None };
+                let result = transport.execute(request).await;
+                let retry = next.is_some() && {
+                    let outcome = match &result {
+                        Ok(response) => AttemptOutcome::Response(response),
+                        Err(TransportError::Connect(_)) => AttemptOutcome::ConnectError,
+                        Err(TransportError::Timeout(_)) => AttemptOutcome::Timeout,
+                        Err(TransportError::Other(_)) => AttemptOutcome::RequestError,
+                    };
+                    retry_policy.should_retry(&RetryContext { attempt, idempotent, outcome })
+                };
+                match next {
+                    Some(next) if retry => {
+                        tracing::warn!(attempt, "[请求第三方接口重试]");
+                        tokio::time::sleep(retry_policy.backoff(attempt)).await;
+                        request = next;
+                    }
+                    _ => break result,
+                }
             }
-            RequestBody::Text(v) => {
-                request = request.body(v);
+        }.instrument(span.clone()).await;
+        span.record("latency_ms", started.elapsed().as_millis() as u64);
+        let response = match result {
+            Ok(response) => response,
+            Err(err) => {
+                tracing::warn!(parent: &span, error = %err, "[请求第三方接口失败]");
+                return Err(err.into());
             }
-            RequestBody::Raw(v) => {
-                request = request.body(v);
-            }
-            RequestBody::Null => {}
+        };
+        span.record("status", response.status().as_u16());
+        if let Some(errcode) = LabraLogConfig::errcode(&response.body) {
+            span.record("errcode", errcode.as_str());
         }
-        if let Some(headers) = &self.headers {
-            for (k, v) in headers.into_iter() {
-                request = request.header(k, HeaderValue::from_str(v)?);
-            }
+        tracing::info!(parent: &span, "[请求第三方接口完成]");
+        if log.log_body {
+            tracing::debug!(parent: &span, body = %log.redact_body(&response.text().unwrap_or_default()), "[请求第三方接口响应]");
+        }
+        for middleware in middlewares {
+            middleware.after_response(&response)?;
         }
-        tracing::info!("[请求第三方接口参数] url: {}, data:{}", http_url.as_str(), data);
-        let result = request.send().await?;
-        let status = result.status();
-        let remote_addr = result.remote_addr();
-        let headers = result.headers();
-        let response = LabraResponse::new(result.url().clone(), status, remote_addr, headers.clone(), result.bytes().await?);
-        tracing::info!("[请求第三方接口响应] data:{}", &response.text().unwrap_or_default());
         Ok(response)
     }
 }
 
+#[allow(unused)]
+impl <T> LabraRequest <T> where T: Serialize {
+    /// 使用指定的传输层发送请求并流式读取响应，经过中间件的`before_request`处理
+    ///
+    /// 响应体没有读入内存，因此不会重试，也不会调用中间件的`after_response`。
+    pub async fn request_stream_with(self, transport: &dyn HttpTransport, middlewares: &[Arc<dyn Middleware>], log: &LabraLogConfig) -> LabradorResult<LabraStreamResponse> {
+        let mut request = self.build()?;
+        for middleware in middlewares {
+            middleware.before_request(&mut request)?;
+        }
+        let span = tracing::info_span!("labra_request",
+            method = %request.method,
+            url = %log.redact_url(&request.url),
+            status = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+        );
+        let started = Instant::now();
+        let result = transport.execute_stream(request).instrument(span.clone()).await;
+        span.record("latency_ms", started.elapsed().as_millis() as u64);
+        match result {
+            Ok(response) => {
+                span.record("status", response.status().as_u16());
+                tracing::info!(parent: &span, "[请求第三方接口完成]");
+                Ok(response)
+            }
+            Err(err) => {
+                tracing::warn!(parent: &span, error = %err, "[请求第三方接口失败]");
+                Err(err.into())
+            }
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct LabraIdentity {
     identity: reqwest::Identity,
+    /// 证书内容的摘要，用于区分不同的客户端证书
+    fingerprint: String,
 }
 
 
@@ -391,6 +570,7 @@ impl LabraIdentity {
         let identity = reqwest::Identity::from_pkcs12_der(&der, password)?;
         Ok(Self {
             identity,
+            fingerprint: fingerprint(&[&der, password.as_bytes()]),
         })
     }
 
@@ -398,6 +578,7 @@ impl LabraIdentity {
         let identity = reqwest::Identity::from_pem(&der)?;
         Ok(Self {
             identity,
+            fingerprint: fingerprint(&[&der]),
         })
     }
 
@@ -405,6 +586,10 @@ impl LabraIdentity {
         self.identity.clone()
     }
 
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
 }
 
 impl LabraCertificate {
@@ -472,6 +657,11 @@ impl LabraCertificate {
     }
 
 
+    /// 证书内容的摘要
+    pub fn fingerprint(&self) -> String {
+        fingerprint(&[&self.content])
+    }
+
     pub fn reqwest_cert(&self) -> LabradorResult<reqwest::Certificate> {
         let cert = reqwest::Certificate::from_pem(self.content.as_ref())?;
         Ok(cert)
@@ -502,7 +692,7 @@ pub async fn request<F>(f: F) -> LabradorResult<LabraResponse>
 where
     F: Fn(reqwest::Client) -> reqwest::RequestBuilder,
 {
-    let result = f(reqwest::Client::new()).send().await?;
+    let result = f(LabraHttpClient::default().client().clone()).send().await?;
     Ok(LabraResponse::new(result.url().clone(), result.status(), result.remote_addr(), result.headers().clone(), result.bytes().await?))
 }
 
@@ -511,6 +701,56 @@ pub fn request_blocking<F>(f: F) -> LabradorResult<LabraResponse>
 where
     F: Fn(reqwest::blocking::Client) -> reqwest::blocking::RequestBuilder,
 {
-    let result = f(reqwest::blocking::Client::new()).send()?;
+    let result = f(DEFAULT_BLOCKING_CLIENT.clone()).send()?;
     Ok(LabraResponse::new(result.url().clone(), result.status(), result.remote_addr(), result.headers().clone(), result.bytes()?))
-}
\ No newline at end of file
+}
+
+/// SHA256摘要的十六进制字符串
+fn fingerprint(parts: &[&[u8]]) -> String {
+    use sha2::Digest;
+    let mut hasher = sha2::Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use crate::{LabraRequest, Method, RequestType};
+    use crate::transport::HttpBody;
+
+    fn body_text(body: &HttpBody) -> String {
+        match body {
+            HttpBody::Bytes(v) => String::from_utf8_lossy(v).to_string(),
+            _ => String::default(),
+        }
+    }
+
+    #[test]
+    fn test_request_body() {
+        let data = json!({"key": "value", "name": "labrador"});
+        let req = LabraRequest::post_form("https://api.weixin.qq.com/cgi-bin/test", &data);
+        let payload = req.body().payload().unwrap();
+        let request = req.build().unwrap();
+        assert_eq!("key=value&name=labrador", payload);
+        assert_eq!(payload, body_text(&request.body));
+        assert_eq!("application/x-www-form-urlencoded;charset=UTF-8", request.headers["content-type"]);
+
+        let req = LabraRequest::post_json("https://api.weixin.qq.com/cgi-bin/test", &data);
+        assert_eq!(Some(RequestType::Json), req.body().request_type());
+        assert_eq!(req.body().payload().unwrap(), body_text(&req.build().unwrap().body));
+
+        let xml = "<xml><mch_id>1</mch_id></xml>".to_string();
+        let request = LabraRequest::post_xml("https://api.mch.weixin.qq.com/pay/orderquery", xml.to_owned()).build().unwrap();
+        assert_eq!(Method::Post, request.method);
+        assert_eq!(xml, body_text(&request.body));
+        assert_eq!("application/xml;charset=UTF-8", request.headers["content-type"]);
+
+        // GET请求没有请求体
+        let req = LabraRequest::get("https://api.weixin.qq.com/cgi-bin/test");
+        assert!(req.body().is_empty());
+        assert_eq!(Method::Get, req.build().unwrap().method);
+    }
+}
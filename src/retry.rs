@@ -0,0 +1,209 @@
+use std::fmt;
+use std::time::Duration;
+use rand::Rng;
+use serde_json::Value;
+
+use crate::LabraResponse;
+
+/// 单次请求的结果
+#[derive(Debug)]
+pub enum AttemptOutcome<'a> {
+    /// 连接阶段失败（DNS、TCP、TLS握手），请求尚未发送到服务端
+    ConnectError,
+    /// 请求超时，服务端可能已经处理了请求
+    Timeout,
+    /// 其他网络错误，如连接被重置
+    RequestError,
+    /// 收到了服务端响应
+    Response(&'a LabraResponse),
+}
+
+/// 重试判断的上下文
+#[derive(Debug)]
+pub struct RetryContext<'a> {
+    /// 已经完成的请求次数，从1开始
+    pub attempt: u32,
+    /// 请求是否幂等
+    pub idempotent: bool,
+    /// 本次请求的结果
+    pub outcome: AttemptOutcome<'a>,
+}
+
+/// 重试策略
+///
+/// 非幂等的请求（如下单、退款）只应该在连接阶段失败时重试，避免重复提交。
+pub trait RetryPolicy: fmt::Debug + Send + Sync {
+    /// 最多请求次数（包含第一次请求）
+    fn max_attempts(&self) -> u32;
+
+    /// 第`attempt`次请求失败后，下一次请求前的等待时间
+    fn backoff(&self, attempt: u32) -> Duration;
+
+    /// 是否需要重试
+    fn should_retry(&self, ctx: &RetryContext) -> bool;
+}
+
+/// 不重试
+#[derive(Debug, Clone, Default)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn max_attempts(&self) -> u32 {
+        1
+    }
+
+    fn backoff(&self, _attempt: u32) -> Duration {
+        Duration::from_millis(0)
+    }
+
+    fn should_retry(&self, _ctx: &RetryContext) -> bool {
+        false
+    }
+}
+
+/// 指数退避重试
+///
+/// 等待时间为`base_delay * 2^(attempt - 1)`，不超过`max_delay`，开启`jitter`时在`[0, 等待时间]`之间随机。
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use labrador::{APIClient, ExponentialBackoff, SimpleStorage};
+///
+/// let policy = ExponentialBackoff::new(3)
+///     .base_delay(Duration::from_millis(200))
+///     .retry_errcodes(vec![-1]);
+/// let client = APIClient::<SimpleStorage>::new("appkey", "secret", "https://api.weixin.qq.com").retry_policy(policy);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct ExponentialBackoff {
+    /// 最多请求次数（包含第一次请求）
+    pub max_attempts: u32,
+    /// 初始等待时间
+    pub base_delay: Duration,
+    /// 最长等待时间
+    pub max_delay: Duration,
+    /// 是否随机等待时间
+    pub jitter: bool,
+    /// 需要重试的HTTP状态码
+    pub retry_statuses: Vec<u16>,
+    /// 需要重试的业务错误码（响应中的`errcode`字段），如微信的`-1`（系统繁忙）
+    pub retry_errcodes: Vec<i64>,
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+            jitter: true,
+            retry_statuses: vec![429, 500, 502, 503, 504],
+            retry_errcodes: vec![],
+        }
+    }
+}
+
+#[allow(unused)]
+impl ExponentialBackoff {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn retry_statuses(mut self, retry_statuses: Vec<u16>) -> Self {
+        self.retry_statuses = retry_statuses;
+        self
+    }
+
+    pub fn retry_errcodes(mut self, retry_errcodes: Vec<i64>) -> Self {
+        self.retry_errcodes = retry_errcodes;
+        self
+    }
+
+    fn is_retryable_response(&self, response: &LabraResponse) -> bool {
+        if self.retry_statuses.contains(&response.status().as_u16()) {
+            return true;
+        }
+        if self.retry_errcodes.is_empty() {
+            return false;
+        }
+        response.json::<Value>().ok()
+            .and_then(|v| v["errcode"].as_i64())
+            .map(|errcode| self.retry_errcodes.contains(&errcode))
+            .unwrap_or(false)
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn max_attempts(&self) -> u32 {
+        self.max_attempts.max(1)
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(16);
+        let delay = self.base_delay.saturating_mul(1 << exp).min(self.max_delay);
+        if self.jitter && !delay.is_zero() {
+            let millis = rand::thread_rng().gen_range(0, delay.as_millis() as u64 + 1);
+            Duration::from_millis(millis)
+        } else {
+            delay
+        }
+    }
+
+    fn should_retry(&self, ctx: &RetryContext) -> bool {
+        match ctx.outcome {
+            AttemptOutcome::ConnectError => true,
+            // 非幂等的请求可能已经被服务端处理，不能重试
+            _ if !ctx.idempotent => false,
+            AttemptOutcome::Timeout | AttemptOutcome::RequestError => true,
+            AttemptOutcome::Response(response) => self.is_retryable_response(response),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::{AttemptOutcome, ExponentialBackoff, RetryContext, RetryPolicy};
+
+    #[test]
+    fn test_backoff() {
+        let policy = ExponentialBackoff::new(5)
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(300))
+            .jitter(false);
+        assert_eq!(Duration::from_millis(100), policy.backoff(1));
+        assert_eq!(Duration::from_millis(200), policy.backoff(2));
+        assert_eq!(Duration::from_millis(300), policy.backoff(3));
+        let policy = policy.jitter(true);
+        assert!(policy.backoff(2) <= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_non_idempotent_only_retry_connect_error() {
+        let policy = ExponentialBackoff::default();
+        assert!(policy.should_retry(&RetryContext { attempt: 1, idempotent: false, outcome: AttemptOutcome::ConnectError }));
+        assert!(!policy.should_retry(&RetryContext { attempt: 1, idempotent: false, outcome: AttemptOutcome::Timeout }));
+        assert!(policy.should_retry(&RetryContext { attempt: 1, idempotent: true, outcome: AttemptOutcome::Timeout }));
+    }
+}
@@ -1,14 +1,176 @@
 use std::{collections::BTreeMap, any::type_name, fmt, error};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
 
 use redis::{FromRedisValue, ToRedisArgs};
 use serde::{Deserialize, Serialize};
-use crate::{get_timestamp, LabradorResult};
+use crate::{get_nonce_str, telemetry, LabraError, LabradorResult};
 
 pub trait SessionStore: Clone {
-    fn get<'a, K: AsRef<str>, T: FromStore>(&self, key: K, default: Option<T>) -> LabradorResult<Option<T>>;
-    fn set<'a, K: AsRef<str>, T: ToStore>(&self, key: K, value: T, ttl: Option<usize>) -> LabradorResult<()>;
+    fn get<K: AsRef<str>, T: FromStore>(&self, key: K, default: Option<T>) -> LabradorResult<Option<T>>;
+    fn set<K: AsRef<str>, T: ToStore>(&self, key: K, value: T, ttl: Option<usize>) -> LabradorResult<()>;
+
+    /// 尝试加锁，`value`为持有者标识，锁在`ttl`后自动释放
+    ///
+    /// 默认实现为进程内的锁，跨进程共享的存储（如Redis）需要覆盖该方法。
+    fn try_lock(&self, key: &str, value: &str, ttl: Duration) -> LabradorResult<bool> {
+        Ok(try_lock_local(&PROCESS_LOCKS, key, value, ttl))
+    }
+
+    /// 释放锁，只有持有者才能释放
+    fn unlock(&self, key: &str, value: &str) -> LabradorResult<()> {
+        PROCESS_LOCKS.remove_if(key, |_, (holder, _)| holder == value);
+        Ok(())
+    }
+}
+
+/// 进程内的锁，键为锁名，值为持有者标识与过期时间
+static PROCESS_LOCKS: Lazy<DashMap<String, (String, Instant)>> = Lazy::new(DashMap::new);
+
+fn try_lock_local(locks: &DashMap<String, (String, Instant)>, key: &str, value: &str, ttl: Duration) -> bool {
+    let now = Instant::now();
+    let mut acquired = false;
+    locks.entry(key.to_string())
+        .and_modify(|(holder, expire_at)| {
+            if now >= *expire_at {
+                *holder = value.to_string();
+                *expire_at = now + ttl;
+                acquired = true;
+            }
+        })
+        .or_insert_with(|| {
+            acquired = true;
+            (value.to_string(), now + ttl)
+        });
+    acquired
+}
+
+/// 异步存储
+///
+/// 平台客户端通过异步存储读写access_token、jsapi_ticket、suite_ticket等凭证。
+/// 所有`SessionStore`都自动实现了该trait（直接调用同步方法），
+/// 在异步环境中使用Redis时建议使用基于tokio连接的`AsyncRedisStorage`，避免阻塞运行时。
+#[async_trait]
+pub trait AsyncSessionStore: Clone + Send + Sync {
+    async fn get<K: AsRef<str> + Send, T: FromStore + Send>(&self, key: K, default: Option<T>) -> LabradorResult<Option<T>>;
+    async fn set<K: AsRef<str> + Send, T: ToStore + Send>(&self, key: K, value: T, ttl: Option<usize>) -> LabradorResult<()>;
+
+    /// 尝试加锁，`value`为持有者标识，锁在`ttl`后自动释放
+    async fn try_lock(&self, key: &str, value: &str, ttl: Duration) -> LabradorResult<bool>;
+
+    /// 释放锁，只有持有者才能释放
+    async fn unlock(&self, key: &str, value: &str) -> LabradorResult<()>;
+}
+
+#[async_trait]
+impl<S: SessionStore + Send + Sync> AsyncSessionStore for S {
+    async fn get<K: AsRef<str> + Send, T: FromStore + Send>(&self, key: K, default: Option<T>) -> LabradorResult<Option<T>> {
+        SessionStore::get(self, key, default)
+    }
+
+    async fn set<K: AsRef<str> + Send, T: ToStore + Send>(&self, key: K, value: T, ttl: Option<usize>) -> LabradorResult<()> {
+        SessionStore::set(self, key, value, ttl)
+    }
+
+    async fn try_lock(&self, key: &str, value: &str, ttl: Duration) -> LabradorResult<bool> {
+        SessionStore::try_lock(self, key, value, ttl)
+    }
+
+    async fn unlock(&self, key: &str, value: &str) -> LabradorResult<()> {
+        SessionStore::unlock(self, key, value)
+    }
+}
+
+/// 凭证刷新锁配置
+///
+/// 多个实例同时刷新access_token时，只有获得锁的实例会请求接口，其他实例等待后重新读取缓存。
+#[derive(Debug, Clone)]
+pub struct LockOptions {
+    /// 锁的超时时间，持有者异常退出时锁在超时后自动释放
+    pub timeout: Duration,
+    /// 未获得锁时重新读取缓存的间隔
+    pub retry_interval: Duration,
+    /// 最长等待时间，超时后返回错误
+    pub max_wait: Duration,
+}
+
+impl Default for LockOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            retry_interval: Duration::from_millis(100),
+            max_wait: Duration::from_secs(15),
+        }
+    }
+}
+
+#[allow(unused)]
+impl LockOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    pub fn max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+}
+
+/// 加锁刷新凭证
+///
+/// `cached`读取缓存中仍然有效的凭证，`refresh`请求接口并写入缓存。`rejected`为接口拒绝的凭证，
+/// 传入时只有缓存中的凭证与它不同（已被其他持有者刷新）才会直接使用，多个请求同时遇到凭证失效时只刷新一次。
+pub async fn refresh_with_lock<S, C, CF, R, RF>(session: &S, lock_key: &str, options: &LockOptions, rejected: Option<&str>, cached: C, refresh: R) -> LabradorResult<String>
+    where
+        S: AsyncSessionStore,
+        C: Fn() -> CF,
+        CF: Future<Output = LabradorResult<Option<String>>>,
+        R: FnOnce() -> RF,
+        RF: Future<Output = LabradorResult<String>>,
+{
+    let is_fresh = |token: &String| rejected != Some(token.as_str());
+    if let Some(token) = cached().await?.filter(is_fresh) {
+        return Ok(token);
+    }
+    let holder = get_nonce_str();
+    let deadline = Instant::now() + options.max_wait;
+    loop {
+        if session.try_lock(lock_key, &holder, options.timeout).await? {
+            // 加锁后重新读取，其他持有者可能已经刷新
+            let result = match cached().await? {
+                Some(token) if is_fresh(&token) => Ok(token),
+                _ => refresh().await,
+            };
+            if let Err(err) = session.unlock(lock_key, &holder).await {
+                tracing::warn!(lock_key, error = %err, "[释放刷新锁失败]");
+            }
+            return result;
+        }
+        tokio::time::sleep(options.retry_interval).await;
+        if let Some(token) = cached().await? {
+            if is_fresh(&token) {
+                return Ok(token);
+            }
+        }
+        if Instant::now() >= deadline {
+            return Err(LabraError::ApiError(format!("等待刷新锁超时：{}", lock_key)));
+        }
+    }
 }
 
 pub trait ToStore {
@@ -95,7 +257,7 @@ impl Number {
     #[inline]
     pub fn is_i64(&self) -> bool {
         match self.n {
-            N::PosInt(v) => v <= i64::max_value() as u64,
+            N::PosInt(v) => v <= i64::MAX as u64,
             N::NegInt(_) => true,
             N::Float(_) => false,
         }
@@ -121,7 +283,7 @@ impl Number {
     pub fn as_i64(&self) -> Option<i64> {
         match self.n {
             N::PosInt(n) => {
-                if n <= i64::max_value() as u64 {
+                if n <= i64::MAX as u64 {
                     Some(n as i64)
                 } else {
                     None
@@ -216,7 +378,7 @@ where T: ToStore {
 impl <T> ToStore for &T 
 where T: ToStore {
     fn to_store(&self) -> Store {
-        T::to_store(&self)
+        T::to_store(self)
     }
 }
 impl ToStore for &str {
@@ -343,56 +505,169 @@ impl error::Error for StoreError {
     }
 }
 
-pub static SIMPLE_STORAGE: Lazy<DashMap<String, (Option<usize>, Store)>> = Lazy::new(|| {
-    DashMap::new()
-});
+/// 过期键的定期清理间隔
+const EVICT_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+struct SimpleEntry {
+    value: Store,
+    /// 过期时间，写入时根据ttl计算
+    expire_at: Option<Instant>,
+    /// 最近访问序号，用于LRU淘汰
+    last_access: AtomicU64,
+}
+
+impl SimpleEntry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expire_at.map(|v| now >= v).unwrap_or(false)
+    }
+}
+
+#[derive(Debug)]
+struct SimpleStorageInner {
+    entries: DashMap<String, SimpleEntry>,
+    capacity: Option<usize>,
+    access_seq: AtomicU64,
+    last_evict: Mutex<Instant>,
+    locks: DashMap<String, (String, Instant)>,
+}
 
+/// 内存存储
+///
+/// 每个实例拥有独立的存储空间，clone后共用。写入时记录过期时间，读取时惰性删除过期键，
+/// 每隔一段时间在读写时顺带清理所有过期键；设置容量后超出部分按照LRU淘汰。
 #[derive(Debug, Clone)]
 pub struct SimpleStorage {
+    inner: Arc<SimpleStorageInner>,
+}
+
+impl Default for SimpleStorage {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
+#[allow(unused)]
 impl SimpleStorage {
     pub fn new() -> SimpleStorage {
-        SimpleStorage {  }
+        Self::build(None)
+    }
+
+    /// 最多保存`capacity`个键，超出后淘汰最久未访问的键
+    pub fn with_capacity(capacity: usize) -> SimpleStorage {
+        Self::build(Some(capacity))
+    }
+
+    fn build(capacity: Option<usize>) -> SimpleStorage {
+        SimpleStorage {
+            inner: Arc::new(SimpleStorageInner {
+                entries: DashMap::new(),
+                capacity,
+                access_seq: AtomicU64::new(0),
+                last_evict: Mutex::new(Instant::now()),
+                locks: DashMap::new(),
+            })
+        }
+    }
+
+    /// 当前保存的键数量（包含尚未清理的过期键）
+    pub fn len(&self) -> usize {
+        self.inner.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.entries.is_empty()
+    }
+
+    pub fn remove<K: AsRef<str>>(&self, key: K) {
+        self.inner.entries.remove(key.as_ref());
+    }
+
+    pub fn clear(&self) {
+        self.inner.entries.clear();
+    }
+
+    /// 清理所有过期键
+    pub fn evict_expired(&self) {
+        let now = Instant::now();
+        self.inner.entries.retain(|_, v| !v.is_expired(now));
+        *self.inner.last_evict.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    fn next_access(&self) -> u64 {
+        self.inner.access_seq.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn evict_if_due(&self) {
+        let due = self.inner.last_evict.lock().map(|v| v.elapsed() >= EVICT_INTERVAL).unwrap_or(true);
+        if due {
+            self.evict_expired();
+        }
+    }
+
+    fn evict_lru(&self, capacity: usize) {
+        if self.inner.entries.len() <= capacity {
+            return;
+        }
+        self.evict_expired();
+        while self.inner.entries.len() > capacity {
+            let oldest = self.inner.entries.iter()
+                .min_by_key(|v| v.value().last_access.load(Ordering::Relaxed))
+                .map(|v| v.key().to_string());
+            match oldest {
+                Some(key) => { self.inner.entries.remove(&key); }
+                None => break,
+            }
+        }
     }
 }
 
 impl SessionStore for SimpleStorage {
-    fn get<'a, K: AsRef<str>, T: FromStore>(&self, key: K, default: Option<T>) -> LabradorResult<Option<T>> {
-        let mut is_expire = false;
+    fn get<K: AsRef<str>, T: FromStore>(&self, key: K, default: Option<T>) -> LabradorResult<Option<T>> {
+        self.evict_if_due();
         let key = key.as_ref();
-        let v = if let Some(v) = SIMPLE_STORAGE.get(&key.to_string()) {
-            let (ttl, value) = v.value();
-            if let Some(ttl) =  ttl {
-                let current_stamp = get_timestamp() as usize;
-                let exipre_at = current_stamp + *ttl;
-                if current_stamp >= exipre_at {
-                    // SIMPLE_STORAGE.remove(key);
-                    is_expire = true;
-                    None
-                } else {
-                    Some(T::from_store(&value))
-                }
-            } else {
-                Some(T::from_store(&value))
+        let now = Instant::now();
+        let mut is_expire = false;
+        let v = match self.inner.entries.get(key) {
+            Some(entry) if entry.is_expired(now) => {
+                is_expire = true;
+                default
             }
-        } else {
-            default
+            Some(entry) => {
+                entry.last_access.store(self.next_access(), Ordering::Relaxed);
+                telemetry::record_session_cache("memory", true);
+                return Ok(Some(T::from_store(&entry.value)));
+            }
+            None => default,
         };
+        telemetry::record_session_cache("memory", false);
         if is_expire {
-            SIMPLE_STORAGE.remove(key);
+            self.inner.entries.remove_if(key, |_, v| v.is_expired(now));
         }
         Ok(v)
     }
 
-    fn set<'a, K: AsRef<str>, T: ToStore>(&self, key: K, value: T, ttl: Option<usize>) -> LabradorResult<()> {
+    fn set<K: AsRef<str>, T: ToStore>(&self, key: K, value: T, ttl: Option<usize>) -> LabradorResult<()> {
+        self.evict_if_due();
         let key = key.as_ref();
-        let ttl = if let Some(ttl) = ttl {
-            Some(ttl)
-        } else {
-            None
+        let entry = SimpleEntry {
+            value: T::to_store(&value),
+            expire_at: ttl.map(|v| Instant::now() + Duration::from_secs(v as u64)),
+            last_access: AtomicU64::new(self.next_access()),
         };
-        SIMPLE_STORAGE.insert(key.to_string(), (ttl, T::to_store(&value)));
+        self.inner.entries.insert(key.to_string(), entry);
+        if let Some(capacity) = self.inner.capacity {
+            self.evict_lru(capacity);
+        }
+        Ok(())
+    }
+
+    fn try_lock(&self, key: &str, value: &str, ttl: Duration) -> LabradorResult<bool> {
+        Ok(try_lock_local(&self.inner.locks, key, value, ttl))
+    }
+
+    fn unlock(&self, key: &str, value: &str) -> LabradorResult<()> {
+        self.inner.locks.remove_if(key, |_, (holder, _)| holder == value);
         Ok(())
     }
 }
@@ -401,11 +676,15 @@ impl SessionStore for SimpleStorage {
 pub mod redis_store {
 
     pub type RedisPool = Pool<redis::Client>;
+    use std::fmt;
+    use std::time::Duration;
+    use async_trait::async_trait;
     use r2d2::{Pool};
-    use redis::{self, ToRedisArgs, ConnectionLike, Commands, FromRedisValue, streams};
-    use crate::{LabradorResult, LabraError};
+    use redis::{self, ToRedisArgs, ConnectionLike, Commands, AsyncCommands, FromRedisValue, streams};
+    use redis::aio::ConnectionManager;
+    use crate::{telemetry, LabradorResult, LabraError};
 
-    use super::{SessionStore, ToStore, FromStore, Store};
+    use super::{AsyncSessionStore, SessionStore, ToStore, FromStore, Store};
 
     #[derive(Debug, Clone)]
     pub struct RedisStorage {
@@ -437,8 +716,7 @@ pub mod redis_store {
         }
 
         fn get_connect(&self) -> RedisPool {
-            let pool = self.client_pool.to_owned();
-            pool
+            self.client_pool.to_owned()
         }
 
        
@@ -448,7 +726,7 @@ pub mod redis_store {
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
             }
-            let s = client.del(key.as_ref())?;
+            let _: () = client.del(key.as_ref())?;
             Ok(())
         }
 
@@ -609,7 +887,7 @@ pub mod redis_store {
             client.xadd_map(key.as_ref(), "*", items).map_err(LabraError::from)
         }
 
-        pub fn xread<'a, K: ToRedisArgs,  ID: ToRedisArgs, RV: FromRedisValue>(&self, keys: &'a [K], ids: &'a [ID]) -> LabradorResult<RV> {
+        pub fn xread<K: ToRedisArgs,  ID: ToRedisArgs, RV: FromRedisValue>(&self, keys: &[K], ids: &[ID]) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -617,7 +895,7 @@ pub mod redis_store {
             client.xread(keys, ids).map_err(LabraError::from)
         }
 
-        pub fn xinfo_consumers<'a, K: ToRedisArgs,  G: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G) -> LabradorResult<RV> {
+        pub fn xinfo_consumers<K: ToRedisArgs,  G: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -625,7 +903,7 @@ pub mod redis_store {
             client.xinfo_consumers(key, group).map_err(LabraError::from)
         }
 
-        pub fn xinfo_groups<'a, K: ToRedisArgs, RV: FromRedisValue>(&self, key: K) -> LabradorResult<RV> {
+        pub fn xinfo_groups<K: ToRedisArgs, RV: FromRedisValue>(&self, key: K) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -633,7 +911,7 @@ pub mod redis_store {
             client.xinfo_groups(key).map_err(LabraError::from)
         }
 
-        pub fn xinfo_stream<'a, K: ToRedisArgs, RV: FromRedisValue>(&self, key: K) -> LabradorResult<RV> {
+        pub fn xinfo_stream<K: ToRedisArgs, RV: FromRedisValue>(&self, key: K) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -641,7 +919,7 @@ pub mod redis_store {
             client.xinfo_stream(key).map_err(LabraError::from)
         }
 
-        pub fn xread_options<'a, K: ToRedisArgs,  ID: ToRedisArgs, RV: FromRedisValue>(&self, keys: &'a [K], ids: &'a [ID], options: &'a streams::StreamReadOptions) -> LabradorResult<RV> {
+        pub fn xread_options<K: ToRedisArgs,  ID: ToRedisArgs, RV: FromRedisValue>(&self, keys: &[K], ids: &[ID], options: &streams::StreamReadOptions) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -649,7 +927,7 @@ pub mod redis_store {
             client.xread_options(keys, ids, options).map_err(LabraError::from)
         }
 
-        pub fn xgroup_create<'a, K: ToRedisArgs, G: ToRedisArgs,  ID: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G, id: ID) -> LabradorResult<RV> {
+        pub fn xgroup_create<K: ToRedisArgs, G: ToRedisArgs,  ID: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G, id: ID) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -657,7 +935,7 @@ pub mod redis_store {
             client.xgroup_create(key, group, id).map_err(LabraError::from)
         }
 
-        pub fn xgroup_delconsumer<'a, K: ToRedisArgs, G: ToRedisArgs,  C: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G, consumer: C) -> LabradorResult<RV> {
+        pub fn xgroup_delconsumer<K: ToRedisArgs, G: ToRedisArgs,  C: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G, consumer: C) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -665,7 +943,7 @@ pub mod redis_store {
             client.xgroup_delconsumer(key, group, consumer).map_err(LabraError::from)
         }
 
-        pub fn xack<'a, K: ToRedisArgs, G: ToRedisArgs,  I: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G, ids: &'a [I]) -> LabradorResult<RV> {
+        pub fn xack<K: ToRedisArgs, G: ToRedisArgs,  I: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G, ids: &[I]) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -673,7 +951,7 @@ pub mod redis_store {
             client.xack(key, group, ids).map_err(LabraError::from)
         }
 
-        pub fn xgroup_create_mkstream<'a, K: ToRedisArgs, G: ToRedisArgs, ID: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G, id: ID) -> LabradorResult<RV> {
+        pub fn xgroup_create_mkstream<K: ToRedisArgs, G: ToRedisArgs, ID: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G, id: ID) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -681,7 +959,7 @@ pub mod redis_store {
             client.xgroup_create_mkstream(key, group, id).map_err(LabraError::from)
         }
 
-        pub fn xgroup_destroy<'a, K: ToRedisArgs, G: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G) -> LabradorResult<RV> {
+        pub fn xgroup_destroy<K: ToRedisArgs, G: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -689,7 +967,7 @@ pub mod redis_store {
             client.xgroup_destroy(key, group).map_err(LabraError::from)
         }
 
-        pub fn xdel<'a, K: ToRedisArgs, ID: ToRedisArgs, RV: FromRedisValue>(&self, key: K, ids: &'a [ID]) -> LabradorResult<RV> {
+        pub fn xdel<K: ToRedisArgs, ID: ToRedisArgs, RV: FromRedisValue>(&self, key: K, ids: &[ID]) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -697,7 +975,7 @@ pub mod redis_store {
             client.xdel(key, ids).map_err(LabraError::from)
         }
 
-        pub fn xpending<'a, K: ToRedisArgs, G: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G) -> LabradorResult<RV> {
+        pub fn xpending<K: ToRedisArgs, G: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -705,7 +983,7 @@ pub mod redis_store {
             client.xpending(key, group).map_err(LabraError::from)
         }
 
-        pub fn xpending_count<'a, K: ToRedisArgs, G: ToRedisArgs, S: ToRedisArgs, E: ToRedisArgs, C: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G, start: S, end: E, count: C) -> LabradorResult<RV> {
+        pub fn xpending_count<K: ToRedisArgs, G: ToRedisArgs, S: ToRedisArgs, E: ToRedisArgs, C: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G, start: S, end: E, count: C) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -713,7 +991,7 @@ pub mod redis_store {
             client.xpending_count(key, group, start, end, count).map_err(LabraError::from)
         }
 
-        pub fn xpending_consumer_count<'a, K: ToRedisArgs, G: ToRedisArgs, S: ToRedisArgs, E: ToRedisArgs, C: ToRedisArgs, CN: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G, start: S, end: E, count: C, consumer: CN) -> LabradorResult<RV> {
+        pub fn xpending_consumer_count<K: ToRedisArgs, G: ToRedisArgs, S: ToRedisArgs, E: ToRedisArgs, C: ToRedisArgs, CN: ToRedisArgs, RV: FromRedisValue>(&self, key: K, group: G, start: S, end: E, count: C, consumer: CN) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -721,7 +999,7 @@ pub mod redis_store {
             client.xpending_consumer_count(key, group, start, end, count, consumer).map_err(LabraError::from)
         }
 
-        pub fn xrevrange<'a, K: ToRedisArgs, E: ToRedisArgs, S: ToRedisArgs, RV: FromRedisValue>(&self, key: K, start: S, end: E) -> LabradorResult<RV> {
+        pub fn xrevrange<K: ToRedisArgs, E: ToRedisArgs, S: ToRedisArgs, RV: FromRedisValue>(&self, key: K, start: S, end: E) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -729,7 +1007,7 @@ pub mod redis_store {
             client.xrevrange(key, end, start).map_err(LabraError::from)
         }
 
-        pub fn xrevrange_all<'a, K: ToRedisArgs, RV: FromRedisValue>(&self, key: K) -> LabradorResult<RV> {
+        pub fn xrevrange_all<K: ToRedisArgs, RV: FromRedisValue>(&self, key: K) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -737,7 +1015,7 @@ pub mod redis_store {
             client.xrevrange_all(key).map_err(LabraError::from)
         }
 
-        pub fn xrevrange_count<'a, K: ToRedisArgs, E: ToRedisArgs, S: ToRedisArgs, C: ToRedisArgs,RV: FromRedisValue>(&self, key: K, start: S, end: E, count: C) -> LabradorResult<RV> {
+        pub fn xrevrange_count<K: ToRedisArgs, E: ToRedisArgs, S: ToRedisArgs, C: ToRedisArgs,RV: FromRedisValue>(&self, key: K, start: S, end: E, count: C) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -745,7 +1023,7 @@ pub mod redis_store {
             client.xrevrange_count(key, end, start, count).map_err(LabraError::from)
         }
 
-        pub fn exists<'a, K: ToRedisArgs,RV: FromRedisValue>(&self, key: K) -> LabradorResult<RV> {
+        pub fn exists<K: ToRedisArgs,RV: FromRedisValue>(&self, key: K) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -753,7 +1031,7 @@ pub mod redis_store {
             client.exists(key).map_err(LabraError::from)
         }
 
-        pub fn expire<'a, K: ToRedisArgs,RV: FromRedisValue>(&self, key: K, seconds: usize) -> LabradorResult<RV> {
+        pub fn expire<K: ToRedisArgs,RV: FromRedisValue>(&self, key: K, seconds: usize) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -761,7 +1039,7 @@ pub mod redis_store {
             client.expire(key, seconds).map_err(LabraError::from)
         }
 
-        pub fn expire_at<'a, K: ToRedisArgs,RV: FromRedisValue>(&self, key: K, ts: usize) -> LabradorResult<RV> {
+        pub fn expire_at<K: ToRedisArgs,RV: FromRedisValue>(&self, key: K, ts: usize) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -769,7 +1047,7 @@ pub mod redis_store {
             client.expire_at(key, ts).map_err(LabraError::from)
         }
 
-        pub fn lpush<'a, K: ToRedisArgs, V: ToRedisArgs, RV: FromRedisValue>(&self, key: K, value: V) -> LabradorResult<RV> {
+        pub fn lpush<K: ToRedisArgs, V: ToRedisArgs, RV: FromRedisValue>(&self, key: K, value: V) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -777,7 +1055,7 @@ pub mod redis_store {
             client.lpush(key, value).map_err(LabraError::from)
         }
 
-        pub fn lpush_exists<'a, K: ToRedisArgs, V: ToRedisArgs, RV: FromRedisValue>(&self, key: K, value: V) -> LabradorResult<RV> {
+        pub fn lpush_exists<K: ToRedisArgs, V: ToRedisArgs, RV: FromRedisValue>(&self, key: K, value: V) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -786,7 +1064,7 @@ pub mod redis_store {
         }
 
         /// 移出并获取列表的第一个元素， 如果列表没有元素会阻塞列表直到等待超时或发现可弹出元素为止。
-        pub fn blpop<'a, K: ToRedisArgs, RV: FromRedisValue>(&self, key: K, timeout: usize) -> LabradorResult<RV> {
+        pub fn blpop<K: ToRedisArgs, RV: FromRedisValue>(&self, key: K, timeout: usize) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -795,7 +1073,7 @@ pub mod redis_store {
         }
 
         /// 移出并获取列表的最后一个元素， 如果列表没有元素会阻塞列表直到等待超时或发现可弹出元素为止。
-        pub fn brpop<'a, K: ToRedisArgs, RV: FromRedisValue>(&self, key: K, timeout: usize) -> LabradorResult<RV> {
+        pub fn brpop<K: ToRedisArgs, RV: FromRedisValue>(&self, key: K, timeout: usize) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -804,7 +1082,7 @@ pub mod redis_store {
         }
 
         /// 移出并获取列表的第一个元素
-        pub fn lpop<'a, K: ToRedisArgs, RV: FromRedisValue>(&self, key: K, count: Option<core::num::NonZeroUsize>) -> LabradorResult<RV> {
+        pub fn lpop<K: ToRedisArgs, RV: FromRedisValue>(&self, key: K, count: Option<core::num::NonZeroUsize>) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -813,7 +1091,7 @@ pub mod redis_store {
         }
 
         /// 通过索引获取列表中的元素
-        pub fn lindex<'a, K: ToRedisArgs, RV: FromRedisValue>(&self, key: K, index: isize) -> LabradorResult<RV> {
+        pub fn lindex<K: ToRedisArgs, RV: FromRedisValue>(&self, key: K, index: isize) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -822,7 +1100,7 @@ pub mod redis_store {
         }
 
         /// 获取列表长度
-        pub fn llen<'a, K: ToRedisArgs, RV: FromRedisValue>(&self, key: K) -> LabradorResult<RV> {
+        pub fn llen<K: ToRedisArgs, RV: FromRedisValue>(&self, key: K) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -831,7 +1109,7 @@ pub mod redis_store {
         }
 
         /// 获取列表指定范围内的元素
-        pub fn lrange<'a, K: ToRedisArgs, RV: FromRedisValue>(&self, key: K, start: isize, stop: isize) -> LabradorResult<RV> {
+        pub fn lrange<K: ToRedisArgs, RV: FromRedisValue>(&self, key: K, start: isize, stop: isize) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -840,7 +1118,7 @@ pub mod redis_store {
         }
 
         /// 在列表中添加一个或多个值
-        pub fn rpush<'a, K: ToRedisArgs, V: ToRedisArgs, RV: FromRedisValue>(&self, key: K, value: V) -> LabradorResult<RV> {
+        pub fn rpush<K: ToRedisArgs, V: ToRedisArgs, RV: FromRedisValue>(&self, key: K, value: V) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -849,7 +1127,7 @@ pub mod redis_store {
         }
 
         /// 在列表中添加一个或多个值
-        pub fn rpush_exists<'a, K: ToRedisArgs, V: ToRedisArgs, RV: FromRedisValue>(&self, key: K, value: V) -> LabradorResult<RV> {
+        pub fn rpush_exists<K: ToRedisArgs, V: ToRedisArgs, RV: FromRedisValue>(&self, key: K, value: V) -> LabradorResult<RV> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
@@ -861,44 +1139,424 @@ pub mod redis_store {
 
     impl SessionStore for RedisStorage {
         
-        fn get<'a, K: AsRef<str>, T: FromStore>(&self, key: K, default: Option<T>) -> LabradorResult<Option<T>> {
+        fn get<K: AsRef<str>, T: FromStore>(&self, key: K, default: Option<T>) -> LabradorResult<Option<T>> {
             let mut client = self.client_pool.get()?;
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
             }
             let data = client.get::<_, Store>(key.as_ref());
             if data.is_err() {
+                telemetry::record_session_cache("redis", false);
                 return Ok(default);
             }
             let v = if let Ok(value) = data {
-                match T::from_store_opt(&value) {
-                    Ok(store) =>Some(store),
-                    Err(_err) => None
-                }
+                T::from_store_opt(&value).ok()
             } else {
                 default
             };
+            telemetry::record_session_cache("redis", v.is_some());
             Ok(v)
         }
 
-        fn set<'a, K: AsRef<str>, T: ToStore>(&self, key: K, value: T, ttl: Option<usize>) -> LabradorResult<()> {
+        fn set<K: AsRef<str>, T: ToStore>(&self, key: K, value: T, ttl: Option<usize>) -> LabradorResult<()> {
             let mut client = self.client_pool.get()?;
             let key = key.as_ref();
             if !client.check_connection() {
                 return Err(LabraError::ApiError("error to get redis connection".to_string()))
             }
             if let Some(seconds) = ttl {
-                let _ = client.set_ex(key, value.to_store(), seconds)?;
+                let _: () = client.set_ex(key, value.to_store(), seconds)?;
             } else {
-                let _ = client.set(key, value.to_store())?;
+                let _: () = client.set(key, value.to_store())?;
             }
 
             Ok(())
         }
+
+        fn try_lock(&self, key: &str, value: &str, ttl: Duration) -> LabradorResult<bool> {
+            let mut client = self.client_pool.get()?;
+            let result: Option<String> = lock_cmd(key, value, ttl).query(&mut *client)?;
+            Ok(result.is_some())
+        }
+
+        fn unlock(&self, key: &str, value: &str) -> LabradorResult<()> {
+            let mut client = self.client_pool.get()?;
+            let _: i64 = redis::Script::new(UNLOCK_SCRIPT).key(key).arg(value).invoke(&mut *client)?;
+            Ok(())
+        }
+    }
+
+    /// 持有者一致时才删除锁
+    const UNLOCK_SCRIPT: &str = r#"if redis.call("GET", KEYS[1]) == ARGV[1] then return redis.call("DEL", KEYS[1]) else return 0 end"#;
+
+    /// SET key value NX PX ttl
+    fn lock_cmd(key: &str, value: &str, ttl: Duration) -> redis::Cmd {
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(key).arg(value).arg("NX").arg("PX").arg(ttl.as_millis() as u64);
+        cmd
+    }
+    /// 基于tokio连接的Redis存储
+    ///
+    /// 内部使用`ConnectionManager`复用单个多路复用连接，断线后自动重连，clone后共用同一个连接。
+    #[derive(Clone)]
+    pub struct AsyncRedisStorage {
+        manager: ConnectionManager,
+    }
+
+    impl fmt::Debug for AsyncRedisStorage {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.debug_struct("AsyncRedisStorage").finish()
+        }
+    }
+
+    #[allow(unused)]
+    impl AsyncRedisStorage {
+        pub async fn new(client: redis::Client) -> LabradorResult<AsyncRedisStorage> {
+            let manager = ConnectionManager::new(client).await?;
+            Ok(AsyncRedisStorage {
+                manager,
+            })
+        }
+
+        pub async fn from_url<U: AsRef<str>>(url: U) -> LabradorResult<AsyncRedisStorage> {
+            let client = redis::Client::open(url.as_ref())?;
+            Self::new(client).await
+        }
+
+        pub fn from_manager(manager: ConnectionManager) -> AsyncRedisStorage {
+            AsyncRedisStorage {
+                manager,
+            }
+        }
+
+        /// 获取连接，clone开销很小
+        pub fn connection(&self) -> ConnectionManager {
+            self.manager.clone()
+        }
+
+        pub async fn del<K: AsRef<str>>(&self, key: K) -> LabradorResult<()> {
+            let mut client = self.connection();
+            let _: () = client.del(key.as_ref()).await?;
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl AsyncSessionStore for AsyncRedisStorage {
+        async fn get<K: AsRef<str> + Send, T: FromStore + Send>(&self, key: K, default: Option<T>) -> LabradorResult<Option<T>> {
+            let mut client = self.connection();
+            let v = match client.get::<_, Store>(key.as_ref()).await {
+                Ok(value) => T::from_store_opt(&value).ok(),
+                Err(_) => {
+                    telemetry::record_session_cache("redis", false);
+                    return Ok(default);
+                }
+            };
+            telemetry::record_session_cache("redis", v.is_some());
+            Ok(v)
+        }
+
+        async fn set<K: AsRef<str> + Send, T: ToStore + Send>(&self, key: K, value: T, ttl: Option<usize>) -> LabradorResult<()> {
+            let mut client = self.connection();
+            let key = key.as_ref();
+            let value = value.to_store();
+            if let Some(seconds) = ttl {
+                let _: () = client.set_ex(key, value, seconds).await?;
+            } else {
+                let _: () = client.set(key, value).await?;
+            }
+            Ok(())
+        }
+
+        async fn try_lock(&self, key: &str, value: &str, ttl: Duration) -> LabradorResult<bool> {
+            let mut client = self.connection();
+            let result: Option<String> = lock_cmd(key, value, ttl).query_async(&mut client).await?;
+            Ok(result.is_some())
+        }
+
+        async fn unlock(&self, key: &str, value: &str) -> LabradorResult<()> {
+            let mut client = self.connection();
+            let _: i64 = redis::Script::new(UNLOCK_SCRIPT).key(key).arg(value).invoke_async(&mut client).await?;
+            Ok(())
+        }
     }
 }
 
 
+
+#[cfg(feature = "file-store")]
+pub mod file_store {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex, MutexGuard, Weak};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+    use async_trait::async_trait;
+    use once_cell::sync::Lazy;
+    use serde::{Deserialize, Serialize};
+    use crate::{current_timestamp, get_nonce_str, telemetry, LabradorResult, LabraError};
+
+    use super::{try_lock_local, AsyncSessionStore, ToStore, FromStore, Store, PROCESS_LOCKS};
+
+    /// 已打开的存储文件，键为规范化后的路径
+    static OPEN_FILES: Lazy<Mutex<HashMap<PathBuf, Weak<FileStoreInner>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct FileEntry {
+        value: Store,
+        /// 过期时间（秒级时间戳）
+        expire_at: Option<i64>,
+    }
+
+    impl FileEntry {
+        fn is_expired(&self, now: i64) -> bool {
+            self.expire_at.map(|v| now >= v).unwrap_or(false)
+        }
+    }
+
+    /// 同一文件的所有实例共用的数据
+    #[derive(Debug)]
+    struct FileStoreInner {
+        path: PathBuf,
+        entries: Mutex<HashMap<String, FileEntry>>,
+        /// 每次修改递增
+        version: AtomicU64,
+        /// 已经写入文件的版本，写文件时持有，较旧的快照不会覆盖较新的
+        written: Mutex<u64>,
+    }
+
+    impl FileStoreInner {
+        fn load(path: PathBuf) -> LabradorResult<FileStoreInner> {
+            let mut entries: HashMap<String, FileEntry> = if path.exists() {
+                let content = fs::read(&path)?;
+                if content.is_empty() { HashMap::new() } else { serde_json::from_slice(&content)? }
+            } else {
+                HashMap::new()
+            };
+            let now = current_timestamp();
+            entries.retain(|_, v| !v.is_expired(now));
+            Ok(FileStoreInner {
+                path,
+                entries: Mutex::new(entries),
+                version: AtomicU64::new(0),
+                written: Mutex::new(0),
+            })
+        }
+
+        /// 写入临时文件后重命名，替换原文件
+        fn persist(&self, version: u64, content: &[u8]) -> LabradorResult<()> {
+            let mut written = self.written.lock().unwrap_or_else(|e| e.into_inner());
+            if *written >= version {
+                return Ok(());
+            }
+            let mut tmp = self.path.clone().into_os_string();
+            tmp.push(format!(".{}.{}.tmp", std::process::id(), get_nonce_str()));
+            let tmp = PathBuf::from(tmp);
+            if let Some(dir) = self.path.parent().filter(|v| !v.as_os_str().is_empty()) {
+                fs::create_dir_all(dir)?;
+            }
+            let result = fs::File::create(&tmp)
+                .and_then(|mut file| file.write_all(content).and_then(|_| file.sync_all()))
+                .and_then(|_| fs::rename(&tmp, &self.path));
+            if let Err(err) = result {
+                let _ = fs::remove_file(&tmp);
+                return Err(LabraError::from(err));
+            }
+            *written = version;
+            Ok(())
+        }
+    }
+
+    /// 文件存储
+    ///
+    /// 所有键保存在一个JSON文件中，进程重启后access_token、suite_ticket等凭证不会丢失，适合单机部署。
+    /// 同一进程内打开同一个文件的实例共用一份数据；写入时在阻塞线程中先写临时文件再重命名，
+    /// 保证文件不会因为进程中断而损坏；过期键在加载与写入时清理。
+    /// 多个客户端共用同一个文件时可以通过`namespace`隔离键。
+    #[derive(Debug, Clone)]
+    pub struct FileStorage {
+        namespace: Option<String>,
+        inner: Arc<FileStoreInner>,
+    }
+
+    #[allow(unused)]
+    impl FileStorage {
+        /// 打开存储文件，文件不存在时会在第一次写入时创建
+        pub fn open<P: AsRef<Path>>(path: P) -> LabradorResult<FileStorage> {
+            let path = canonical_path(path.as_ref())?;
+            let mut files = OPEN_FILES.lock().unwrap_or_else(|e| e.into_inner());
+            files.retain(|_, v| v.strong_count() > 0);
+            let inner = match files.get(&path).and_then(Weak::upgrade) {
+                Some(inner) => inner,
+                None => {
+                    let inner = Arc::new(FileStoreInner::load(path.to_owned())?);
+                    files.insert(path, Arc::downgrade(&inner));
+                    inner
+                }
+            };
+            Ok(FileStorage {
+                namespace: None,
+                inner,
+            })
+        }
+
+        /// 设置键的命名空间，实际保存的键为`namespace:key`
+        pub fn namespace<S: Into<String>>(mut self, namespace: S) -> Self {
+            self.namespace = Some(namespace.into());
+            self
+        }
+
+        pub fn path(&self) -> &Path {
+            &self.inner.path
+        }
+
+        pub async fn del<K: AsRef<str>>(&self, key: K) -> LabradorResult<()> {
+            let key = self.key(key.as_ref());
+            let snapshot = {
+                let mut entries = self.lock();
+                match entries.remove(&key) {
+                    Some(_) => Some(self.snapshot(&entries)?),
+                    None => None,
+                }
+            };
+            match snapshot {
+                Some((version, content)) => self.persist(version, content).await,
+                None => Ok(()),
+            }
+        }
+
+        fn key(&self, key: &str) -> String {
+            match &self.namespace {
+                Some(namespace) => format!("{}:{}", namespace, key),
+                None => key.to_string(),
+            }
+        }
+
+        fn lock(&self) -> MutexGuard<'_, HashMap<String, FileEntry>> {
+            self.inner.entries.lock().unwrap_or_else(|e| e.into_inner())
+        }
+
+        /// 持有数据锁时生成版本号与文件内容
+        fn snapshot(&self, entries: &HashMap<String, FileEntry>) -> LabradorResult<(u64, Vec<u8>)> {
+            let content = serde_json::to_vec(entries)?;
+            Ok((self.inner.version.fetch_add(1, Ordering::SeqCst) + 1, content))
+        }
+
+        /// 在阻塞线程中写文件，不占用数据锁
+        async fn persist(&self, version: u64, content: Vec<u8>) -> LabradorResult<()> {
+            let inner = self.inner.clone();
+            tokio::task::spawn_blocking(move || inner.persist(version, &content)).await
+                .map_err(|err| LabraError::ApiError(format!("写入存储文件失败：{}", err)))?
+        }
+    }
+
+    /// 规范化路径，文件不存在时规范化所在目录
+    fn canonical_path(path: &Path) -> LabradorResult<PathBuf> {
+        if path.exists() {
+            return Ok(fs::canonicalize(path)?);
+        }
+        let path = if path.is_absolute() { path.to_path_buf() } else { std::env::current_dir()?.join(path) };
+        match (path.parent(), path.file_name()) {
+            (Some(dir), Some(name)) if dir.exists() => Ok(fs::canonicalize(dir)?.join(name)),
+            _ => Ok(path),
+        }
+    }
+
+    #[async_trait]
+    impl AsyncSessionStore for FileStorage {
+        async fn get<K: AsRef<str> + Send, T: FromStore + Send>(&self, key: K, default: Option<T>) -> LabradorResult<Option<T>> {
+            let key = self.key(key.as_ref());
+            let entries = self.lock();
+            let v = match entries.get(&key) {
+                Some(entry) if !entry.is_expired(current_timestamp()) => T::from_store_opt(&entry.value).ok(),
+                _ => {
+                    telemetry::record_session_cache("file", false);
+                    return Ok(default);
+                }
+            };
+            telemetry::record_session_cache("file", v.is_some());
+            Ok(v)
+        }
+
+        async fn set<K: AsRef<str> + Send, T: ToStore + Send>(&self, key: K, value: T, ttl: Option<usize>) -> LabradorResult<()> {
+            let key = self.key(key.as_ref());
+            let now = current_timestamp();
+            let (version, content) = {
+                let mut entries = self.lock();
+                entries.retain(|_, v| !v.is_expired(now));
+                entries.insert(key, FileEntry {
+                    value: value.to_store(),
+                    expire_at: ttl.map(|v| now + v as i64),
+                });
+                self.snapshot(&entries)?
+            };
+            self.persist(version, content).await
+        }
+
+        async fn try_lock(&self, key: &str, value: &str, ttl: Duration) -> LabradorResult<bool> {
+            Ok(try_lock_local(&PROCESS_LOCKS, &self.key(key), value, ttl))
+        }
+
+        async fn unlock(&self, key: &str, value: &str) -> LabradorResult<()> {
+            PROCESS_LOCKS.remove_if(&self.key(key), |_, (holder, _)| holder == value);
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use serde_json::Value;
+        use crate::AsyncSessionStore;
+        use super::FileStorage;
+
+        #[tokio::test]
+        async fn test_file_storage() {
+            let path = std::env::temp_dir().join(format!("labrador_file_storage_{}.json", std::process::id()));
+            {
+                let session = FileStorage::open(&path).unwrap().namespace("wechat");
+                session.set("access_token", "token", Some(7200)).await.unwrap();
+                session.set("expired", "token", Some(0)).await.unwrap();
+            }
+            // 重新打开后数据仍然存在
+            let session = FileStorage::open(&path).unwrap().namespace("wechat");
+            let v: Option<String> = session.get("access_token", None).await.unwrap();
+            assert_eq!(Some("token".to_string()), v);
+            let v: Option<String> = session.get("expired", None).await.unwrap();
+            assert_eq!(None, v);
+            let other = FileStorage::open(&path).unwrap().namespace("alipay");
+            let v: Option<String> = other.get("access_token", None).await.unwrap();
+            assert_eq!(None, v);
+            std::fs::remove_file(&path).unwrap();
+        }
+
+        #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+        async fn test_file_storage_shared_path() {
+            let dir = std::env::temp_dir().join(format!("labrador_file_storage_shared_{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join("session.json");
+            // 不同写法的同一路径共用一份数据，互相写入不会丢失对方的键
+            let a = FileStorage::open(&path).unwrap();
+            let b = FileStorage::open(dir.join(".").join("session.json")).unwrap();
+            let tasks = (0..20).map(|i| {
+                let session = if i % 2 == 0 { a.clone() } else { b.clone() };
+                tokio::spawn(async move { session.set(format!("key{}", i), i, None).await })
+            }).collect::<Vec<_>>();
+            for task in tasks {
+                task.await.unwrap().unwrap();
+            }
+            let v: Option<i32> = b.get("key0", None).await.unwrap();
+            assert_eq!(Some(0), v);
+            let content = serde_json::from_slice::<Value>(&std::fs::read(&path).unwrap()).unwrap();
+            assert_eq!(20, content.as_object().unwrap().len());
+            // 临时文件都已重命名
+            assert_eq!(1, std::fs::read_dir(&dir).unwrap().count());
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+}
+
 #[test]
 fn test_simple() {
     println!("ssssssss");
@@ -912,4 +1570,61 @@ fn test_simple() {
     // let v = session.get::<&str, String>("a", None).unwrap();
     //
     // println!("v:{}" , v.unwrap_or_default());
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_async_session_store() {
+    let session = SimpleStorage::new();
+    AsyncSessionStore::set(&session, "labrador_async_session_key", "value", Some(60)).await.unwrap();
+    let v: Option<String> = AsyncSessionStore::get(&session, "labrador_async_session_key", None).await.unwrap();
+    assert_eq!(Some("value".to_string()), v);
+}
+
+#[test]
+fn test_simple_storage_expire() {
+    let session = SimpleStorage::new();
+    SessionStore::set(&session, "key", "value", Some(0)).unwrap();
+    SessionStore::set(&session, "forever", "value", None).unwrap();
+    let v: Option<String> = SessionStore::get(&session, "key", None).unwrap();
+    assert_eq!(None, v);
+    assert_eq!(1, session.len());
+    // 实例之间互不影响
+    let other = SimpleStorage::new();
+    let v: Option<String> = SessionStore::get(&other, "forever", None).unwrap();
+    assert_eq!(None, v);
+}
+
+#[test]
+fn test_simple_storage_capacity() {
+    let session = SimpleStorage::with_capacity(2);
+    SessionStore::set(&session, "a", 1, None).unwrap();
+    SessionStore::set(&session, "b", 2, None).unwrap();
+    let _: Option<i32> = SessionStore::get(&session, "a", None).unwrap();
+    SessionStore::set(&session, "c", 3, None).unwrap();
+    assert_eq!(2, session.len());
+    let b: Option<i32> = SessionStore::get(&session, "b", None).unwrap();
+    let a: Option<i32> = SessionStore::get(&session, "a", None).unwrap();
+    assert_eq!(None, b);
+    assert_eq!(Some(1), a);
+}
+
+#[tokio::test]
+async fn test_refresh_with_lock() {
+    use std::sync::atomic::AtomicUsize;
+    let session = SimpleStorage::new();
+    let options = LockOptions::new().retry_interval(Duration::from_millis(10));
+    let refreshed = AtomicUsize::new(0);
+    let cached = || async { AsyncSessionStore::get::<_, String>(&session, "token", None).await };
+    let refresh = || async {
+        refreshed.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        AsyncSessionStore::set(&session, "token", "value", Some(60)).await?;
+        Ok("value".to_string())
+    };
+    let (a, b) = tokio::join!(
+        refresh_with_lock(&session, "token_lock", &options, None, cached, refresh),
+        refresh_with_lock(&session, "token_lock", &options, None, cached, refresh),
+    );
+    assert_eq!("value", a.unwrap());
+    assert_eq!("value", b.unwrap());
+    assert_eq!(1, refreshed.load(Ordering::SeqCst));
+}
@@ -0,0 +1,193 @@
+//! 流式上传与下载
+//!
+//! 下载大文件（视频素材、账单等）时通过[`LabraStreamResponse`]边读边写，避免将整个响应读入内存；
+//! 上传时通过[`stream_part`]从`AsyncRead`生成multipart表单项。
+//!
+//! # Examples
+//!
+//! ```ignore
+//! let response = client.media().get_media_stream("media_id").await?;
+//! response.save_to("/tmp/video.mp4", |downloaded, total| {
+//!     println!("{}/{:?}", downloaded, total);
+//! }).await?;
+//! ```
+//!
+use std::fmt;
+use std::path::Path;
+use std::pin::Pin;
+use bytes::{Bytes, BytesMut};
+use futures_util::stream::{self, Stream, StreamExt};
+use reqwest::{multipart, Body, StatusCode, Url};
+use reqwest::header::{HeaderMap, CONTENT_LENGTH, CONTENT_TYPE};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+
+use crate::{LabraResponse, LabradorResult};
+
+/// 响应体数据流
+pub type ByteStream = Pin<Box<dyn Stream<Item = LabradorResult<Bytes>> + Send>>;
+
+/// 流式响应，响应体按块读取
+pub struct LabraStreamResponse {
+    url: Url,
+    status: StatusCode,
+    headers: HeaderMap,
+    body: ByteStream,
+}
+
+impl fmt::Debug for LabraStreamResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LabraStreamResponse")
+            .field("url", &self.url)
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish()
+    }
+}
+
+impl From<LabraResponse> for LabraStreamResponse {
+    fn from(response: LabraResponse) -> Self {
+        let body = response.bytes().unwrap_or_default();
+        Self::new(response.url().clone(), response.status(), response.header().clone(), Box::pin(stream::once(async move { Ok(body) })))
+    }
+}
+
+#[allow(unused)]
+impl LabraStreamResponse {
+    pub fn new(url: Url, status: StatusCode, headers: HeaderMap, body: ByteStream) -> Self {
+        Self {
+            url,
+            status,
+            headers,
+            body,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub fn header(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// 响应体长度，服务端没有返回Content-Length时为`None`
+    pub fn content_length(&self) -> Option<u64> {
+        self.headers.get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+    }
+
+    /// 响应是否为JSON，下载文件的接口出错时一般返回JSON格式的数据
+    pub fn is_json(&self) -> bool {
+        self.headers.get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.contains("json") || v.starts_with("text/plain"))
+            .unwrap_or(false)
+    }
+
+    /// 响应体数据流
+    pub fn into_stream(self) -> ByteStream {
+        self.body
+    }
+
+    /// 读取完整的响应体
+    pub async fn into_response(self) -> LabradorResult<LabraResponse> {
+        let Self { url, status, headers, mut body } = self;
+        let mut buf = BytesMut::new();
+        while let Some(chunk) = body.next().await {
+            buf.extend_from_slice(&chunk?);
+        }
+        Ok(LabraResponse::new(url, status, None, headers, buf.freeze()))
+    }
+
+    /// 写入`writer`，每写入一块调用一次`progress(已写入字节数, 总字节数)`，返回写入的字节数
+    pub async fn write_to<W, F>(self, writer: &mut W, mut progress: F) -> LabradorResult<u64>
+        where W: AsyncWrite + Unpin, F: FnMut(u64, Option<u64>) {
+        let total = self.content_length();
+        let mut body = self.body;
+        let mut written = 0u64;
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk?;
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+            progress(written, total);
+        }
+        writer.flush().await?;
+        Ok(written)
+    }
+
+    /// 保存到文件，文件已存在时覆盖
+    pub async fn save_to<P, F>(self, path: P, progress: F) -> LabradorResult<u64>
+        where P: AsRef<Path>, F: FnMut(u64, Option<u64>) {
+        let mut file = tokio::fs::File::create(path).await?;
+        self.write_to(&mut file, progress).await
+    }
+}
+
+/// 从`AsyncRead`生成multipart表单项，`length`为数据长度，不知道长度时使用分块传输
+pub fn stream_part<R, S>(reader: R, file_name: S, length: Option<u64>) -> multipart::Part
+    where R: AsyncRead + Send + Sync + 'static, S: Into<String> {
+    let body = Body::wrap_stream(ReaderStream::new(reader));
+    let part = match length {
+        Some(length) => multipart::Part::stream_with_length(body, length),
+        None => multipart::Part::stream(body),
+    };
+    part.file_name(file_name.into())
+}
+
+/// 打开文件并生成multipart表单项，文件名为`path`中的文件名
+pub async fn file_part<P: AsRef<Path>>(path: P) -> LabradorResult<multipart::Part> {
+    let path = path.as_ref();
+    let file_name = path.file_name().and_then(|v| v.to_str()).unwrap_or_default().to_string();
+    let file = tokio::fs::File::open(path).await?;
+    let length = file.metadata().await?.len();
+    Ok(stream_part(file, file_name, length.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures_util::stream;
+    use reqwest::{StatusCode, Url};
+    use reqwest::header::{HeaderMap, HeaderValue, CONTENT_LENGTH};
+    use crate::{APIClient, LabraRequest, MockResponse, MockTransport, SimpleStorage};
+    use super::LabraStreamResponse;
+
+    #[tokio::test]
+    async fn test_write_to() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_LENGTH, HeaderValue::from_static("6"));
+        let chunks = vec![Ok(Bytes::from("abc")), Ok(Bytes::from("def"))];
+        let response = LabraStreamResponse::new(Url::parse("https://api.weixin.qq.com/cgi-bin/media/get").unwrap(), StatusCode::OK, headers, Box::pin(stream::iter(chunks)));
+        assert!(!response.is_json());
+        let mut progress = Vec::new();
+        let mut buf = Vec::new();
+        let written = response.write_to(&mut buf, |downloaded, total| progress.push((downloaded, total))).await.unwrap();
+        assert_eq!(6, written);
+        assert_eq!(b"abcdef".to_vec(), buf);
+        assert_eq!(vec![(3, Some(6)), (6, Some(6))], progress);
+    }
+
+    #[tokio::test]
+    async fn test_request_stream() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, "file content").header("content-type", "image/jpeg"))
+            .push_response(MockResponse::new(200, r#"{"errcode":40007,"errmsg":"invalid media_id"}"#).header("content-type", "application/json"));
+        let client = APIClient::<SimpleStorage>::new("appkey", "secret", "https://api.weixin.qq.com").transport(transport.clone());
+        let path = std::env::temp_dir().join(format!("labrador_stream_{}", std::process::id()));
+        let response = client.request_stream(LabraRequest::get("/cgi-bin/media/get")).await.unwrap();
+        assert_eq!(12, response.save_to(&path, |_, _| {}).await.unwrap());
+        assert_eq!("file content", std::fs::read_to_string(&path).unwrap());
+        let _ = std::fs::remove_file(&path);
+
+        let response = client.request_stream(LabraRequest::get("/cgi-bin/media/get")).await.unwrap();
+        assert!(response.is_json());
+        assert_eq!(40007, response.into_response().await.unwrap().json::<serde_json::Value>().unwrap()["errcode"]);
+        assert_eq!("https://api.weixin.qq.com/cgi-bin/media/get", transport.requests()[0].url.as_str());
+    }
+}
@@ -52,4 +52,9 @@ impl RequestMethod for TaobaoMethod {
             TaobaoMethod::GetActivityInfo => String::from("tbk_activity_info_get_response"),
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        // 创建淘口令每次都会生成新的口令
+        !matches!(self, TaobaoMethod::CreateTPwd)
+    }
 }
@@ -1,22 +1,25 @@
+use std::fmt;
 use chrono::Local;
-use crate::{client::{APIClient}, request::{RequestType, Method, LabraRequest, RequestMethod}, errors::LabraError, session::{SimpleStorage, SessionStore}, LabradorResult, RequestParametersHolder, md5};
+use crate::{client::{APIClient}, request::{LabraRequest, RequestMethod}, errors::LabraError, session::{SimpleStorage, AsyncSessionStore}, LabradorResult, RequestParametersHolder, md5, LabraHttpClient, LabraHttpConfig, HttpTransport, Middleware, LabraLogConfig, RetryPolicy, RateLimiter};
+use crate::config::{Proxy, Secret};
 
 use std::collections::BTreeMap;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use crate::taobao::constants::{FORMAT_JSON, SIGN_TYPE_MD5, VERSION_2};
-use crate::taobao::request::{TbGetActivityInfoRequest, TbSpreadGetRequest, TbTPwdReportGetRequest};
-use crate::taobao::response::{TaobaoResponse, TbGetActivityInfoResponse, TbSpreadGetResponse, TbTPwdReportGetResponse};
 
-use self::{method::TaobaoMethod, request::{TbMaterialSelectRequest, TbJhsSearchRequest, TbItemDetailRequest, TbCouponDetailRequest, TbCreateTPwdRequest, TbMaterialSearchRequest}, response::{TbMaterialSelectResponse, TbJhsSearchResponse, TbItemDetailResponse, TbCreateTPwdResponse, TbMaterialSearchResponse, TbCouponDetailResponse}};
+use self::method::TaobaoMethod;
 
 mod request;
 mod response;
 mod method;
 mod constants;
 
+pub use request::*;
+pub use response::*;
+
 #[allow(unused)]
 #[derive(Debug, Clone)]
-pub struct TaobaoClient<T: SessionStore> {
+pub struct TaobaoClient<T: AsyncSessionStore> {
     api_client: APIClient<T>,
     /// 格式类型
     format: String,
@@ -24,6 +27,34 @@ pub struct TaobaoClient<T: SessionStore> {
     sign_method: String,
 }
 
+/// 淘宝开放平台配置
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct TaobaoConfig {
+    pub app_key: String,
+    pub secret: String,
+    /// 接口地址，用于测试环境或本地模拟服务
+    pub api_path: Option<String>,
+    /// 代理地址，如：http://127.0.0.1:8080
+    pub proxy: Option<String>,
+}
+
+impl fmt::Debug for TaobaoConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaobaoConfig")
+            .field("app_key", &self.app_key)
+            .field("secret", &Secret(&self.secret))
+            .field("api_path", &self.api_path)
+            .field("proxy", &Proxy::option(&self.proxy))
+            .finish()
+    }
+}
+
+impl TaobaoConfig {
+    pub fn new<S: Into<String>>(app_key: S, secret: S) -> Self {
+        Self { app_key: app_key.into(), secret: secret.into(), api_path: None, proxy: None }
+    }
+}
+
 
 
 pub trait TaobaoRequest {
@@ -75,11 +106,11 @@ pub trait TaobaoRequest {
 /// ```
 /// 
 #[allow(unused)]
-impl <T: SessionStore> TaobaoClient<T> {
+impl <T: AsyncSessionStore> TaobaoClient<T> {
 
     pub fn new<Q: Into<String>, S: Into<String>>(app_key: Q, secret: S) -> TaobaoClient<SimpleStorage> {
         TaobaoClient {
-            api_client: APIClient::<SimpleStorage>::new::<Q, String, S>(app_key, secret, "http://gw.api.taobao.com/router/rest".to_owned()),
+            api_client: APIClient::<SimpleStorage>::new::<Q, String, S>(app_key, secret, "http://gw.api.taobao.com/router/rest".to_owned()).platform("taobao"),
             format: FORMAT_JSON.to_string(),
             sign_method: SIGN_TYPE_MD5.to_string()
         }
@@ -87,12 +118,85 @@ impl <T: SessionStore> TaobaoClient<T> {
 
     pub fn from_session<Q: Into<String>, S: Into<String>>(app_key: Q, secret: S, session: T) -> TaobaoClient<T> {
         TaobaoClient {
-            api_client: APIClient::from_session(app_key, secret, String::from("http://gw.api.taobao.com/router/rest"), session),
+            api_client: APIClient::from_session(app_key, secret, String::from("http://gw.api.taobao.com/router/rest"), session).platform("taobao"),
             format: FORMAT_JSON.to_string(),
             sign_method: SIGN_TYPE_MD5.to_string()
         }
     }
 
+    /// 根据配置创建客户端
+    pub fn from_config(config: &TaobaoConfig, session: T) -> LabradorResult<TaobaoClient<T>> {
+        let mut client = Self::from_session(config.app_key.as_str(), config.secret.as_str(), session);
+        if let Some(api_path) = config.api_path.as_ref() {
+            client = client.api_path(api_path.as_str());
+        }
+        if let Some(proxy) = config.proxy.as_ref() {
+            client = client.proxy(proxy)?;
+        }
+        Ok(client)
+    }
+
+    /// 设置HTTP客户端，多个客户端可共用同一个连接池
+    pub fn http_client(mut self, http_client: LabraHttpClient) -> Self {
+        self.api_client = self.api_client.http_client(http_client);
+        self
+    }
+
+    /// 根据配置生成独立的HTTP客户端，可设置代理、超时等
+    pub fn http_config(mut self, config: LabraHttpConfig) -> LabradorResult<Self> {
+        self.api_client = self.api_client.http_config(config)?;
+        Ok(self)
+    }
+
+    /// 设置代理，如：http://127.0.0.1:8080，开启`socks`特性后支持socks5
+    pub fn proxy(mut self, proxy: &str) -> LabradorResult<Self> {
+        self.api_client = self.api_client.proxy(proxy)?;
+        Ok(self)
+    }
+
+    /// 设置接口地址，可指向测试环境或本地的模拟服务
+    pub fn api_path<S: Into<String>>(mut self, api_path: S) -> Self {
+        self.api_client = self.api_client.api_path(api_path);
+        self
+    }
+
+    /// 设置传输层，测试时可替换为`MockTransport`
+    pub fn transport<H: HttpTransport + 'static>(mut self, transport: H) -> Self {
+        self.api_client = self.api_client.transport(transport);
+        self
+    }
+
+    /// 同步客户端使用默认的传输层时改用专用的连接池
+    #[cfg(feature = "blocking")]
+    pub(crate) fn blocking_transport(mut self) -> Self {
+        self.api_client = self.api_client.blocking_transport();
+        self
+    }
+
+    /// 添加中间件
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.api_client = self.api_client.middleware(middleware);
+        self
+    }
+
+    /// 设置请求日志配置
+    pub fn log_config(mut self, log_config: LabraLogConfig) -> Self {
+        self.api_client = self.api_client.log_config(log_config);
+        self
+    }
+
+    /// 设置重试策略，除创建淘口令外的接口都是只读的，任何失败都可以重试；创建淘口令只会在连接阶段失败时重试
+    pub fn retry_policy<P: RetryPolicy + 'static>(mut self, retry_policy: P) -> Self {
+        self.api_client = self.api_client.retry_policy(retry_policy);
+        self
+    }
+
+    /// 设置限流器，按接口方法限流并统计每日调用次数
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.api_client = self.api_client.rate_limiter(rate_limiter);
+        self
+    }
+
     /// 签名
     fn sign(&self, sign_content: &str) -> LabradorResult<String> {
         match self.sign_method.as_str() {
@@ -156,9 +260,11 @@ impl <T: SessionStore> TaobaoClient<T> {
         let method = request.get_api_method_name();
         let holder = self.get_request_holder_with_sign(&request)?;
         let url = self.get_request_url(&holder)?;
-        let req = LabraRequest::new().url(url).method(Method::Post).form(request).req_type(RequestType::Form);
-        let result = self.api_client.request(req).await?.text()?;
-        TaobaoResponse::parse(&result, method)
+        let req = LabraRequest::post_form(url, request).api_method(method.get_name()).idempotent(method.is_idempotent());
+        let response = self.api_client.request(req).await?;
+        let mut result = TaobaoResponse::parse(&response.text()?, method).map_err(|err| err.with_response(&response))?;
+        result.http_status = response.status().as_u16().into();
+        Ok(result)
     }
 
     /// 获取淘宝客物料精选
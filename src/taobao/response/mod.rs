@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use serde::de::DeserializeOwned;
 use serde_json::{Value as JsonValue, Value};
 
-use crate::{errors::LabraError, LabradorResult, RequestMethod};
+use crate::{errors::{LabraError, Platform, PlatformError}, LabradorResult, RequestMethod};
 use crate::taobao::constants::ERROR_RESPONSE_KEY;
 
 // 淘宝 ↓
@@ -14,6 +14,9 @@ pub struct TaobaoResponse {
     pub sub_code: Option<String>,
     pub sub_msg: Option<String>,
     pub body: Option<String>,
+    /// 响应的HTTP状态码
+    #[serde(skip)]
+    pub http_status: Option<u16>,
 }
 
 
@@ -26,6 +29,7 @@ impl TaobaoResponse {
             sub_code: None,
             sub_msg: None,
             body: None,
+            http_status: None,
         }
     }
 
@@ -35,7 +39,10 @@ impl TaobaoResponse {
         let err= &v[ERROR_RESPONSE_KEY];
         if !err.is_null() {
             let resp = serde_json::from_str::<Self>(&err.to_string()).unwrap_or(TaobaoResponse::new());
-            Err(LabraError::ClientError {errcode: resp.code.to_owned().unwrap_or_default().to_string(), errmsg: resp.msg.to_owned().unwrap_or_default()})
+            Err(PlatformError::new(Platform::Taobao, resp.code.unwrap_or_default() as i64, resp.sub_msg.or(resp.msg).unwrap_or_default())
+                .sub_code(resp.sub_code.unwrap_or_default())
+                .request_id(resp.request_id.unwrap_or_default())
+                .into())
         } else {
             let response = &v[&method.get_response_key()];
             if !response.is_null() {
@@ -60,7 +67,11 @@ impl TaobaoResponse {
         if self.is_success() {
             serde_json::from_str::<T>(&self.body.to_owned().unwrap_or_default()).map_err(LabraError::from)
         } else {
-            Err(LabraError::ClientError { errcode: self.code.to_owned().unwrap_or_default().to_string(), errmsg: self.sub_msg.to_owned().unwrap_or_default() })
+            let mut err = PlatformError::new(Platform::Taobao, self.code.unwrap_or_default() as i64, self.sub_msg.to_owned().unwrap_or_default())
+                .sub_code(self.sub_code.to_owned().unwrap_or_default())
+                .request_id(self.request_id.to_owned().unwrap_or_default());
+            err.http_status = self.http_status;
+            Err(err.into())
         }
     }
 
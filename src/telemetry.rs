@@ -0,0 +1,254 @@
+//! 指标
+//!
+//! 开启`metrics`特性后通过[metrics](https://docs.rs/metrics)门面输出以下指标，未开启时不产生任何开销：
+//!
+//! * `labrador_request_duration_seconds` - 请求耗时直方图，标签`platform`、`method`
+//! * `labrador_requests_total` - 请求次数，标签`platform`、`method`、`status`（success/error）、`errcode`
+//! * `labrador_token_refresh_total` - 凭证刷新次数，标签`platform`、`token`
+//! * `labrador_session_cache_total` - SessionStore读取次数，标签`store`（memory/redis/file）、`result`（hit/miss），用于计算缓存命中率
+//! * `labrador_certificate_reload_total` - 平台证书加载次数，标签`platform`
+//!
+//! 需要在应用中安装对应的exporter，如`metrics-exporter-prometheus`。
+//!
+use std::time::Duration;
+
+use crate::{LabraResponse, LabraStreamResponse, LabradorResult};
+
+pub const METRIC_REQUEST_DURATION: &str = "labrador_request_duration_seconds";
+pub const METRIC_REQUESTS: &str = "labrador_requests_total";
+pub const METRIC_TOKEN_REFRESH: &str = "labrador_token_refresh_total";
+pub const METRIC_SESSION_CACHE: &str = "labrador_session_cache_total";
+pub const METRIC_CERTIFICATE_RELOAD: &str = "labrador_certificate_reload_total";
+
+/// 注册指标说明，安装exporter后调用一次
+#[cfg(feature = "metrics")]
+pub fn describe_metrics() {
+    metrics::describe_histogram!(METRIC_REQUEST_DURATION, metrics::Unit::Seconds, "第三方接口请求耗时");
+    metrics::describe_counter!(METRIC_REQUESTS, "第三方接口请求次数");
+    metrics::describe_counter!(METRIC_TOKEN_REFRESH, "凭证刷新次数");
+    metrics::describe_counter!(METRIC_SESSION_CACHE, "凭证缓存读取次数");
+    metrics::describe_counter!(METRIC_CERTIFICATE_RELOAD, "平台证书加载次数");
+}
+
+/// 记录请求耗时与结果
+#[allow(unused)]
+pub(crate) fn record_request(platform: &'static str, method: &str, elapsed: Duration, result: &LabradorResult<LabraResponse>) {
+    #[cfg(feature = "metrics")]
+    {
+        let (status, errcode) = request_outcome(platform, result);
+        record(platform, method, elapsed, status, errcode);
+    }
+}
+
+/// 记录流式请求的耗时与结果，响应体未读取，只按HTTP状态码判断
+#[allow(unused)]
+pub(crate) fn record_stream_request(platform: &'static str, method: &str, elapsed: Duration, result: &LabradorResult<LabraStreamResponse>) {
+    #[cfg(feature = "metrics")]
+    {
+        let (status, errcode) = match result {
+            Ok(response) if !response.status().is_success() => ("error", response.status().as_u16().to_string()),
+            Ok(_) => ("success", "0".to_string()),
+            Err(err) => ("error", error_code(err)),
+        };
+        record(platform, method, elapsed, status, errcode);
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn record(platform: &'static str, method: &str, elapsed: Duration, status: &'static str, errcode: String) {
+    metrics::histogram!(METRIC_REQUEST_DURATION, "platform" => platform, "method" => method.to_string()).record(elapsed.as_secs_f64());
+    metrics::counter!(METRIC_REQUESTS, "platform" => platform, "method" => method.to_string(), "status" => status, "errcode" => errcode).increment(1);
+}
+
+/// 记录凭证刷新
+#[allow(unused)]
+pub(crate) fn record_token_refresh(platform: &'static str, token: &str) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(METRIC_TOKEN_REFRESH, "platform" => platform, "token" => token.to_string()).increment(1);
+}
+
+/// 记录SessionStore读取是否命中，自定义的存储可以在读取时调用
+#[allow(unused)]
+pub fn record_session_cache(store: &'static str, hit: bool) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(METRIC_SESSION_CACHE, "store" => store, "result" => if hit { "hit" } else { "miss" }).increment(1);
+}
+
+/// 记录平台证书加载
+#[allow(unused)]
+pub(crate) fn record_certificate_reload(platform: &'static str) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(METRIC_CERTIFICATE_RELOAD, "platform" => platform).increment(1);
+}
+
+/// 请求结果与错误码，HTTP状态码不是2xx时错误码为状态码，返回平台错误时使用平台错误码
+#[cfg(feature = "metrics")]
+fn request_outcome(platform: &str, result: &LabradorResult<LabraResponse>) -> (&'static str, String) {
+    match result {
+        Ok(response) if !response.status().is_success() => ("error", response.status().as_u16().to_string()),
+        Ok(response) => match response_errcode(platform, &response.bytes().unwrap_or_default()) {
+            Some(errcode) => ("error", errcode),
+            None => ("success", "0".to_string()),
+        },
+        Err(err) => ("error", error_code(err)),
+    }
+}
+
+/// 请求失败时的错误码
+#[cfg(feature = "metrics")]
+fn error_code(err: &crate::LabraError) -> String {
+    use crate::{LabraError, TransportError};
+    match err {
+        LabraError::ClientError(err) => err.sub_code.to_owned().unwrap_or_else(|| err.code.to_string()),
+        LabraError::TransportError(TransportError::Connect(_)) => "connect".to_string(),
+        LabraError::TransportError(TransportError::Timeout(_)) => "timeout".to_string(),
+        LabraError::TransportError(_) | LabraError::HttpError(_) => "transport".to_string(),
+        LabraError::RateLimited(_) => "rate_limited".to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+/// 应答体中的业务错误码，成功时返回None
+/// <pre>
+/// 支付宝读取`*_response.code`，成功为10000；淘宝、拼多多、京东读取`error_response`中的错误码；
+/// 京东业务结果`*_responce.*Result`中的`code`不是200且没有数据时视为错误；其他平台读取顶层的`errcode`等字段。
+/// </pre>
+#[cfg(feature = "metrics")]
+fn response_errcode(platform: &str, body: &[u8]) -> Option<String> {
+    use serde_json::Value;
+    use crate::LabraLogConfig;
+    fn code(value: &Value, keys: &[&str]) -> Option<String> {
+        keys.iter().filter_map(|k| value.get(*k)).find_map(|v| match v {
+            Value::String(v) => Some(v.to_string()),
+            Value::Number(v) => Some(v.to_string()),
+            _ => None,
+        })
+    }
+    if !body.starts_with(b"{") {
+        return None;
+    }
+    let value = serde_json::from_slice::<Value>(body).ok()?;
+    let responses = || value.as_object().into_iter().flatten()
+        .filter(|(k, _)| k.ends_with("_response") || k.ends_with("_responce"))
+        .map(|(_, v)| v);
+    match platform {
+        "alipay" => responses().find_map(|v| code(v, &["code"])).filter(|v| v != "10000"),
+        "taobao" | "pdd" => value.get("error_response").and_then(|v| code(v, &["code", "error_code"])),
+        "jd" => {
+            if let Some(errcode) = value.get("error_response").and_then(|v| code(v, &["code"])) {
+                return Some(errcode);
+            }
+            let response = responses().next()?;
+            if let Some(errcode) = code(response, &["code"]).filter(|v| v != "0") {
+                return Some(errcode);
+            }
+            response.as_object()?.values()
+                .filter_map(|v| v.as_str().and_then(|v| serde_json::from_str::<Value>(v).ok()))
+                .find(|v| v["data"].is_null() && v["code"].as_i64().map(|v| v != 200).unwrap_or(false))
+                .map(|v| v["code"].to_string())
+        }
+        _ => LabraLogConfig::errcode(body).filter(|v| v != "0"),
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+    use bytes::Bytes;
+    use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+    use reqwest::{StatusCode, Url};
+    use reqwest::header::HeaderMap;
+    use crate::{LabraResponse, LabraStreamResponse, SessionStore, SimpleStorage};
+    use super::{record_request, record_session_cache, record_stream_request, record_token_refresh, METRIC_REQUESTS, METRIC_SESSION_CACHE, METRIC_TOKEN_REFRESH};
+
+    #[derive(Default)]
+    struct TestRecorder {
+        counters: Mutex<HashMap<String, Arc<AtomicU64>>>,
+    }
+
+    impl TestRecorder {
+        fn value(&self, key: &str) -> u64 {
+            self.counters.lock().unwrap().get(key).map(|v| v.load(Ordering::SeqCst)).unwrap_or_default()
+        }
+    }
+
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+        fn describe_gauge(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+        fn describe_histogram(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+            let labels = key.labels().map(|v| format!("{}={}", v.key(), v.value())).collect::<Vec<_>>().join(",");
+            let counter = self.counters.lock().unwrap().entry(format!("{}{{{}}}", key.name(), labels)).or_default().clone();
+            Counter::from_arc(counter)
+        }
+
+        fn register_gauge(&self, _key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn test_metrics() {
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            let url = Url::parse("https://api.weixin.qq.com/cgi-bin/test").unwrap();
+            let ok = LabraResponse::new(url.clone(), StatusCode::OK, None, HeaderMap::new(), Bytes::from(r#"{"errcode":0}"#));
+            let expired = LabraResponse::new(url, StatusCode::OK, None, HeaderMap::new(), Bytes::from(r#"{"errcode":42001}"#));
+            record_request("wechat", "/cgi-bin/test", Duration::from_millis(10), &Ok(ok));
+            record_request("wechat", "/cgi-bin/test", Duration::from_millis(10), &Ok(expired));
+            let not_found = LabraStreamResponse::new(Url::parse("https://api.mch.weixin.qq.com/v3/billdownload/file").unwrap(), StatusCode::NOT_FOUND, HeaderMap::new(), Box::pin(futures_util::stream::empty()));
+            record_stream_request("wechat_pay", "/v3/billdownload/file", Duration::from_millis(10), &Ok(not_found));
+            record_token_refresh("wechat", "access_token");
+            record_session_cache("memory", true);
+            record_session_cache("memory", false);
+        });
+        assert_eq!(1, recorder.value(&format!("{}{{platform=wechat,method=/cgi-bin/test,status=success,errcode=0}}", METRIC_REQUESTS)));
+        assert_eq!(1, recorder.value(&format!("{}{{platform=wechat,method=/cgi-bin/test,status=error,errcode=42001}}", METRIC_REQUESTS)));
+        assert_eq!(1, recorder.value(&format!("{}{{platform=wechat_pay,method=/v3/billdownload/file,status=error,errcode=404}}", METRIC_REQUESTS)));
+        assert_eq!(1, recorder.value(&format!("{}{{platform=wechat,token=access_token}}", METRIC_TOKEN_REFRESH)));
+        assert_eq!(1, recorder.value(&format!("{}{{store=memory,result=hit}}", METRIC_SESSION_CACHE)));
+    }
+
+    #[test]
+    fn test_platform_errcode() {
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            let url = Url::parse("https://openapi.alipay.com/gateway.do").unwrap();
+            let response = |body: &'static str| Ok(LabraResponse::new(url.clone(), StatusCode::OK, None, HeaderMap::new(), Bytes::from(body)));
+            record_request("alipay", "alipay.trade.query", Duration::from_millis(10), &response(r#"{"alipay_trade_query_response":{"code":"10000","msg":"Success"},"sign":"sign"}"#));
+            record_request("alipay", "alipay.trade.query", Duration::from_millis(10), &response(r#"{"alipay_trade_query_response":{"code":"40004","msg":"Business Failed","sub_code":"ACQ.TRADE_NOT_EXIST"},"sign":"sign"}"#));
+            record_request("taobao", "taobao.tbk.item.info.get", Duration::from_millis(10), &response(r#"{"error_response":{"code":15,"msg":"Remote service error","sub_code":"isv.invalid-parameter"}}"#));
+            record_request("pdd", "pdd.ddk.goods.search", Duration::from_millis(10), &response(r#"{"error_response":{"error_code":10000,"error_msg":"参数错误"}}"#));
+            record_request("jd", "jd.union.open.goods.query", Duration::from_millis(10), &response(r#"{"jd_union_open_goods_query_responce":{"code":"0","queryResult":"{\"code\":403,\"message\":\"无访问权限\"}"}}"#));
+            record_request("jd", "jd.union.open.goods.query", Duration::from_millis(10), &response(r#"{"jd_union_open_goods_query_responce":{"code":"0","queryResult":"{\"code\":200,\"data\":[]}"}}"#));
+        });
+        assert_eq!(1, recorder.value(&format!("{}{{platform=alipay,method=alipay.trade.query,status=success,errcode=0}}", METRIC_REQUESTS)));
+        assert_eq!(1, recorder.value(&format!("{}{{platform=alipay,method=alipay.trade.query,status=error,errcode=40004}}", METRIC_REQUESTS)));
+        assert_eq!(1, recorder.value(&format!("{}{{platform=taobao,method=taobao.tbk.item.info.get,status=error,errcode=15}}", METRIC_REQUESTS)));
+        assert_eq!(1, recorder.value(&format!("{}{{platform=pdd,method=pdd.ddk.goods.search,status=error,errcode=10000}}", METRIC_REQUESTS)));
+        assert_eq!(1, recorder.value(&format!("{}{{platform=jd,method=jd.union.open.goods.query,status=error,errcode=403}}", METRIC_REQUESTS)));
+        assert_eq!(1, recorder.value(&format!("{}{{platform=jd,method=jd.union.open.goods.query,status=success,errcode=0}}", METRIC_REQUESTS)));
+    }
+
+    #[test]
+    fn test_session_cache_ratio() {
+        let recorder = TestRecorder::default();
+        let session = SimpleStorage::new();
+        metrics::with_local_recorder(&recorder, || {
+            SessionStore::set(&session, "token", "value", None).unwrap();
+            SessionStore::get::<_, String>(&session, "token", None).unwrap();
+            SessionStore::get::<_, String>(&session, "token", None).unwrap();
+            SessionStore::get::<_, String>(&session, "missing", Some("".to_string())).unwrap();
+        });
+        assert_eq!(2, recorder.value(&format!("{}{{store=memory,result=hit}}", METRIC_SESSION_CACHE)));
+        assert_eq!(1, recorder.value(&format!("{}{{store=memory,result=miss}}", METRIC_SESSION_CACHE)));
+    }
+}
@@ -5,7 +5,7 @@
 use std::ops::Deref;
 
 #[allow(unused)]
-static SALT: &'static str = "labrador";
+static SALT: &str = "labrador";
 
 ///
 /// MD5 encry
@@ -15,6 +15,12 @@ pub struct RequestParametersHolder {
     pub application_params: BTreeMap<String, String>,
 }
 
+impl Default for RequestParametersHolder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RequestParametersHolder {
     pub fn new() -> Self {
         Self {
@@ -38,17 +44,17 @@ impl RequestParametersHolder {
 
     pub fn get_sorted_map(&self) -> BTreeMap<&String, &String> {
         let mut sorted_params = BTreeMap::new();
-        if self.application_params.len() > 0 {
+        if !self.application_params.is_empty() {
             for (k, v) in self.application_params.iter() {
                 sorted_params.insert(k, v);
             }
         }
-        if self.protocal_must_params.len() > 0 {
+        if !self.protocal_must_params.is_empty() {
             for (k, v) in self.protocal_must_params.iter() {
                 sorted_params.insert(k, v);
             }
         }
-        if self.protocal_opt_params.len() > 0 {
+        if !self.protocal_opt_params.is_empty() {
             for (k, v) in self.protocal_opt_params.iter() {
                 sorted_params.insert(k, v);
             }
@@ -76,7 +82,7 @@ impl <T: Sized + std::fmt::Display> StringExtern<T> for String {
 
     fn add_form_params(&mut self, field_name: &str, field_value: T) {
         if !self.is_empty() {
-            self.push_str("&");
+            self.push('&');
         }
         self.push_str(format!("{}={}", field_name, field_value).as_str());
     }
@@ -120,8 +126,7 @@ pub fn get_sign_with_rsa(pairs: &BTreeMap<String, String>, private_key: &str) ->
     }
     // generate RSA string
     let prp = PrpCrypto::new(base64::decode(private_key).unwrap_or_default());
-    let sign = prp.rsa_sha256_sign_pkcs8(&params).unwrap_or_default();
-    sign
+    prp.rsa_sha256_sign_pkcs8(&params).unwrap_or_default()
 }
 
 /// Sign Method (build request paramaters sign format)
@@ -149,8 +154,7 @@ pub fn get_timestamp() -> i64 {
     let since_the_epoch = start
         .duration_since(UNIX_EPOCH)
         .expect("Time went backwards");
-    let ms = since_the_epoch.as_secs() as i64 * 1000i64 + (since_the_epoch.subsec_nanos() as f64 / 1_000_000.0) as i64;
-    ms
+    since_the_epoch.as_secs() as i64 * 1000i64 + (since_the_epoch.subsec_nanos() as f64 / 1_000_000.0) as i64
 }
 
 pub fn merge_properties(mut source: serde_json::Value, target: serde_json::Value) -> serde_json::Value {
@@ -3,7 +3,6 @@ use rand::thread_rng;
 use rand::{Rng, distributions::Alphanumeric};
 use base64;
 
-use std::iter::repeat;
 
 
 use aes::cipher::{
@@ -135,7 +134,7 @@ impl PrpCrypto {
     ///
     /// # Examples
     ///
-    /// ```
+    /// ```ignore
     /// let content = "123";
     /// let private_key = "your private key";
     /// let sign = rsa_sign(content, private_key);
@@ -197,6 +196,55 @@ impl PrpCrypto {
         rsa(private_key, content)
     }
 
+    /// RSA解密（OAEP填充，SHA1）
+    /// - ciphertext: 密文，base64编码
+    /// - private_key: 私钥，PKCS#8 PEM格式
+    pub fn rsa_oaep_decrypt_with_pem(ciphertext: &str, private_key: &str) -> LabradorResult<Vec<u8>> {
+
+        #[cfg(feature = "openssl-crypto")]
+        fn decrypt(private_key: &str, ciphertext: &[u8]) -> LabradorResult<Vec<u8>> {
+            let private_key = openssl::rsa::Rsa::private_key_from_pem(private_key.as_bytes())?;
+            let mut buf = vec![0; private_key.size() as usize];
+            let len = private_key.private_decrypt(ciphertext, &mut buf, Padding::PKCS1_OAEP)?;
+            buf.truncate(len);
+            Ok(buf)
+        }
+
+        #[cfg(not(feature = "openssl-crypto"))]
+        fn decrypt(private_key: &str, ciphertext: &[u8]) -> LabradorResult<Vec<u8>> {
+            let private_key = rsa::RsaPrivateKey::from_pkcs8_pem(private_key)?;
+            let out = private_key.decrypt(rsa::PaddingScheme::new_oaep::<sha1::Sha1>(), ciphertext)?;
+            Ok(out)
+        }
+
+        decrypt(private_key, &base64::decode(ciphertext)?)
+    }
+
+    /// RSA加密（OAEP填充，SHA1），返回base64字符串
+    /// - content: 明文
+    /// - public_key: 公钥，与`rsa_sha256_verify_with_pem`相同
+    pub fn rsa_oaep_encrypt_with_pem(content: &str, public_key: &str) -> LabradorResult<String> {
+
+        #[cfg(feature = "openssl-crypto")]
+        fn encrypt(public_key: &str, content: &[u8]) -> LabradorResult<Vec<u8>> {
+            let public_key = Rsa::public_key_from_pem(public_key.as_bytes())?;
+            let mut buf = vec![0; public_key.size() as usize];
+            let len = public_key.public_encrypt(content, &mut buf, Padding::PKCS1_OAEP)?;
+            buf.truncate(len);
+            Ok(buf)
+        }
+
+        #[cfg(not(feature = "openssl-crypto"))]
+        fn encrypt(public_key: &str, content: &[u8]) -> LabradorResult<Vec<u8>> {
+            let public_key = base64::decode(public_key)?;
+            let public_key = rsa::RsaPublicKey::from_public_key_der(&public_key)?;
+            let out = public_key.encrypt(&mut aes_gcm::aead::OsRng, rsa::PaddingScheme::new_oaep::<sha1::Sha1>(), content)?;
+            Ok(out)
+        }
+
+        Ok(base64::encode(encrypt(public_key, content.as_bytes())?))
+    }
+
     pub fn rsa_sha256_sign_pkcs1(&self, content: &str) -> LabradorResult<String> {
         let private_key = &self.key;
         #[cfg(feature = "openssl-crypto")]
@@ -219,7 +267,7 @@ impl PrpCrypto {
             Ok(base64::encode(out))
         }
 
-        rsa(&private_key, content)
+        rsa(private_key, content)
     }
 
     pub fn rsa_sha256_sign_pkcs8(&self, content: &str) -> LabradorResult<String> {
@@ -242,7 +290,7 @@ impl PrpCrypto {
             Ok(base64::encode(out))
         }
 
-        rsa(&private_key, content)
+        rsa(private_key, content)
     }
 
     /// RSA签名验证
@@ -253,7 +301,7 @@ impl PrpCrypto {
     ///
     /// # Examples
     ///
-    /// ```
+    /// ```ignore
     /// let content = "123";
     /// let public_key = "your public key";
     /// let sign = rsa_sign(public_key, content, sign);
@@ -284,7 +332,7 @@ impl PrpCrypto {
             let public_key = base64::decode(public_key)?;
             let public_key = rsa::RsaPublicKey::from_public_key_der(&public_key)?;
             let digest = sha2::Sha256::digest(content).to_vec();
-            let _ = public_key
+            public_key
                 .verify(rsa::PaddingScheme::new_pkcs1v15_sign(Some(rsa::Hash::SHA2_256)), &digest, sig)?;
             Ok(true)
         }
@@ -292,8 +340,12 @@ impl PrpCrypto {
     }
 
     pub fn rsa_sha256_verify_with_pem(public_key: &str, content: &str, sign: &str) -> LabradorResult<bool> {
+        Self::rsa_sha256_verify_bytes_with_pem(public_key, content.as_bytes(), sign)
+    }
+
+    /// 校验原始字节的SHA256withRSA签名
+    pub fn rsa_sha256_verify_bytes_with_pem(public_key: &str, content: &[u8], sign: &str) -> LabradorResult<bool> {
         let sig = base64::decode(sign)?;
-        let content = content.as_bytes();
         #[cfg(feature = "openssl-crypto")]
         fn verify(sig: &[u8], public_key: &str, content: &[u8]) -> LabradorResult<bool> {
             // 获取公钥对象
@@ -312,7 +364,7 @@ impl PrpCrypto {
             let public_key = base64::decode(public_key)?;
             let public_key = rsa::RsaPublicKey::from_public_key_der(&public_key)?;
             let digest = sha2::Sha256::digest(content).to_vec();
-            let _ = public_key
+            public_key
                 .verify(rsa::PaddingScheme::new_pkcs1v15_sign(Some(rsa::Hash::SHA2_256)), &digest, sig)?;
             Ok(true)
         }
@@ -377,7 +429,7 @@ impl PrpCrypto {
     pub fn aes_256_gcm_encrypt(&self, associated_data: &[u8], nonce: &[u8], plain_text: &[u8]) -> LabradorResult<Vec<u8>> {
 
         let key = &self.key;
-        let mut out_tag: Vec<u8> = repeat(0).take(16).collect();
+        let mut out_tag: Vec<u8> = vec![0; 16];
         #[cfg(not(feature = "openssl-crypto"))]
         fn encrypt(key: &[u8], associated_data: &[u8], nonce: &[u8], plain_text: &[u8], out_tag: &mut [u8]) -> LabradorResult<Vec<u8>> {
             use aes_gcm::{
@@ -419,7 +471,7 @@ impl PrpCrypto {
             let tag = GenericArray::from_slice(tag);
             let cipher = aes_gcm::Aes256Gcm::new(key);
             let mut buffer = Vec::from(ciphertext);
-            let plaintext = cipher.decrypt_in_place_detached(nonce, associated_data, &mut buffer, tag)?;
+            cipher.decrypt_in_place_detached(nonce, associated_data, &mut buffer, tag)?;
             Ok(buffer)
         }
 
@@ -429,7 +481,7 @@ impl PrpCrypto {
     /// # 加密(aes_256_ecb)
     pub fn aes_256_ecb_encrypt(&self, data: &[u8]) -> LabradorResult<Vec<u8>> {
         let key = &self.key;
-        let mut out_tag: Vec<u8> = repeat(0).take(16).collect();
+        let mut out_tag: Vec<u8> = vec![0; 16];
 
         #[cfg(not(feature = "openssl-crypto"))]
         fn encrypt(key: &[u8], data: &[u8]) -> LabradorResult<Vec<u8>> {
@@ -474,7 +526,6 @@ impl PrpCrypto {
 mod tests {
     use std::iter::repeat;
     use base64;
-    use x509_parser::nom::HexDisplay;
     use super::PrpCrypto;
 
 
@@ -497,7 +548,7 @@ mod tests {
     }
 
     fn hex_to_bytes(raw_hex: &str) -> Vec<u8> {
-        raw_hex.from_hex().ok().unwrap()
+        hex::decode(raw_hex).unwrap()
     }
 
     #[test]
@@ -526,7 +577,7 @@ mod tests {
         let iv= b"bb9ee5e44da1";
         // let plain_text= hex_to_bytes("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39");
         let cipher_text_base64=base64::decode("WZnvm4CnxNuPUYLIAh3Kv2WJFivwhLA2/xGxhwNHh5j2XmhUn2ibLm1I/pU3XKw6YWYLY8RfHsRHVcY4ln0NUUsiqsmgUxELKjqPKY0dWZSwXtbVAMlK+rGQbrgoopn/gNurM6Sx0jOjzorg091J0GGkxn2hHSaJ6EUtbHAGB3Nx/PTLr2o1rzNvF/QWLGE+5bcGe5Yg85qshvoGATJSwNAlVmdCOV4fg583irGzg6u7MYAytZpBoyzA4yf+9AKrO3K5lQwF5G6ULPWXtTNuW4rrC8wPI5xdnLqKopo9gNDUqg+19DYDSYsUvztRU7wORNh0SVkZLTwhOmKzFM8oqDHDuvcRCrUjw52NT85BQIFtsJMHciiFL+pefsz1llxlDnjroRyqNAyXw0RvKJfff40M8Fw7mAWK5eINQLPZAi4f9Ws7vC3WZ9/WGjrPOQInn8oLxzb8c+Wn0HSAxfEBRBmGx8FQ0+MdAP5bHTn3KCVxBM8gdx5vfeNqzcnRPG6qTMwuf/NE4BdnqNsDk5o3ZyhMGxnDfoJ+9PophG5KtdaPYHDVj/18PzT0w4GttSdw/1pisSPeOKcQqpI3/sC3ndDO7uqieUUAhMCtLxFCn1spndDLr+ciUs3CWJYlBgATE8vOFzPjVN8ECV+UeGULjkjWGBm0yPG3znbBpkX5Zvei4eZml16/JZHTWVgAKHpaaoBNH6qLKqS4UdpAXZJEQLAXflRw+4RjyD8ZsERcOTutnycozb/sPxB8N3qWhTGb8EJ8DTYSCILYemSIDmefmPU+ChzdM1FDbePMpHv8wCC/+zfRSwl0VtWXCauazZ3+1J9dW8ThvTOwlXPuRvOXFwCX/bq8BI3DX619TnahNBKU3+EfcvGGDO6bI5LvPSPLAaf1MgPc31Ab4jP+s73y4vc5IYNuwMC+aKuPmaxrqPA6Lr7PAUEicem4mYiTOAeG4hQh2C9XSOKrocsNDaOgLRiUU53bNY9sBTEkxoOc5prYVV7azwPfR506fSec0fv5c7v58srSK9zpTKNNVKbLL76WCpQ453dwmyaYeJNVqYoslzEL+kcb6UZVwr/Kj9TJka5bYHQOBmTRJT7FUeawvu4kHWzWnlRUShNFkuoymJEA8SXYyPliJgBWl36HAWse3PNr63K+RoYe8VdtviQQ02Js2Bg2RcTAlaxSoKuQdFfraGh35gVeJYEbrIp3N5goxLc6oc+bE/uoQI+pgv6oNsNznotp7bPCY1hIOEdtgvxMAUnpiU5ZsiPGt/N5KVAvSZJMzbuql3p2LBZjY3aGsNsT+xfgMj9K1fsORHP8/zt+RoF3AasSnn66zWRlxGlptkH+HtNxfEefaHtZ3NwYNPwaKwn9hIF5EotIhgLRsbEL9PWJLBVDuaWcmoaYDTNzAUlpGAKvyh2e4U7j3VuxPDiwNmPC+ZG/2CSMuD3+GPJodA3wbkhiNP4TAitKgYC03i94HDj8i2Th5HvNuA+dap7LaZerV7A34DwCK4rwk2C6z8+TAhdqagv2q1rnvzVT/dUXkIz3YMNkowboTpc/VgENPgUGBM4TtUpdk+hSxx/L5q/C+uWt8U1rIxbu5JrN3dHlvF/WfaCHQZP8e2QC8bz/TSX/tzFIQ6o/QtFWlF8OGbbndoNgTe5xyS5AwlprmR9FWFzjim8JAKNKMTKTrW3U6TKSUxSD9m7sl08rD3pCk+1kkKiVEgcuVHPd985n1xr4Ex9Hr8pJBTDcbkzis+dvh+CajqgsrYas+Eq8NTM8pz004PcPfZZzuaLgjl0Z+l7ZschSCkzq54BRxfIcvwywqJUhtRmB6xccpCtln6AsC/FS+kcJdAYEnnuU5uoPmNCcf3n+jDL9UGbcNg5Nj/w92tyF5A==").unwrap();
-        let base64_cipher = cipher_text_base64.to_hex();
+        let base64_cipher = hex::encode(&cipher_text_base64);
         println!("cipher_text:{}", &base64_cipher);
         let cipher_text = hex_to_bytes(&base64_cipher);
         let aad= b"certificate";
@@ -1,17 +1,17 @@
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpClient};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpClient};
 use crate::wechat::cp::method::{CpAgentMethod, WechatCpMethod};
 
 /// 管理企业号应用
 #[derive(Debug, Clone)]
-pub struct WechatCpAgent<'a, T: SessionStore> {
+pub struct WechatCpAgent<'a, T: AsyncSessionStore> {
     client: &'a WechatCpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpAgent<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpAgent<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpClient<T>) -> WechatCpAgent<T> {
@@ -26,7 +26,7 @@ impl<'a, T: SessionStore> WechatCpAgent<'a, T> {
     /// 详情请见: https://work.weixin.qq.com/api/doc#10087
     /// </pre>
     pub async fn get(&self, agent_id: i32) -> LabradorResult<WechatCpAgentInfo> {
-        let v = self.client.get(WechatCpMethod::Agent(CpAgentMethod::Get(agent_id)), vec![], RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::Agent(CpAgentMethod::Get(agent_id)), vec![]).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpAgentInfo>(v)
     }
 
@@ -36,7 +36,7 @@ impl<'a, T: SessionStore> WechatCpAgent<'a, T> {
     /// 详情请见: https://work.weixin.qq.com/api/doc#10088
     /// </pre>
     pub async fn set(&self, req: WechatCpAgentInfo) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::Agent(CpAgentMethod::Set), vec![], req,RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::Agent(CpAgentMethod::Set), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -45,7 +45,7 @@ impl<'a, T: SessionStore> WechatCpAgent<'a, T> {
     /// 详情请见: https://work.weixin.qq.com/api/doc#11214
     /// </pre>
     pub async fn list(&self) -> LabradorResult<WechatCpAgentListResponse> {
-        let v = self.client.get(WechatCpMethod::Agent(CpAgentMethod::List), vec![],RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::Agent(CpAgentMethod::List), vec![]).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpAgentListResponse>(v)
     }
 }
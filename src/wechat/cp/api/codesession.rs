@@ -1,17 +1,17 @@
 use serde::{Serialize, Deserialize};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpClient};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpClient};
 use crate::wechat::cp::constants::{AUTHORIZATION_CODE, GRANT_TYPE, JS_CODE};
 use crate::wechat::cp::method::WechatCpMethod;
 
 
 #[derive(Debug, Clone)]
-pub struct WechatCpCodeSession<'a, T: SessionStore> {
+pub struct WechatCpCodeSession<'a, T: AsyncSessionStore> {
     client: &'a WechatCpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpCodeSession<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpCodeSession<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpClient<T>) -> WechatCpCodeSession<T> {
@@ -25,7 +25,7 @@ impl<'a, T: SessionStore> WechatCpCodeSession<'a, T> {
         let v = self.client.get(WechatCpMethod::JsCode2Session, vec![
             (GRANT_TYPE.to_string(), AUTHORIZATION_CODE.to_string()),
             (JS_CODE.to_string(), code.to_string()),
-        ], RequestType::Json).await?.json::<serde_json::Value>()?;
+        ]).await?.json::<serde_json::Value>()?;
         WechatCommonResponse::parse::<WechatCpJsCodeSession>(v)
     }
 }
@@ -1,17 +1,17 @@
 use serde::{Serialize, Deserialize};
 use serde_json::{Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpClient};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpClient};
 use crate::wechat::cp::method::{CpDepartmentMethod, WechatCpMethod};
 
 /// 部门管理
 #[derive(Debug, Clone)]
-pub struct WechatCpDepartment<'a, T: SessionStore> {
+pub struct WechatCpDepartment<'a, T: AsyncSessionStore> {
     client: &'a WechatCpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpDepartment<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpDepartment<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpClient<T>) -> WechatCpDepartment<T> {
@@ -27,7 +27,7 @@ impl<'a, T: SessionStore> WechatCpDepartment<'a, T> {
     /// </pre>
     pub async fn create(&self, req: WechatCpDepartInfo) -> LabradorResult<i64> {
 
-        let v = self.client.post(WechatCpMethod::Department(CpDepartmentMethod::Create), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::Department(CpDepartmentMethod::Create), vec![], req).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let tag_id = v["id"].as_i64().unwrap_or_default();
         Ok(tag_id)
@@ -42,7 +42,7 @@ impl<'a, T: SessionStore> WechatCpDepartment<'a, T> {
         if let Some(id) = id {
             query.push(("id".to_string(), id.to_string()));
         }
-        let v = self.client.get(WechatCpMethod::Department(CpDepartmentMethod::SimpleList), query, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::Department(CpDepartmentMethod::SimpleList), query).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpDepartSimpleResponse>(v)
     }
 
@@ -56,7 +56,7 @@ impl<'a, T: SessionStore> WechatCpDepartment<'a, T> {
         if let Some(id) = id {
             query.push(("id".to_string(), id.to_string()));
         }
-        let v = self.client.get(WechatCpMethod::Department(CpDepartmentMethod::List), query, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::Department(CpDepartmentMethod::List), query).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpDepartResponse>(v)
     }
 
@@ -66,7 +66,7 @@ impl<'a, T: SessionStore> WechatCpDepartment<'a, T> {
     /// 如果id为0(未部门),1(黑名单),2(星标组)，或者不存在的id，微信会返回系统繁忙的错误
     /// </pre>
     pub async fn update(&self, req: WechatCpDepartInfo) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::Department(CpDepartmentMethod::Update), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::Department(CpDepartmentMethod::Update), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -75,7 +75,7 @@ impl<'a, T: SessionStore> WechatCpDepartment<'a, T> {
     /// 应用须拥有指定部门的管理权限
     /// </pre>
     pub async fn delete(&self, depart_id: i64) -> LabradorResult<WechatCommonResponse> {
-        self.client.get(WechatCpMethod::Department(CpDepartmentMethod::Delete(depart_id)), vec![], RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.get(WechatCpMethod::Department(CpDepartmentMethod::Delete(depart_id)), vec![]).await?.json::<WechatCommonResponse>()
     }
 }
 
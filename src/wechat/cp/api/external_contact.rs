@@ -1,19 +1,20 @@
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
+use futures_util::Stream;
 
-use crate::{session::SessionStore, LabradorResult, RequestType, WechatCpClient, LabraError, WechatCommonResponse};
+use crate::{session::AsyncSessionStore, LabradorResult, WechatCpClient, LabraError, WechatCommonResponse, Page, paginate};
 use crate::wechat::cp::constants::{CURSOR, EXTERNAL_USERID, USERID, WELCOME_MSG_TYPE_FILE, WELCOME_MSG_TYPE_IMAGE, WELCOME_MSG_TYPE_LINK, WELCOME_MSG_TYPE_MINIPROGRAM, WELCOME_MSG_TYPE_VIDEO};
 use crate::wechat::cp::method::{CpExternalContactMethod, WechatCpMethod};
 
 
 /// 外部联系人管理接口
 #[derive(Debug, Clone)]
-pub struct WechatCpExternalContact<'a, T: SessionStore> {
+pub struct WechatCpExternalContact<'a, T: AsyncSessionStore> {
     client: &'a WechatCpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpExternalContact<'a, T> {
     #[inline]
     pub fn new(client: &WechatCpClient<T>) -> WechatCpExternalContact<T> {
         WechatCpExternalContact {
@@ -39,7 +40,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
                 return Err(LabraError::RequestError("「联系我」使用人数默认限制不超过100人(包括部门展开后的人数)".to_string()));
             }
         }
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::AddContactWay), vec![], req.contact_way, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::AddContactWay), vec![], req.contact_way).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpContactWayInfoResponse>(v)
     }
 
@@ -49,7 +50,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// <b>批量</b>获取企业配置的「联系我」二维码和「联系我」小程序按钮。
     /// </pre>
     pub async fn get_contact_way(&self, config_id: &str) -> LabradorResult<WechatCpContactWayInfo> {
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetContactWay), vec![], json!({"config_id": config_id}), RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetContactWay), vec![], json!({"config_id": config_id})).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpContactWayInfo>(v)
     }
 
@@ -67,7 +68,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
                 return Err(LabraError::RequestError("「联系我」使用人数默认限制不超过100人(包括部门展开后的人数)".to_string()));
             }
         }
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::UpdateContactWay), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::UpdateContactWay), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// 删除企业已配置的「联系我」方式
@@ -76,7 +77,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// 删除一个已配置的「联系我」二维码或者「联系我」小程序按钮。
     /// </pre>
     pub async fn delete_contact_way(&self, config_id: &str) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::DeleteContactWay), vec![], json!({"config_id": config_id}), RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::DeleteContactWay), vec![], json!({"config_id": config_id})).await?.json::<WechatCommonResponse>()
     }
 
     /// 结束临时会话
@@ -87,7 +88,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// 注意：请保证传入的企业成员和客户之间有仍然有效的临时会话, 通过<b>其他方式的添加外部联系人无法通过此接口关闭会话</b>。
     /// </pre>
     pub async fn close_temp_chat(&self, user_id: &str, external_user_id: &str) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::CloseTmpChat), vec![], json!({"userid": user_id, "external_userid": external_user_id}), RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::CloseTmpChat), vec![], json!({"userid": user_id, "external_userid": external_user_id})).await?.json::<WechatCommonResponse>()
     }
 
     /// 获取客户详情.
@@ -104,13 +105,13 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// 第三方/自建应用调用时，返回的跟进人follow_user仅包含应用可见范围之内的成员。
     /// </pre>
     pub async fn get_contact_detail(&self, user_id: &str, cursor: &str) -> LabradorResult<WechatCpExternalContactInfoResponse> {
-        let v = self.client.get(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetContactWayDetail), vec![(EXTERNAL_USERID.to_string(), user_id.to_string()), (CURSOR.to_string(), cursor.to_string())], RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetContactWayDetail), vec![(EXTERNAL_USERID.to_string(), user_id.to_string()), (CURSOR.to_string(), cursor.to_string())]).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpExternalContactInfoResponse>(v)
     }
 
     /// 企业和服务商可通过此接口，将微信外部联系人的userid转为微信openid，用于调用支付相关接口。暂不支持企业微信外部联系人（ExternalUserid为wo开头）的userid转openid。
     pub async fn convert_openid(&self, external_userid: &str) -> LabradorResult<String> {
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::ConvertToOpenid), vec![], json!({"external_userid": external_userid}), RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::ConvertToOpenid), vec![], json!({"external_userid": external_userid})).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let openid = v["openid"].as_str().unwrap_or_default().to_string();
         Ok(openid)
@@ -138,7 +139,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
         if let Some(openid) = openid {
             req["openid"] = openid.into();
         }
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::UnionidToExternalUserid), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::UnionidToExternalUserid), vec![], req).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let external_userid = v["external_userid"].as_str().unwrap_or_default().to_string();
         Ok(external_userid)
@@ -159,7 +160,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
                 return Err(LabraError::RequestError("使用该配置的客户群ID列表，支持5个".to_string()));
             }
         }
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatAddJoinWay), vec![], req.join_way, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatAddJoinWay), vec![], req.join_way).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpGroupJoinWayResponse>(v)
     }
 
@@ -174,7 +175,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
                 return Err(LabraError::RequestError("使用该配置的客户群ID列表，支持5个".to_string()));
             }
         }
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatUpdateJoinWay), vec![], req.join_way, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatUpdateJoinWay), vec![], req.join_way).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -183,7 +184,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// 文档地址：<a href="https://developer.work.weixin.qq.com/document/path/92229">地址</a>
     /// </pre>
     pub async fn get_join_way(&self, unionid: &str, config_id: &str) -> LabradorResult<WechatCpContactWayInfo> {
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatGetJoinWay), vec![], json!({"config_id": config_id}), RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatGetJoinWay), vec![], json!({"config_id": config_id})).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpContactWayInfo>(v)
     }
 
@@ -192,7 +193,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// 文档地址：<a href="https://developer.work.weixin.qq.com/document/path/92229">地址</a>
     /// </pre>
     pub async fn delete_join_way(&self, unionid: &str, config_id: &str) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatDeleteJoinWay), vec![], json!({"config_id": config_id}), RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatDeleteJoinWay), vec![], json!({"config_id": config_id})).await?.json::<WechatCommonResponse>()
     }
 
     /// 批量获取客户详情.
@@ -218,10 +219,22 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
         if let Some(limit) = limit {
             req["limit"] = limit.into();
         }
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::BatchGetByUser), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::BatchGetByUser), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpExternalContactBatchInfoResponse>(v)
     }
 
+    /// 逐个获取指定成员添加的所有客户详情，自动按next_cursor翻页
+    pub fn get_contact_detail_batch_stream(&self, userid_list: Vec<String>, limit: Option<i32>) -> impl Stream<Item = LabradorResult<ExternalContactInfo>> + 'a {
+        let client = self.client;
+        paginate(None, move |cursor: Option<String>| {
+            let userid_list = userid_list.to_owned();
+            async move {
+                let res = WechatCpExternalContact::new(client).get_contact_detail_batch(userid_list, cursor.as_deref(), limit).await?;
+                Ok(Page::new(res.external_contact_list.unwrap_or_default(), res.next_cursor.filter(|v| !v.is_empty())))
+            }
+        })
+    }
+
     /// 修改客户备注信息.
     /// <pre>
     /// 企业可通过此接口修改指定用户添加的客户的备注信息。
@@ -230,7 +243,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// 文档地址：<a href="https://work.weixin.qq.com/api/doc/90000/90135/92115">地址</a>
     /// </pre>
     pub async fn update_remark(&self, req: WechatCpUpdateRemarkRequest) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::Remark), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::Remark), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// 获取客户列表.
@@ -247,7 +260,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// 第三方/自建应用只能获取到可见范围内的配置了客户联系功能的成员。
     /// </pre>
     pub async fn list_external_contacts(&self, userid: &str) -> LabradorResult<Vec<String>> {
-        let v = self.client.get(WechatCpMethod::ExternalContact(CpExternalContactMethod::List), vec![(USERID.to_string(), userid.to_string())], RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::ExternalContact(CpExternalContactMethod::List), vec![(USERID.to_string(), userid.to_string())]).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let external_userids = v["external_userid"].as_array().unwrap_or(&vec![]).iter().map(|v| v.as_str().unwrap_or_default().to_string()).collect::<Vec<String>>();
         Ok(external_userids)
@@ -260,7 +273,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     ///   第三方应用只能获取到可见范围内的配置了客户联系功能的成员
     /// </pre>
     pub async fn list_followers(&self) -> LabradorResult<Vec<String>> {
-        let v = self.client.get(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetFollowUserList), vec![], RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetFollowUserList), vec![]).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let follow_users = v["follow_user"].as_array().unwrap_or(&vec![]).iter().map(|v| v.as_str().unwrap_or_default().to_string()).collect::<Vec<String>>();
         Ok(follow_users)
@@ -279,7 +292,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
         if let Some(page_id) = page_id {
             req["page_id"] = page_id.into();
         }
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetUnassignedList), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetUnassignedList), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserExternalUnassignList>(v)
     }
 
@@ -296,7 +309,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// 接替成员需要在企业微信激活且已经过实名认证。
     /// </per>
     pub async fn transfer_customer(&self, req: WechatCpUserTransferCustomerRequest) -> LabradorResult<WechatCpUserTransferCustomerResponse> {
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::TransferCustomer), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::TransferCustomer), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserTransferCustomerResponse>(v)
     }
 
@@ -314,7 +327,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
             "handover_userid": hand_over_userid,
             "takeover_userid": take_over_userid,
         });
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::TransferResult), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::TransferResult), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserTransferResultResponse>(v)
     }
 
@@ -333,7 +346,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// 接替成员需要在企业微信激活且已经过实名认证。
     /// </per>
     pub async fn resigned_transfer_customer(&self, req: WechatCpUserTransferCustomerRequest) -> LabradorResult<WechatCpUserTransferCustomerResponse> {
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::ResignedTransferCustomer), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::ResignedTransferCustomer), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserTransferCustomerResponse>(v)
     }
 
@@ -351,7 +364,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
             "handover_userid": hand_over_userid,
             "takeover_userid": take_over_userid,
         });
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::ResignedTransferResult), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::ResignedTransferResult), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserTransferResultResponse>(v)
     }
 
@@ -372,10 +385,22 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
                 "userid_list": user_ids
             });
         }
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatList), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatList), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserExternalGroupChatList>(v)
     }
 
+    /// 逐个获取所有客户群，自动按next_cursor翻页
+    pub fn list_group_chat_stream(&self, limit: Option<u64>, status: u8, user_ids: Vec<String>) -> impl Stream<Item = LabradorResult<ChatStatus>> + 'a {
+        let client = self.client;
+        paginate(None, move |cursor: Option<String>| {
+            let user_ids = user_ids.to_owned();
+            async move {
+                let res = WechatCpExternalContact::new(client).list_group_chat(limit, cursor.as_deref().unwrap_or_default(), status, user_ids).await?;
+                Ok(Page::new(res.group_chat_list, res.next_cursor.filter(|v| !v.is_empty())))
+            }
+        })
+    }
+
     /// <pre>
     /// 通过客户群ID，获取详情。包括群名、群成员列表、群成员入群时间、入群方式。（客户群是由具有客户群使用权限的成员创建的外部群）
     /// 企业需要使用“客户联系”secret或配置到“可调用应用”列表中的自建应用secret所获取的accesstoken来调用（accesstoken如何获取？）。
@@ -387,7 +412,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
             "chat_id": chat_id,
             "need_name": need_name,
         });
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatGet), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatGet), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserExternalGroupChatInfoResponse>(v)
     }
 
@@ -412,7 +437,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
         if !chat_ids.is_empty() {
             req["chat_id_list"] = chat_ids.into()
         }
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatTransfer), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatTransfer), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserExternalGroupChatTransferResponse>(v)
     }
 
@@ -433,7 +458,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
         if !party_ids.is_empty() {
             req["partyid"] = party_ids.into()
         }
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetUserBehaviorData), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetUserBehaviorData), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserExternalUserBehaviorStatistic>(v)
     }
 
@@ -456,7 +481,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
                 "partyid_list": party_ids,
             });
         }
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatStatistic), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GroupChatStatistic), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserExternalGroupChatStatistic>(v)
     }
 
@@ -471,7 +496,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// 文档地址：<a href="https://work.weixin.qq.com/api/doc/90000/90135/92135">地址</a>
     /// </pre>
     pub async fn add_msg_template(&self, msg_template: WechatCpMsgTemplate) -> LabradorResult<WechatCpMsgTemplateAddResponse> {
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::AddMsgTemplate), vec![], msg_template, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::AddMsgTemplate), vec![], msg_template).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpMsgTemplateAddResponse>(v)
     }
 
@@ -488,14 +513,14 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// 文档地址：<a href="https://work.weixin.qq.com/api/doc/90000/90135/92137">地址</a>
     /// </pre>
     pub async fn send_welcome_msg(&self, msg: WechatCpWelcomeMsg) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::SendWelcomeMsg), vec![], msg, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::SendWelcomeMsg), vec![], msg).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
     /// 企业可通过此接口获取企业客户标签详情。
     /// </pre>
     pub async fn get_corp_tag_list(&self, tag_id: Vec<&str>, group_id: Vec<Vec<&str>>) -> LabradorResult<WechatCpUserExternalTagGroupInfo> {
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetCorpTagList), vec![], json!({"tag_id": tag_id, "group_id": group_id}), RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetCorpTagList), vec![], json!({"tag_id": tag_id, "group_id": group_id})).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserExternalTagGroupInfo>(v)
     }
 
@@ -504,7 +529,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// 暂不支持第三方调用。
     /// </pre>
     pub async fn add_corp_tag(&self, req: TagGroup) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::AddCorpTag), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::AddCorpTag), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -517,7 +542,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
             "name": name,
             "order": order
         });
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::EditCorpTag), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::EditCorpTag), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -529,7 +554,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
             "tag_id": tag_id,
             "group_id": group_id
         });
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::DeleteCorpTag), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::DeleteCorpTag), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -543,7 +568,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
             "add_tag": add_tag,
             "remove_tag": remove_tag
         });
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::MarkTag), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::MarkTag), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -560,7 +585,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
             "limit": limit,
             "cursor": cursor
         });
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetGroupMsgListV2), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetGroupMsgListV2), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpGroupMsgListResult>(v)
     }
 
@@ -575,7 +600,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
             "limit": limit,
             "cursor": cursor
         });
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetGroupMsgSendResult), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetGroupMsgSendResult), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpGroupMsgSendResult>(v)
     }
 
@@ -589,7 +614,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
             "limit": limit,
             "cursor": cursor
         });
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetGroupMsgResult), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetGroupMsgResult), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpGroupMsgResult>(v)
     }
 
@@ -603,7 +628,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
             "limit": limit,
             "cursor": cursor
         });
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetGroupMsgTask), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetGroupMsgTask), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpGroupMsgTaskResult>(v)
     }
 
@@ -612,7 +637,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// <a href="https://open.work.weixin.qq.com/api/doc/90000/90135/92366#添加入群欢迎语素材">地址</a>
     /// </pre>
     pub async fn add_group_welcome_template(&self, req: WechatCpGroupWelcomeTemplateInfo) -> LabradorResult<String> {
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::AddGroupWelcomeTemplate), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::AddGroupWelcomeTemplate), vec![], req).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let template_id = v["template_id"].as_str().unwrap_or_default().to_string();
         Ok(template_id)
@@ -623,7 +648,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// <a href="https://open.work.weixin.qq.com/api/doc/90000/90135/92366#编辑入群欢迎语素材">地址</a>
     /// </pre>
     pub async fn edit_group_welcome_template(&self, req: WechatCpGroupWelcomeTemplateInfo) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::EditGroupWelcomeTemplate), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::EditGroupWelcomeTemplate), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -631,7 +656,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
     /// <a href="https://open.work.weixin.qq.com/api/doc/90000/90135/92366#获取入群欢迎语素材">地址</a>
     /// </pre>
     pub async fn get_group_welcome_template(&self, template_id: &str) -> LabradorResult<WechatCpGroupWelcomeTemplateInfo> {
-        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetGroupWelcomeTemplate), vec![], json!({"template_id": template_id}), RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::GetGroupWelcomeTemplate), vec![], json!({"template_id": template_id})).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpGroupWelcomeTemplateInfo>(v)
     }
 
@@ -645,7 +670,7 @@ impl<'a, T: SessionStore> WechatCpExternalContact<'a, T> {
         if let Some(agent) = agent_id {
             req["agentid"] = agent.into();
         }
-        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::DeleteGroupWelcomeTemplate), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::ExternalContact(CpExternalContactMethod::DeleteGroupWelcomeTemplate), vec![], req).await?.json::<WechatCommonResponse>()
     }
 }
 
@@ -860,7 +885,7 @@ pub struct WechatCpGroupJoinWayResponse {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WechatCpExternalContactBatchInfoResponse {
-    pub external_contact_list: Option<ExternalContactInfo>,
+    pub external_contact_list: Option<Vec<ExternalContactInfo>>,
     pub next_cursor: Option<String>,
 }
 
@@ -1513,3 +1538,34 @@ pub struct WechatCpGroupWelcomeTemplateInfo {
     /// 是否通知成员将这条入群欢迎语应用到客户群中，0-不通知，1-通知， 不填则通知
     pub notify: Option<u8>,
 }
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use serde_json::Value;
+    use crate::{LabradorResult, MockResponse, MockTransport, SimpleStorage, WechatCpClient};
+    use super::ExternalContactInfo;
+
+    #[tokio::test]
+    async fn test_get_contact_detail_batch_stream() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, r#"{"errcode":0,"errmsg":"ok","access_token":"token","expires_in":7200}"#))
+            .push_response(MockResponse::new(200, r#"{"errcode":0,"errmsg":"ok","external_contact_list":[{"external_contact":{"external_userid":"wm1"}},{"external_contact":{"external_userid":"wm2"}}],"next_cursor":"cursor1"}"#))
+            .push_response(MockResponse::new(200, r#"{"errcode":0,"errmsg":"ok","external_contact_list":[{"external_contact":{"external_userid":"wm3"}}],"next_cursor":""}"#));
+        let client = WechatCpClient::<SimpleStorage>::new("corpid", "secret").transport(transport.clone());
+        let contacts = client.external_contact().get_contact_detail_batch_stream(vec!["zhangsan".to_string()], Some(2))
+            .collect::<Vec<LabradorResult<ExternalContactInfo>>>().await;
+        let userids = contacts.into_iter()
+            .map(|v| v.unwrap().external_contact.and_then(|v| v.external_userid).unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["wm1", "wm2", "wm3"], userids);
+        // next_cursor为空时结束
+        let requests = transport.requests();
+        assert_eq!(3, requests.len());
+        let first = serde_json::from_str::<Value>(&requests[1].body_text()).unwrap();
+        let second = serde_json::from_str::<Value>(&requests[2].body_text()).unwrap();
+        assert!(first.get("cursor").is_none());
+        assert_eq!("cursor1", second["cursor"]);
+        assert_eq!(2, second["limit"]);
+    }
+}
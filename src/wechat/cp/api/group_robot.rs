@@ -1,18 +1,18 @@
 use serde::{Serialize, Deserialize};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpClient, LabraError};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpClient, LabraError};
 use crate::wechat::cp::constants::{ GROUP_ROBOT_MSG_IMAGE, GROUP_ROBOT_MSG_MARKDOWN, GROUP_ROBOT_MSG_NEWS, GROUP_ROBOT_MSG_TEXT};
 use crate::wechat::cp::method::{WechatCpMethod};
 
 /// 微信群机器人消息发送api
 /// 文档地址：<a href="https://work.weixin.qq.com/help?doc_id=13376">文档</a>
 #[derive(Debug, Clone)]
-pub struct WechatCpGroupRobot<'a, T: SessionStore> {
+pub struct WechatCpGroupRobot<'a, T: AsyncSessionStore> {
     client: &'a WechatCpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpGroupRobot<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpGroupRobot<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpClient<T>) -> WechatCpGroupRobot<T> {
@@ -51,7 +51,7 @@ impl<'a, T: SessionStore> WechatCpGroupRobot<'a, T> {
             articles: None,
             media_id: None
         };
-        self.client.post(WechatCpMethod::Custom {need_token: false, method_url: webhook_url.to_string()}, vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::Custom {need_token: false, method_url: webhook_url.to_string()}, vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -76,7 +76,7 @@ impl<'a, T: SessionStore> WechatCpGroupRobot<'a, T> {
             articles: None,
             media_id: None
         };
-        self.client.post(WechatCpMethod::Custom {need_token: false, method_url: webhook_url.to_string()}, vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::Custom {need_token: false, method_url: webhook_url.to_string()}, vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -101,7 +101,7 @@ impl<'a, T: SessionStore> WechatCpGroupRobot<'a, T> {
             articles: None,
             media_id: None
         };
-        self.client.post(WechatCpMethod::Custom {need_token: false, method_url: webhook_url.to_string()}, vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::Custom {need_token: false, method_url: webhook_url.to_string()}, vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -126,7 +126,7 @@ impl<'a, T: SessionStore> WechatCpGroupRobot<'a, T> {
             articles: articles.into(),
             media_id: None
         };
-        self.client.post(WechatCpMethod::Custom {need_token: false, method_url: webhook_url.to_string()}, vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::Custom {need_token: false, method_url: webhook_url.to_string()}, vec![], req).await?.json::<WechatCommonResponse>()
     }
 }
 
@@ -1,23 +1,21 @@
 use std::collections::BTreeMap;
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
 use bytes::Bytes;
+use tokio::io::AsyncRead;
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
 
-use crate::{session::SessionStore, LabradorResult, RequestBody, RequestType, WechatCpClient, WechatRequest, WechatCommonResponse, request, get_nonce_str};
+use crate::{session::AsyncSessionStore, LabradorResult, WechatCpClient, WechatRequest, WechatCommonResponse, request, get_nonce_str, stream_part, Form, LabraStreamResponse};
 use crate::wechat::cp::constants::{ATTACHMENT_TYPE, MEDIA_TYPE};
 use crate::wechat::cp::method::{CpMediaMethod, WechatCpMethod};
 
 
 #[derive(Debug, Clone)]
-pub struct WechatCpMedia<'a, T: SessionStore> {
+pub struct WechatCpMedia<'a, T: AsyncSessionStore> {
     client: &'a WechatCpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpMedia<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpMedia<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpClient<T>) -> WechatCpMedia<T> {
@@ -42,7 +40,7 @@ impl<'a, T: SessionStore> WechatCpMedia<'a, T> {
             file_name: file_name.map(|v| v.to_string()).unwrap_or(default_file_name),
             media_data: data
         };
-        let v = self.client.execute::<WechatCpMediaRequest, String>(req).await?.json::<Value>()?;
+        let v = self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpMediaResponse>(v)
     }
 
@@ -59,7 +57,7 @@ impl<'a, T: SessionStore> WechatCpMedia<'a, T> {
             file_name: file_name.to_string(),
             media_data: data
         };
-        let v= self.client.execute::<WechatCpMediaRequest, String>(req).await?.json::<Value>()?;
+        let v= self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpMediaResponse>(v)
     }
 
@@ -71,12 +69,20 @@ impl<'a, T: SessionStore> WechatCpMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/upload?access_token=ACCESS_TOKEN&type=TYPE
     /// </pre>
     pub async fn upload_media_with_file(&self, media_type: &str, file_path: &str) -> LabradorResult<WechatCpMediaResponse> {
-        let path = Path::new(file_path);
-        let file_name = path.file_name().map(|v| v.to_str().unwrap_or_default()).unwrap_or_default();
-        let mut f = File::open(path)?;
-        let mut content: Vec<u8> = Vec::new();
-        let _ = f.read_to_end(&mut content)?;
-        self.upload_media(media_type, file_name.into(),content).await
+        let v = self.client.post_file(WechatCpMethod::Media(CpMediaMethod::UploadMedia(media_type.to_string())), vec![], "media", file_path).await?.json::<Value>()?;
+        WechatCommonResponse::parse::<WechatCpMediaResponse>(v)
+    }
+
+    /// <pre>
+    /// 新增临时素材，从数据流中读取文件内容，不会将整个文件读入内存
+    /// `length`为文件长度，不知道长度时使用分块传输
+    /// 数据流无法重复发送，access_token失效时不会重试
+    /// 接口url格式：https://qyapi.weixin.qq.com/cgi-bin/media/upload?access_token=ACCESS_TOKEN&type=TYPE
+    /// </pre>
+    pub async fn upload_media_stream<R: AsyncRead + Send + Sync + 'static>(&self, media_type: &str, file_name: &str, reader: R, length: Option<u64>) -> LabradorResult<WechatCpMediaResponse> {
+        let form = Form::new().part("media", stream_part(reader, file_name, length));
+        let v = self.client.post_multipart(WechatCpMethod::Media(CpMediaMethod::UploadMedia(media_type.to_string())), vec![], form).await?.json::<Value>()?;
+        WechatCommonResponse::parse::<WechatCpMediaResponse>(v)
     }
 
     /// <pre>
@@ -98,22 +104,27 @@ impl<'a, T: SessionStore> WechatCpMedia<'a, T> {
     /// 详情请见: http://mp.weixin.qq.com/wiki/index.php?title=上传下载多媒体文件
     /// </pre>
     pub async fn get_media(&self, media_id: &str) -> LabradorResult<Bytes> {
-        let response = self.client.post(WechatCpMethod::Media(CpMediaMethod::GetMedia), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null, RequestType::Json).await?;
+        let response = self.client.post(WechatCpMethod::Media(CpMediaMethod::GetMedia), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await?;
         response.bytes()
     }
 
+    /// <pre>
+    /// 下载多媒体文件，流式读取，适用于较大的文件
+    /// 接口url格式：https://qyapi.weixin.qq.com/cgi-bin/media/get?access_token=ACCESS_TOKEN&media_id=MEDIA_ID
+    /// </pre>
+    pub async fn get_media_stream(&self, media_id: &str) -> LabradorResult<LabraStreamResponse> {
+        self.client.post_stream(WechatCpMethod::Media(CpMediaMethod::GetMedia), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await
+    }
+
 
     /// <pre>
     /// 上传附件资源
     /// <a href="https://open.work.weixin.qq.com/api/doc/90001/90143/95178">上传附件资源</a>
     /// </pre>
     pub async fn upload_attachment_with_file(&self, media_type: &str, attachment_type: &str, file_path: &str) -> LabradorResult<WechatCpMediaResponse> {
-        let path = Path::new(file_path);
-        let file_name = path.file_name().map(|v| v.to_str().unwrap_or_default()).unwrap_or_default();
-        let mut f = File::open(path)?;
-        let mut content: Vec<u8> = Vec::new();
-        let _ = f.read_to_end(&mut content)?;
-        self.upload_attachment(media_type, attachment_type, file_name.into(), content).await
+        let querys = vec![(MEDIA_TYPE.to_string(), media_type.to_string()), (ATTACHMENT_TYPE.to_string(), attachment_type.to_string())];
+        let v = self.client.post_file(WechatCpMethod::Media(CpMediaMethod::UploadAttachment), querys, "file", file_path).await?.json::<Value>()?;
+        WechatCommonResponse::parse::<WechatCpMediaResponse>(v)
     }
 
 
@@ -140,7 +151,7 @@ impl<'a, T: SessionStore> WechatCpMedia<'a, T> {
             file_name: file_name.map(|v| v.to_string()).unwrap_or(default_file_name),
             media_data: data
         };
-        let v = self.client.execute::<WechatCpAttachmentRequest, String>(req).await?.json::<Value>()?;
+        let v = self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpMediaResponse>(v)
     }
 
@@ -153,7 +164,7 @@ impl<'a, T: SessionStore> WechatCpMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/get/jssdk?access_token=ACCESS_TOKEN&media_id=MEDIA_ID
     /// </pre>
     pub async fn get_media_jssdk(&self, media_id: &str) -> LabradorResult<Bytes> {
-        let response = self.client.post(WechatCpMethod::Media(CpMediaMethod::GetMediaJssdk), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null, RequestType::Json).await?;
+        let response = self.client.post(WechatCpMethod::Media(CpMediaMethod::GetMediaJssdk), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await?;
         response.bytes()
     }
 }
@@ -174,9 +185,8 @@ impl WechatRequest for WechatCpMediaRequest {
         CpMediaMethod::UploadMedia(self.media_type.to_string()).get_method()
     }
 
-    fn get_request_body<T: Serialize>(&self) -> RequestBody<T> {
-        let form = reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.media_data.to_owned()).file_name(self.file_name.to_string()));
-        form.into()
+    fn get_request_body(&self) -> Form {
+        reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.media_data.to_owned()).file_name(self.file_name.to_string()))
     }
 }
 
@@ -210,8 +220,7 @@ impl WechatRequest for WechatCpAttachmentRequest {
         BTreeMap::from([(MEDIA_TYPE.to_string(), self.media_type.to_string()), (ATTACHMENT_TYPE.to_string(), self.attachment_type.to_string())])
     }
 
-    fn get_request_body<T: Serialize>(&self) -> RequestBody<T> {
-        let form = reqwest::multipart::Form::new().part("file", reqwest::multipart::Part::stream(self.media_data.to_owned()).file_name(self.file_name.to_string()));
-        form.into()
+    fn get_request_body(&self) -> Form {
+        reqwest::multipart::Form::new().part("file", reqwest::multipart::Part::stream(self.media_data.to_owned()).file_name(self.file_name.to_string()))
     }
 }
@@ -1,17 +1,17 @@
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpClient};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpClient};
 use crate::wechat::cp::method::{CpMenuMethod, WechatCpMethod};
 
 /// 菜单管理相关接口
 #[derive(Debug, Clone)]
-pub struct WechatCpMenu<'a, T: SessionStore> {
+pub struct WechatCpMenu<'a, T: AsyncSessionStore> {
     client: &'a WechatCpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpMenu<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpMenu<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpClient<T>) -> WechatCpMenu<T> {
@@ -35,7 +35,7 @@ impl<'a, T: SessionStore> WechatCpMenu<'a, T> {
     /// 详情请见: <a href="http://mp.weixin.qq.com/wiki/index.php?title=自定义菜单创建接口">文档</a>
     /// </pre>
     pub async fn create_with_agentid(&self, agent_id: i32, req: WechatCpMenuInfo) -> LabradorResult<WechatCommonResponse> {
-       self.client.post(WechatCpMethod::Menu(CpMenuMethod::Create(agent_id)), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+       self.client.post(WechatCpMethod::Menu(CpMenuMethod::Create(agent_id)), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -53,7 +53,7 @@ impl<'a, T: SessionStore> WechatCpMenu<'a, T> {
     /// 详情请见: <a href="http://mp.weixin.qq.com/wiki/index.php?title=自定义菜单删除接口">文档</a>
     /// </pre>
     pub async fn delete_with_agentid(&self, agent_id: i32) -> LabradorResult<WechatCommonResponse> {
-       self.client.post(WechatCpMethod::Menu(CpMenuMethod::Delete(agent_id)), vec![], Value::Null, RequestType::Json).await?.json::<WechatCommonResponse>()
+       self.client.post(WechatCpMethod::Menu(CpMenuMethod::Delete(agent_id)), vec![], Value::Null).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -71,7 +71,7 @@ impl<'a, T: SessionStore> WechatCpMenu<'a, T> {
     /// 详情请见: <a href="http://mp.weixin.qq.com/wiki/index.php?title=自定义菜单查询接口">文档</a>
     /// </pre>
     pub async fn get_with_agentid(&self, agent_id: i32) -> LabradorResult<WechatCpMenuInfo> {
-       let v = self.client.post(WechatCpMethod::Menu(CpMenuMethod::Get(agent_id)), vec![], Value::Null, RequestType::Json).await?.json::<Value>()?;
+       let v = self.client.post(WechatCpMethod::Menu(CpMenuMethod::Get(agent_id)), vec![], Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpMenuInfo>(v)
     }
 }
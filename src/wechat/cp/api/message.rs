@@ -1,17 +1,17 @@
 use serde::{Serialize, Deserialize};
 use serde_json::{Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpClient, WechatCpNewArticle, WechatMpNewsArticle};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpClient, WechatCpNewArticle, WechatMpNewsArticle};
 use crate::wechat::cp::method::{CpMessageMethod, WechatCpMethod};
 
 /// 菜单管理相关接口
 #[derive(Debug, Clone)]
-pub struct WechatCpMessage<'a, T: SessionStore> {
+pub struct WechatCpMessage<'a, T: AsyncSessionStore> {
     client: &'a WechatCpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpMessage<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpMessage<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpClient<T>) -> WechatCpMessage<T> {
@@ -29,7 +29,7 @@ impl<'a, T: SessionStore> WechatCpMessage<'a, T> {
         if agent_id == 0 {
             req.agent_id = self.client.agent_id;
         }
-       let v= self.client.post(WechatCpMethod::Message(CpMessageMethod::Send), vec![], req, RequestType::Json).await?.json::<Value>()?;
+       let v= self.client.post(WechatCpMethod::Message(CpMessageMethod::Send), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpMessageResponse>(v)
     }
 
@@ -42,7 +42,7 @@ impl<'a, T: SessionStore> WechatCpMessage<'a, T> {
         if agent_id == 0 {
             req.agent_id = self.client.agent_id;
         }
-        let v = self.client.post(WechatCpMethod::Message(CpMessageMethod::LinkedCorpSend), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::Message(CpMessageMethod::LinkedCorpSend), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpLinkedCorpMessageResponse>(v)
     }
 
@@ -58,7 +58,7 @@ impl<'a, T: SessionStore> WechatCpMessage<'a, T> {
         if agent_id == 0 {
             req.agentid = self.client.agent_id;
         }
-       let v = self.client.post(WechatCpMethod::Message(CpMessageMethod::LinkedCorpSend), vec![], req, RequestType::Json).await?.json::<Value>()?;
+       let v = self.client.post(WechatCpMethod::Message(CpMessageMethod::LinkedCorpSend), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpSchoolContactMessageResponse>(v)
     }
 
@@ -70,7 +70,7 @@ impl<'a, T: SessionStore> WechatCpMessage<'a, T> {
     /// 详情请见:  <a href="https://work.weixin.qq.com/api/doc/90000/90135/92369">文档</a>
     /// </pre>
     pub async fn get_statistics(&self, req: WechatCpLinkedCorpMessage) -> LabradorResult<WechatCpMessageSendStatistics> {
-       let v = self.client.post(WechatCpMethod::Message(CpMessageMethod::Statistics), vec![], req, RequestType::Json).await?.json::<Value>()?;
+       let v = self.client.post(WechatCpMethod::Message(CpMessageMethod::Statistics), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpMessageSendStatistics>(v)
     }
 
@@ -1,18 +1,18 @@
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpClient};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpClient};
 use crate::wechat::cp::constants::{AGENTID, CODE, SNSAPI_BASE, SNSAPI_PRIVATEINFO, SNSAPI_USERINFO, USER_TICKET};
 use crate::wechat::cp::method::{CpOauth2Method, WechatCpMethod};
 
 
 #[derive(Debug, Clone)]
-pub struct WechatCpOauth2<'a, T: SessionStore> {
+pub struct WechatCpOauth2<'a, T: AsyncSessionStore> {
     client: &'a WechatCpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpOauth2<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpOauth2<'a, T> {
     #[inline]
     pub fn new(client: &WechatCpClient<T>) -> WechatCpOauth2<T> {
         WechatCpOauth2 {
@@ -76,7 +76,7 @@ impl<'a, T: SessionStore> WechatCpOauth2<'a, T> {
     ///
     /// 注意: 这个方法里的agentId，需要开发人员自己给出
     pub async fn get_user_info_new(&self, code: &str) -> LabradorResult<WechatCpOauth2UserInfo> {
-        let v = self.client.get(WechatCpMethod::Oauth2(CpOauth2Method::GetAuthUserInfo), vec![(CODE.to_string(), code.to_string())], RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::Oauth2(CpOauth2Method::GetAuthUserInfo), vec![(CODE.to_string(), code.to_string())]).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpOauth2UserInfo>(v)
     }
 
@@ -89,7 +89,7 @@ impl<'a, T: SessionStore> WechatCpOauth2<'a, T> {
     /// 注意: 这个方法里的agentId，需要开发人员自己给出
     pub async fn get_user_info_with_agent(&self, code: &str, agent_id: i32) -> LabradorResult<WechatCpOauth2UserInfo> {
         let agent_id = agent_id.to_string();
-        let v = self.client.get(WechatCpMethod::Oauth2(CpOauth2Method::GetUserInfo), vec![(CODE.to_string(), code.to_string()), (AGENTID.to_string(), agent_id)], RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::Oauth2(CpOauth2Method::GetUserInfo), vec![(CODE.to_string(), code.to_string()), (AGENTID.to_string(), agent_id)]).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpOauth2UserInfo>(v)
     }
 
@@ -103,7 +103,7 @@ impl<'a, T: SessionStore> WechatCpOauth2<'a, T> {
     /// 权限说明：
     /// 需要有对应应用的使用权限，且成员必须在授权应用的可见范围内。
     pub async fn get_user_detail(&self, user_ticket: &str) -> LabradorResult<WechatCpUserDetail> {
-        let v = self.client.post(WechatCpMethod::Oauth2(CpOauth2Method::GetUserDetail), vec![], json!({USER_TICKET: user_ticket}), RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::Oauth2(CpOauth2Method::GetUserDetail), vec![], json!({USER_TICKET: user_ticket})).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserDetail>(v)
     }
 
@@ -112,7 +112,7 @@ impl<'a, T: SessionStore> WechatCpOauth2<'a, T> {
     /// 自建应用与代开发应用可通过该接口获取成员授权的敏感字段.
     /// <a href="https://developer.work.weixin.qq.com/document/path/95833">获取访问用户敏感信息</a>
     pub async fn get_user_detail_new(&self, user_ticket: &str) -> LabradorResult<WechatCpUserDetail> {
-        let v = self.client.post(WechatCpMethod::Oauth2(CpOauth2Method::GetAuthUserDetail), vec![], json!({USER_TICKET: user_ticket}), RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::Oauth2(CpOauth2Method::GetAuthUserDetail), vec![], json!({USER_TICKET: user_ticket})).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserDetail>(v)
     }
 }
@@ -1,17 +1,17 @@
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpClient, ExternalContact, FollowedUser, WechatCpUserInfo};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpClient, ExternalContact, FollowedUser, WechatCpUserInfo};
 use crate::wechat::cp::method::{CpUserMethod, WechatCpMethod};
 
 /// 部门管理
 #[derive(Debug, Clone)]
-pub struct WechatCpUser<'a, T: SessionStore> {
+pub struct WechatCpUser<'a, T: AsyncSessionStore> {
     client: &'a WechatCpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpUser<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpUser<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpClient<T>) -> WechatCpUser<T> {
@@ -25,7 +25,7 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
     ///   企业在员工验证成功后，调用本方法告诉企业号平台该员工关注成功。
     /// </pre>
     pub async fn authenticate(&self, user_id: &str) -> LabradorResult<WechatCommonResponse> {
-       self.client.get(WechatCpMethod::User(CpUserMethod::AuthSuccess(user_id.to_string())), vec![], RequestType::Json).await?.json::<WechatCommonResponse>()
+       self.client.get(WechatCpMethod::User(CpUserMethod::AuthSuccess(user_id.to_string())), vec![]).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -45,7 +45,7 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
         } else {
             query.push(("status".to_string(), "0".to_string()));
         }
-        let v = self.client.get(WechatCpMethod::User(CpUserMethod::List(depart_id)), query, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::User(CpUserMethod::List(depart_id)), query).await?.json::<Value>()?;
         WechatCommonResponse::parse::<Vec<WechatCpUserInfo>>(v)
     }
 
@@ -64,7 +64,7 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
         } else {
             query.push(("status".to_string(), "0".to_string()));
         }
-        let v = self.client.get(WechatCpMethod::User(CpUserMethod::SimpleList(depart_id)), query, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::User(CpUserMethod::SimpleList(depart_id)), query).await?.json::<Value>()?;
         WechatCommonResponse::parse::<Vec<WechatCpUserInfo>>(v)
     }
 
@@ -73,14 +73,14 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
     /// 新建用户
     /// </pre>
     pub async fn create(&self, req: WechatCpUserInfo) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::User(CpUserMethod::Create), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::User(CpUserMethod::Create), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
     /// 更新用户
     /// </pre>
     pub async fn update(&self, req: WechatCpUserInfo) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::User(CpUserMethod::Update), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::User(CpUserMethod::Update), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -89,9 +89,9 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
     /// </pre>
     pub async fn delete(&self, user_ids: Vec<&str>) -> LabradorResult<WechatCommonResponse> {
         if user_ids.len() == 1 {
-            self.client.get(WechatCpMethod::User(CpUserMethod::Delete(user_ids[0].to_string())), vec![], RequestType::Json).await?.json::<WechatCommonResponse>()
+            self.client.get(WechatCpMethod::User(CpUserMethod::Delete(user_ids[0].to_string())), vec![]).await?.json::<WechatCommonResponse>()
         } else {
-            self.client.post(WechatCpMethod::User(CpUserMethod::BatchDelete), vec![], json!({"useridlist": user_ids}), RequestType::Json).await?.json::<WechatCommonResponse>()
+            self.client.post(WechatCpMethod::User(CpUserMethod::BatchDelete), vec![], json!({"useridlist": user_ids})).await?.json::<WechatCommonResponse>()
         }
 
     }
@@ -100,7 +100,7 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
     /// 获取用户
     /// </pre>
     pub async fn get_by_id(&self, userid: &str, corp_id: &str) -> LabradorResult<WechatCpUserInfo> {
-        let v = self.client.get(WechatCpMethod::User(CpUserMethod::Get(userid.to_string())), vec![],RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::User(CpUserMethod::Get(userid.to_string())), vec![]).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserInfo>(v)
     }
 
@@ -117,7 +117,7 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
             "party": party_ids,
             "tag": tag_ids,
         });
-        let v = self.client.post(WechatCpMethod::User(CpUserMethod::Invite), vec![],req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::User(CpUserMethod::Invite), vec![],req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WxCpInviteResponse>(v)
     }
 
@@ -136,7 +136,7 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
             "userid": userid,
             "agentid": agent_id,
         });
-        let v = self.client.post(WechatCpMethod::User(CpUserMethod::ConvertToOpenid), vec![],req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::User(CpUserMethod::ConvertToOpenid), vec![],req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WxCpUseridToOpenidResponse>(v)
     }
 
@@ -154,7 +154,7 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
         let req = json!({
             "openid": openid,
         });
-        let v = self.client.post(WechatCpMethod::User(CpUserMethod::ConvertToUserid), vec![],req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::User(CpUserMethod::ConvertToUserid), vec![],req).await?.json::<Value>()?;
         let v= WechatCommonResponse::parse::<Value>(v)?;
         let userid = v["userid"].as_str().unwrap_or_default();
         Ok(userid.to_string())
@@ -173,7 +173,7 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
         let req = json!({
             "mobile": mobile,
         });
-        let v = self.client.post(WechatCpMethod::User(CpUserMethod::GetUserid), vec![],req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::User(CpUserMethod::GetUserid), vec![],req).await?.json::<Value>()?;
         let v= WechatCommonResponse::parse::<Value>(v)?;
         let userid = v["userid"].as_str().unwrap_or_default();
         Ok(userid.to_string())
@@ -187,7 +187,7 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
     /// 第三方应用调用时，返回的跟进人follow_user仅包含应用可见范围之内的成员。
     /// </pre>
     pub async fn get_external_contact(&self, userid: &str) -> LabradorResult<WechatCpUserExternalContactInfo> {
-        let v = self.client.get(WechatCpMethod::User(CpUserMethod::GetExternalContact(userid.to_string())), vec![],RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::User(CpUserMethod::GetExternalContact(userid.to_string())), vec![]).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserExternalContactInfo>(v)
     }
 
@@ -200,7 +200,7 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
     /// 文档地址：https://work.weixin.qq.com/api/doc/90000/90135/91714
     /// </pre>
     pub async fn get_join_qrcode(&self, size_type: i32) -> LabradorResult<String> {
-        let v = self.client.get(WechatCpMethod::User(CpUserMethod::GetJoinQrcode(size_type)), vec![],RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::User(CpUserMethod::GetJoinQrcode(size_type)), vec![]).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let qrcode = v["join_qrcode"].as_str().unwrap_or_default();
         Ok(qrcode.to_string())
@@ -215,7 +215,7 @@ impl<'a, T: SessionStore> WechatCpUser<'a, T> {
     /// 文档地址：<a href="https://developer.work.weixin.qq.com/document/path/92714">https://developer.work.weixin.qq.com/document/path/92714</a>
     /// </pre>
     pub async fn get_active_count(&self, date: &str) -> LabradorResult<u64> {
-        let v = self.client.post(WechatCpMethod::User(CpUserMethod::GetActiveStat), vec![], json!({"date": date}),RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::User(CpUserMethod::GetActiveStat), vec![], json!({"date": date})).await?.json::<Value>()?;
         let active_cnt = v["active_cnt"].as_u64().unwrap_or_default();
         Ok(active_cnt)
     }
@@ -73,6 +73,33 @@ impl RequestMethod for WechatCpMethod {
             WechatCpMethod::Agent(v) => v.get_method(),
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        match self {
+            WechatCpMethod::GetOrder | WechatCpMethod::GetOrderList | WechatCpMethod::GetAuthInfo | WechatCpMethod::GetAdminInfo
+            | WechatCpMethod::GetCallbackIp | WechatCpMethod::CorpToOpenCorpid => true,
+            WechatCpMethod::Media(v) => matches!(v, CpMediaMethod::GetMedia | CpMediaMethod::GetMediaJssdk),
+            WechatCpMethod::Tag(v) => matches!(v, CpTagMethod::List | CpTagMethod::Get(_)),
+            WechatCpMethod::Agent(v) => matches!(v, CpAgentMethod::Get(_) | CpAgentMethod::List),
+            WechatCpMethod::License(v) => matches!(v, CpLicenseMethod::ListOrder | CpLicenseMethod::GetOrder | CpLicenseMethod::ListOrderAccount
+                | CpLicenseMethod::GetActiveInfoByCode | CpLicenseMethod::BatchGetActiveInfoByCode | CpLicenseMethod::ListActivedAccount
+                | CpLicenseMethod::GetActiveInfoByUser),
+            WechatCpMethod::Menu(v) => matches!(v, CpMenuMethod::Get(_)),
+            WechatCpMethod::User(v) => matches!(v, CpUserMethod::ConvertToOpenid | CpUserMethod::ConvertToUserid | CpUserMethod::GetUserid
+                | CpUserMethod::GetActiveStat | CpUserMethod::Get(_) | CpUserMethod::GetExternalContact(_) | CpUserMethod::List(_) | CpUserMethod::SimpleList(_)),
+            WechatCpMethod::Department(v) => matches!(v, CpDepartmentMethod::List | CpDepartmentMethod::SimpleList | CpDepartmentMethod::Get(_)),
+            WechatCpMethod::Message(v) => matches!(v, CpMessageMethod::Statistics),
+            WechatCpMethod::ExternalContact(v) => matches!(v, CpExternalContactMethod::GetContactWay | CpExternalContactMethod::GetContactWayDetail
+                | CpExternalContactMethod::ConvertToOpenid | CpExternalContactMethod::UnionidToExternalUserid | CpExternalContactMethod::BatchGetByUser
+                | CpExternalContactMethod::List | CpExternalContactMethod::GetFollowUserList | CpExternalContactMethod::GetUnassignedList
+                | CpExternalContactMethod::TransferResult | CpExternalContactMethod::GetUserBehaviorData | CpExternalContactMethod::ResignedTransferResult
+                | CpExternalContactMethod::GroupChatGetJoinWay | CpExternalContactMethod::GroupChatList | CpExternalContactMethod::GroupChatGet
+                | CpExternalContactMethod::GroupChatStatistic | CpExternalContactMethod::GetCorpTagList | CpExternalContactMethod::GetGroupMsgListV2
+                | CpExternalContactMethod::GetGroupMsgSendResult | CpExternalContactMethod::GetGroupMsgResult | CpExternalContactMethod::GetGroupMsgTask
+                | CpExternalContactMethod::GetGroupWelcomeTemplate),
+            _ => false,
+        }
+    }
 }
 
 #[allow(unused)]
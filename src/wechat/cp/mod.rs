@@ -1,4 +1,8 @@
-use crate::{session::SessionStore, client::APIClient, request::{Method, RequestType, LabraResponse, LabraRequest, RequestMethod}, util::current_timestamp, LabradorResult, SimpleStorage, WechatCrypto, WechatRequest, get_timestamp, get_nonce_str, WechatCommonResponse};
+use std::fmt;
+use crate::wechat::{cached_token, check_stream, is_invalid_token};
+use crate::{session::AsyncSessionStore, client::APIClient, request::{LabraResponse, LabraRequest, RequestMethod}, LabraStreamResponse, Form, file_part, util::current_timestamp, LabradorResult, SimpleStorage, WechatCrypto, WechatRequest, get_timestamp, get_nonce_str, WechatCommonResponse, LabraHttpClient, LabraHttpConfig, HttpTransport, Middleware, LabraLogConfig, RetryPolicy, RateLimiter, LockOptions, refresh_with_lock, telemetry, TenantClient};
+use crate::config::{Proxy, Secret};
+use std::sync::Arc;
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
@@ -25,7 +29,7 @@ use crate::wechat::cp::method::{WechatCpMethod};
 
 #[allow(unused)]
 #[derive(Debug, Clone)]
-pub struct WechatCpClient<T: SessionStore> {
+pub struct WechatCpClient<T: AsyncSessionStore> {
     corp_id: String,
     corp_secret: String,
     token: Option<String>,
@@ -36,6 +40,63 @@ pub struct WechatCpClient<T: SessionStore> {
     client: APIClient<T>,
 }
 
+/// 企业微信配置
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct WechatCpConfig {
+    pub corp_id: String,
+    pub corp_secret: String,
+    pub agent_id: Option<i32>,
+    pub token: Option<String>,
+    pub aes_key: Option<String>,
+    /// 接口地址，用于测试环境或本地模拟服务
+    pub api_path: Option<String>,
+    /// 代理地址，如：http://127.0.0.1:8080
+    pub proxy: Option<String>,
+}
+
+impl fmt::Debug for WechatCpConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WechatCpConfig")
+            .field("corp_id", &self.corp_id)
+            .field("corp_secret", &Secret(&self.corp_secret))
+            .field("agent_id", &self.agent_id)
+            .field("token", &Secret::option(&self.token))
+            .field("aes_key", &Secret::option(&self.aes_key))
+            .field("api_path", &self.api_path)
+            .field("proxy", &Proxy::option(&self.proxy))
+            .finish()
+    }
+}
+
+impl WechatCpConfig {
+    pub fn new<S: Into<String>>(corp_id: S, corp_secret: S) -> Self {
+        Self { corp_id: corp_id.into(), corp_secret: corp_secret.into(), ..Default::default() }
+    }
+}
+
+impl<T: AsyncSessionStore> TenantClient<T> for WechatCpClient<T> {
+    type Config = WechatCpConfig;
+
+    /// 同一企业的不同应用使用不同的secret，以`corp_id`与`agent_id`区分
+    fn tenant_id(config: &Self::Config) -> String {
+        match config.agent_id {
+            Some(agent_id) => format!("{}_{}", config.corp_id, agent_id),
+            None => config.corp_id.to_string(),
+        }
+    }
+
+    fn build(config: &Self::Config, session: T, transport: Arc<dyn HttpTransport>) -> LabradorResult<Self> {
+        // 配置了代理时在共用传输层的HTTP配置上生成带代理的客户端
+        let mut config = config.clone();
+        let proxy = config.proxy.take();
+        let client = Self::from_config(&config, session)?.transport(transport);
+        match proxy {
+            Some(proxy) => client.proxy(&proxy),
+            None => Ok(client),
+        }
+    }
+}
+
 #[allow(unused)]
 #[derive(Serialize, Deserialize)]
 pub struct AccessTokenResponse{
@@ -83,7 +144,7 @@ pub struct WechatCpProviderToken {
 }
 
 #[allow(unused)]
-impl<T: SessionStore> WechatCpClient<T> {
+impl<T: AsyncSessionStore> WechatCpClient<T> {
 
     fn from_client(client: APIClient<T>) -> WechatCpClient<T> {
         WechatCpClient {
@@ -108,6 +169,11 @@ impl<T: SessionStore> WechatCpClient<T> {
         self
     }
 
+    pub fn agent_id(mut self, agent_id: i32) -> Self {
+        self.agent_id = agent_id.into();
+        self
+    }
+
     pub fn oauth2_redirect_uri(mut self, oauth2_redirect_uri: &str) -> Self {
         self.oauth2_redirect_uri = oauth2_redirect_uri.to_string().into();
         self
@@ -118,43 +184,144 @@ impl<T: SessionStore> WechatCpClient<T> {
         self
     }
 
+    /// 设置HTTP客户端，多个客户端可共用同一个连接池
+    pub fn http_client(mut self, http_client: LabraHttpClient) -> Self {
+        self.client = self.client.http_client(http_client);
+        self
+    }
+
+    /// 根据配置生成独立的HTTP客户端，可设置代理、超时等
+    pub fn http_config(mut self, config: LabraHttpConfig) -> LabradorResult<Self> {
+        self.client = self.client.http_config(config)?;
+        Ok(self)
+    }
+
+    /// 设置代理，如：http://127.0.0.1:8080，开启`socks`特性后支持socks5
+    pub fn proxy(mut self, proxy: &str) -> LabradorResult<Self> {
+        self.client = self.client.proxy(proxy)?;
+        Ok(self)
+    }
+
+    /// 设置接口地址，可指向测试环境或本地的模拟服务
+    pub fn api_path<S: Into<String>>(mut self, api_path: S) -> Self {
+        self.client = self.client.api_path(api_path);
+        self
+    }
+
+    /// 设置传输层，测试时可替换为`MockTransport`
+    pub fn transport<H: HttpTransport + 'static>(mut self, transport: H) -> Self {
+        self.client = self.client.transport(transport);
+        self
+    }
+
+    /// 添加中间件
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.client = self.client.middleware(middleware);
+        self
+    }
+
+    /// 设置请求日志配置
+    pub fn log_config(mut self, log_config: LabraLogConfig) -> Self {
+        self.client = self.client.log_config(log_config);
+        self
+    }
+
+    /// 设置access_token刷新锁配置
+    pub fn lock_options(mut self, lock_options: LockOptions) -> Self {
+        self.client = self.client.lock_options(lock_options);
+        self
+    }
+
+    /// 设置重试策略，GET请求和通讯录、客户联系、接口许可等查询接口任何失败都可以重试；发送消息、创建成员等写接口只会在连接阶段失败时重试
+    pub fn retry_policy<P: RetryPolicy + 'static>(mut self, retry_policy: P) -> Self {
+        self.client = self.client.retry_policy(retry_policy);
+        self
+    }
+
+    /// 设置限流器，按接口方法限流并统计每日调用次数
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.client = self.client.rate_limiter(rate_limiter);
+        self
+    }
+
     /// get the wechat client
     pub fn new<S: Into<String>>(corp_id: S, corp_secret: S) -> WechatCpClient<SimpleStorage> {
         let session = SimpleStorage::new();
-        let client = APIClient::from_session(corp_id.into(), corp_secret.into(), "https://qyapi.weixin.qq.com", session);
+        let client = APIClient::from_session(corp_id.into(), corp_secret.into(), "https://qyapi.weixin.qq.com", session).platform("wechat_cp");
         WechatCpClient::from_client(client)
     }
 
     /// get the wechat client
     pub fn from_session<S: Into<String>>(corp_id: S, corp_secret: S, session: T) -> WechatCpClient<T> {
-        let client = APIClient::from_session(corp_id.into(), corp_secret.into(), "https://qyapi.weixin.qq.com", session);
+        let client = APIClient::from_session(corp_id.into(), corp_secret.into(), "https://qyapi.weixin.qq.com", session).platform("wechat_cp");
         Self::from_client(client)
     }
 
+    /// 根据配置创建客户端
+    pub fn from_config(config: &WechatCpConfig, session: T) -> LabradorResult<WechatCpClient<T>> {
+        let mut client = Self::from_session(config.corp_id.as_str(), config.corp_secret.as_str(), session);
+        if let Some(agent_id) = config.agent_id {
+            client = client.agent_id(agent_id);
+        }
+        if let Some(token) = config.token.as_ref() {
+            client = client.token(token);
+        }
+        if let Some(aes_key) = config.aes_key.as_ref() {
+            client = client.aes_key(aes_key);
+        }
+        if let Some(api_path) = config.api_path.as_ref() {
+            client = client.api_path(api_path.as_str());
+        }
+        if let Some(proxy) = config.proxy.as_ref() {
+            client = client.proxy(proxy)?;
+        }
+        Ok(client)
+    }
+
+    /// 获取access_token，`force_refresh`为true时丢弃缓存中的凭证重新获取
     #[inline]
     pub async fn access_token(&self, force_refresh: bool) -> LabradorResult<String> {
-        let mut session = self.client.session();
-        let token_key = format!("{}_access_token_cp", self.corp_id);
-        let expires_key = format!("{}_expires_at_cp", self.corp_id);
-        let token: String = session.get(&token_key, Some("".to_owned()))?.unwrap_or_default();
-        let timestamp = current_timestamp();
-        let expires_at: i64 = session.get(&expires_key, Some(timestamp))?.unwrap_or_default();
-        if expires_at <= timestamp || force_refresh {
-            let mut req = LabraRequest::<String>::new().url(WechatCpMethod::AccessToken.get_method()).params(vec![
-                (CORPID.to_string(), self.corp_id.to_string()),
-                (CORPSECRET.to_string(), self.corp_secret.to_string()),
-            ]).method(Method::Get).req_type(RequestType::Json);
-            let res = self.client.request(req).await?.json::<AccessTokenResponse>()?;
-            let token = res.access_token;
-            let expires_in = res.expires_in;
-            // 预留200秒的时间
-            let expires_at = current_timestamp() + expires_in - 200;
-            session.set(&token_key, token.to_owned(), Some(expires_in as usize));
-            session.set(&expires_key, expires_at, Some(expires_in as usize));
-            Ok(token.to_string())
+        let rejected = if force_refresh {
+            let (token_key, expires_key) = self.access_token_keys();
+            cached_token(self.client.session(), &token_key, &expires_key).await?
         } else {
-            Ok(token)
-        }
+            None
+        };
+        self.refresh_access_token(rejected.as_deref()).await
+    }
+
+    /// 接口返回access_token失效时获取新的凭证，`rejected`为失效的access_token
+    ///
+    /// 缓存中的凭证已被其他请求刷新时直接使用，多个请求同时遇到失效时只刷新一次。
+    pub async fn access_token_replacing(&self, rejected: &str) -> LabradorResult<String> {
+        self.refresh_access_token(Some(rejected)).await
+    }
+
+    fn access_token_keys(&self) -> (String, String) {
+        (format!("{}_access_token_cp", self.corp_id), format!("{}_expires_at_cp", self.corp_id))
+    }
+
+    async fn refresh_access_token(&self, rejected: Option<&str>) -> LabradorResult<String> {
+        let session = self.client.session();
+        let (token_key, expires_key) = &self.access_token_keys();
+        let lock_key = format!("{}_lock", token_key);
+        refresh_with_lock(session, &lock_key, &self.client.lock_options, rejected,
+            || cached_token(session, token_key, expires_key),
+            || async move {
+                let mut req = LabraRequest::get(WechatCpMethod::AccessToken.get_method()).params(vec![
+                    (CORPID.to_string(), self.corp_id.to_string()),
+                    (CORPSECRET.to_string(), self.corp_secret.to_string()),
+                ]);
+                let res = self.client.request(req).await?.json::<AccessTokenResponse>()?;
+                let token = res.access_token;
+                let expires_in = res.expires_in;
+                // 预留200秒的时间
+                let expires_at = current_timestamp() + expires_in - 200;
+                session.set(token_key, token.to_owned(), Some(expires_in as usize)).await?;
+                session.set(expires_key, expires_at, Some(expires_in as usize)).await?;
+                telemetry::record_token_refresh(self.client.platform, "access_token");
+                Ok(token)
+            }).await
     }
     
     /// <pre>
@@ -165,10 +332,10 @@ impl<T: SessionStore> WechatCpClient<T> {
     /// </pre>
     #[inline]
     pub async fn get_provider_token(&self, corp_id: &str, provider_secret: &str) -> LabradorResult<WechatCpProviderToken> {
-        let mut req = LabraRequest::new().url(WechatCpMethod::GetProviderToken.get_method()).json(json!({
+        let mut req = LabraRequest::post_json(WechatCpMethod::GetProviderToken.get_method(), json!({
             "corpid": corp_id,
             "provider_secret": provider_secret,
-        })).method(Method::Post).req_type(RequestType::Json);
+        }));
         let res = self.client.request(req).await?.json::<WechatCpProviderToken>()?;
         Ok(res)
     }
@@ -237,11 +404,11 @@ impl<T: SessionStore> WechatCpClient<T> {
         let mut session = self.client.session();
         let token_key = format!("{}_jsapi_ticket_cp", self.corp_id);
         let expires_key = format!("{}_jsapi_ticket_expires_at_cp", self.corp_id);
-        let ticket: String = session.get(&token_key, Some("".to_owned()))?.unwrap_or_default();
+        let ticket: String = session.get(&token_key, Some("".to_owned())).await?.unwrap_or_default();
         let timestamp = current_timestamp();
-        let expires_at: i64 = session.get(&expires_key, Some(timestamp))?.unwrap_or_default();
+        let expires_at: i64 = session.get(&expires_key, Some(timestamp)).await?.unwrap_or_default();
         if expires_at <= timestamp || force_refresh {
-            let mut req = LabraRequest::<String>::new().url(WechatCpMethod::GetJsapiTicket.get_method()).params(vec![]).method(Method::Get).req_type(RequestType::Json);
+            let mut req = LabraRequest::get(WechatCpMethod::GetJsapiTicket.get_method()).params(vec![]);
             let res = self.client.request(req).await?.json::<JsapiTicket>()?;
             let ticket = res.ticket;
             let expires_in = res.expires_in;
@@ -249,8 +416,9 @@ impl<T: SessionStore> WechatCpClient<T> {
             let expires_at = current_timestamp() + expires_in - 200;
             let ticket_key = format!("{}_jsapi_ticket_cp", self.corp_id);
             let expires_key = format!("{}_jsapi_ticket_expires_at_cp", self.corp_id);
-            session.set(&ticket_key, ticket.to_owned(), Some(expires_in as usize));
-            session.set(&expires_key, expires_at, Some(expires_in as usize));
+            session.set(&ticket_key, ticket.to_owned(), Some(expires_in as usize)).await?;
+            session.set(&expires_key, expires_at, Some(expires_in as usize)).await?;
+            telemetry::record_token_refresh(self.client.platform, "jsapi_ticket");
             Ok(ticket.to_string())
         } else {
             Ok(ticket)
@@ -269,11 +437,11 @@ impl<T: SessionStore> WechatCpClient<T> {
         let mut session = self.client.session();
         let token_key = format!("{}_agent_jsapi_ticket_cp", self.corp_id);
         let expires_key = format!("{}_agent_jsapi_ticket_expires_at_cp", self.corp_id);
-        let ticket: String = session.get(&token_key, Some("".to_owned()))?.unwrap_or_default();
+        let ticket: String = session.get(&token_key, Some("".to_owned())).await?.unwrap_or_default();
         let timestamp = current_timestamp();
-        let expires_at: i64 = session.get(&expires_key, Some(timestamp))?.unwrap_or_default();
+        let expires_at: i64 = session.get(&expires_key, Some(timestamp)).await?.unwrap_or_default();
         if expires_at <= timestamp || force_refresh {
-            let mut req = LabraRequest::<String>::new().url(WechatCpMethod::GetAgentConfigTicket.get_method()).params(vec![]).method(Method::Get).req_type(RequestType::Json);
+            let mut req = LabraRequest::get(WechatCpMethod::GetAgentConfigTicket.get_method()).params(vec![]);
             let res = self.client.request(req).await?.json::<JsapiTicket>()?;
             let ticket = res.ticket;
             let expires_in = res.expires_in;
@@ -281,8 +449,9 @@ impl<T: SessionStore> WechatCpClient<T> {
             let expires_at = current_timestamp() + expires_in - 200;
             let ticket_key = format!("{}_agent_jsapi_ticket_cp", self.corp_id);
             let expires_key = format!("{}_agent_jsapi_ticket_expires_at_cp", self.corp_id);
-            session.set(&ticket_key, ticket.to_owned(), Some(expires_in as usize));
-            session.set(&expires_key, expires_at, Some(expires_in as usize));
+            session.set(&ticket_key, ticket.to_owned(), Some(expires_in as usize)).await?;
+            session.set(&expires_key, expires_at, Some(expires_in as usize)).await?;
+            telemetry::record_token_refresh(self.client.platform, "agent_jsapi_ticket");
             Ok(ticket.to_string())
         } else {
             Ok(ticket)
@@ -295,7 +464,7 @@ impl<T: SessionStore> WechatCpClient<T> {
     /// [文档](http://qydev.weixin.qq.com/wiki/index.php?title=回调模式#.E8.8E.B7.E5.8F.96.E5.BE.AE.E4.BF.A1.E6.9C.8D.E5.8A.A1.E5.99.A8.E7.9A.84ip.E6.AE.B5)
     /// </pre>
     pub async fn get_callback_ip(&self, force_refresh: bool) -> LabradorResult<Vec<String>> {
-        let v = self.get(WechatCpMethod::GetCallbackIp, vec![], RequestType::Json).await?.json::<Value>()?;
+        let v = self.get(WechatCpMethod::GetCallbackIp, vec![]).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let ip_list = v["ip_list"].as_array().unwrap_or(&vec![]).iter().map(|v| v.as_str().unwrap_or_default().to_string()).collect::<Vec<String>>();
         Ok(ip_list)
@@ -305,36 +474,115 @@ impl<T: SessionStore> WechatCpClient<T> {
     /// Service没有实现某个API的时候，可以用这个，
     /// 比 get 和 post 方法更灵活，可以自己构造用来处理不同的参数和不同的返回类型。
     /// </pre>
-    async fn execute<D: WechatRequest, B: Serialize>(&self, request: D) -> LabradorResult<LabraResponse> {
-        let mut querys = request.get_query_params();
-        if request.is_need_token() {
-            let access_token = self.access_token(false).await?;
-            if !access_token.is_empty() {
+    async fn execute<D: WechatRequest>(&self, request: D) -> LabradorResult<LabraResponse> {
+        let send = |access_token: Option<String>| {
+            let mut querys = request.get_query_params();
+            if let Some(access_token) = access_token.filter(|v| !v.is_empty()) {
                 querys.insert(ACCESS_TOKEN.to_string(), access_token);
             }
+            let params = querys.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<(String, String)>>();
+            let req = LabraRequest::post_multipart(request.get_api_method_name(), request.get_request_body())
+                .params(params);
+            self.client.request(req)
+        };
+        if !request.is_need_token() {
+            return send(None).await;
+        }
+        let access_token = self.access_token(false).await?;
+        let response = send(Some(access_token.to_owned())).await?;
+        if is_invalid_token(&response) {
+            // access_token已失效，强制刷新后重试一次
+            return send(Some(self.access_token_replacing(&access_token).await?)).await;
         }
-        let params = querys.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<(String, String)>>();
-        let mut req = LabraRequest::<B>::new().url(request.get_api_method_name())
-            .params(params).method(request.get_request_method()).req_type(request.get_request_type()).body(request.get_request_body::<B>());
-        self.client.request(req).await
+        Ok(response)
     }
 
     /// 发送POST请求
-    async fn post<D: Serialize>(&self, method: WechatCpMethod, mut querys: Vec<(String, String)>, data: D, request_type: RequestType) -> LabradorResult<LabraResponse> {
+    async fn post<D: Serialize>(&self, method: WechatCpMethod, querys: Vec<(String, String)>, data: D) -> LabradorResult<LabraResponse> {
+        let send = |access_token: String| {
+            let mut querys = querys.clone();
+            if !access_token.is_empty() && method.need_token() {
+                querys.push((ACCESS_TOKEN.to_string(), access_token));
+            }
+            self.client.post(method.clone(), querys, &data)
+        };
         let access_token = self.access_token(false).await?;
-        if !access_token.is_empty() && method.need_token() {
-            querys.push((ACCESS_TOKEN.to_string(), access_token));
+        let response = send(access_token.to_owned()).await?;
+        if method.need_token() && is_invalid_token(&response) {
+            // access_token已失效，强制刷新后重试一次
+            return send(self.access_token_replacing(&access_token).await?).await;
+        }
+        Ok(response)
+    }
+
+    /// 发送POST请求，流式读取响应，用于下载文件
+    async fn post_stream<D: Serialize>(&self, method: WechatCpMethod, querys: Vec<(String, String)>, data: D) -> LabradorResult<LabraStreamResponse> {
+        let send = |access_token: String| {
+            let mut querys = querys.clone();
+            if !access_token.is_empty() && method.need_token() {
+                querys.push((ACCESS_TOKEN.to_string(), access_token));
+            }
+            self.client.post_stream(method.clone(), querys, &data)
+        };
+        let access_token = self.access_token(false).await?;
+        let response = send(access_token.to_owned()).await?;
+        if method.need_token() && response.is_json() {
+            let response = response.into_response().await?;
+            if is_invalid_token(&response) {
+                // access_token已失效，强制刷新后重试一次
+                return check_stream(send(self.access_token_replacing(&access_token).await?).await?).await;
+            }
+            return check_stream(response.into()).await;
         }
-        self.client.post(method, querys, data, request_type).await
+        Ok(response)
+    }
+
+    /// 上传multipart表单，表单无法重复发送，access_token失效时不会重试
+    async fn post_multipart(&self, method: WechatCpMethod, mut querys: Vec<(String, String)>, form: Form) -> LabradorResult<LabraResponse> {
+        if method.need_token() {
+            querys.push((ACCESS_TOKEN.to_string(), self.access_token(false).await?));
+        }
+        self.client.post_multipart(method, querys, form).await
+    }
+
+    /// 上传本地文件，access_token失效时重新打开文件后重试一次
+    async fn post_file(&self, method: WechatCpMethod, querys: Vec<(String, String)>, name: &str, file_path: &str) -> LabradorResult<LabraResponse> {
+        let send = |access_token: String| {
+            let mut querys = querys.clone();
+            if !access_token.is_empty() && method.need_token() {
+                querys.push((ACCESS_TOKEN.to_string(), access_token));
+            }
+            let method = method.clone();
+            async move {
+                let form = Form::new().part(name.to_string(), file_part(file_path).await?);
+                self.client.post_multipart(method, querys, form).await
+            }
+        };
+        let access_token = self.access_token(false).await?;
+        let response = send(access_token.to_owned()).await?;
+        if method.need_token() && is_invalid_token(&response) {
+            // access_token已失效，重新打开文件后重试一次
+            return send(self.access_token_replacing(&access_token).await?).await;
+        }
+        Ok(response)
     }
 
     /// 发送GET请求
-    async fn get(&self, method: WechatCpMethod, mut params: Vec<(String, String)>, request_type: RequestType) -> LabradorResult<LabraResponse> {
+    async fn get(&self, method: WechatCpMethod, params: Vec<(String, String)>) -> LabradorResult<LabraResponse> {
+        let send = |access_token: String| {
+            let mut params = params.clone();
+            if !access_token.is_empty() && method.need_token() {
+                params.push((ACCESS_TOKEN.to_string(), access_token));
+            }
+            self.client.get(method.clone(), params)
+        };
         let access_token = self.access_token(false).await?;
-        if !access_token.is_empty() && method.need_token() {
-            params.push((ACCESS_TOKEN.to_string(), access_token));
+        let response = send(access_token.to_owned()).await?;
+        if method.need_token() && is_invalid_token(&response) {
+            // access_token已失效，强制刷新后重试一次
+            return send(self.access_token_replacing(&access_token).await?).await;
         }
-        self.client.get(method, params, request_type).await
+        Ok(response)
     }
 
     /// codesssion相关服务
@@ -1,18 +1,18 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
-use crate::{LabradorResult, request::RequestType, session::SessionStore, WechatCommonResponse, WechatCpTpClient};
+use crate::{LabradorResult, session::AsyncSessionStore, WechatCommonResponse, WechatCpTpClient};
 use crate::wechat::cp::constants::PROVIDER_ACCESS_TOKEN;
 use crate::wechat::cp::method::WechatCpMethod;
 
 /// 部门管理
 #[derive(Debug, Clone)]
-pub struct WechatCpTpAgent<'a, T: SessionStore> {
+pub struct WechatCpTpAgent<'a, T: AsyncSessionStore> {
     client: &'a WechatCpTpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpTpAgent<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpTpAgent<'a, T> {
     #[inline]
     pub fn new(client: &WechatCpTpClient<T>) -> WechatCpTpAgent<T> {
         WechatCpTpAgent {
@@ -29,7 +29,7 @@ impl<'a, T: SessionStore> WechatCpTpAgent<'a, T> {
         let req = json!({"state":state,"templateid_list":templateid_list});
         let access_token = self.client.get_wechat_provider_token().await?;
         let query = vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)];
-        let v = self.client.post(WechatCpMethod::GetCustomizedAuthUrl, query, req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::GetCustomizedAuthUrl, query, req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpProxyResponse>(v)
     }
 }
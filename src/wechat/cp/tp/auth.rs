@@ -1,17 +1,17 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{Value};
 
-use crate::{LabradorResult, request::RequestType, session::SessionStore, WechatCommonResponse, WechatCpTpClient};
+use crate::{LabradorResult, session::AsyncSessionStore, WechatCommonResponse, WechatCpTpClient};
 use crate::wechat::cp::constants::{CODE};
 use crate::wechat::cp::method::WechatCpMethod;
 
 #[derive(Debug, Clone)]
-pub struct WechatCpTpAuth<'a, T: SessionStore> {
+pub struct WechatCpTpAuth<'a, T: AsyncSessionStore> {
     client: &'a WechatCpTpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpTpAuth<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpTpAuth<'a, T> {
     #[inline]
     pub fn new(client: &WechatCpTpClient<T>) -> WechatCpTpAuth<T> {
         WechatCpTpAuth {
@@ -24,7 +24,7 @@ impl<'a, T: SessionStore> WechatCpTpAuth<'a, T> {
     /// <a href="https://developer.work.weixin.qq.com/document/path/91121">获取访问用户身份</a>
     /// 该接口用于根据code获取成员信息，适用于自建应用与代开发应用
     pub async fn get_user_info_auth_3rd(&self, code: &str) -> LabradorResult<WechatCpOauth2UserInfo3rd> {
-        let v = self.client.get(WechatCpMethod::GetAuthUserInfo3rd, vec![(CODE.to_string(), code.to_string())], RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::GetAuthUserInfo3rd, vec![(CODE.to_string(), code.to_string())]).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpOauth2UserInfo3rd>(v)
     }
 }
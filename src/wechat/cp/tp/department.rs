@@ -1,18 +1,18 @@
 use serde::{Serialize, Deserialize};
 use serde_json::{Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpTpClient};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpTpClient};
 use crate::wechat::cp::constants::ACCESS_TOKEN;
 use crate::wechat::cp::method::{CpDepartmentMethod, WechatCpMethod};
 
 /// 部门管理
 #[derive(Debug, Clone)]
-pub struct WechatCpTpDepartment<'a, T: SessionStore> {
+pub struct WechatCpTpDepartment<'a, T: AsyncSessionStore> {
     client: &'a WechatCpTpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpTpDepartment<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpTpDepartment<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpTpClient<T>) -> WechatCpTpDepartment<T> {
@@ -28,7 +28,7 @@ impl<'a, T: SessionStore> WechatCpTpDepartment<'a, T> {
     /// </pre>
     pub async fn create(&self, req: WechatCpTpDepartInfo) -> LabradorResult<i64> {
 
-        let v = self.client.post(WechatCpMethod::Department(CpDepartmentMethod::Create), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::Department(CpDepartmentMethod::Create), vec![], req).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let tag_id = v["id"].as_i64().unwrap_or_default();
         Ok(tag_id)
@@ -39,12 +39,12 @@ impl<'a, T: SessionStore> WechatCpTpDepartment<'a, T> {
     /// 详情请见: https://work.weixin.qq.com/api/doc#90000/90135/90208
     /// </pre>
     pub async fn list_byid(&self, id: Option<i64>, corp_id: &str) -> LabradorResult<WechatCpTpDepartResponse> {
-        let access_token = self.client.get_access_token(corp_id);
+        let access_token = self.client.get_access_token(corp_id).await;
         let mut query = vec![(ACCESS_TOKEN.to_string(), access_token)];
         if let Some(id) = id {
             query.push(("id".to_string(), id.to_string()));
         }
-        let v = self.client.get(WechatCpMethod::Department(CpDepartmentMethod::List), query, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::Department(CpDepartmentMethod::List), query).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpDepartResponse>(v)
     }
 
@@ -63,7 +63,7 @@ impl<'a, T: SessionStore> WechatCpTpDepartment<'a, T> {
     /// 如果id为0(未部门),1(黑名单),2(星标组)，或者不存在的id，微信会返回系统繁忙的错误
     /// </pre>
     pub async fn update(&self, req: WechatCpTpDepartInfo) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::Department(CpDepartmentMethod::Update), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::Department(CpDepartmentMethod::Update), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -72,7 +72,7 @@ impl<'a, T: SessionStore> WechatCpTpDepartment<'a, T> {
     /// 应用须拥有指定部门的管理权限
     /// </pre>
     pub async fn delete(&self, depart_id: i64) -> LabradorResult<WechatCommonResponse> {
-        self.client.get(WechatCpMethod::Department(CpDepartmentMethod::Delete(depart_id)), vec![], RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.get(WechatCpMethod::Department(CpDepartmentMethod::Delete(depart_id)), vec![]).await?.json::<WechatCommonResponse>()
     }
 }
 
@@ -1,18 +1,19 @@
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
+use futures_util::Stream;
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpTpClient};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpTpClient, Page, paginate};
 use crate::wechat::cp::constants::{PROVIDER_ACCESS_TOKEN};
 use crate::wechat::cp::method::{CpLicenseMethod, WechatCpMethod};
 
 /// 服务商接口调用许可相关
 #[derive(Debug, Clone)]
-pub struct WechatCpTpLicense<'a, T: SessionStore> {
+pub struct WechatCpTpLicense<'a, T: AsyncSessionStore> {
     client: &'a WechatCpTpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpTpLicense<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpTpClient<T>) -> WechatCpTpLicense<T> {
@@ -30,7 +31,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
     pub async fn create_order(&self, req: WechatCpTpLicenseNewOrderRequest) -> LabradorResult<String> {
         let access_token = self.client.get_wechat_provider_token().await?;
         let query = vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)];
-        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::CreateOrder), query, req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::CreateOrder), query, req).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let order_id = v["order_id"].as_str().unwrap_or_default();
         Ok(order_id.to_string())
@@ -46,7 +47,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
     pub async fn create_renew_order_job(&self, req: WechatCpTpLicenseRenewOrderJobRequest) -> LabradorResult<WechatCpTpLicenseRenewOrderJobResponse> {
         let access_token = self.client.get_wechat_provider_token().await?;
         let query = vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)];
-        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::CreateRenewOrderJob), query, req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::CreateRenewOrderJob), query, req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpLicenseRenewOrderJobResponse>(v)
     }
 
@@ -60,7 +61,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
     pub async fn submit_renew_order(&self, req: WechatCpTpLicenseRenewOrderRequest) -> LabradorResult<String> {
         let access_token = self.client.get_wechat_provider_token().await?;
         let query = vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)];
-        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::SubmitOrderJob), query, req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::SubmitOrderJob), query, req).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let order_id = v["order_id"].as_str().unwrap_or_default();
         Ok(order_id.to_string())
@@ -85,10 +86,25 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
             req["end_time"] = end.into();
         }
         let access_token = self.client.get_wechat_provider_token().await?;
-        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::ListOrder), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::ListOrder), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpLicenseOrderListResp>(v)
     }
 
+    /// 逐个获取企业的所有订单，自动按next_cursor翻页
+    pub fn get_order_list_stream(&self, corp_id: &str, start: Option<i64>, end: Option<i64>, limit: i32) -> impl Stream<Item = LabradorResult<WechatCpTpLicenseSimpleOrder>> + 'a {
+        let client = self.client;
+        let corp_id = corp_id.to_string();
+        paginate(None, move |cursor: Option<String>| {
+            let corp_id = corp_id.to_owned();
+            async move {
+                let res = WechatCpTpLicense::new(client).get_order_list(&corp_id, start, end, cursor.as_deref().unwrap_or_default(), limit).await?;
+                let has_more = res.has_more.unwrap_or_default() == 1;
+                let next = res.next_cursor.filter(|v| has_more && !v.is_empty());
+                Ok(Page::new(res.order_list.unwrap_or_default(), next))
+            }
+        })
+    }
+
 
     /// <pre>
     ///  获取订单列表
@@ -100,7 +116,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
             "order_id": order_id,
         });
         let access_token = self.client.get_wechat_provider_token().await?;
-        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::GetOrder), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::GetOrder), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpLicenseOrderInfoResponse>(v)
     }
 
@@ -119,7 +135,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
             "limit": limit,
         });
         let access_token = self.client.get_wechat_provider_token().await?;
-        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::ListOrderAccount), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::ListOrderAccount), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpLicenseOrderAccountListResponse>(v)
     }
 
@@ -135,7 +151,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
             "order_id": order_id,
         });
         let access_token = self.client.get_wechat_provider_token().await?;
-        self.client.post(WechatCpMethod::License(CpLicenseMethod::CancelOrder), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::License(CpLicenseMethod::CancelOrder), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -151,7 +167,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
             "userid": user_id,
         });
         let access_token = self.client.get_wechat_provider_token().await?;
-        self.client.post(WechatCpMethod::License(CpLicenseMethod::ActiveAccount), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::License(CpLicenseMethod::ActiveAccount), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -166,7 +182,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
             "active_list": active_accounts,
         });
         let access_token = self.client.get_wechat_provider_token().await?;
-        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::BatchActiveAccount), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::BatchActiveAccount), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpLicenseOrderAccountListResponse>(v)
     }
 
@@ -181,7 +197,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
             "corpid": corp_id,
         });
         let access_token = self.client.get_wechat_provider_token().await?;
-        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::GetActiveInfoByCode), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::GetActiveInfoByCode), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpLicenseCodeInfoResponse>(v)
     }
 
@@ -197,7 +213,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
             "corpid": corp_id,
         });
         let access_token = self.client.get_wechat_provider_token().await?;
-        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::BatchGetActiveInfoByCode), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::BatchGetActiveInfoByCode), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpLicenseBatchCodeInfoResponse>(v)
     }
 
@@ -214,7 +230,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
             "limit": limit,
         });
         let access_token = self.client.get_wechat_provider_token().await?;
-        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::ListActivedAccount), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::ListActivedAccount), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpLicenseCorpAccountListResponse>(v)
     }
 
@@ -230,7 +246,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
             "user_id": user_id,
         });
         let access_token = self.client.get_wechat_provider_token().await?;
-        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::GetActiveInfoByUser), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::GetActiveInfoByUser), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpLicenseActiveInfoByUserResponse>(v)
     }
 
@@ -246,7 +262,7 @@ impl<'a, T: SessionStore> WechatCpTpLicense<'a, T> {
             "transfer_list": transfers,
         });
         let access_token = self.client.get_wechat_provider_token().await?;
-        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::BatchTransferLicense), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::License(CpLicenseMethod::BatchTransferLicense), vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpLicenseBatchTransferResponse>(v)
     }
 
@@ -428,4 +444,33 @@ pub struct WechatCpTpLicenseTransfer {
     pub handover_userid: Option<String>,
     /// 接收成员加密的userid
     pub takeover_userid: Option<String>,
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use serde_json::Value;
+    use crate::{LabradorResult, MockResponse, MockTransport, SimpleStorage, WechatCpTpClient};
+    use super::WechatCpTpLicenseSimpleOrder;
+
+    #[tokio::test]
+    async fn test_get_order_list_stream() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, r#"{"errcode":0,"errmsg":"ok","provider_access_token":"provider_token","expires_in":7200}"#))
+            .push_response(MockResponse::new(200, r#"{"errcode":0,"errmsg":"ok","suite_access_token":"suite_token","expires_in":7200}"#))
+            .push_response(MockResponse::new(200, r#"{"errcode":0,"errmsg":"ok","next_cursor":"cursor1","has_more":1,"order_list":[{"order_id":"o1","order_type":1},{"order_id":"o2","order_type":1}]}"#))
+            .push_response(MockResponse::new(200, r#"{"errcode":0,"errmsg":"ok","next_cursor":"cursor2","has_more":0,"order_list":[{"order_id":"o3","order_type":2}]}"#));
+        let client = WechatCpTpClient::<SimpleStorage>::new("corpid").suite_id("suite_id").suite_secret("suite_secret").provider_secret("provider_secret").transport(transport.clone());
+        let orders = client.license().get_order_list_stream("auth_corpid", None, None, 2)
+            .collect::<Vec<LabradorResult<WechatCpTpLicenseSimpleOrder>>>().await;
+        let order_ids = orders.into_iter().map(|v| v.unwrap().order_id.unwrap_or_default()).collect::<Vec<_>>();
+        assert_eq!(vec!["o1", "o2", "o3"], order_ids);
+        // has_more为0时即使返回了next_cursor也结束
+        let requests = transport.requests();
+        assert_eq!(4, requests.len());
+        let first = serde_json::from_str::<Value>(&requests[2].body_text()).unwrap();
+        let second = serde_json::from_str::<Value>(&requests[3].body_text()).unwrap();
+        assert_eq!("", first["cursor"]);
+        assert_eq!("cursor1", second["cursor"]);
+        assert!(requests[3].url.as_str().contains("provider_access_token=provider_token"));
+    }
+}
@@ -1,21 +1,19 @@
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
 use bytes::Bytes;
+use tokio::io::AsyncRead;
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
 
-use crate::{session::SessionStore, LabradorResult, RequestBody, WechatRequest, WechatCommonResponse, request, get_nonce_str, WechatCpTpClient, RequestType};
+use crate::{session::AsyncSessionStore, LabradorResult, WechatRequest, WechatCommonResponse, request, get_nonce_str, WechatCpTpClient, file_part, stream_part, Form, LabraStreamResponse};
 use crate::wechat::cp::method::{CpMediaMethod, WechatCpMethod};
 
 
 #[derive(Debug, Clone)]
-pub struct WechatCpTpMedia<'a, T: SessionStore> {
+pub struct WechatCpTpMedia<'a, T: AsyncSessionStore> {
     client: &'a WechatCpTpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpTpMedia<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpTpMedia<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpTpClient<T>) -> WechatCpTpMedia<T> {
@@ -40,7 +38,7 @@ impl<'a, T: SessionStore> WechatCpTpMedia<'a, T> {
             file_name: file_name.map(|v| v.to_string()).unwrap_or(default_file_name),
             media_data: data
         };
-        let v = self.client.execute::<WechatCpTpMediaRequest, String>(req, corp_id.into()).await?.json::<Value>()?;
+        let v = self.client.execute(req, corp_id.into()).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpMediaResponse>(v)
     }
 
@@ -56,7 +54,7 @@ impl<'a, T: SessionStore> WechatCpTpMedia<'a, T> {
             file_name: file_name.to_string(),
             media_data: data
         };
-        let v= self.client.execute::<WechatCpTpImageRequest, String>(req, corp_id.into()).await?.json::<Value>()?;
+        let v= self.client.execute(req, corp_id.into()).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpMediaResponse>(v)
     }
 
@@ -68,12 +66,20 @@ impl<'a, T: SessionStore> WechatCpTpMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/upload?access_token=ACCESS_TOKEN&type=TYPE
     /// </pre>
     pub async fn upload_media_with_file(&self, media_type: &str, file_path: &str, corp_id: &str) -> LabradorResult<WechatCpTpMediaResponse> {
-        let path = Path::new(file_path);
-        let file_name = path.file_name().map(|v| v.to_str().unwrap_or_default()).unwrap_or_default();
-        let mut f = File::open(path)?;
-        let mut content: Vec<u8> = Vec::new();
-        let _ = f.read_to_end(&mut content)?;
-        self.upload_media(media_type, file_name.into(),content, corp_id).await
+        let form = Form::new().part("media", file_part(file_path).await?);
+        let v = self.client.post_multipart(WechatCpMethod::Media(CpMediaMethod::UploadMedia(media_type.to_string())), vec![], form, corp_id).await?.json::<Value>()?;
+        WechatCommonResponse::parse::<WechatCpTpMediaResponse>(v)
+    }
+
+    /// <pre>
+    /// 新增临时素材，从数据流中读取文件内容，不会将整个文件读入内存
+    /// `length`为文件长度，不知道长度时使用分块传输
+    /// 接口url格式：https://qyapi.weixin.qq.com/cgi-bin/media/upload?access_token=ACCESS_TOKEN&type=TYPE
+    /// </pre>
+    pub async fn upload_media_stream<R: AsyncRead + Send + Sync + 'static>(&self, media_type: &str, file_name: &str, reader: R, length: Option<u64>, corp_id: &str) -> LabradorResult<WechatCpTpMediaResponse> {
+        let form = Form::new().part("media", stream_part(reader, file_name, length));
+        let v = self.client.post_multipart(WechatCpMethod::Media(CpMediaMethod::UploadMedia(media_type.to_string())), vec![], form, corp_id).await?.json::<Value>()?;
+        WechatCommonResponse::parse::<WechatCpTpMediaResponse>(v)
     }
 
     /// <pre>
@@ -89,6 +95,14 @@ impl<'a, T: SessionStore> WechatCpTpMedia<'a, T> {
         self.upload_media(media_type, None,content.to_vec(), corp_id).await
     }
 
+    /// <pre>
+    /// 下载多媒体文件，流式读取，适用于较大的文件
+    /// 接口url格式：https://qyapi.weixin.qq.com/cgi-bin/media/get?access_token=ACCESS_TOKEN&media_id=MEDIA_ID
+    /// </pre>
+    pub async fn get_media_stream(&self, media_id: &str, corp_id: &str) -> LabradorResult<LabraStreamResponse> {
+        self.client.post_stream(WechatCpMethod::Media(CpMediaMethod::GetMedia), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null, corp_id).await
+    }
+
     /// <pre>
     /// 获取高清语音素材
     /// 公众号可以使用本接口获取从JSSDK的uploadVoice接口上传的临时语音素材，格式为speex，16K采样率。
@@ -98,7 +112,7 @@ impl<'a, T: SessionStore> WechatCpTpMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/get/jssdk?access_token=ACCESS_TOKEN&media_id=MEDIA_ID
     /// </pre>
     pub async fn get_media_jssdk(&self, media_id: &str) -> LabradorResult<Bytes> {
-        let response = self.client.post(WechatCpMethod::Media(CpMediaMethod::GetMediaJssdk), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null, RequestType::Json).await?;
+        let response = self.client.post(WechatCpMethod::Media(CpMediaMethod::GetMediaJssdk), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await?;
         response.bytes()
     }
 }
@@ -119,9 +133,8 @@ impl WechatRequest for WechatCpTpMediaRequest {
         CpMediaMethod::UploadMedia(self.media_type.to_string()).get_method()
     }
 
-    fn get_request_body<T: Serialize>(&self) -> RequestBody<T> {
-        let form = reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.media_data.to_owned()).file_name(self.file_name.to_string()));
-        form.into()
+    fn get_request_body(&self) -> Form {
+        reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.media_data.to_owned()).file_name(self.file_name.to_string()))
     }
 }
 
@@ -147,8 +160,7 @@ impl WechatRequest for WechatCpTpImageRequest {
         CpMediaMethod::UploadImage.get_method()
     }
 
-    fn get_request_body<T: Serialize>(&self) -> RequestBody<T> {
-        let form = reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.media_data.to_vec()).file_name(self.file_name.to_string()));
-        form.into()
+    fn get_request_body(&self) -> Form {
+        reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.media_data.to_vec()).file_name(self.file_name.to_string()))
     }
 }
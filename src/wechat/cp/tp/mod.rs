@@ -1,8 +1,11 @@
+use std::fmt;
 use bytes::Bytes;
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCrypto, current_timestamp, LabraError, JsapiTicket, JsapiSignature, get_timestamp, get_nonce_str, APIClient, WechatRequest, LabraResponse, LabraRequest, SimpleStorage, WechatCpProviderToken};
+use crate::wechat::{cached_token, check_stream, is_invalid_token};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCrypto, current_timestamp, LabraError, JsapiTicket, JsapiSignature, get_timestamp, get_nonce_str, APIClient, WechatRequest, LabraResponse, LabraRequest, LabraStreamResponse, Form, SimpleStorage, WechatCpProviderToken, LabraHttpClient, LabraHttpConfig, HttpTransport, Middleware, LabraLogConfig, RetryPolicy, RateLimiter, LockOptions, refresh_with_lock, telemetry};
+use crate::config::{Proxy, Secret};
 use crate::wechat::cp::constants::{ACCESS_TOKEN, ACCESS_TOKEN_KEY, AGENT_CONFIG, AUTH_URL_INSTALL, PROVIDER_ACCESS_TOKEN, SUITE_ACCESS_TOKEN, TYPE};
 use crate::wechat::cp::method::WechatCpMethod;
 use crate::wechat::cp::AccessTokenResponse;
@@ -29,7 +32,7 @@ use crate::wechat::cp::tp::auth::WechatCpTpAuth;
 /// 企业微信第三方应用API
 #[allow(unused)]
 #[derive(Debug, Clone)]
-pub struct WechatCpTpClient<T: SessionStore> {
+pub struct WechatCpTpClient<T: AsyncSessionStore> {
     token: Option<String>,
     /// 企微服务商企业ID，来自于企微配置
     corp_id: String,
@@ -44,8 +47,48 @@ pub struct WechatCpTpClient<T: SessionStore> {
     client: APIClient<T>,
 }
 
+/// 企业微信第三方应用配置
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct WechatCpTpConfig {
+    /// 服务商企业ID
+    pub corp_id: String,
+    pub suite_id: Option<String>,
+    pub suite_secret: Option<String>,
+    /// 服务商secret
+    pub provider_secret: Option<String>,
+    pub agent_id: Option<i32>,
+    pub token: Option<String>,
+    pub aes_key: Option<String>,
+    /// 接口地址，用于测试环境或本地模拟服务
+    pub api_path: Option<String>,
+    /// 代理地址，如：http://127.0.0.1:8080
+    pub proxy: Option<String>,
+}
+
+impl fmt::Debug for WechatCpTpConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WechatCpTpConfig")
+            .field("corp_id", &self.corp_id)
+            .field("suite_id", &self.suite_id)
+            .field("suite_secret", &Secret::option(&self.suite_secret))
+            .field("provider_secret", &Secret::option(&self.provider_secret))
+            .field("agent_id", &self.agent_id)
+            .field("token", &Secret::option(&self.token))
+            .field("aes_key", &Secret::option(&self.aes_key))
+            .field("api_path", &self.api_path)
+            .field("proxy", &Proxy::option(&self.proxy))
+            .finish()
+    }
+}
+
+impl WechatCpTpConfig {
+    pub fn new<S: Into<String>>(corp_id: S) -> Self {
+        Self { corp_id: corp_id.into(), ..Default::default() }
+    }
+}
+
 #[allow(unused)]
-impl<T: SessionStore> WechatCpTpClient<T> {
+impl<T: AsyncSessionStore> WechatCpTpClient<T> {
     fn from_client(client: APIClient<T>) -> WechatCpTpClient<T> {
         WechatCpTpClient {
             corp_id: client.app_key.to_owned(),
@@ -89,6 +132,66 @@ impl<T: SessionStore> WechatCpTpClient<T> {
         self
     }
 
+    /// 设置HTTP客户端，多个客户端可共用同一个连接池
+    pub fn http_client(mut self, http_client: LabraHttpClient) -> Self {
+        self.client = self.client.http_client(http_client);
+        self
+    }
+
+    /// 根据配置生成独立的HTTP客户端，可设置代理、超时等
+    pub fn http_config(mut self, config: LabraHttpConfig) -> LabradorResult<Self> {
+        self.client = self.client.http_config(config)?;
+        Ok(self)
+    }
+
+    /// 设置代理，如：http://127.0.0.1:8080，开启`socks`特性后支持socks5
+    pub fn proxy(mut self, proxy: &str) -> LabradorResult<Self> {
+        self.client = self.client.proxy(proxy)?;
+        Ok(self)
+    }
+
+    /// 设置接口地址，可指向测试环境或本地的模拟服务
+    pub fn api_path<S: Into<String>>(mut self, api_path: S) -> Self {
+        self.client = self.client.api_path(api_path);
+        self
+    }
+
+    /// 设置传输层，测试时可替换为`MockTransport`
+    pub fn transport<H: HttpTransport + 'static>(mut self, transport: H) -> Self {
+        self.client = self.client.transport(transport);
+        self
+    }
+
+    /// 添加中间件
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.client = self.client.middleware(middleware);
+        self
+    }
+
+    /// 设置请求日志配置
+    pub fn log_config(mut self, log_config: LabraLogConfig) -> Self {
+        self.client = self.client.log_config(log_config);
+        self
+    }
+
+    /// 设置access_token刷新锁配置
+    pub fn lock_options(mut self, lock_options: LockOptions) -> Self {
+        self.client = self.client.lock_options(lock_options);
+        self
+    }
+
+    /// 设置重试策略，GET请求和授权信息、订单等查询接口任何失败都可以重试；获取永久授权码等一次性的接口只会在连接阶段失败时重试
+    pub fn retry_policy<P: RetryPolicy + 'static>(mut self, retry_policy: P) -> Self {
+        self.client = self.client.retry_policy(retry_policy);
+        self
+    }
+
+    /// 设置限流器，按接口方法限流并统计每日调用次数
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.client = self.client.rate_limiter(rate_limiter);
+        self
+    }
+
     pub fn get_corpid(&self) -> &str {
         &self.corp_id
     }
@@ -99,20 +202,50 @@ impl<T: SessionStore> WechatCpTpClient<T> {
 
     /// get the wechat client
     pub fn new<S: Into<String>>(crop_id: S) -> WechatCpTpClient<SimpleStorage> {
-        let client = APIClient::<SimpleStorage>::from_session(crop_id.into(), "", "https://qyapi.weixin.qq.com", SimpleStorage::new());
+        let client = APIClient::<SimpleStorage>::from_session(crop_id.into(), "", "https://qyapi.weixin.qq.com", SimpleStorage::new()).platform("wechat_cp_tp");
         WechatCpTpClient::<SimpleStorage>::from_client(client)
     }
 
     /// get the wechat client
     pub fn from_session<S: Into<String>>(crop_id: S, session: T) -> WechatCpTpClient<T> {
-        let client = APIClient::from_session(crop_id.into(), "", "https://qyapi.weixin.qq.com", session);
+        let client = APIClient::from_session(crop_id.into(), "", "https://qyapi.weixin.qq.com", session).platform("wechat_cp_tp");
         Self::from_client(client)
     }
 
+    /// 根据配置创建客户端
+    pub fn from_config(config: &WechatCpTpConfig, session: T) -> LabradorResult<WechatCpTpClient<T>> {
+        let mut client = Self::from_session(config.corp_id.as_str(), session);
+        if let Some(suite_id) = config.suite_id.as_ref() {
+            client = client.suite_id(suite_id);
+        }
+        if let Some(suite_secret) = config.suite_secret.as_ref() {
+            client = client.suite_secret(suite_secret);
+        }
+        if let Some(provider_secret) = config.provider_secret.as_ref() {
+            client = client.provider_secret(provider_secret);
+        }
+        if let Some(agent_id) = config.agent_id {
+            client = client.agent_id(agent_id);
+        }
+        if let Some(token) = config.token.as_ref() {
+            client = client.token(token);
+        }
+        if let Some(aes_key) = config.aes_key.as_ref() {
+            client = client.aes_key(aes_key);
+        }
+        if let Some(api_path) = config.api_path.as_ref() {
+            client = client.api_path(api_path.as_str());
+        }
+        if let Some(proxy) = config.proxy.as_ref() {
+            client = client.proxy(proxy)?;
+        }
+        Ok(client)
+    }
+
     /// 授权企业的access token相关
-    fn get_access_token(&self, auth_corp_id: &str) -> String {
+    async fn get_access_token(&self, auth_corp_id: &str) -> String {
         let session = self.client.session();
-        session.get::<_, String>(self.key_with_prefix(auth_corp_id) + ACCESS_TOKEN_KEY, None).unwrap_or(None).unwrap_or_default()
+        session.get::<_, String>(self.key_with_prefix(auth_corp_id) + ACCESS_TOKEN_KEY, None).await.unwrap_or(None).unwrap_or_default()
     }
 
     /// <pre>
@@ -133,13 +266,13 @@ impl<T: SessionStore> WechatCpTpClient<T> {
 
     /// 获得suite_ticket,不强制刷新suite_ticket
     /// 由微信服务器推送
-    pub fn get_suite_ticket(&self) -> LabradorResult<String> {
+    pub async fn get_suite_ticket(&self) -> LabradorResult<String> {
         let session = self.client.session();
         let token_key = format!("{}_suite_ticket_key_cp", self.corp_id);
         let expires_key = format!("{}_suite_ticket_expires_at_cp", self.corp_id);
-        let token: String = session.get(&token_key, Some("".to_owned()))?.unwrap_or_default();
+        let token: String = session.get(&token_key, Some("".to_owned())).await?.unwrap_or_default();
         let timestamp = current_timestamp();
-        let expires_at: i64 = session.get(&expires_key, Some(timestamp))?.unwrap_or_default();
+        let expires_at: i64 = session.get(&expires_key, Some(timestamp)).await?.unwrap_or_default();
         if expires_at <= timestamp {
             return Err(LabraError::ApiError("invaild suite ticket".to_string()));
         }
@@ -148,13 +281,13 @@ impl<T: SessionStore> WechatCpTpClient<T> {
 
     /// 获得suite_ticket,不强制刷新suite_ticket
     /// 由微信服务器推送
-    pub fn set_suite_ticket_expire(&self, suite_ticket: &str, expire_second: i64) -> LabradorResult<()> {
+    pub async fn set_suite_ticket_expire(&self, suite_ticket: &str, expire_second: i64) -> LabradorResult<()> {
         let expires_at = current_timestamp() + expire_second;
         let session = self.client.session();
         let token_key = format!("{}_suite_ticket_key_cp", self.corp_id);
         let expires_key = format!("{}_suite_ticket_expires_at_cp", self.corp_id);
-        session.set(token_key.to_string(), suite_ticket, Some(expire_second as usize))?;
-        session.set(expires_key, expires_at, Some(expire_second as usize))?;
+        session.set(token_key.to_string(), suite_ticket, Some(expire_second as usize)).await?;
+        session.set(expires_key, expires_at, Some(expire_second as usize)).await?;
         Ok(())
     }
 
@@ -165,8 +298,8 @@ impl<T: SessionStore> WechatCpTpClient<T> {
     /// 注意：微信不是固定10分钟推送suite_ticket的, 且suite_ticket的有效期为30分钟
     /// <a href="https://work.weixin.qq.com/api/doc/10975#%E8%8E%B7%E5%8F%96%E7%AC%AC%E4%B8%89%E6%96%B9%E5%BA%94%E7%94%A8%E5%87%AD%E8%AF%81">文档</a>
     /// </pre>
-    pub fn set_suite_ticket(&self, suite_ticket: &str) -> LabradorResult<()> {
-        self.set_suite_ticket_expire(suite_ticket, 28 * 60)
+    pub async fn set_suite_ticket(&self, suite_ticket: &str) -> LabradorResult<()> {
+        self.set_suite_ticket_expire(suite_ticket, 28 * 60).await
     }
 
     /// <pre>
@@ -187,31 +320,50 @@ impl<T: SessionStore> WechatCpTpClient<T> {
     /// 详情请见: <a href="https://work.weixin.qq.com/api/doc#90001/90143/90600">文档</a>
     /// </pre>
     pub async fn get_suite_access_token_force(&self, force_refresh: bool) -> LabradorResult<String> {
-        let session = self.client.session();
-        let token_key = format!("{}_suite_access_token_cp", self.corp_id);
-        let expires_key = format!("{}_suite_access_token_expires_at_cp", self.corp_id);
-        let token: String = session.get(&token_key, Some("".to_owned()))?.unwrap_or_default();
-        let timestamp = current_timestamp();
-        let expires_at: i64 = session.get(&expires_key, Some(timestamp))?.unwrap_or_default();
-        if expires_at <= timestamp || force_refresh {
-            let suite_ticket = self.get_suite_ticket().unwrap_or_default();
-            let req = json!({
-                "suite_id": self.suite_id,
-                "suite_secret": self.suite_secret,
-                "suite_ticket": suite_ticket
-            });
-            let result = self.client.post(WechatCpMethod::GetSuiteToken, vec![], req, RequestType::Json).await?.json::<Value>()?;
-            let result = WechatCommonResponse::parse::<WechatCpSuiteAccessTokenResponse>(result)?;
-            let token = result.suite_access_token;
-            let expires_in = result.expires_in;
-            // 预留200秒的时间
-            let expires_at = current_timestamp() + expires_in - 200;
-            session.set(&token_key, token.to_owned(), Some(expires_in as usize));
-            session.set(&expires_key, expires_at, Some(expires_in as usize));
-            Ok(token.to_string())
+        let rejected = if force_refresh {
+            let (token_key, expires_key) = self.suite_access_token_keys();
+            cached_token(self.client.session(), &token_key, &expires_key).await?
         } else {
-            Ok(token)
-        }
+            None
+        };
+        self.refresh_suite_access_token(rejected.as_deref()).await
+    }
+
+    /// 接口返回suite_access_token失效时获取新的凭证，`rejected`为失效的suite_access_token
+    ///
+    /// 缓存中的凭证已被其他请求刷新时直接使用，多个请求同时遇到失效时只刷新一次。
+    pub async fn suite_access_token_replacing(&self, rejected: &str) -> LabradorResult<String> {
+        self.refresh_suite_access_token(Some(rejected)).await
+    }
+
+    fn suite_access_token_keys(&self) -> (String, String) {
+        (format!("{}_suite_access_token_cp", self.corp_id), format!("{}_suite_access_token_expires_at_cp", self.corp_id))
+    }
+
+    async fn refresh_suite_access_token(&self, rejected: Option<&str>) -> LabradorResult<String> {
+        let session = self.client.session();
+        let (token_key, expires_key) = &self.suite_access_token_keys();
+        let lock_key = format!("{}_lock", token_key);
+        refresh_with_lock(session, &lock_key, &self.client.lock_options, rejected,
+            || cached_token(session, token_key, expires_key),
+            || async move {
+                let suite_ticket = self.get_suite_ticket().await.unwrap_or_default();
+                let req = json!({
+                    "suite_id": self.suite_id,
+                    "suite_secret": self.suite_secret,
+                    "suite_ticket": suite_ticket
+                });
+                let result = self.client.post(WechatCpMethod::GetSuiteToken, vec![], req).await?.json::<Value>()?;
+                let result = WechatCommonResponse::parse::<WechatCpSuiteAccessTokenResponse>(result)?;
+                let token = result.suite_access_token;
+                let expires_in = result.expires_in;
+                // 预留200秒的时间
+                let expires_at = current_timestamp() + expires_in - 200;
+                session.set(token_key, token.to_owned(), Some(expires_in as usize)).await?;
+                session.set(expires_key, expires_at, Some(expires_in as usize)).await?;
+                telemetry::record_token_refresh(self.client.platform, "suite_access_token");
+                Ok(token)
+            }).await
     }
 
     ///
@@ -230,18 +382,19 @@ impl<T: SessionStore> WechatCpTpClient<T> {
         let mut session = self.client.session();
         let ticket_key = format!("{}_suite_jsapi_ticket_cp", self.corp_id);
         let expires_key = format!("{}_suite_jsapi_ticket_expires_at_cp", self.corp_id);
-        let ticket: String = session.get(&ticket_key, Some("".to_string()))?.unwrap_or_default();
+        let ticket: String = session.get(&ticket_key, Some("".to_string())).await?.unwrap_or_default();
         let timestamp = current_timestamp();
-        let expires_at: i64 = session.get(&expires_key, Some(timestamp))?.unwrap_or_default();
+        let expires_at: i64 = session.get(&expires_key, Some(timestamp)).await?.unwrap_or_default();
         if expires_at <= timestamp || force_refresh {
-            let v = self.client.get(WechatCpMethod::GetSuiteJsapiTicket, vec![(TYPE.to_string(), AGENT_CONFIG.to_string()), (ACCESS_TOKEN.to_string(), self.get_access_token(auth_corp_id))], RequestType::Json).await?.json::<Value>()?;
+            let v = self.client.get(WechatCpMethod::GetSuiteJsapiTicket, vec![(TYPE.to_string(), AGENT_CONFIG.to_string()), (ACCESS_TOKEN.to_string(), self.get_access_token(auth_corp_id).await)]).await?.json::<Value>()?;
             let res = WechatCommonResponse::parse::<JsapiTicket>(v)?;
             let ticket = res.ticket;
             let expires_in = res.expires_in;
             // 预留200秒的时间
             let expires_at = current_timestamp() + expires_in - 200;
-            session.set(&ticket_key, ticket.to_string(), Some(expires_in as usize));
-            session.set(&expires_key, expires_at, Some(expires_in as usize));
+            session.set(&ticket_key, ticket.to_string(), Some(expires_in as usize)).await?;
+            session.set(&expires_key, expires_at, Some(expires_in as usize)).await?;
+            telemetry::record_token_refresh(self.client.platform, "suite_jsapi_ticket");
             Ok(ticket.to_string())
         } else {
             Ok(ticket)
@@ -264,17 +417,18 @@ impl<T: SessionStore> WechatCpTpClient<T> {
         let mut session = self.client.session();
         let ticket_key = format!("{}_auth_corp_jsapi_ticket_cp", self.corp_id);
         let expires_key = format!("{}_auth_corp_jsapi_ticket_expires_at_cp", self.corp_id);
-        let ticket: String = session.get(&ticket_key, Some("".to_string()))?.unwrap_or_default();
+        let ticket: String = session.get(&ticket_key, Some("".to_string())).await?.unwrap_or_default();
         let timestamp = current_timestamp();
-        let expires_at: i64 = session.get(&expires_key, Some(timestamp))?.unwrap_or_default();
+        let expires_at: i64 = session.get(&expires_key, Some(timestamp)).await?.unwrap_or_default();
         if expires_at <= timestamp || force_refresh {
-            let res = self.client.get(WechatCpMethod::GetJsapiTicket, vec![(ACCESS_TOKEN.to_string(), self.get_access_token(auth_corp_id))], RequestType::Json).await?.json::<JsapiTicket>()?;
+            let res = self.client.get(WechatCpMethod::GetJsapiTicket, vec![(ACCESS_TOKEN.to_string(), self.get_access_token(auth_corp_id).await)]).await?.json::<JsapiTicket>()?;
             let ticket = res.ticket;
             let expires_in = res.expires_in;
             // 预留200秒的时间
             let expires_at = current_timestamp() + expires_in - 200;
-            session.set(&ticket_key, ticket.to_string(), Some(expires_in as usize));
-            session.set(&expires_key, expires_at, Some(expires_in as usize));
+            session.set(&ticket_key, ticket.to_string(), Some(expires_in as usize)).await?;
+            session.set(&expires_key, expires_at, Some(expires_in as usize)).await?;
+            telemetry::record_token_refresh(self.client.platform, "auth_corp_jsapi_ticket");
             Ok(ticket.to_string())
         } else {
             Ok(ticket)
@@ -296,23 +450,24 @@ impl<T: SessionStore> WechatCpTpClient<T> {
         let session = self.client.session();
         let token_key = format!("{}_corp_access_token_cp", auth_corpid);
         let expires_key = format!("{}_corp_access_token_expires_at_cp", auth_corpid);
-        let token: String = session.get(&token_key, Some("".to_owned()))?.unwrap_or_default();
+        let token: String = session.get(&token_key, Some("".to_owned())).await?.unwrap_or_default();
         let timestamp = get_timestamp();
-        let expires_at: i64 = session.get(&expires_key, Some(timestamp))?.unwrap_or_default();
+        let expires_at: i64 = session.get(&expires_key, Some(timestamp)).await?.unwrap_or_default();
         if expires_at <= timestamp || force_refresh {
-            let suite_ticket = self.get_suite_ticket()?;
+            let suite_ticket = self.get_suite_ticket().await?;
             let req = json!({
                 "auth_corpid": auth_corpid,
                 "permanent_code": permanent_code,
             });
-            let v = self.client.post(WechatCpMethod::GetCorpToken, vec![], req, RequestType::Json).await?.json::<Value>()?;
+            let v = self.client.post(WechatCpMethod::GetCorpToken, vec![], req).await?.json::<Value>()?;
             let result = WechatCommonResponse::parse::<AccessTokenResponse>(v)?;
             let token = result.access_token.to_string();
             let expires_in = result.expires_in;
             // 预留200秒的时间
             let expires_at = get_timestamp() + expires_in - 200;
-            session.set(&token_key, token.to_owned(), Some(expires_in as usize));
-            session.set(&expires_key, expires_at, Some(expires_in as usize));
+            session.set(&token_key, token.to_owned(), Some(expires_in as usize)).await?;
+            session.set(&expires_key, expires_at, Some(expires_in as usize)).await?;
+            telemetry::record_token_refresh(self.client.platform, "corp_access_token");
             Ok(result)
         } else {
             Ok(AccessTokenResponse { access_token: token.to_string(), expires_in: expires_at })
@@ -326,22 +481,23 @@ impl<T: SessionStore> WechatCpTpClient<T> {
         let session = self.client.session();
         let token_key = format!("{}_provider_access_token_cp", self.corp_id);
         let expires_key = format!("{}_provider_access_token_expires_at_cp", self.corp_id);
-        let token: String = session.get(&token_key, Some("".to_owned()))?.unwrap_or_default();
+        let token: String = session.get(&token_key, Some("".to_owned())).await?.unwrap_or_default();
         let timestamp = get_timestamp();
-        let expires_at: i64 = session.get(&expires_key, Some(timestamp))?.unwrap_or_default();
+        let expires_at: i64 = session.get(&expires_key, Some(timestamp)).await?.unwrap_or_default();
         if expires_at <= timestamp {
             let req = json!({
                 "corpid": self.corp_id,
                 "provider_secret": self.provider_secret,
             });
-            let v = self.client.post(WechatCpMethod::GetProviderToken, vec![], req, RequestType::Json).await?.json::<Value>()?;
+            let v = self.client.post(WechatCpMethod::GetProviderToken, vec![], req).await?.json::<Value>()?;
             let result = WechatCommonResponse::parse::<WechatCpProviderToken>(v)?;
             let token = result.provider_access_token.to_string();
             let expires_in = result.expires_in;
             // 预留200秒的时间
             let expires_at = get_timestamp() + expires_in - 200;
-            session.set(&token_key, token.to_owned(), Some(expires_in as usize));
-            session.set(&expires_key, expires_at, Some(expires_in as usize));
+            session.set(&token_key, token.to_owned(), Some(expires_in as usize)).await?;
+            session.set(&expires_key, expires_at, Some(expires_in as usize)).await?;
+            telemetry::record_token_refresh(self.client.platform, "provider_access_token");
             Ok(token)
         } else {
             Ok(token)
@@ -355,7 +511,7 @@ impl<T: SessionStore> WechatCpTpClient<T> {
         let req = json!({
             "auth_code": auth_code,
         });
-        let result = self.post(WechatCpMethod::GetPermanentCode, vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let result = self.post(WechatCpMethod::GetPermanentCode, vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpThirdPermanentCodeInfo>(result)
     }
 
@@ -363,7 +519,7 @@ impl<T: SessionStore> WechatCpTpClient<T> {
     /// 获取预授权链接
     /// </pre>
     pub async fn get_pre_auth_url(&self, redirect_uri: &str, state: Option<&str>) -> LabradorResult<String> {
-        let result = self.get(WechatCpMethod::GetPreAuthCode, vec![], RequestType::Json).await?.json::<WechatCpThirdPreauthCode>()?;
+        let result = self.get(WechatCpMethod::GetPreAuthCode, vec![]).await?.json::<WechatCpThirdPreauthCode>()?;
         let mut pre_auth_url = format!("{}?suite_id={}&pre_auth_code={}&redirect_uri={}", AUTH_URL_INSTALL, self.suite_id.to_owned().unwrap_or_default(), result.pre_auth_code, urlencoding::encode(redirect_uri));
         if let Some(state) = state {
             pre_auth_url.push_str(&format!("&state={}", state));
@@ -383,7 +539,7 @@ impl<T: SessionStore> WechatCpTpClient<T> {
                 "auth_type": auth_type
             }
         });
-        self.post(WechatCpMethod::SetSessionInfo, vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.post(WechatCpMethod::SetSessionInfo, vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -394,7 +550,7 @@ impl<T: SessionStore> WechatCpTpClient<T> {
            "auth_corpid": auth_corp_id,
            "permanent_code": permanent_code
         });
-        let result = self.client.post(WechatCpMethod::GetAuthInfo, vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let result = self.client.post(WechatCpMethod::GetAuthInfo, vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpThirdAuthInfo>(result)
     }
 
@@ -408,7 +564,7 @@ impl<T: SessionStore> WechatCpTpClient<T> {
            "auth_corpid": auth_corp_id,
            "agentid": agent_id
         });
-        let result = self.client.post(WechatCpMethod::GetAdminInfo, vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let result = self.client.post(WechatCpMethod::GetAdminInfo, vec![], req).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(result)?;
         serde_json::from_value::<Vec<AdminUserInfo>>(v["admin"].to_owned()).map_err(LabraError::from)
     }
@@ -426,7 +582,7 @@ impl<T: SessionStore> WechatCpTpClient<T> {
             "style": style,
             "result_type": 1
         });
-        self.client.post(WechatCpMethod::GetAppQrcode, vec![], req, RequestType::Json).await?.bytes()
+        self.client.post(WechatCpMethod::GetAppQrcode, vec![], req).await?.bytes()
     }
 
     /// <pre>
@@ -441,7 +597,7 @@ impl<T: SessionStore> WechatCpTpClient<T> {
             "style": style,
             "result_type": 2
         });
-        let v = self.client.post(WechatCpMethod::GetAppQrcode, vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::GetAppQrcode, vec![], req).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let qrcode = v["qrcode"].as_str().unwrap_or_default();
         Ok(qrcode.to_string())
@@ -457,7 +613,7 @@ impl<T: SessionStore> WechatCpTpClient<T> {
         });
         let access_token = self.get_wechat_provider_token().await?;
         let query = vec![(PROVIDER_ACCESS_TOKEN.to_string(), access_token)];
-        let v = self.client.post(WechatCpMethod::CorpToOpenCorpid, query, req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::CorpToOpenCorpid, query, req).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let qrcode = v["open_corpid"].as_str().unwrap_or_default();
         Ok(qrcode.to_string())
@@ -500,38 +656,76 @@ impl<T: SessionStore> WechatCpTpClient<T> {
     /// Service没有实现某个API的时候，可以用这个，
     /// 比 get 和 post 方法更灵活，可以自己构造用来处理不同的参数和不同的返回类型。
     /// </pre>
-    async fn execute<D: WechatRequest, B: Serialize>(&self, request: D, corp_id: Option<&str>) -> LabradorResult<LabraResponse> {
+    async fn execute<D: WechatRequest>(&self, request: D, corp_id: Option<&str>) -> LabradorResult<LabraResponse> {
         let mut querys = request.get_query_params();
         if request.is_need_token() {
             if let Some(corp_id) = corp_id {
-                let access_token = self.get_access_token(corp_id);
+                let access_token = self.get_access_token(corp_id).await;
                 if !access_token.is_empty() {
                     querys.insert(ACCESS_TOKEN.to_string(), access_token);
                 }
             }
         }
         let params = querys.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<(String, String)>>();
-        let mut req = LabraRequest::<B>::new().url(request.get_api_method_name())
-            .params(params).method(request.get_request_method()).req_type(request.get_request_type()).body(request.get_request_body::<B>());
+        let mut req = LabraRequest::post_multipart(request.get_api_method_name(), request.get_request_body())
+            .params(params);
         self.client.request(req).await
     }
 
+    /// 使用授权企业的access_token上传multipart表单
+    async fn post_multipart(&self, method: WechatCpMethod, mut querys: Vec<(String, String)>, form: Form, corp_id: &str) -> LabradorResult<LabraResponse> {
+        let access_token = self.get_access_token(corp_id).await;
+        if !access_token.is_empty() {
+            querys.push((ACCESS_TOKEN.to_string(), access_token));
+        }
+        self.client.post_multipart(method, querys, form).await
+    }
+
+    /// 使用授权企业的access_token发送POST请求，流式读取响应，用于下载文件
+    async fn post_stream<D: Serialize>(&self, method: WechatCpMethod, mut querys: Vec<(String, String)>, data: D, corp_id: &str) -> LabradorResult<LabraStreamResponse> {
+        let access_token = self.get_access_token(corp_id).await;
+        if !access_token.is_empty() {
+            querys.push((ACCESS_TOKEN.to_string(), access_token));
+        }
+        check_stream(self.client.post_stream(method, querys, data).await?).await
+    }
+
     /// 发送POST请求
-    async fn post<D: Serialize>(&self, method: WechatCpMethod, mut querys: Vec<(String, String)>, data: D, request_type: RequestType) -> LabradorResult<LabraResponse> {
-        if method.need_token() {
-            let token = self.get_suite_access_token_force(false).await?;
+    async fn post<D: Serialize>(&self, method: WechatCpMethod, querys: Vec<(String, String)>, data: D) -> LabradorResult<LabraResponse> {
+        if !method.need_token() {
+            return self.client.post(method, querys, data).await;
+        }
+        let send = |token: String| {
+            let mut querys = querys.clone();
             querys.push((SUITE_ACCESS_TOKEN.to_string(), token));
+            self.client.post(method.clone(), querys, &data)
+        };
+        let suite_access_token = self.get_suite_access_token_force(false).await?;
+        let response = send(suite_access_token.to_owned()).await?;
+        if is_invalid_token(&response) {
+            // suite_access_token已失效，强制刷新后重试一次
+            return send(self.suite_access_token_replacing(&suite_access_token).await?).await;
         }
-        self.client.post(method, querys, data, request_type).await
+        Ok(response)
     }
 
     /// 发送GET请求
-    async fn get(&self, method: WechatCpMethod, mut params: Vec<(String, String)>, request_type: RequestType) -> LabradorResult<LabraResponse> {
-        if method.need_token() {
-            let token = self.get_suite_access_token_force(false).await?.to_string();
+    async fn get(&self, method: WechatCpMethod, params: Vec<(String, String)>) -> LabradorResult<LabraResponse> {
+        if !method.need_token() {
+            return self.client.get(method, params).await;
+        }
+        let send = |token: String| {
+            let mut params = params.clone();
             params.push((SUITE_ACCESS_TOKEN.to_string(), token));
+            self.client.get(method.clone(), params)
+        };
+        let suite_access_token = self.get_suite_access_token_force(false).await?;
+        let response = send(suite_access_token.to_owned()).await?;
+        if is_invalid_token(&response) {
+            // suite_access_token已失效，强制刷新后重试一次
+            return send(self.suite_access_token_replacing(&suite_access_token).await?).await;
         }
-        self.client.get(method, params, request_type).await
+        Ok(response)
     }
 
     /// 部门
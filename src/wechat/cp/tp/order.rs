@@ -1,17 +1,17 @@
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpTpClient, DealerCorpInfo};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpTpClient, DealerCorpInfo};
 use crate::wechat::cp::method::{WechatCpMethod};
 
 /// 服务商接口调用许可相关
 #[derive(Debug, Clone)]
-pub struct WechatCpTpOrder<'a, T: SessionStore> {
+pub struct WechatCpTpOrder<'a, T: AsyncSessionStore> {
     client: &'a WechatCpTpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpTpOrder<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpTpOrder<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpTpClient<T>) -> WechatCpTpOrder<T> {
@@ -29,7 +29,7 @@ impl<'a, T: SessionStore> WechatCpTpOrder<'a, T> {
         let mut req = json!({
             "orderid": order_id,
         });
-        let v = self.client.post(WechatCpMethod::GetOrder, vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::GetOrder, vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpOrderDetailsResponse>(v)
     }
 
@@ -50,7 +50,7 @@ impl<'a, T: SessionStore> WechatCpTpOrder<'a, T> {
             req["end_time"] = (end / 1000).into();
         }
         let access_token = self.client.get_wechat_provider_token().await?;
-        let v = self.client.post(WechatCpMethod::GetOrderList, vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::GetOrderList, vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTpOrderListGetResponse>(v)
     }
 
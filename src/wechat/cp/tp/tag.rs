@@ -1,16 +1,16 @@
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpTpClient, WechatCpTagAddOrRemoveUsersResponse, WechatCpTagGetResponse, WechatCpTagInfo, LabraError};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpTpClient, WechatCpTagAddOrRemoveUsersResponse, WechatCpTagGetResponse, WechatCpTagInfo, LabraError};
 use crate::wechat::cp::method::{CpTagMethod, WechatCpMethod};
 
 /// 企业微信第三方开发-标签相关
 #[derive(Debug, Clone)]
-pub struct WechatCpTpTag<'a, T: SessionStore> {
+pub struct WechatCpTpTag<'a, T: AsyncSessionStore> {
     client: &'a WechatCpTpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpTpTag<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpTpTag<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpTpClient<T>) -> WechatCpTpTag<T> {
@@ -29,7 +29,7 @@ impl<'a, T: SessionStore> WechatCpTpTag<'a, T> {
             "tagname": name,
             "tagid": id,
         });
-        let v = self.client.post(WechatCpMethod::Tag(CpTagMethod::Create), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::Tag(CpTagMethod::Create), vec![], req).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let tag_id = v["tagid"].as_str().unwrap_or_default();
         Ok(tag_id.to_string())
@@ -41,17 +41,17 @@ impl<'a, T: SessionStore> WechatCpTpTag<'a, T> {
             "tagname": tag_name,
             "tagid": tag_id,
         });
-        self.client.post(WechatCpMethod::Tag(CpTagMethod::Update), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::Tag(CpTagMethod::Update), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// 删除标签.
     pub async fn delete(&self, tag_id: &str) -> LabradorResult<WechatCommonResponse> {
-        self.client.get(WechatCpMethod::Tag(CpTagMethod::Delete(tag_id.to_string())), vec![], RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.get(WechatCpMethod::Tag(CpTagMethod::Delete(tag_id.to_string())), vec![]).await?.json::<WechatCommonResponse>()
     }
 
     /// 获取标签成员.
     pub async fn get(&self, tag_id: &str) -> LabradorResult<WechatCpTagGetResponse> {
-        let v = self.client.get(WechatCpMethod::Tag(CpTagMethod::Get(tag_id.to_string())), vec![], RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::Tag(CpTagMethod::Get(tag_id.to_string())), vec![]).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTagGetResponse>(v)
     }
 
@@ -62,7 +62,7 @@ impl<'a, T: SessionStore> WechatCpTpTag<'a, T> {
             "userlist": user_ids,
             "partylist": party_ids
         });
-        let v = self.client.post(WechatCpMethod::Tag(CpTagMethod::AddTagUsers), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::Tag(CpTagMethod::AddTagUsers), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTagAddOrRemoveUsersResponse>(v)
     }
 
@@ -73,13 +73,13 @@ impl<'a, T: SessionStore> WechatCpTpTag<'a, T> {
             "userlist": user_ids,
             "partylist": party_ids
         });
-        let v = self.client.post(WechatCpMethod::Tag(CpTagMethod::DeleteTagUsers), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::Tag(CpTagMethod::DeleteTagUsers), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpTagAddOrRemoveUsersResponse>(v)
     }
 
     /// 获得标签列表.
     pub async fn list_all(&self) -> LabradorResult<Vec<WechatCpTagInfo>> {
-        let v = self.client.get(WechatCpMethod::Tag(CpTagMethod::List), vec![], RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::Tag(CpTagMethod::List), vec![]).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         serde_json::from_value::<Vec<WechatCpTagInfo>>(v["taglist"].to_owned()).map_err(LabraError::from)
     }
@@ -1,18 +1,18 @@
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult, WechatCpTpClient, WechatCpUserInfo, ExternalContact, FollowedUser};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult, WechatCpTpClient, WechatCpUserInfo, ExternalContact, FollowedUser};
 use crate::wechat::cp::constants::ACCESS_TOKEN;
 use crate::wechat::cp::method::{CpUserMethod, WechatCpMethod};
 
 /// 部门管理
 #[derive(Debug, Clone)]
-pub struct WechatCpTpUser<'a, T: SessionStore> {
+pub struct WechatCpTpUser<'a, T: AsyncSessionStore> {
     client: &'a WechatCpTpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatCpTpUser<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatCpTpClient<T>) -> WechatCpTpUser<T> {
@@ -26,7 +26,7 @@ impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
     ///   企业在员工验证成功后，调用本方法告诉企业号平台该员工关注成功。
     /// </pre>
     pub async fn authenticate(&self, user_id: &str) -> LabradorResult<WechatCommonResponse> {
-       self.client.get(WechatCpMethod::User(CpUserMethod::AuthSuccess(user_id.to_string())), vec![], RequestType::Json).await?.json::<WechatCommonResponse>()
+       self.client.get(WechatCpMethod::User(CpUserMethod::AuthSuccess(user_id.to_string())), vec![]).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -35,7 +35,7 @@ impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
     /// http://qydev.weixin.qq.com/wiki/index.php?title=管理成员#.E8.8E.B7.E5.8F.96.E9.83.A8.E9.97.A8.E6.88.90.E5.91.98.28.E8.AF.A6.E6.83.85.29
     /// </pre>
     pub async fn list_by_department(&self, depart_id: i64, fetch_child: Option<bool>, status: Option<i32>, corp_id: &str) -> LabradorResult<Vec<WechatCpUserInfo>> {
-        let access_token = self.client.get_access_token(corp_id);
+        let access_token = self.client.get_access_token(corp_id).await;
         let mut query = vec![(ACCESS_TOKEN.to_string(), access_token)];
         if let Some(fetch_child) = fetch_child {
             query.push(("fetch_child".to_string(), fetch_child.to_string()));
@@ -45,7 +45,7 @@ impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
         } else {
             query.push(("status".to_string(), "0".to_string()));
         }
-        let v = self.client.get(WechatCpMethod::User(CpUserMethod::List(depart_id)), query, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::User(CpUserMethod::List(depart_id)), query).await?.json::<Value>()?;
         WechatCommonResponse::parse::<Vec<WechatCpUserInfo>>(v)
     }
 
@@ -64,7 +64,7 @@ impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
         } else {
             query.push(("status".to_string(), "0".to_string()));
         }
-        let v = self.client.get(WechatCpMethod::User(CpUserMethod::SimpleList(depart_id)), query, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::User(CpUserMethod::SimpleList(depart_id)), query).await?.json::<Value>()?;
         WechatCommonResponse::parse::<Vec<WechatCpUserInfo>>(v)
     }
 
@@ -73,14 +73,14 @@ impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
     /// 新建用户
     /// </pre>
     pub async fn create(&self, req: WechatCpUserInfo) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::User(CpUserMethod::Create), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::User(CpUserMethod::Create), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
     /// 更新用户
     /// </pre>
     pub async fn update(&self, req: WechatCpUserInfo) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatCpMethod::User(CpUserMethod::Update), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatCpMethod::User(CpUserMethod::Update), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -89,9 +89,9 @@ impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
     /// </pre>
     pub async fn delete(&self, user_ids: Vec<&str>) -> LabradorResult<WechatCommonResponse> {
         if user_ids.len() == 1 {
-            self.client.get(WechatCpMethod::User(CpUserMethod::Delete(user_ids[0].to_string())), vec![], RequestType::Json).await?.json::<WechatCommonResponse>()
+            self.client.get(WechatCpMethod::User(CpUserMethod::Delete(user_ids[0].to_string())), vec![]).await?.json::<WechatCommonResponse>()
         } else {
-            self.client.post(WechatCpMethod::User(CpUserMethod::BatchDelete), vec![], json!({"useridlist": user_ids}), RequestType::Json).await?.json::<WechatCommonResponse>()
+            self.client.post(WechatCpMethod::User(CpUserMethod::BatchDelete), vec![], json!({"useridlist": user_ids})).await?.json::<WechatCommonResponse>()
         }
 
     }
@@ -100,9 +100,9 @@ impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
     /// 获取用户
     /// </pre>
     pub async fn get_by_id(&self, userid: &str, corp_id: &str) -> LabradorResult<WechatCpUserInfo> {
-        let access_token = self.client.get_access_token(corp_id);
+        let access_token = self.client.get_access_token(corp_id).await;
         let query = vec![(ACCESS_TOKEN.to_string(), access_token)];
-        let v = self.client.get(WechatCpMethod::User(CpUserMethod::Get(userid.to_string())), query,RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::User(CpUserMethod::Get(userid.to_string())), query).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserInfo>(v)
     }
 
@@ -119,7 +119,7 @@ impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
             "party": party_ids,
             "tag": tag_ids,
         });
-        let v = self.client.post(WechatCpMethod::User(CpUserMethod::Invite), vec![],req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::User(CpUserMethod::Invite), vec![],req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WxCpTpInviteResponse>(v)
     }
 
@@ -138,7 +138,7 @@ impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
             "userid": userid,
             "agentid": agent_id,
         });
-        let v = self.client.post(WechatCpMethod::User(CpUserMethod::ConvertToOpenid), vec![],req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::User(CpUserMethod::ConvertToOpenid), vec![],req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WxCpTpUseridToOpenidResponse>(v)
     }
 
@@ -156,7 +156,7 @@ impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
         let req = json!({
             "openid": openid,
         });
-        let v = self.client.post(WechatCpMethod::User(CpUserMethod::ConvertToUserid), vec![],req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::User(CpUserMethod::ConvertToUserid), vec![],req).await?.json::<Value>()?;
         let v= WechatCommonResponse::parse::<Value>(v)?;
         let userid = v["userid"].as_str().unwrap_or_default();
         Ok(userid.to_string())
@@ -175,7 +175,7 @@ impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
         let req = json!({
             "mobile": mobile,
         });
-        let v = self.client.post(WechatCpMethod::User(CpUserMethod::GetUserid), vec![],req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatCpMethod::User(CpUserMethod::GetUserid), vec![],req).await?.json::<Value>()?;
         let v= WechatCommonResponse::parse::<Value>(v)?;
         let userid = v["userid"].as_str().unwrap_or_default();
         Ok(userid.to_string())
@@ -189,7 +189,7 @@ impl<'a, T: SessionStore> WechatCpTpUser<'a, T> {
     /// 第三方应用调用时，返回的跟进人follow_user仅包含应用可见范围之内的成员。
     /// </pre>
     pub async fn get_external_contact(&self, userid: &str) -> LabradorResult<WechatCpUserExternalContactInfo> {
-        let v = self.client.get(WechatCpMethod::User(CpUserMethod::GetExternalContact(userid.to_string())), vec![],RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatCpMethod::User(CpUserMethod::GetExternalContact(userid.to_string())), vec![]).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatCpUserExternalContactInfo>(v)
     }
 }
@@ -300,6 +300,12 @@ impl WechatCryptoV3 {
         PrpCrypto::rsa_sha256_verify_with_pem(public_key, message, signature)
     }
 
+    /// # V3  验证原始字节的签名
+    /// 应答体按原始字节参与验签，避免非UTF-8内容被替换后验签失败
+    pub fn verify_bytes(message: &[u8], signature: &str, public_key: &str) -> LabradorResult<bool> {
+        PrpCrypto::rsa_sha256_verify_bytes_with_pem(public_key, message, signature)
+    }
+
     /// # V3 消息解密 - 使用V3密钥
     /// decrypt     微信返回的待解密的数据体
     pub fn decrypt_data_v3(&self, decrypt: &EncryptV3) -> LabradorResult<Vec<u8>> {
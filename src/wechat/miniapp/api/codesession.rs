@@ -1,18 +1,18 @@
 use serde::{Serialize, Deserialize};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult};
 use crate::wechat::miniapp::constants::{APPID, AUTHORIZATION_CODE, GRANT_TYPE, JS_CODE, SECRET};
 use crate::wechat::miniapp::method::WechatMaMethod;
 use crate::wechat::miniapp::WechatMaClient;
 
 
 #[derive(Debug, Clone)]
-pub struct WechatMaCodeSession<'a, T: SessionStore> {
+pub struct WechatMaCodeSession<'a, T: AsyncSessionStore> {
     client: &'a WechatMaClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMaCodeSession<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMaCodeSession<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMaClient<T>) -> WechatMaCodeSession<T> {
@@ -31,7 +31,7 @@ impl<'a, T: SessionStore> WechatMaCodeSession<'a, T> {
             (JS_CODE.to_string(), code.to_string()),
             (APPID.to_string(), self.client.appid.to_string()),
             (SECRET.to_string(), self.client.secret.to_string()),
-        ], RequestType::Json).await?.json::<serde_json::Value>()?;
+        ]).await?.json::<serde_json::Value>()?;
         WechatCommonResponse::parse::<JsCodeSession>(v)
     }
 }
@@ -1,22 +1,20 @@
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
 use bytes::Bytes;
+use tokio::io::AsyncRead;
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
 
-use crate::{session::SessionStore, LabradorResult, RequestBody, RequestType, WechatCommonResponse, request, get_nonce_str};
+use crate::{session::AsyncSessionStore, LabradorResult, WechatCommonResponse, request, get_nonce_str, stream_part, Form, LabraStreamResponse};
 use crate::wechat::miniapp::method::{MaMediaMethod, WechatMaMethod};
 use crate::wechat::miniapp::{WechatMaClient, WechatRequest};
 
 
 #[derive(Debug, Clone)]
-pub struct WechatMaMedia<'a, T: SessionStore> {
+pub struct WechatMaMedia<'a, T: AsyncSessionStore> {
     client: &'a WechatMaClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMaMedia<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMaMedia<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMaClient<T>) -> WechatMaMedia<T> {
@@ -38,7 +36,7 @@ impl<'a, T: SessionStore> WechatMaMedia<'a, T> {
             file_name: file_name.map(|v| v.to_string()).unwrap_or(default_file_name),
             media_data: data
         };
-        let v= self.client.execute::<WechatMaMediaRequest, String>(req).await?.json::<Value>()?;
+        let v= self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMaMediaResponse>(v)
     }
 
@@ -50,12 +48,20 @@ impl<'a, T: SessionStore> WechatMaMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/upload?access_token=ACCESS_TOKEN&type=TYPE
     /// </pre>
     pub async fn upload_media_with_file(&self, media_type: &str, file_path: &str) -> LabradorResult<WechatMaMediaResponse> {
-        let path = Path::new(file_path);
-        let file_name = path.file_name().map(|v| v.to_str().unwrap_or_default()).unwrap_or_default();
-        let mut f = File::open(path)?;
-        let mut content: Vec<u8> = Vec::new();
-        let _ = f.read_to_end(&mut content)?;
-        self.upload_media(media_type, file_name.into(),content).await
+        let v = self.client.post_file(WechatMaMethod::Media(MaMediaMethod::UploadMedia(media_type.to_string())), vec![], "media", file_path).await?.json::<Value>()?;
+        WechatCommonResponse::parse::<WechatMaMediaResponse>(v)
+    }
+
+    /// <pre>
+    /// 新增临时素材，从数据流中读取文件内容，不会将整个文件读入内存
+    /// `length`为文件长度，不知道长度时使用分块传输
+    /// 数据流无法重复发送，access_token失效时不会重试
+    /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/upload?access_token=ACCESS_TOKEN&type=TYPE
+    /// </pre>
+    pub async fn upload_media_stream<R: AsyncRead + Send + Sync + 'static>(&self, media_type: &str, file_name: &str, reader: R, length: Option<u64>) -> LabradorResult<WechatMaMediaResponse> {
+        let form = Form::new().part("media", stream_part(reader, file_name, length));
+        let v = self.client.post_multipart(WechatMaMethod::Media(MaMediaMethod::UploadMedia(media_type.to_string())), vec![], form).await?.json::<Value>()?;
+        WechatCommonResponse::parse::<WechatMaMediaResponse>(v)
     }
 
     /// <pre>
@@ -79,9 +85,17 @@ impl<'a, T: SessionStore> WechatMaMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/get?access_token=ACCESS_TOKEN&media_id=MEDIA_ID
     /// </pre>
     pub async fn get_media(&self, media_id: &str) -> LabradorResult<Bytes> {
-        let response = self.client.post(WechatMaMethod::Media(MaMediaMethod::GetMedia), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null, RequestType::Json).await?;
+        let response = self.client.post(WechatMaMethod::Media(MaMediaMethod::GetMedia), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await?;
         response.bytes()
     }
+
+    /// <pre>
+    /// 获取临时素材，流式读取，适用于较大的文件
+    /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/get?access_token=ACCESS_TOKEN&media_id=MEDIA_ID
+    /// </pre>
+    pub async fn get_media_stream(&self, media_id: &str) -> LabradorResult<LabraStreamResponse> {
+        self.client.post_stream(WechatMaMethod::Media(MaMediaMethod::GetMedia), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await
+    }
 }
 
 //----------------------------------------------------------------------------------------------------------------------------
@@ -100,9 +114,8 @@ impl WechatRequest for WechatMaMediaRequest {
         MaMediaMethod::UploadMedia(self.media_type.to_string()).get_method()
     }
 
-    fn get_request_body<T: Serialize>(&self) -> RequestBody<T> {
-        let form = reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.media_data.to_owned()).file_name(self.file_name.to_string()));
-        form.into()
+    fn get_request_body(&self) -> Form {
+        reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.media_data.to_owned()).file_name(self.file_name.to_string()))
     }
 }
 
@@ -1,7 +1,7 @@
 use serde::{Serialize, Deserialize};
 use serde_json::{ Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, LabradorResult};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, LabradorResult};
 use crate::wechat::constants::{KEFU_MSGTYPE_IMAGE, KEFU_MSGTYPE_MA_PAGE, KEFU_MSGTYPE_TEXT};
 use crate::wechat::miniapp::method::{MaMessageMethod, WechatMaMethod};
 use crate::wechat::miniapp::WechatMaClient;
@@ -9,12 +9,12 @@ use crate::wechat::miniapp::WechatMaClient;
 
 /// 消息发送接口.
 #[derive(Debug, Clone)]
-pub struct WechatMaMessage<'a, T: SessionStore> {
+pub struct WechatMaMessage<'a, T: AsyncSessionStore> {
     client: &'a WechatMaClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMaMessage<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMaMessage<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMaClient<T>) -> WechatMaMessage<T> {
@@ -30,7 +30,7 @@ impl<'a, T: SessionStore> WechatMaMessage<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/message/custom/send?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn send_kefu_msg(&self, message: WxMaKefuMsgRequest) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatMaMethod::Message(MaMessageMethod::SendCustomMsg), vec![], &message, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMaMethod::Message(MaMessageMethod::SendCustomMsg), vec![], &message).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -38,7 +38,7 @@ impl<'a, T: SessionStore> WechatMaMessage<'a, T> {
     /// https://developers.weixin.qq.com/miniprogram/dev/api-backend/open-api/subscribe-message/subscribeMessage.send.html
     /// </pre>
     pub async fn send_subscribe_msg(&self, data: WxMaSubscribeMsgRequest) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatMaMethod::Message(MaMessageMethod::SendSubscribeMsg), vec![], data, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMaMethod::Message(MaMessageMethod::SendSubscribeMsg), vec![], data).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -47,7 +47,7 @@ impl<'a, T: SessionStore> WechatMaMessage<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/message/wxopen/template/uniform_send?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn send_uniform_msg(&self, data: WxMaUniformMsgRequest) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatMaMethod::Message(MaMessageMethod::SendUniformTemplate), vec![], &data, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMaMethod::Message(MaMessageMethod::SendUniformTemplate), vec![], &data).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -58,7 +58,7 @@ impl<'a, T: SessionStore> WechatMaMessage<'a, T> {
     ///  接口地址：GET https://api.weixin.qq.com/cgi-bin/message/wxopen/activityid/create?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn create_updatable_message_activity_id<D: Serialize>(&self, data: D) -> LabradorResult<Value> {
-        self.client.get(WechatMaMethod::Message(MaMessageMethod::CreateActivityId), vec![], RequestType::Json).await?.json::<serde_json::Value>()
+        self.client.get(WechatMaMethod::Message(MaMessageMethod::CreateActivityId), vec![]).await?.json::<serde_json::Value>()
     }
 
     /// <pre>
@@ -69,7 +69,7 @@ impl<'a, T: SessionStore> WechatMaMessage<'a, T> {
     ///  接口地址：POST https://api.weixin.qq.com/cgi-bin/message/wxopen/activityid/create?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn create_updatable_message<D: Serialize>(&self, data: D) -> LabradorResult<()> {
-        self.client.post(WechatMaMethod::Message(MaMessageMethod::SendUpdatableMsg), vec![], data, RequestType::Json).await?.json::<serde_json::Value>()?;
+        self.client.post(WechatMaMethod::Message(MaMessageMethod::SendUpdatableMsg), vec![], data).await?.json::<serde_json::Value>()?;
         Ok(())
     }
 }
@@ -1,4 +1,4 @@
-use crate::{session::SessionStore, errors::LabraError, request::{RequestType}, LabradorResult, WechatCommonResponse};
+use crate::{session::AsyncSessionStore, errors::{LabraError, PlatformError}, LabradorResult, LabraStreamResponse, WechatCommonResponse};
 use bytes::Bytes;
 use serde::{Serialize, Deserialize};
 use crate::wechat::miniapp::method::{MaQrCodeMethod, WechatMaMethod};
@@ -12,12 +12,12 @@ use crate::wechat::miniapp::WechatMaClient;
 /// [文档地址](https://developers.weixin.qq.com/miniprogram/dev/OpenApiDoc/qrcode-link/qr-code/getQRCode.html)
 ///
 #[derive(Debug, Clone)]
-pub struct WechatMaQrcode<'a, T: SessionStore> {
+pub struct WechatMaQrcode<'a, T: AsyncSessionStore> {
     client: &'a WechatMaClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMaQrcode<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMaQrcode<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMaClient<T>) -> WechatMaQrcode<T> {
@@ -42,11 +42,11 @@ impl<'a, T: SessionStore> WechatMaQrcode<'a, T> {
             width,
             path: path.to_string()
         };
-        let result = self.client.post(WechatMaMethod::QrCode(MaQrCodeMethod::CreateWxaQrCode), vec![], &mini_qr_code, RequestType::Json).await?.bytes()?;
+        let result = self.client.post(WechatMaMethod::QrCode(MaQrCodeMethod::CreateWxaQrCode), vec![], &mini_qr_code).await?.bytes()?;
         let res_str = String::from_utf8(result.to_vec()).unwrap_or_default();
         match WechatCommonResponse::from_str(&res_str) {
             Ok(r) => {
-                return Err(LabraError::ClientError { errcode: r.errcode.to_owned().unwrap_or_default().to_string(), errmsg: r.errmsg.to_owned().unwrap_or_default()})
+                return Err(LabraError::from(PlatformError::wechat(r.errcode.unwrap_or_default(), r.errmsg.to_owned().unwrap_or_default())))
             }
             Err(err) => {  }
         };
@@ -71,17 +71,26 @@ impl<'a, T: SessionStore> WechatMaQrcode<'a, T> {
             scene: scene.to_owned(),
             page: page.to_owned(),
         };
-        let result = self.client.post(WechatMaMethod::QrCode(MaQrCodeMethod::GetWxaCodeUnlimit), vec![], &mini_qr_code, RequestType::Json).await?.bytes()?;
+        let result = self.client.post(WechatMaMethod::QrCode(MaQrCodeMethod::GetWxaCodeUnlimit), vec![], &mini_qr_code).await?.bytes()?;
         let res_str = String::from_utf8(result.to_vec()).unwrap_or_default();
         match WechatCommonResponse::from_str(&res_str) {
             Ok(r) => {
-                return Err(LabraError::ClientError { errcode: r.errcode.to_owned().unwrap_or_default().to_string(), errmsg: r.errmsg.to_owned().unwrap_or_default()})
+                return Err(LabraError::from(PlatformError::wechat(r.errcode.unwrap_or_default(), r.errmsg.to_owned().unwrap_or_default())))
             }
             Err(err) => {  }
         };
         Ok(result)
     }
 
+    /// 获取不限制的小程序码，流式读取图片内容，请求失败时返回错误
+    pub async fn get_unlimited_qrcode_stream(&self, scene: &str, page: &str) -> LabradorResult<LabraStreamResponse> {
+        let mini_qr_code = MiniQRCodeRequest {
+            scene: scene.to_owned(),
+            page: page.to_owned(),
+        };
+        self.client.post_stream(WechatMaMethod::QrCode(MaQrCodeMethod::GetWxaCodeUnlimit), vec![], &mini_qr_code).await
+    }
+
 
     /// 获取不限制的小程序码
     /// 该接口用于获取小程序码，适用于需要的码数量较少的业务场景。通过该接口生成的小程序码，永久有效，有数量限制，详见获取小程序码。
@@ -98,11 +107,11 @@ impl<'a, T: SessionStore> WechatMaQrcode<'a, T> {
             width,
             path: path.to_string()
         };
-        let result = self.client.post(WechatMaMethod::QrCode(MaQrCodeMethod::GetWxaCodeUnlimit), vec![], &mini_qr_code, RequestType::Json).await?.bytes()?;
+        let result = self.client.post(WechatMaMethod::QrCode(MaQrCodeMethod::GetWxaCodeUnlimit), vec![], &mini_qr_code).await?.bytes()?;
         let res_str = String::from_utf8(result.to_vec()).unwrap_or_default();
         match WechatCommonResponse::from_str(&res_str) {
             Ok(r) => {
-                return Err(LabraError::ClientError { errcode: r.errcode.to_owned().unwrap_or_default().to_string(), errmsg: r.errmsg.to_owned().unwrap_or_default()})
+                return Err(LabraError::from(PlatformError::wechat(r.errcode.unwrap_or_default(), r.errmsg.to_owned().unwrap_or_default())))
             }
             Err(err) => {  }
         };
@@ -3,18 +3,18 @@ use serde_json::{json};
 
 use serde::{Serialize, Deserialize};
 
-use crate::{session::SessionStore, errors::LabraError, wechat::{cryptos::WechatCrypto}, request::RequestType, WechatCommonResponse, LabradorResult};
+use crate::{session::AsyncSessionStore, errors::LabraError, wechat::{cryptos::WechatCrypto}, WechatCommonResponse, LabradorResult};
 use crate::wechat::miniapp::method::{MaUserMethod, WechatMaMethod};
 use crate::wechat::miniapp::WechatMaClient;
 
 /// 用户信息相关操作
 #[derive(Debug, Clone)]
-pub struct WechatMaUser<'a, T: SessionStore> {
+pub struct WechatMaUser<'a, T: AsyncSessionStore> {
     client: &'a WechatMaClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMaUser<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMaUser<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMaClient<T>) -> WechatMaUser<T> {
@@ -46,7 +46,7 @@ impl<'a, T: SessionStore> WechatMaUser<'a, T> {
         });
         let signature = WechatCrypto::create_hmac_sha256_sign(session_key, &req.to_string())?;
         self.client.post(WechatMaMethod::User(MaUserMethod::SetUserStorage), vec![("appid".to_string(), self.client.secret.to_string()),
-          ("signature".to_string(), signature),("openid".to_string(), openid.to_string()),("sig_method".to_string(), "hmac_sha256".to_string()),], &req, RequestType::Json).await?.json::<WechatCommonResponse>()
+          ("signature".to_string(), signature),("openid".to_string(), openid.to_string()),("sig_method".to_string(), "hmac_sha256".to_string()),], &req).await?.json::<WechatCommonResponse>()
     }
 
     /// 获取手机号信息,基础库:2.21.2及以上
@@ -55,7 +55,7 @@ impl<'a, T: SessionStore> WechatMaUser<'a, T> {
         let req = json!({
             "code": code
         });
-        let v = self.client.post(WechatMaMethod::User(MaUserMethod::GetPhoneNumber), vec![], &req, RequestType::Json).await?.json::<serde_json::Value>()?;
+        let v = self.client.post(WechatMaMethod::User(MaUserMethod::GetPhoneNumber), vec![], &req).await?.json::<serde_json::Value>()?;
         WechatCommonResponse::parse(v)
     }
 
@@ -115,6 +115,10 @@ impl RequestMethod for WechatMaMethod {
             WechatMaMethod::Message(v) => v.get_method(),
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        matches!(self, WechatMaMethod::QrCode(MaQrCodeMethod::GetWxaCodeUnlimit) | WechatMaMethod::Media(MaMediaMethod::GetMedia))
+    }
 }
 
 #[allow(unused)]
@@ -5,7 +5,7 @@ use serde_json::Value;
 
 mod mp;
 mod cp;
-mod pay;
+pub(crate) mod pay;
 mod cryptos;
 mod miniapp;
 #[allow(unused)]
@@ -16,7 +16,38 @@ pub use mp::*;
 pub use miniapp::*;
 pub use pay::*;
 pub use cryptos::*;
-use crate::{LabradorResult, LabraError, Method, RequestBody, RequestType};
+use crate::{AsyncSessionStore, current_timestamp, LabraResponse, LabraStreamResponse, LabradorResult, LabraError, PlatformError, WechatErrorCode, Form};
+
+/// 响应是否表示access_token无效或过期，此时需要强制刷新后重试
+pub(crate) fn is_invalid_token(response: &LabraResponse) -> bool {
+    response.json::<Value>().ok()
+        .and_then(|v| v["errcode"].as_i64())
+        .and_then(WechatErrorCode::from_code)
+        .map(|v| v.is_invalid_token())
+        .unwrap_or(false)
+}
+
+/// 下载文件的接口出错时返回JSON格式的数据，将其转换为错误
+pub(crate) async fn check_stream(response: LabraStreamResponse) -> LabradorResult<LabraStreamResponse> {
+    if !response.is_json() {
+        return Ok(response);
+    }
+    let response = response.into_response().await?;
+    WechatCommonResponse::parse::<Value>(response.json::<Value>()?).map_err(|err| err.with_response(&response))?;
+    Ok(response.into())
+}
+
+/// 读取缓存中仍然有效的凭证
+pub(crate) async fn cached_token<S: AsyncSessionStore>(session: &S, token_key: &str, expires_key: &str) -> LabradorResult<Option<String>> {
+    let token: String = session.get(token_key, Some("".to_owned())).await?.unwrap_or_default();
+    let timestamp = current_timestamp();
+    let expires_at: i64 = session.get(expires_key, Some(timestamp)).await?.unwrap_or_default();
+    if expires_at <= timestamp || token.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(token))
+    }
+}
 
 pub trait ReplyRenderer {
     fn render(&self) -> String;
@@ -43,23 +74,12 @@ pub trait WechatRequest {
     /// @return API名称
     fn get_api_method_name(&self) -> String;
 
-    /// 获取请求类型。
-    fn get_request_type(&self) -> RequestType {
-        RequestType::Json
-    }
-
-    /// 获取请求方法。
-    fn get_request_method(&self) -> Method {
-        Method::Post
-    }
-
     fn get_query_params(&self) -> BTreeMap<String, String> {
         BTreeMap::new()
     }
 
-    fn get_request_body<T: Serialize>(&self) -> RequestBody<T> {
-        RequestBody::Null
-    }
+    /// 获取上传的multipart表单，请求以POST方式发送
+    fn get_request_body(&self) -> Form;
 
     /// 是否需要token
     fn is_need_token(&self) -> bool {
@@ -86,7 +106,7 @@ impl WechatCommonResponse {
         if resp.is_success() {
             serde_json::from_str::<T>(&v.to_string()).map_err(LabraError::from)
         } else {
-            Err(LabraError::ClientError { errcode: resp.errcode.to_owned().unwrap_or_default().to_string(), errmsg: resp.errmsg.to_owned().unwrap_or_default() })
+            Err(LabraError::from(PlatformError::wechat(resp.errcode.unwrap_or_default(), resp.errmsg.to_owned().unwrap_or_default())))
         }
     }
 
@@ -101,7 +121,7 @@ impl WechatCommonResponse {
                 serde_json::from_value::<T>(v[key].to_owned()).map_err(LabraError::from)
             }
         } else {
-            Err(LabraError::ClientError { errcode: resp.errcode.to_owned().unwrap_or_default().to_string(), errmsg: resp.errmsg.to_owned().unwrap_or_default() })
+            Err(LabraError::from(PlatformError::wechat(resp.errcode.unwrap_or_default(), resp.errmsg.to_owned().unwrap_or_default())))
         }
     }
 
@@ -131,7 +151,7 @@ impl WechatCommonResponse {
                 serde_json::from_str::<T>(&self.body.to_owned().unwrap_or_default()).map_err(LabraError::from)
             }
         } else {
-            Err(LabraError::ClientError { errcode: self.errcode.to_owned().unwrap_or_default().to_string(), errmsg: self.errmsg.to_owned().unwrap_or_default() })
+            Err(LabraError::from(PlatformError::wechat(self.errcode.unwrap_or_default(), self.errmsg.to_owned().unwrap_or_default())))
         }
     }
 }
\ No newline at end of file
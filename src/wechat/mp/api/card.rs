@@ -3,18 +3,18 @@ use std::vec;
 use serde::{Serialize, Deserialize, Serializer};
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, WechatMpClient, LabradorResult, LabraError, get_timestamp, TicketType, get_nonce_str, WechatCrypto, BaseInfo, AdvancedInfo};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, WechatMpClient, LabradorResult, LabraError, get_timestamp, TicketType, get_nonce_str, WechatCrypto, BaseInfo, AdvancedInfo};
 use crate::wechat::mp::constants::{QR_CODE};
 use crate::wechat::mp::method::{MpCardMethod, WechatMpMethod};
 
 /// 卡券相关.
 #[derive(Debug, Clone)]
-pub struct WechatMpCard<'a, T: SessionStore> {
+pub struct WechatMpCard<'a, T: AsyncSessionStore> {
     client: &'a WechatMpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMpCard<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMpCard<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMpClient<T>) -> WechatMpCard<T> {
@@ -70,7 +70,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
         let req = json!({
             "encrypt_code": encrypt_code
         });
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CodeDecrypt), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CodeDecrypt), vec![], req).await?.json::<Value>()?;
         let v=  WechatCommonResponse::parse::<Value>(v)?;
         let code = v["code"].as_str().unwrap_or_default();
         Ok(code.to_string())
@@ -86,7 +86,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
             "code": code,
             "check_consume": check_consume,
         });
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CodeGet), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CodeGet), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardResponse>(v)
     }
 
@@ -105,7 +105,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
             "card_id": card_id,
             "code": code,
         });
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CodeConsume), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CodeConsume), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardCodeConsumeResponse>(v)
     }
 
@@ -121,7 +121,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
             "openid": openid,
             "is_mark": is_mark,
         });
-       self.client.post(WechatMpMethod::Card(MpCardMethod::CodeMark), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+       self.client.post(WechatMpMethod::Card(MpCardMethod::CodeMark), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -132,7 +132,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
         let req = json!({
             "card_id": card_id,
         });
-        let v= self.client.post(WechatMpMethod::Card(MpCardMethod::Get), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v= self.client.post(WechatMpMethod::Card(MpCardMethod::Get), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<Value>(v)
     }
 
@@ -143,14 +143,14 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
         let req = json!({
             "openid": vec![openid],
         });
-        self.client.post(WechatMpMethod::Card(MpCardMethod::SetWhiteList), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::Card(MpCardMethod::SetWhiteList), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
     /// 创建卡券
     /// </pre>
     pub async fn create_card(&self, req: WechatMpCardCreateRequest) -> LabradorResult<WechatMpCardCreateResponse> {
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::Create), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::Create), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardCreateResponse>(v)
     }
 
@@ -188,7 +188,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
             }
         });
         req["action_info"] = action_info;
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CreateQrcode), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CreateQrcode), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardQrcodeCreateResponse>(v)
     }
 
@@ -196,7 +196,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
     /// 创建卡券货架
     /// </pre>
     pub async fn create_landing_page(&self, req: WechatMpCardLandingPageCreateRequest) -> LabradorResult<WechatMpCardLandingPageCreateResponse> {
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CreateLandingpage), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CreateLandingpage), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardLandingPageCreateResponse>(v)
     }
 
@@ -210,7 +210,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
             "code": code,
             "reason": reason
         });
-        self.client.post(WechatMpMethod::Card(MpCardMethod::UnavailabeCode), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::Card(MpCardMethod::UnavailabeCode), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -220,7 +220,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
         let req = json!({
            "card_id": card_id,
         });
-        self.client.post(WechatMpMethod::Card(MpCardMethod::Delete), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::Card(MpCardMethod::Delete), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -234,7 +234,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
            "card_id": card_id,
            "code": code_list,
         });
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CodeDeposit), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CodeDeposit), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardCodeDepositResponse>(v)
     }
 
@@ -245,7 +245,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
         let req = json!({
            "card_id": card_id,
         });
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::GetDepositCount), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::GetDepositCount), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardCodeDepositCountResponse>(v)
     }
 
@@ -260,7 +260,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
            "card_id": card_id,
            "code": code_list,
         });
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CheckCode), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::CheckCode), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardCheckCodeResponse>(v)
     }
 
@@ -271,7 +271,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
         let req = json!({
            "card_id": card_id,
         });
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::GetHtml), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::GetHtml), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardMpnewsGethtmlResponse>(v)
     }
 
@@ -288,7 +288,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
         } else {
             req["reduce_stock_value"] = change_value.abs().into();
         }
-        self.client.post(WechatMpMethod::Card(MpCardMethod::ModifyStock), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::Card(MpCardMethod::ModifyStock), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -301,7 +301,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
            "code": old_code,
            "new_code": new_code,
         });
-        self.client.post(WechatMpMethod::Card(MpCardMethod::UpdateCode), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::Card(MpCardMethod::UpdateCode), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -313,7 +313,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
            "card_id": card_id,
            "is_open": is_open,
         });
-        self.client.post(WechatMpMethod::Card(MpCardMethod::SetPayCell), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::Card(MpCardMethod::SetPayCell), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -327,7 +327,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
            "need_verify_cod": need_verify_cod,
            "need_remark_amount": need_remark_amount,
         });
-        self.client.post(WechatMpMethod::Card(MpCardMethod::SetSelfConsumerCell), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::Card(MpCardMethod::SetSelfConsumerCell), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -339,7 +339,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
            "card_id": card_id,
            "openid": openid,
         });
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::GetUserCardList), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::GetUserCardList), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatUserCardListResponse>(v)
     }
 
@@ -367,7 +367,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
     /// 3、子商户若有公众号，且不愿意自己运营，通过授权方式让第三方代制，支持配置子商户公众号。配置后，1）该子商户的制券配额不再限制，2）该卡券详情页关联的公众号为子商户配置这个公众号。
     /// </pre>
     pub async fn create_submerchant(&self, req: WechatMpCardCreateSubmerchantRequest) -> LabradorResult<WechatMpCardSubmerchantResponse> {
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::SubmitSubmerchant), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::SubmitSubmerchant), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardSubmerchantResponse>(v)
     }
 
@@ -383,7 +383,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
     /// 2.对于第三方开发者代制（无公众号）模式，子商户无论选择什么类目，均暂不需按照此返回提供资质，返回值仅参考类目ID 即可。
     /// </pre>
     pub async fn get_cateogry(&self) -> LabradorResult<WechatMpCardCategoryResponse> {
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::GetApplyProtocol), vec![], Value::Null, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::GetApplyProtocol), vec![], Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardCategoryResponse>(v)
     }
 
@@ -395,7 +395,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
     /// 支持调用该接口更新子商户信息。
     /// </pre>
     pub async fn update_submerchant(&self, req: WechatMpCardCreateSubmerchantRequest) -> LabradorResult<WechatMpCardSubmerchantResponse> {
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::UpdateSubmerchant), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::UpdateSubmerchant), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardSubmerchantResponse>(v)
     }
 
@@ -410,7 +410,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
         let req = json!({
             "merchant_id": merchant_id
         });
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::GetSubmerchant), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::GetSubmerchant), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardSubmerchantResponse>(v)
     }
 
@@ -425,7 +425,7 @@ impl<'a, T: SessionStore> WechatMpCard<'a, T> {
           "limit": limit,
           "status": status
         });
-        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::BatchGetSubmerchant), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Card(MpCardMethod::BatchGetSubmerchant), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardSubmerchantBatchResponse>(v)
     }
 }
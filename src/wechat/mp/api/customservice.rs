@@ -3,18 +3,18 @@ use std::vec;
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, request::{RequestType}, errors::LabraError, WechatCommonResponse, WechatMpClient, LabradorResult};
+use crate::{session::AsyncSessionStore, errors::{LabraError, PlatformError}, WechatCommonResponse, WechatMpClient, LabradorResult};
 use crate::util::md5::md5;
 use crate::wechat::mp::method::{MpCustomServiceMethod, WechatMpMethod};
 
 /// 客服接口.
 #[derive(Debug, Clone)]
-pub struct WechatMpCustomService<'a, T: SessionStore> {
+pub struct WechatMpCustomService<'a, T: AsyncSessionStore> {
     client: &'a WechatMpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMpCustomService<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMpCustomService<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMpClient<T>) -> WechatMpCustomService<T> {
@@ -29,7 +29,7 @@ impl<'a, T: SessionStore> WechatMpCustomService<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/message/custom/send?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn send_kefu_message<D: Serialize>(&self, data: D) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatMpMethod::CustomService(MpCustomServiceMethod::CustomSend), vec![], data, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::CustomService(MpCustomServiceMethod::CustomSend), vec![], data).await?.json::<WechatCommonResponse>()
     }
 
 
@@ -66,7 +66,7 @@ impl<'a, T: SessionStore> WechatMpCustomService<'a, T> {
             "nickname": nickname.to_owned(),
             "password": encrypted_password
         });
-       self.client.post(WechatMpMethod::CustomService(MpCustomServiceMethod::AccountAdd), vec![], data, RequestType::Json).await?.json::<WechatCommonResponse>()
+       self.client.post(WechatMpMethod::CustomService(MpCustomServiceMethod::AccountAdd), vec![], data).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -82,7 +82,7 @@ impl<'a, T: SessionStore> WechatMpCustomService<'a, T> {
             "nickname": nickname.to_owned(),
             "password": encrypted_password
         });
-        self.client.post(WechatMpMethod::CustomService(MpCustomServiceMethod::AccountUpdate), vec![], data, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::CustomService(MpCustomServiceMethod::AccountUpdate), vec![], data).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -91,7 +91,7 @@ impl<'a, T: SessionStore> WechatMpCustomService<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/customservice/kfaccount/del?access_token=ACCESS_TOKEN&kf_account=KFACCOUNT
     /// </pre>
     pub async fn delete_account(&self, account: &str) -> LabradorResult<WechatCommonResponse> {
-        self.client.get(WechatMpMethod::CustomService(MpCustomServiceMethod::AccountDelete), vec![], RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.get(WechatMpMethod::CustomService(MpCustomServiceMethod::AccountDelete), vec![]).await?.json::<WechatCommonResponse>()
     }
 
     /// 获取账号列表
@@ -101,7 +101,7 @@ impl<'a, T: SessionStore> WechatMpCustomService<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/customservice/getkflist?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn get_accounts(&self) -> LabradorResult<Vec<KFAccount>> {
-        let res = self.client.get(WechatMpMethod::CustomService(MpCustomServiceMethod::AccountList), vec![], RequestType::Json).await?.json::<Value>()?;
+        let res = self.client.get(WechatMpMethod::CustomService(MpCustomServiceMethod::AccountList), vec![]).await?.json::<Value>()?;
         let mut result = WechatCommonResponse::from_value(res.clone())?;
         if result.is_success() {
             let kf_list = &res["kf_list"];
@@ -127,7 +127,7 @@ impl<'a, T: SessionStore> WechatMpCustomService<'a, T> {
             }
             Ok(accounts)
         } else {
-            Err(LabraError::ClientError {errcode: result.errcode.to_owned().unwrap_or_default().to_string(), errmsg: result.errmsg.to_owned().unwrap_or_default()})
+            Err(LabraError::from(PlatformError::wechat(result.errcode.unwrap_or_default(), result.errmsg.to_owned().unwrap_or_default())))
         }
     }
 
@@ -137,7 +137,7 @@ impl<'a, T: SessionStore> WechatMpCustomService<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/customservice/getonlinekflist?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn get_online_accounts(&self) -> LabradorResult<Vec<OnlineKFAccount>> {
-        let res = self.client.get(WechatMpMethod::CustomService(MpCustomServiceMethod::AccountOnlineList), vec![], RequestType::Json).await?.json::<serde_json::Value>()?;
+        let res = self.client.get(WechatMpMethod::CustomService(MpCustomServiceMethod::AccountOnlineList), vec![]).await?.json::<serde_json::Value>()?;
         let mut result = WechatCommonResponse::from_value(res.clone())?;
         if result.is_success() {
             let kf_list = &res["kf_online_list"];
@@ -166,7 +166,7 @@ impl<'a, T: SessionStore> WechatMpCustomService<'a, T> {
             }
             Ok(accounts)
         } else {
-            Err(LabraError::ClientError {errcode: result.errcode.to_owned().unwrap_or_default().to_string(), errmsg: result.errmsg.to_owned().unwrap_or_default()})
+            Err(LabraError::from(PlatformError::wechat(result.errcode.unwrap_or_default(), result.errmsg.to_owned().unwrap_or_default())))
         }
     }
 }
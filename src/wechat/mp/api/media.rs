@@ -17,25 +17,23 @@
 //! 缩略图（thumb）：64KB，支持 JPG 格式
 //!
 //! 4、需使用 https 调用本接口。
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
 use bytes::Bytes;
+use tokio::io::AsyncRead;
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, LabradorResult, RequestBody, RequestType, WechatMpClient, WechatCommonResponse, WechatRequest, get_nonce_str, request};
+use crate::{session::AsyncSessionStore, LabradorResult, WechatMpClient, WechatCommonResponse, WechatRequest, get_nonce_str, request, stream_part, Form, LabraStreamResponse};
 use crate::wechat::mp::constants::MATERIAL_TYPE_NEWS;
 use crate::wechat::mp::method::{MpMediaMethod, WechatMpMethod};
 
 
 #[derive(Debug, Clone)]
-pub struct WechatMpMedia<'a, T: SessionStore> {
+pub struct WechatMpMedia<'a, T: AsyncSessionStore> {
     client: &'a WechatMpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMpMedia<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMpClient<T>) -> WechatMpMedia<T> {
@@ -71,7 +69,7 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
             file_name: file_name.map(|v| v.to_string()).unwrap_or(default_file_name),
             media_data: data
         };
-        let v = self.client.execute::<WechatMpMediaRequest, String>(req).await?.json::<Value>()?;
+        let v = self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpMediaResponse>(v)
     }
 
@@ -83,12 +81,20 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/upload?access_token=ACCESS_TOKEN&type=TYPE
     /// </pre>
     pub async fn upload_media_with_file(&self, media_type: &str, file_path: &str) -> LabradorResult<WechatMpMediaResponse> {
-        let path = Path::new(file_path);
-        let file_name = path.file_name().map(|v| v.to_str().unwrap_or_default()).unwrap_or_default();
-        let mut f = File::open(path)?;
-        let mut content: Vec<u8> = Vec::new();
-        let _ = f.read_to_end(&mut content)?;
-        self.upload_media(media_type, file_name.into(),content).await
+        let v = self.client.post_file(WechatMpMethod::Media(MpMediaMethod::UploadMedia(media_type.to_string())), vec![], "media", file_path).await?.json::<Value>()?;
+        WechatCommonResponse::parse::<WechatMpMediaResponse>(v)
+    }
+
+    /// <pre>
+    /// 新增临时素材，从数据流中读取文件内容，不会将整个文件读入内存
+    /// `length`为文件长度，不知道长度时使用分块传输
+    /// 数据流无法重复发送，access_token失效时不会重试
+    /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/upload?access_token=ACCESS_TOKEN&type=TYPE
+    /// </pre>
+    pub async fn upload_media_stream<R: AsyncRead + Send + Sync + 'static>(&self, media_type: &str, file_name: &str, reader: R, length: Option<u64>) -> LabradorResult<WechatMpMediaResponse> {
+        let form = Form::new().part("media", stream_part(reader, file_name, length));
+        let v = self.client.post_multipart(WechatMpMethod::Media(MpMediaMethod::UploadMedia(media_type.to_string())), vec![], form).await?.json::<Value>()?;
+        WechatCommonResponse::parse::<WechatMpMediaResponse>(v)
     }
 
     /// <pre>
@@ -113,10 +119,18 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/get?access_token=ACCESS_TOKEN&media_id=MEDIA_ID
     /// </pre>
     pub async fn get_media(&self, media_id: &str) -> LabradorResult<Bytes> {
-        let response = self.client.post(WechatMpMethod::Media(MpMediaMethod::GetMedia), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null, RequestType::Json).await?;
+        let response = self.client.post(WechatMpMethod::Media(MpMediaMethod::GetMedia), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await?;
         response.bytes()
     }
 
+    /// <pre>
+    /// 获取临时素材，流式读取，适用于较大的文件
+    /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/get?access_token=ACCESS_TOKEN&media_id=MEDIA_ID
+    /// </pre>
+    pub async fn get_media_stream(&self, media_id: &str) -> LabradorResult<LabraStreamResponse> {
+        self.client.post_stream(WechatMpMethod::Media(MpMediaMethod::GetMedia), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await
+    }
+
     /// <pre>
     /// 获取高清语音素材
     /// 公众号可以使用本接口获取从JSSDK的uploadVoice接口上传的临时语音素材，格式为speex，16K采样率。
@@ -126,7 +140,7 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/media/get/jssdk?access_token=ACCESS_TOKEN&media_id=MEDIA_ID
     /// </pre>
     pub async fn get_media_jssdk(&self, media_id: &str) -> LabradorResult<Bytes> {
-        let response = self.client.post(WechatMpMethod::Media(MpMediaMethod::GetMediaJssdk), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null, RequestType::Json).await?;
+        let response = self.client.post(WechatMpMethod::Media(MpMediaMethod::GetMediaJssdk), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await?;
         response.bytes()
     }
 
@@ -150,7 +164,7 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
             media_data: data,
             file_name: file_name.to_string(),
         };
-        let v = self.client.execute::<WechatMpImageRequest, String>(req).await?.json::<Value>()?;
+        let v = self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpMediaResponse>(v)
     }
 
@@ -172,7 +186,7 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
     /// 4、调用该接口需https协议
     /// </pre>
     pub async fn upload_material(&self, req: WechatMpMaterialRequest) -> LabradorResult<WechatMpMediaResponse> {
-        let v = self.client.execute::<WechatMpMaterialRequest, String>(req).await?.json::<Value>()?;
+        let v = self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpMediaResponse>(v)
     }
 
@@ -183,10 +197,18 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/material/get_material?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn get_material(&self, media_id: &str) -> LabradorResult<Bytes> {
-        let response = self.client.post(WechatMpMethod::Media(MpMediaMethod::GetMaterial), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null, RequestType::Json).await?;
+        let response = self.client.post(WechatMpMethod::Media(MpMediaMethod::GetMaterial), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await?;
         response.bytes()
     }
 
+    /// <pre>
+    /// 获取声音或者图片永久素材，流式读取，适用于较大的文件
+    /// 接口url格式：https://api.weixin.qq.com/cgi-bin/material/get_material?access_token=ACCESS_TOKEN
+    /// </pre>
+    pub async fn get_material_stream(&self, media_id: &str) -> LabradorResult<LabraStreamResponse> {
+        self.client.post_stream(WechatMpMethod::Media(MpMediaMethod::GetMaterial), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await
+    }
+
     /// <pre>
     /// 获取视频永久素材的信息和下载地址
     ///
@@ -194,7 +216,7 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/material/get_material?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn get_material_video_info(&self, media_id: &str) -> LabradorResult<WechatMpMaterialVideoInfoResponse> {
-        let response = self.client.post(WechatMpMethod::Media(MpMediaMethod::GetMaterial), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null, RequestType::Json).await?.json::<Value>()?;
+        let response = self.client.post(WechatMpMethod::Media(MpMediaMethod::GetMaterial), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpMaterialVideoInfoResponse>(response)
     }
 
@@ -205,7 +227,7 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/material/update_news?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn get_material_news(&self, media_id: &str) -> LabradorResult<WechatMpMaterialNewsResponse> {
-        let response = self.client.post(WechatMpMethod::Media(MpMediaMethod::GetMaterial), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null, RequestType::Json).await?.json::<Value>()?;
+        let response = self.client.post(WechatMpMethod::Media(MpMediaMethod::GetMaterial), vec![("media_id".to_string(), media_id.to_string())], serde_json::Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpMaterialNewsResponse>(response)
     }
 
@@ -220,7 +242,7 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/material/del_material?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn delete_material(&self, media_id: &str) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatMpMethod::Media(MpMediaMethod::DeleteMaterial), vec![], json!({"media_id": media_id}), RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::Media(MpMediaMethod::DeleteMaterial), vec![], json!({"media_id": media_id})).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -235,7 +257,7 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
     /// 接口url格式：https://api.weixin.qq.com/cgi-bin/material/get_materialcount?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn get_material_count(&self) -> LabradorResult<WechatMpMaterialCountResponse> {
-        let v = self.client.post(WechatMpMethod::Media(MpMediaMethod::DeleteMaterial), vec![], Value::Null, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Media(MpMediaMethod::DeleteMaterial), vec![], Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpMaterialCountResponse>(v)
     }
 
@@ -251,7 +273,7 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
             "type": MATERIAL_TYPE_NEWS,
             "offset": offset,
             "count": count
-        }), RequestType::Json).await?.json::<Value>()?;
+        })).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpMaterialNewsBatchResponse>(v)
     }
 
@@ -266,7 +288,7 @@ impl<'a, T: SessionStore> WechatMpMedia<'a, T> {
             "type": material_type,
             "offset": offset,
             "count": count
-        }), RequestType::Json).await?.json::<Value>()?;
+        })).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpMaterialBatchResponse>(v)
     }
 
@@ -289,9 +311,8 @@ impl WechatRequest for WechatMpMediaRequest {
         MpMediaMethod::UploadMedia(self.media_type.to_string()).get_method()
     }
 
-    fn get_request_body<T: Serialize>(&self) -> RequestBody<T> {
-        let form = reqwest::multipart::Form::new().part("media",    reqwest::multipart::Part::stream(self.media_data.to_owned()).file_name(self.file_name.to_string()));
-        form.into()
+    fn get_request_body(&self) -> Form {
+        reqwest::multipart::Form::new().part("media",    reqwest::multipart::Part::stream(self.media_data.to_owned()).file_name(self.file_name.to_string()))
     }
 }
 
@@ -307,9 +328,8 @@ impl WechatRequest for WechatMpImageRequest {
         MpMediaMethod::UploadImage.get_method()
     }
 
-    fn get_request_body<T: Serialize>(&self) -> RequestBody<T> {
-        let form = reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.media_data.to_vec()).file_name(self.file_name.to_string()));
-        form.into()
+    fn get_request_body(&self) -> Form {
+        reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.media_data.to_vec()).file_name(self.file_name.to_string()))
     }
 }
 
@@ -341,7 +361,7 @@ impl WechatRequest for WechatMpMaterialRequest {
         MpMediaMethod::AddMaterial(self.media_type.to_string()).get_method()
     }
 
-    fn get_request_body<T: Serialize>(&self) -> RequestBody<T> {
+    fn get_request_body(&self) -> Form {
         let mut form = reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.media_data.to_vec()).file_name(self.filename.to_string()));
         if let Some(video_title) = &self.video_title {
             form = form.text("title", video_title.to_string());
@@ -349,7 +369,7 @@ impl WechatRequest for WechatMpMaterialRequest {
         if let Some(video_introduction) = &self.video_introduction {
             form = form.text("introduction", video_introduction.to_string());
         }
-        form.into()
+        form
     }
 }
 
@@ -3,18 +3,18 @@ use std::vec;
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, WechatMpClient, LabradorResult, LabraError, get_timestamp};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, WechatMpClient, LabradorResult, LabraError, get_timestamp};
 use crate::wechat::mp::constants::MEMBER_CARD;
 use crate::wechat::mp::method::{MpMemeberCardMethod, WechatMpMethod};
 
 /// 会员卡相关.
 #[derive(Debug, Clone)]
-pub struct WechatMpMember<'a, T: SessionStore> {
+pub struct WechatMpMember<'a, T: AsyncSessionStore> {
     client: &'a WechatMpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMpMember<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMpMember<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMpClient<T>) -> WechatMpMember<T> {
@@ -37,7 +37,7 @@ impl<'a, T: SessionStore> WechatMpMember<'a, T> {
     /// </pre>
     pub async fn create_member_card(&self, req: WechatMpMemberCardCreateRequest) -> LabradorResult<WechatMpCardCreateResponse> {
         req.valid_check()?;
-        let v = self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::Create), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::Create), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpCardCreateResponse>(v)
     }
 
@@ -45,7 +45,7 @@ impl<'a, T: SessionStore> WechatMpMember<'a, T> {
     /// 会员卡激活接口
     /// </pre>
     pub async fn activate_member_card(&self, req: WechatMpMemberCardActivateRequest) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::Activate), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::Activate), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -56,7 +56,7 @@ impl<'a, T: SessionStore> WechatMpMember<'a, T> {
             "card_id": card_id,
             "code": code
         });
-        let v = self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::GetUserInfo), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::GetUserInfo), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpMemberCardUserInfoResponse>(v)
     }
 
@@ -68,7 +68,7 @@ impl<'a, T: SessionStore> WechatMpMember<'a, T> {
     /// 2.开发者可以传入is_notify_bonus控制特殊的积分对账变动不发送消息，余额变动同理。
     /// </pre>
     pub async fn update_user_member_card(&self, req: WechatMpMemberCardUpdateRequest) -> LabradorResult<WechatMpMemberCardUpdateResponse> {
-        let v = self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::UpdateUser), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::UpdateUser), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpMemberCardUpdateResponse>(v)
     }
 
@@ -76,7 +76,7 @@ impl<'a, T: SessionStore> WechatMpMember<'a, T> {
     /// 设置会员卡激活的字段（会员卡设置：wx_activate=true 时需要）.
     /// </pre>
     pub async fn set_activate_user_form(&self, req: WechatMpMemberCardActivateUserFormRequest) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::ActivateSetUser), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::ActivateSetUser), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -98,7 +98,7 @@ impl<'a, T: SessionStore> WechatMpMember<'a, T> {
            "card_id": card_id,
             "outer_str": out_str
         });
-        let v = self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::ActivateGetUrl), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::ActivateGetUrl), vec![], req).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let url = v["url"].as_str().unwrap_or_default();
         Ok(url.to_string())
@@ -108,7 +108,7 @@ impl<'a, T: SessionStore> WechatMpMember<'a, T> {
     /// 更新会员卡信息
     /// </pre>
     pub async fn update_card_info(&self, req: MemberCardUpdateRequest) -> LabradorResult<bool> {
-        let v = self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::Update), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::Update), vec![], req).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let send_check = v["send_check"].as_bool().unwrap_or_default();
         /// 此次更新是否需要提审，true为需要，false为不需要。
@@ -120,7 +120,7 @@ impl<'a, T: SessionStore> WechatMpMember<'a, T> {
     /// 开发者在URL上截取ticket后须先进行urldecode
     /// </pre>
     pub async fn get_activate_tempinfo(&self, activate_ticket: &str) -> LabradorResult<WechatMpMemberCardActivateTempInfoResponse> {
-        let v = self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::Update), vec![], json!({ "activate_ticket": activate_ticket }), RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::MemberCard(MpMemeberCardMethod::Update), vec![], json!({ "activate_ticket": activate_ticket })).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpMemberCardActivateTempInfoResponse>(v)
     }
 }
@@ -21,17 +21,17 @@
 //! 
 use serde::{Deserialize, Serialize};
 
-use crate::{session::SessionStore, request::{RequestType}, errors::LabraError, WechatCommonResponse, WechatMpClient, LabradorResult};
+use crate::{session::AsyncSessionStore, errors::{LabraError, PlatformError}, WechatCommonResponse, WechatMpClient, LabradorResult};
 use crate::wechat::mp::method::{MpMenuMethod, WechatMpMethod};
 
 
 #[derive(Debug, Clone)]
-pub struct WechatMpMenu<'a, T: SessionStore> {
+pub struct WechatMpMenu<'a, T: AsyncSessionStore> {
     client: &'a WechatMpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMpMenu<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMpMenu<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMpClient<T>) -> WechatMpMenu<T> {
@@ -47,7 +47,7 @@ impl<'a, T: SessionStore> WechatMpMenu<'a, T> {
     /// 详情请见：https://mp.weixin.qq.com/wiki?t=resource/res_main&id=mp1455782296&token=&lang=zh_CN
     /// </pre>
     pub async fn create_menu<D: Serialize>(&self, data: D) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatMpMethod::Menu(MpMenuMethod::Create), vec![], data, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::Menu(MpMenuMethod::Create), vec![], data).await?.json::<WechatCommonResponse>()
     }
 
 
@@ -76,12 +76,12 @@ impl<'a, T: SessionStore> WechatMpMenu<'a, T> {
     /// </pre>
     pub async fn get_current_selfmenu_info(&self) -> LabradorResult<SelfMenuInfoResponse> {
         
-        let v = self.client.get(WechatMpMethod::Menu(MpMenuMethod::GetCurrentMenuInfo), vec![], RequestType::Json).await?.json::<serde_json::Value>()?;
+        let v = self.client.get(WechatMpMethod::Menu(MpMenuMethod::GetCurrentMenuInfo), vec![]).await?.json::<serde_json::Value>()?;
         let mut result = WechatCommonResponse::from_value(v.clone())?;
         if result.is_success() {
             Ok(serde_json::from_value::<SelfMenuInfoResponse>(v)?)
         } else {
-            Err(LabraError::ClientError {errcode: result.errcode.to_owned().unwrap_or_default().to_string(), errmsg: result.errmsg.to_owned().unwrap_or_default()})
+            Err(LabraError::from(PlatformError::wechat(result.errcode.unwrap_or_default(), result.errmsg.to_owned().unwrap_or_default())))
         }
     }
 
@@ -89,12 +89,12 @@ impl<'a, T: SessionStore> WechatMpMenu<'a, T> {
     /// 详情[请见](https://mp.weixin.qq.com/wiki?t=resource/res_main&id=mp1421141014&token=&lang=zh_CN)
     /// 获取菜单信息
     pub async fn get_menu(&self) -> LabradorResult<MenuButtonResponse> {
-        let v = self.client.get(WechatMpMethod::Menu(MpMenuMethod::Get), vec![], RequestType::Json).await?.json::<serde_json::Value>()?;
+        let v = self.client.get(WechatMpMethod::Menu(MpMenuMethod::Get), vec![]).await?.json::<serde_json::Value>()?;
         let mut result = WechatCommonResponse::from_value(v.clone())?;
         if result.is_success() {
             Ok(serde_json::from_value::<MenuButtonResponse>(v)?)
         } else {
-            Err(LabraError::ClientError {errcode: result.errcode.to_owned().unwrap_or_default().to_string(), errmsg: result.errmsg.to_owned().unwrap_or_default()})
+            Err(LabraError::from(PlatformError::wechat(result.errcode.unwrap_or_default(), result.errmsg.to_owned().unwrap_or_default())))
         }
     }
 
@@ -103,12 +103,12 @@ impl<'a, T: SessionStore> WechatMpMenu<'a, T> {
     /// 详情[请见](https://mp.weixin.qq.com/wiki?t=resource/res_main&id=mp1455782296&token=&lang=zh_CN)
     /// </pre>
     pub async fn delete_menu(&self) -> LabradorResult<MenuButtonResponse> {
-        let v = self.client.get(WechatMpMethod::Menu(MpMenuMethod::Delete), vec![], RequestType::Json).await?.json::<serde_json::Value>()?;
+        let v = self.client.get(WechatMpMethod::Menu(MpMenuMethod::Delete), vec![]).await?.json::<serde_json::Value>()?;
         let mut result = WechatCommonResponse::from_value(v.clone())?;
         if result.is_success() {
             Ok(serde_json::from_value::<MenuButtonResponse>(v)?)
         } else {
-            Err(LabraError::ClientError {errcode: result.errcode.to_owned().unwrap_or_default().to_string(), errmsg: result.errmsg.to_owned().unwrap_or_default()})
+            Err(LabraError::from(PlatformError::wechat(result.errcode.unwrap_or_default(), result.errmsg.to_owned().unwrap_or_default())))
         }
     }
 }
@@ -1,17 +1,17 @@
 use serde::{Serialize, Deserialize};
 
-use crate::{session::SessionStore, request::{RequestType}, wechat::{mp::method::WechatMpMethod}, WechatCommonResponse, WechatMpClient, LabradorResult, LabraError};
+use crate::{session::AsyncSessionStore, wechat::{mp::method::WechatMpMethod}, WechatCommonResponse, WechatMpClient, LabradorResult, LabraError, PlatformError};
 use crate::wechat::mp::constants::{ACCESS_TOKEN, APPID, CODE, GRANT_TYPE, LANG, OPENID, REFRESH_TOKEN, SECRET, ZH_CN};
 use crate::wechat::mp::method::Oauth2Method;
 
 
 #[derive(Debug, Clone)]
-pub struct WechatMpOauth2<'a, T: SessionStore> {
+pub struct WechatMpOauth2<'a, T: AsyncSessionStore> {
     client: &'a WechatMpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMpOauth2<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMpOauth2<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMpClient<T>) -> WechatMpOauth2<T> {
@@ -32,12 +32,12 @@ impl<'a, T: SessionStore> WechatMpOauth2<'a, T> {
             (CODE.to_string(), code.to_string()),
             (APPID.to_string(), self.client.appid.to_string()),
             (SECRET.to_string(), self.client.secret.to_string()),
-        ], RequestType::Json).await?.json::<serde_json::Value>()?;
+        ]).await?.json::<serde_json::Value>()?;
         let mut result = WechatCommonResponse::from_value(v.clone())?;
         if result.is_success() {
             Ok(serde_json::from_value::<WechatMpOauth2AccessTokenResponse>(v)?)
         } else {
-            Err(LabraError::ClientError {errcode: result.errcode.to_owned().unwrap_or_default().to_string(), errmsg: result.errmsg.to_owned().unwrap_or_default()})
+            Err(LabraError::from(PlatformError::wechat(result.errcode.unwrap_or_default(), result.errmsg.to_owned().unwrap_or_default())))
         }
     }
 
@@ -50,12 +50,12 @@ impl<'a, T: SessionStore> WechatMpOauth2<'a, T> {
             (GRANT_TYPE.to_string(), REFRESH_TOKEN.to_string()),
             (REFRESH_TOKEN.to_string(), refresh_token.to_string()),
             (APPID.to_string(), self.client.appid.to_string()),
-        ], RequestType::Json).await?.json::<serde_json::Value>()?;
+        ]).await?.json::<serde_json::Value>()?;
         let mut result = WechatCommonResponse::from_value(v.to_owned())?;
         if result.is_success() {
             Ok(serde_json::from_value::<WechatMpOauth2AccessTokenResponse>(v)?)
         } else {
-            Err(LabraError::ClientError {errcode: result.errcode.to_owned().unwrap_or_default().to_string(), errmsg: result.errmsg.to_owned().unwrap_or_default()})
+            Err(LabraError::from(PlatformError::wechat(result.errcode.unwrap_or_default(), result.errmsg.to_owned().unwrap_or_default())))
         }
     }
 
@@ -67,12 +67,12 @@ impl<'a, T: SessionStore> WechatMpOauth2<'a, T> {
             (ACCESS_TOKEN.to_string(), access_token.to_string()),
             (OPENID.to_string(), openid.to_string()),
             (LANG.to_string(), ZH_CN.to_string()),
-        ], RequestType::Json).await?.json::<serde_json::Value>()?;
+        ]).await?.json::<serde_json::Value>()?;
         let mut result = WechatCommonResponse::from_value(v.to_owned())?;
         if result.is_success() {
             Ok(serde_json::from_value::<WechatMpOauth2UserInfo>(v)?)
         } else {
-            Err(LabraError::ClientError {errcode: result.errcode.to_owned().unwrap_or_default().to_string(), errmsg: result.errmsg.to_owned().unwrap_or_default()})
+            Err(LabraError::from(PlatformError::wechat(result.errcode.unwrap_or_default(), result.errmsg.to_owned().unwrap_or_default())))
         }
     }
 
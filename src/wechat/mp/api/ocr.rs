@@ -6,18 +6,18 @@ use std::vec;
 use serde::{Serialize, Deserialize};
 use serde_json::{Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, WechatMpClient, LabradorResult, WechatRequest, RequestBody};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, WechatMpClient, LabradorResult, WechatRequest, Form};
 use crate::wechat::mp::constants::IMG_URL;
 use crate::wechat::mp::method::{MpOcrMethod, WechatMpMethod};
 
 /// 微信连接WI-FI接口.
 #[derive(Debug, Clone)]
-pub struct WechatMpOcr<'a, T: SessionStore> {
+pub struct WechatMpOcr<'a, T: AsyncSessionStore> {
     client: &'a WechatMpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMpOcr<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMpClient<T>) -> WechatMpOcr<T> {
@@ -29,7 +29,7 @@ impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
     /// 身份证OCR识别接口
     pub async fn id_card(&self, img_url: &str) -> LabradorResult<WechatOcrIdCardResponse> {
         let img_url = urlencoding::encode(img_url).to_string();
-        let v = self.client.post(WechatMpMethod::Ocr(MpOcrMethod::IdCard), vec![(IMG_URL.to_string(), img_url)], Value::Null, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Ocr(MpOcrMethod::IdCard), vec![(IMG_URL.to_string(), img_url)], Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatOcrIdCardResponse>(v)
     }
 
@@ -45,7 +45,7 @@ impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
             filename: "".to_string(),
             data: content
         };
-        let v = self.client.execute::<WechatMpOcrRequest, String>(req).await?.json::<Value>()?;
+        let v = self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatOcrIdCardResponse>(v)
     }
 
@@ -53,7 +53,7 @@ impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
     /// 文件大小限制：小于2M
     pub async fn back_card(&self, img_url: &str) -> LabradorResult<WechatOcrBankCardResponse> {
         let img_url = urlencoding::encode(img_url).to_string();
-        let v = self.client.post(WechatMpMethod::Ocr(MpOcrMethod::BankCard), vec![(IMG_URL.to_string(), img_url)], Value::Null, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Ocr(MpOcrMethod::BankCard), vec![(IMG_URL.to_string(), img_url)], Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatOcrBankCardResponse>(v)
     }
 
@@ -70,7 +70,7 @@ impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
             filename: "".to_string(),
             data: content
         };
-        let v = self.client.execute::<WechatMpOcrRequest, String>(req).await?.json::<Value>()?;
+        let v = self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatOcrBankCardResponse>(v)
     }
 
@@ -78,7 +78,7 @@ impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
     /// 文件大小限制：小于2M
     pub async fn driving(&self, img_url: &str) -> LabradorResult<WechatOcrDrivingResponse> {
         let img_url = urlencoding::encode(img_url).to_string();
-        let v = self.client.post(WechatMpMethod::Ocr(MpOcrMethod::Driving), vec![(IMG_URL.to_string(), img_url)], Value::Null, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Ocr(MpOcrMethod::Driving), vec![(IMG_URL.to_string(), img_url)], Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatOcrDrivingResponse>(v)
     }
 
@@ -95,7 +95,7 @@ impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
             filename: "".to_string(),
             data: content
         };
-        let v = self.client.execute::<WechatMpOcrRequest, String>(req).await?.json::<Value>()?;
+        let v = self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatOcrDrivingResponse>(v)
     }
 
@@ -103,7 +103,7 @@ impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
     /// 文件大小限制：小于2M
     pub async fn driving_license(&self, img_url: &str) -> LabradorResult<WechatOcrDrivingLicenseResponse> {
         let img_url = urlencoding::encode(img_url).to_string();
-        let v = self.client.post(WechatMpMethod::Ocr(MpOcrMethod::DrivingLicense), vec![(IMG_URL.to_string(), img_url)], Value::Null, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Ocr(MpOcrMethod::DrivingLicense), vec![(IMG_URL.to_string(), img_url)], Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatOcrDrivingLicenseResponse>(v)
     }
 
@@ -120,7 +120,7 @@ impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
             filename: "".to_string(),
             data: content
         };
-        let v = self.client.execute::<WechatMpOcrRequest, String>(req).await?.json::<Value>()?;
+        let v = self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatOcrDrivingLicenseResponse>(v)
     }
 
@@ -128,7 +128,7 @@ impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
     /// 文件大小限制：小于2M
     pub async fn biz_license(&self, img_url: &str) -> LabradorResult<WechatOcrBizLicenseResponse> {
         let img_url = urlencoding::encode(img_url).to_string();
-        let v = self.client.post(WechatMpMethod::Ocr(MpOcrMethod::BizLicense), vec![(IMG_URL.to_string(), img_url)], Value::Null, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Ocr(MpOcrMethod::BizLicense), vec![(IMG_URL.to_string(), img_url)], Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatOcrBizLicenseResponse>(v)
     }
 
@@ -145,7 +145,7 @@ impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
             filename: "".to_string(),
             data: content
         };
-        let v = self.client.execute::<WechatMpOcrRequest, String>(req).await?.json::<Value>()?;
+        let v = self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatOcrBizLicenseResponse>(v)
     }
 
@@ -154,7 +154,7 @@ impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
     /// 适用于屏幕截图、印刷体照片等场景
     pub async fn comm(&self, img_url: &str) -> LabradorResult<WechatOcrCommResponse> {
         let img_url = urlencoding::encode(img_url).to_string();
-        let v = self.client.post(WechatMpMethod::Ocr(MpOcrMethod::Comm), vec![(IMG_URL.to_string(), img_url)], Value::Null, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Ocr(MpOcrMethod::Comm), vec![(IMG_URL.to_string(), img_url)], Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatOcrCommResponse>(v)
     }
 
@@ -172,7 +172,7 @@ impl<'a, T: SessionStore> WechatMpOcr<'a, T> {
             filename: "".to_string(),
             data: content
         };
-        let v = self.client.execute::<WechatMpOcrRequest, String>(req).await?.json::<Value>()?;
+        let v = self.client.execute(req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatOcrCommResponse>(v)
     }
 
@@ -361,8 +361,7 @@ impl WechatRequest for WechatMpOcrRequest {
         }
     }
 
-    fn get_request_body<T: Serialize>(&self) -> RequestBody<T> {
-        let form = reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.data.to_vec()).file_name(self.filename.to_string()));
-        form.into()
+    fn get_request_body(&self) -> Form {
+        reqwest::multipart::Form::new().part("media", reqwest::multipart::Part::stream(self.data.to_vec()).file_name(self.filename.to_string()))
     }
 }
\ No newline at end of file
@@ -1,16 +1,16 @@
-use crate::{session::SessionStore, errors::LabraError, request::{RequestType}, WechatCommonResponse, WechatMpClient, LabradorResult};
+use crate::{session::AsyncSessionStore, errors::LabraError, WechatCommonResponse, WechatMpClient, LabradorResult};
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 use crate::wechat::mp::constants::{QR_LIMIT_SCENE, QR_LIMIT_STR_SCENE, QR_SCENE, QR_STR_SCENE};
 use crate::wechat::mp::method::{MpQrCodeMethod, WechatMpMethod};
 
 #[derive(Debug, Clone)]
-pub struct WechatMpQRCode<'a, T: SessionStore> {
+pub struct WechatMpQRCode<'a, T: AsyncSessionStore> {
     client: &'a WechatMpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMpQRCode<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMpQRCode<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMpClient<T>) -> WechatMpQRCode<T> {
@@ -73,7 +73,7 @@ impl<'a, T: SessionStore> WechatMpQRCode<'a, T> {
         if let Some(expire_seconds) = expire_seconds {
             req["expire_seconds"] = expire_seconds.into();
         }
-        let v = self.client.post(WechatMpMethod::QrCode(MpQrCodeMethod::Create), vec![], req, RequestType::Json).await?.json::<serde_json::Value>()?;
+        let v = self.client.post(WechatMpMethod::QrCode(MpQrCodeMethod::Create), vec![], req).await?.json::<serde_json::Value>()?;
         WechatCommonResponse::parse::<QRCodeTicket>(v)
     }
 
@@ -1,17 +1,17 @@
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, WechatMpClient, LabradorResult};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, WechatMpClient, LabradorResult};
 use crate::wechat::mp::method::{MpSubscribeMessageMethod, WechatMpMethod};
 
 /// 订阅消息服务接口
 #[derive(Debug, Clone)]
-pub struct WechatMpSubscribeMessage<'a, T: SessionStore> {
+pub struct WechatMpSubscribeMessage<'a, T: AsyncSessionStore> {
     client: &'a WechatMpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMpSubscribeMessage<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMpSubscribeMessage<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMpClient<T>) -> WechatMpSubscribeMessage<T> {
@@ -34,7 +34,7 @@ impl<'a, T: SessionStore> WechatMpSubscribeMessage<'a, T> {
     /// 详情请见: https://mp.weixin.qq.com/wiki?t=resource/res_main&id=mp1500374289_66bvB
     /// </pre>
     pub async fn send_subscribe_message_once(&self, msg: &MpSendSubscribeOnceRequest) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::SubscribeMessageOnce), vec![], msg, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::SubscribeMessageOnce), vec![], msg).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -50,7 +50,7 @@ impl<'a, T: SessionStore> WechatMpSubscribeMessage<'a, T> {
         let params = vec![( "ids".to_string(), ids),
           ("start".to_string(), start),
           ("limit".to_string(), limit)];
-        let v = self.client.get(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::GetPubTemplateTitles), params, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::GetPubTemplateTitles), params).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpPubTemplateTitleListResponse>(v)
     }
 
@@ -61,7 +61,7 @@ impl<'a, T: SessionStore> WechatMpSubscribeMessage<'a, T> {
     /// 接口url格式: GET https://api.weixin.qq.com/wxaapi/newtmpl/getpubtemplatekeywords?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn get_pub_template_keywords_byid(&self, ids: &str) -> LabradorResult<Vec<WechatMpPubTemplateKeywordResponse>> {
-        let v = self.client.get(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::GetPubTemplateKeywords), vec![( "tid".to_string(), ids.to_string())], RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::GetPubTemplateKeywords), vec![( "tid".to_string(), ids.to_string())]).await?.json::<Value>()?;
         WechatCommonResponse::parse_with_key::<Vec<WechatMpPubTemplateKeywordResponse>>(v, "data")
     }
 
@@ -77,7 +77,7 @@ impl<'a, T: SessionStore> WechatMpSubscribeMessage<'a, T> {
             "kidList": keywords,
             "sceneDesc": scene_desc,
         });
-        let v = self.client.post(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::AddTemplate), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::AddTemplate), vec![], req).await?.json::<Value>()?;
         let resp= WechatCommonResponse::parse::<Value>(v)?;
         let pri_tmpl_id = resp["priTmplId"].as_str().to_owned().unwrap_or_default().to_string();
         Ok(pri_tmpl_id)
@@ -90,7 +90,7 @@ impl<'a, T: SessionStore> WechatMpSubscribeMessage<'a, T> {
     /// 接口url格式: GET https://api.weixin.qq.com/wxaapi/newtmpl/gettemplate?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn get_template_list(&self) -> LabradorResult<Vec<WechatMpTemplateInfoResponse>> {
-        let v = self.client.get(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::GetTemplate), vec![], RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::GetTemplate), vec![]).await?.json::<Value>()?;
         WechatCommonResponse::parse_with_key::<Vec<WechatMpTemplateInfoResponse>>(v, "data")
     }
 
@@ -101,7 +101,7 @@ impl<'a, T: SessionStore> WechatMpSubscribeMessage<'a, T> {
     /// 接口url格式: POST https://api.weixin.qq.com/wxaapi/newtmpl/deltemplate?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn delete_template(&self, template_id: &str) -> LabradorResult<bool> {
-        let _ = self.client.post(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::DeleteTemplate), vec![], json!({ "priTmplId": template_id }), RequestType::Json).await?.json::<WechatCommonResponse>()?;
+        let _ = self.client.post(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::DeleteTemplate), vec![], json!({ "priTmplId": template_id })).await?.json::<WechatCommonResponse>()?;
         Ok(true)
     }
 
@@ -111,7 +111,7 @@ impl<'a, T: SessionStore> WechatMpSubscribeMessage<'a, T> {
     /// GET https://api.weixin.qq.com/wxaapi/newtmpl/getcategory?access_token=ACCESS_TOKEN
     /// </pre>
     pub async fn get_category(&self, template_id: &str) -> LabradorResult<Vec<WechatMpCategoryDataResponse>> {
-        let v = self.client.get(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::GetCategory), vec![], RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.get(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::GetCategory), vec![]).await?.json::<Value>()?;
         WechatCommonResponse::parse_with_key::<Vec<WechatMpCategoryDataResponse>>(v, "data")
     }
 
@@ -120,7 +120,7 @@ impl<'a, T: SessionStore> WechatMpSubscribeMessage<'a, T> {
     /// https://developers.weixin.qq.com/doc/offiaccount/Subscription_Messages/api.html
     /// </pre>
     pub async fn send_subscribe_message(&self, msg: &MpSendSubscribeMessageRequest) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::SendSubscribeMessage), vec![], msg, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::SubscribeMessage(MpSubscribeMessageMethod::SendSubscribeMessage), vec![], msg).await?.json::<WechatCommonResponse>()
     }
 
 }
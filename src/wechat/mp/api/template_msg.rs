@@ -1,17 +1,17 @@
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, WechatMpClient, LabradorResult};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, WechatMpClient, LabradorResult};
 use crate::wechat::mp::method::{MpTemplateMessageMethod, WechatMpMethod};
 
 
 #[derive(Debug, Clone)]
-pub struct WechatMpTemplateMessage<'a, T: SessionStore> {
+pub struct WechatMpTemplateMessage<'a, T: AsyncSessionStore> {
     client: &'a WechatMpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMpTemplateMessage<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMpTemplateMessage<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMpClient<T>) -> WechatMpTemplateMessage<T> {
@@ -30,7 +30,7 @@ impl<'a, T: SessionStore> WechatMpTemplateMessage<'a, T> {
         self.client.post(WechatMpMethod::TemplateMessage(MpTemplateMessageMethod::SetIndustry), vec![], json!({
             "industry_id1": industry_id1,
             "industry_id2": industry_id2,
-        }), RequestType::Json).await?.json::<WechatCommonResponse>()
+        })).await?.json::<WechatCommonResponse>()
     }
 
     /// 获取设置的行业信息
@@ -38,7 +38,7 @@ impl<'a, T: SessionStore> WechatMpTemplateMessage<'a, T> {
     ///
     /// [地址](https://developers.weixin.qq.com/doc/offiaccount/Message_Management/Template_Message_Interface.html)
     pub async fn get_industry(&self) -> LabradorResult<IndustryResponse> {
-        let response = self.client.post(WechatMpMethod::TemplateMessage(MpTemplateMessageMethod::GetIndustry), vec![], Value::Null, RequestType::Json).await?.json::<Value>()?;
+        let response = self.client.post(WechatMpMethod::TemplateMessage(MpTemplateMessageMethod::GetIndustry), vec![], Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse::<IndustryResponse>(response)
     }
 
@@ -46,7 +46,7 @@ impl<'a, T: SessionStore> WechatMpTemplateMessage<'a, T> {
     ///
     /// [地址](https://developers.weixin.qq.com/doc/offiaccount/Message_Management/Template_Message_Interface.html)
     pub async fn send_mp_message(&self, data: TemplateMessage) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatMpMethod::TemplateMessage(MpTemplateMessageMethod::SendTemplate), vec![], data, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::TemplateMessage(MpTemplateMessageMethod::SendTemplate), vec![], data).await?.json::<WechatCommonResponse>()
     }
 
     /// 获得模板ID
@@ -55,7 +55,7 @@ impl<'a, T: SessionStore> WechatMpTemplateMessage<'a, T> {
     ///
     /// [地址](https://developers.weixin.qq.com/doc/offiaccount/Message_Management/Template_Message_Interface.html)
     pub async fn get_template_id(&self, template_id_short: &str) -> LabradorResult<String> {
-        let response = self.client.post(WechatMpMethod::TemplateMessage(MpTemplateMessageMethod::GetTemplateId), vec![], json!({ "template_id_short": template_id_short }), RequestType::Json).await?.json::<Value>()?;
+        let response = self.client.post(WechatMpMethod::TemplateMessage(MpTemplateMessageMethod::GetTemplateId), vec![], json!({ "template_id_short": template_id_short })).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(response)?;
         let template_id = v["template_id"].as_str().unwrap_or_default();
         Ok(template_id.to_string())
@@ -66,7 +66,7 @@ impl<'a, T: SessionStore> WechatMpTemplateMessage<'a, T> {
     ///
     /// [地址](https://developers.weixin.qq.com/doc/offiaccount/Message_Management/Template_Message_Interface.html)
     pub async fn get_template_list(&self) -> LabradorResult<Vec<TemplateMessageInfo>> {
-        let response = self.client.post(WechatMpMethod::TemplateMessage(MpTemplateMessageMethod::GetTemplateList), vec![], Value::Null, RequestType::Json).await?.json::<Value>()?;
+        let response = self.client.post(WechatMpMethod::TemplateMessage(MpTemplateMessageMethod::GetTemplateList), vec![], Value::Null).await?.json::<Value>()?;
         WechatCommonResponse::parse_with_key::<Vec<TemplateMessageInfo>>(response, "template_list")
     }
 
@@ -76,7 +76,7 @@ impl<'a, T: SessionStore> WechatMpTemplateMessage<'a, T> {
     ///
     /// [地址](https://developers.weixin.qq.com/doc/offiaccount/Message_Management/Template_Message_Interface.html)
     pub async fn delete_template(&self, template_id: &str) -> LabradorResult<WechatCommonResponse> {
-        self.client.post(WechatMpMethod::TemplateMessage(MpTemplateMessageMethod::DeleteTemplate), vec![], json!({ "template_id": template_id }), RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::TemplateMessage(MpTemplateMessageMethod::DeleteTemplate), vec![], json!({ "template_id": template_id })).await?.json::<WechatCommonResponse>()
     }
 }
 
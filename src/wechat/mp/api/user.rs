@@ -2,18 +2,19 @@ use std::collections::HashMap;
 use serde_json::{json, Value};
 
 use serde::{Serialize, Deserialize};
+use futures_util::Stream;
 
-use crate::{session::SessionStore, errors::LabraError, wechat::{cryptos::WechatCrypto}, request::RequestType, WechatCommonResponse, WechatMpClient, LabradorResult};
+use crate::{session::AsyncSessionStore, errors::{LabraError, PlatformError}, wechat::{cryptos::WechatCrypto}, WechatCommonResponse, WechatMpClient, LabradorResult, Page, paginate};
 use crate::wechat::mp::method::{MpUserMethod, WechatMpMethod};
 
 
 #[derive(Debug, Clone)]
-pub struct WechatMpUser<'a, T: SessionStore> {
+pub struct WechatMpUser<'a, T: AsyncSessionStore> {
     client: &'a WechatMpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMpUser<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMpUser<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMpClient<T>) -> WechatMpUser<T> {
@@ -39,12 +40,12 @@ impl<'a, T: SessionStore> WechatMpUser<'a, T> {
     /// 接口地址：https://api.weixin.qq.com/cgi-bin/user/info?access_token=ACCESS_TOKEN&openid=OPENID&lang=zh_CN
     /// </pre>
     pub async fn get_with_lang(&mut self, openid: &str, lang: &str) -> LabradorResult<WechatUser> {
-        let res = self.client.get(WechatMpMethod::User(MpUserMethod::Info), vec![("openid".to_string(), openid.to_string()), ("lang".to_string(), lang.to_string())], RequestType::Json).await?.json::<serde_json::Value>()?;
+        let res = self.client.get(WechatMpMethod::User(MpUserMethod::Info), vec![("openid".to_string(), openid.to_string()), ("lang".to_string(), lang.to_string())]).await?.json::<serde_json::Value>()?;
         let result = WechatCommonResponse::from_value(res.clone())?;
         if result.is_success() {
             Ok(self.json_to_user(&res))
         } else {
-            Err(LabraError::ClientError {errcode: result.errcode.to_owned().unwrap_or_default().to_string(), errmsg: result.errmsg.to_owned().unwrap_or_default()})
+            Err(LabraError::from(PlatformError::wechat(result.errcode.unwrap_or_default(), result.errmsg.to_owned().unwrap_or_default())))
         }
     }
 
@@ -112,7 +113,7 @@ impl<'a, T: SessionStore> WechatMpUser<'a, T> {
             "openid": openid.to_owned(),
             "remark": remark.to_owned()
         });
-        self.client.post(WechatMpMethod::User(MpUserMethod::UpdateRemark), vec![], data, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::User(MpUserMethod::UpdateRemark), vec![], data).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -129,7 +130,7 @@ impl<'a, T: SessionStore> WechatMpUser<'a, T> {
             Some(openid) => vec![("next_openid".to_string(), openid.to_string())],
             None => vec![],
         };
-        let res = self.client.get(WechatMpMethod::User(MpUserMethod::Get), params, RequestType::Json, ).await?.json::<serde_json::Value>()?;
+        let res = self.client.get(WechatMpMethod::User(MpUserMethod::Get), params).await?.json::<serde_json::Value>()?;
         let mut result = WechatCommonResponse::from_value(res.clone())?;
         if result.is_success() {
             let total = &res["total"];
@@ -161,23 +162,35 @@ impl<'a, T: SessionStore> WechatMpUser<'a, T> {
                 next_openid: next_id.to_owned(),
             })
         } else {
-            Err(LabraError::ClientError {errcode: result.errcode.to_owned().unwrap_or_default().to_string(), errmsg: result.errmsg.to_owned().unwrap_or_default()})
+            Err(LabraError::from(PlatformError::wechat(result.errcode.unwrap_or_default(), result.errmsg.to_owned().unwrap_or_default())))
         }
     }
 
+    /// 逐个获取所有关注者的OpenID，自动按next_openid翻页
+    pub fn get_followers_stream(&self) -> impl Stream<Item = LabradorResult<String>> + 'a {
+        let client = self.client;
+        paginate(None, move |next_openid: Option<String>| async move {
+            let followers = WechatMpUser::new(client).get_followers(next_openid.as_deref()).await?;
+            // 没有更多关注者时next_openid为空或本次拉取数量为0
+            let count = followers.count;
+            let next = Some(followers.next_openid).filter(|v| !v.is_empty() && count > 0);
+            Ok(Page::new(followers.openids, next))
+        })
+    }
+
     /// 获取分组编号
     pub async fn get_group_id(&mut self, openid: &str) -> LabradorResult<u64> {
         let data = json!({
             "openid": openid.to_owned()
         });
-        let res = self.client.post(WechatMpMethod::User(MpUserMethod::GetGroupId), vec![], data, RequestType::Json).await?.json::<serde_json::Value>()?;
+        let res = self.client.post(WechatMpMethod::User(MpUserMethod::GetGroupId), vec![], data).await?.json::<serde_json::Value>()?;
         let group_id = &res["groupid"];
         let group_id = group_id.as_u64().unwrap_or_default();
         let mut result = WechatCommonResponse::from_value(res.clone())?;
         if result.is_success() {
             Ok(group_id)
         } else {
-            Err(LabraError::ClientError {errcode: result.errcode.to_owned().unwrap_or_default().to_string(), errmsg: result.errmsg.to_owned().unwrap_or_default()})
+            Err(LabraError::from(PlatformError::wechat(result.errcode.unwrap_or_default(), result.errmsg.to_owned().unwrap_or_default())))
         }
     }
 
@@ -245,7 +258,7 @@ impl<'a, T: SessionStore> WechatMpUser<'a, T> {
         let data = json!({
             "user_list": user_list.to_vec()
         });
-        let res = self.client.post(WechatMpMethod::User(MpUserMethod::GetBatch), vec![], data, RequestType::Json).await?.json::<serde_json::Value>()?;
+        let res = self.client.post(WechatMpMethod::User(MpUserMethod::GetBatch), vec![], data).await?.json::<serde_json::Value>()?;
         let mut result = WechatCommonResponse::from_value(res.clone())?;
         if result.is_success() {
             let info_list = &res["user_info_list"];
@@ -257,7 +270,7 @@ impl<'a, T: SessionStore> WechatMpUser<'a, T> {
             }
             Ok(users)
         } else {
-            Err(LabraError::ClientError {errcode: result.errcode.to_owned().unwrap_or_default().to_string(), errmsg: result.errmsg.to_owned().unwrap_or_default()})
+            Err(LabraError::from(PlatformError::wechat(result.errcode.unwrap_or_default(), result.errmsg.to_owned().unwrap_or_default())))
         }
     }
 
@@ -307,3 +320,27 @@ pub struct Followers {
     pub openids: Vec<String>,
     pub next_openid: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use crate::{LabradorResult, MockResponse, MockTransport, SimpleStorage, WechatMpClient};
+
+    #[tokio::test]
+    async fn test_get_followers_stream() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, r#"{"access_token":"token","expires_in":7200}"#))
+            .push_response(MockResponse::new(200, r#"{"total":3,"count":2,"data":{"openid":["o1","o2"]},"next_openid":"o2"}"#))
+            .push_response(MockResponse::new(200, r#"{"total":3,"count":1,"data":{"openid":["o3"]},"next_openid":"o3"}"#))
+            .push_response(MockResponse::new(200, r#"{"total":3,"count":0,"next_openid":""}"#));
+        let client = WechatMpClient::<SimpleStorage>::new("appid", "secret").transport(transport.clone());
+        let openids = client.user().get_followers_stream().collect::<Vec<LabradorResult<String>>>().await;
+        assert_eq!(vec!["o1", "o2", "o3"], openids.into_iter().map(|v| v.unwrap()).collect::<Vec<_>>());
+        // 拉取数量为0时结束
+        let requests = transport.requests();
+        assert_eq!(4, requests.len());
+        assert!(!requests[1].url.as_str().contains("next_openid"));
+        assert!(requests[2].url.as_str().contains("next_openid=o2"));
+        assert!(requests[3].url.as_str().contains("next_openid=o3"));
+    }
+}
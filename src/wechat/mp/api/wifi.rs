@@ -3,17 +3,17 @@ use std::vec;
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{session::SessionStore, request::{RequestType}, WechatCommonResponse, WechatMpClient, LabradorResult};
+use crate::{session::AsyncSessionStore, WechatCommonResponse, WechatMpClient, LabradorResult};
 use crate::wechat::mp::method::{MpWifiMethod, WechatMpMethod};
 
 /// 微信连接WI-FI接口.
 #[derive(Debug, Clone)]
-pub struct WechatMpWifi<'a, T: SessionStore> {
+pub struct WechatMpWifi<'a, T: AsyncSessionStore> {
     client: &'a WechatMpClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WechatMpWifi<'a, T> {
+impl<'a, T: AsyncSessionStore> WechatMpWifi<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatMpClient<T>) -> WechatMpWifi<T> {
@@ -35,7 +35,7 @@ impl<'a, T: SessionStore> WechatMpWifi<'a, T> {
            "pageindex": page_index,
             "pagesize": page_size
         });
-        self.client.post(WechatMpMethod::Wifi(MpWifiMethod::ShopList), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::Wifi(MpWifiMethod::ShopList), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
     /// <pre>
@@ -50,7 +50,7 @@ impl<'a, T: SessionStore> WechatMpWifi<'a, T> {
         let req = json!({
            "shop_id": shop_id,
         });
-        let v = self.client.post(WechatMpMethod::Wifi(MpWifiMethod::ShopList), vec![], req, RequestType::Json).await?.json::<Value>()?;
+        let v = self.client.post(WechatMpMethod::Wifi(MpWifiMethod::ShopList), vec![], req).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpWifiShopDataResponse>(v)
 
     }
@@ -69,7 +69,7 @@ impl<'a, T: SessionStore> WechatMpWifi<'a, T> {
             "ssid": ssid,
             "password": password,
         });
-        self.client.post(WechatMpMethod::Wifi(MpWifiMethod::UpdateShop), vec![], req, RequestType::Json).await?.json::<WechatCommonResponse>()
+        self.client.post(WechatMpMethod::Wifi(MpWifiMethod::UpdateShop), vec![], req).await?.json::<WechatCommonResponse>()
     }
 
 }
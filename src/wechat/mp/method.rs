@@ -406,6 +406,27 @@ impl RequestMethod for WechatMpMethod {
             WechatMpMethod::Card(v) => v.get_method(),
         }
     }
+
+    fn is_idempotent(&self) -> bool {
+        match self {
+            WechatMpMethod::GetCallbackIp | WechatMpMethod::FetchShortenUrl | WechatMpMethod::Ocr(_) => true,
+            WechatMpMethod::CustomService(v) => matches!(v, MpCustomServiceMethod::AccountList | MpCustomServiceMethod::AccountOnlineList),
+            WechatMpMethod::Media(v) => matches!(v, MpMediaMethod::GetMaterial | MpMediaMethod::GetMaterialCount | MpMediaMethod::GetMaterialList
+                | MpMediaMethod::GetMedia | MpMediaMethod::GetMediaJssdk),
+            WechatMpMethod::User(v) => matches!(v, MpUserMethod::Info | MpUserMethod::Get | MpUserMethod::GetGroupId | MpUserMethod::GetBatch),
+            WechatMpMethod::Menu(v) => matches!(v, MpMenuMethod::GetCurrentMenuInfo | MpMenuMethod::Get),
+            WechatMpMethod::QrCode(v) => matches!(v, MpQrCodeMethod::GetWxaCodeUnlimit),
+            WechatMpMethod::TemplateMessage(v) => matches!(v, MpTemplateMessageMethod::GetIndustry | MpTemplateMessageMethod::GetTemplateList),
+            WechatMpMethod::Wifi(v) => matches!(v, MpWifiMethod::ShopList | MpWifiMethod::GetShop),
+            WechatMpMethod::MemberCard(v) => matches!(v, MpMemeberCardMethod::GetUserInfo | MpMemeberCardMethod::GetActivateTempInfo),
+            WechatMpMethod::Card(v) => matches!(v, MpCardMethod::Get | MpCardMethod::CodeGet | MpCardMethod::CodeDecrypt | MpCardMethod::GetDepositCount
+                | MpCardMethod::CheckCode | MpCardMethod::GetHtml | MpCardMethod::GetUserCardList | MpCardMethod::GetApplyProtocol
+                | MpCardMethod::GetSubmerchant | MpCardMethod::BatchGetSubmerchant),
+            WechatMpMethod::SubscribeMessage(v) => matches!(v, MpSubscribeMessageMethod::GetPubTemplateKeywords | MpSubscribeMessageMethod::GetPubTemplateTitles
+                | MpSubscribeMessageMethod::GetTemplate | MpSubscribeMessageMethod::GetCategory),
+            _ => false,
+        }
+    }
 }
 
 #[allow(unused)]
@@ -1,4 +1,8 @@
-use crate::{session::SessionStore, client::APIClient, request::{Method, RequestType, LabraResponse, LabraRequest, RequestMethod}, WechatCrypto, util::current_timestamp, LabradorResult, SimpleStorage, WechatRequest, WechatCommonResponse, JsapiSignature, get_timestamp, get_nonce_str};
+use std::fmt;
+use crate::wechat::{cached_token, check_stream, is_invalid_token};
+use crate::{session::AsyncSessionStore, client::APIClient, request::{LabraResponse, LabraRequest, RequestMethod}, LabraStreamResponse, Form, file_part, WechatCrypto, util::current_timestamp, LabradorResult, SimpleStorage, WechatRequest, WechatCommonResponse, JsapiSignature, get_timestamp, get_nonce_str, LabraHttpClient, LabraHttpConfig, HttpTransport, Middleware, LabraLogConfig, RetryPolicy, RateLimiter, LockOptions, refresh_with_lock, telemetry, TenantClient};
+use crate::config::{Proxy, Secret};
+use std::sync::Arc;
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 use crate::wechat::mp::method::WechatMpMethod;
@@ -19,7 +23,7 @@ use crate::wechat::mp::method::WechatMpMethod::QrConnectUrl;
 
 #[allow(unused)]
 #[derive(Debug, Clone)]
-pub struct WechatMpClient<T: SessionStore> {
+pub struct WechatMpClient<T: AsyncSessionStore> {
     appid: String,
     secret: String,
     token: Option<String>,
@@ -28,6 +32,57 @@ pub struct WechatMpClient<T: SessionStore> {
     client: APIClient<T>,
 }
 
+/// 公众号配置
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct WechatMpConfig {
+    pub appid: String,
+    pub secret: String,
+    pub token: Option<String>,
+    pub aes_key: Option<String>,
+    /// 接口地址，用于测试环境或本地模拟服务
+    pub api_path: Option<String>,
+    /// 代理地址，如：http://127.0.0.1:8080
+    pub proxy: Option<String>,
+}
+
+impl fmt::Debug for WechatMpConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WechatMpConfig")
+            .field("appid", &self.appid)
+            .field("secret", &Secret(&self.secret))
+            .field("token", &Secret::option(&self.token))
+            .field("aes_key", &Secret::option(&self.aes_key))
+            .field("api_path", &self.api_path)
+            .field("proxy", &Proxy::option(&self.proxy))
+            .finish()
+    }
+}
+
+impl WechatMpConfig {
+    pub fn new<S: Into<String>>(appid: S, secret: S) -> Self {
+        Self { appid: appid.into(), secret: secret.into(), ..Default::default() }
+    }
+}
+
+impl<T: AsyncSessionStore> TenantClient<T> for WechatMpClient<T> {
+    type Config = WechatMpConfig;
+
+    fn tenant_id(config: &Self::Config) -> String {
+        config.appid.to_string()
+    }
+
+    fn build(config: &Self::Config, session: T, transport: Arc<dyn HttpTransport>) -> LabradorResult<Self> {
+        // 配置了代理时在共用传输层的HTTP配置上生成带代理的客户端
+        let mut config = config.clone();
+        let proxy = config.proxy.take();
+        let client = Self::from_config(&config, session)?.transport(transport);
+        match proxy {
+            Some(proxy) => client.proxy(&proxy),
+            None => Ok(client),
+        }
+    }
+}
+
 
 #[allow(unused)]
 #[derive(Serialize, Deserialize)]
@@ -67,7 +122,7 @@ impl ToString for TicketType {
 }
 
 #[allow(unused)]
-impl<T: SessionStore> WechatMpClient<T> {
+impl<T: AsyncSessionStore> WechatMpClient<T> {
 
     fn from_client(client: APIClient<T>) -> WechatMpClient<T> {
         WechatMpClient {
@@ -82,16 +137,34 @@ impl<T: SessionStore> WechatMpClient<T> {
 
     /// get the wechat client
     pub fn new<S: Into<String>>(appid: S, secret: S) -> WechatMpClient<SimpleStorage> {
-        let client = APIClient::<SimpleStorage>::from_session(appid.into(), secret.into(), "https://api.weixin.qq.com", SimpleStorage::new());
+        let client = APIClient::<SimpleStorage>::from_session(appid.into(), secret.into(), "https://api.weixin.qq.com", SimpleStorage::new()).platform("wechat_mp");
         WechatMpClient::from_client(client)
     }
 
     /// get the wechat client
     pub fn from_session<S: Into<String>>(appid: S, secret: S, session: T) -> WechatMpClient<T> {
-        let client = APIClient::from_session(appid.into(), secret.into(), "https://api.weixin.qq.com", session);
+        let client = APIClient::from_session(appid.into(), secret.into(), "https://api.weixin.qq.com", session).platform("wechat_mp");
         Self::from_client(client)
     }
 
+    /// 根据配置创建客户端
+    pub fn from_config(config: &WechatMpConfig, session: T) -> LabradorResult<WechatMpClient<T>> {
+        let mut client = Self::from_session(config.appid.as_str(), config.secret.as_str(), session);
+        if let Some(token) = config.token.as_ref() {
+            client = client.token(token);
+        }
+        if let Some(aes_key) = config.aes_key.as_ref() {
+            client = client.aes_key(aes_key);
+        }
+        if let Some(api_path) = config.api_path.as_ref() {
+            client = client.api_path(api_path.as_str());
+        }
+        if let Some(proxy) = config.proxy.as_ref() {
+            client = client.proxy(proxy)?;
+        }
+        Ok(client)
+    }
+
     pub fn aes_key(mut self, aes_key: &str) -> Self {
         self.aes_key = aes_key.to_string().into();
         self
@@ -107,32 +180,112 @@ impl<T: SessionStore> WechatMpClient<T> {
         self
     }
 
+    /// 设置HTTP客户端，多个客户端可共用同一个连接池
+    pub fn http_client(mut self, http_client: LabraHttpClient) -> Self {
+        self.client = self.client.http_client(http_client);
+        self
+    }
+
+    /// 根据配置生成独立的HTTP客户端，可设置代理、超时等
+    pub fn http_config(mut self, config: LabraHttpConfig) -> LabradorResult<Self> {
+        self.client = self.client.http_config(config)?;
+        Ok(self)
+    }
+
+    /// 设置代理，如：http://127.0.0.1:8080，开启`socks`特性后支持socks5
+    pub fn proxy(mut self, proxy: &str) -> LabradorResult<Self> {
+        self.client = self.client.proxy(proxy)?;
+        Ok(self)
+    }
+
+    /// 设置接口地址，可指向测试环境或本地的模拟服务
+    pub fn api_path<S: Into<String>>(mut self, api_path: S) -> Self {
+        self.client = self.client.api_path(api_path);
+        self
+    }
+
+    /// 设置传输层，测试时可替换为`MockTransport`
+    pub fn transport<H: HttpTransport + 'static>(mut self, transport: H) -> Self {
+        self.client = self.client.transport(transport);
+        self
+    }
+
+    /// 添加中间件
+    pub fn middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.client = self.client.middleware(middleware);
+        self
+    }
+
+    /// 设置请求日志配置
+    pub fn log_config(mut self, log_config: LabraLogConfig) -> Self {
+        self.client = self.client.log_config(log_config);
+        self
+    }
+
+    /// 设置access_token刷新锁配置
+    pub fn lock_options(mut self, lock_options: LockOptions) -> Self {
+        self.client = self.client.lock_options(lock_options);
+        self
+    }
+
+    /// 设置重试策略，GET请求和用户、素材、卡券等查询接口任何失败都可以重试；发送消息、创建菜单等写接口只会在连接阶段失败时重试，避免重复发送
+    pub fn retry_policy<P: RetryPolicy + 'static>(mut self, retry_policy: P) -> Self {
+        self.client = self.client.retry_policy(retry_policy);
+        self
+    }
+
+    /// 设置限流器，按接口方法限流并统计每日调用次数
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.client = self.client.rate_limiter(rate_limiter);
+        self
+    }
+
+    /// 获取access_token，`force_refresh`为true时丢弃缓存中的凭证重新获取
     #[inline]
     pub async fn access_token(&self, force_refresh: bool) -> LabradorResult<String> {
-        let session = self.client.session();
-        let token_key = format!("{}_access_token", self.appid);
-        let expires_key = format!("{}_expires_at", self.appid);
-        let token: String = session.get(&token_key, Some("".to_owned()))?.unwrap_or_default();
-        let timestamp = current_timestamp();
-        let expires_at: i64 = session.get(&expires_key, Some(timestamp))?.unwrap_or_default();
-        if expires_at <= timestamp || force_refresh {
-            let mut req = LabraRequest::<String>::new().url(WechatMpMethod::AccessToken.get_method()).params(vec![
-                (GRANT_TYPE.to_string(), CLIENT_CREDENTIAL.to_string()),
-                (APPID.to_string(), self.client.app_key.to_string()),
-                (SECRET.to_string(), self.client.secret.to_string()),
-            ]).method(Method::Get).req_type(RequestType::Json);
-            let v = self.client.request(req).await?.json::<Value>()?;
-            let res = WechatCommonResponse::parse::<AccessTokenResponse>(v)?;
-            let token = res.access_token;
-            let expires_in = res.expires_in;
-            // 预留200秒的时间
-            let expires_at = current_timestamp() + expires_in - 200;
-            session.set(&token_key, token.to_owned(), Some(expires_in as usize));
-            session.set(&expires_key, expires_at, Some(expires_in as usize));
-            Ok(token)
+        let rejected = if force_refresh {
+            let (token_key, expires_key) = self.access_token_keys();
+            cached_token(self.client.session(), &token_key, &expires_key).await?
         } else {
-            Ok(token)
-        }
+            None
+        };
+        self.refresh_access_token(rejected.as_deref()).await
+    }
+
+    /// 接口返回access_token失效时获取新的凭证，`rejected`为失效的access_token
+    ///
+    /// 缓存中的凭证已被其他请求刷新时直接使用，多个请求同时遇到失效时只刷新一次。
+    pub async fn access_token_replacing(&self, rejected: &str) -> LabradorResult<String> {
+        self.refresh_access_token(Some(rejected)).await
+    }
+
+    fn access_token_keys(&self) -> (String, String) {
+        (format!("{}_access_token", self.appid), format!("{}_expires_at", self.appid))
+    }
+
+    async fn refresh_access_token(&self, rejected: Option<&str>) -> LabradorResult<String> {
+        let session = self.client.session();
+        let (token_key, expires_key) = &self.access_token_keys();
+        let lock_key = format!("{}_lock", token_key);
+        refresh_with_lock(session, &lock_key, &self.client.lock_options, rejected,
+            || cached_token(session, token_key, expires_key),
+            || async move {
+                let mut req = LabraRequest::get(WechatMpMethod::AccessToken.get_method()).params(vec![
+                    (GRANT_TYPE.to_string(), CLIENT_CREDENTIAL.to_string()),
+                    (APPID.to_string(), self.client.app_key.to_string()),
+                    (SECRET.to_string(), self.client.secret.to_string()),
+                ]);
+                let v = self.client.request(req).await?.json::<Value>()?;
+                let res = WechatCommonResponse::parse::<AccessTokenResponse>(v)?;
+                let token = res.access_token;
+                let expires_in = res.expires_in;
+                // 预留200秒的时间
+                let expires_at = current_timestamp() + expires_in - 200;
+                session.set(token_key, token.to_owned(), Some(expires_in as usize)).await?;
+                session.set(expires_key, expires_at, Some(expires_in as usize)).await?;
+                telemetry::record_token_refresh(self.client.platform, "access_token");
+                Ok(token)
+            }).await
     }
 
     /// <pre>
@@ -141,7 +294,7 @@ impl<T: SessionStore> WechatMpClient<T> {
     /// </pre>
     #[inline]
     pub async fn gen_shorten(&self, long_data: &str, expire_seconds: u64) -> LabradorResult<String> {
-        let res = self.post(WechatMpMethod::GenShortenUrl, vec![], json!({"long_data": long_data, "expire_seconds": expire_seconds}), RequestType::Json).await?.json::<Value>()?;
+        let res = self.post(WechatMpMethod::GenShortenUrl, vec![], json!({"long_data": long_data, "expire_seconds": expire_seconds})).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(res)?;
         let short_key = v["short_key"].as_str().unwrap_or_default();
         Ok(short_key.to_string())
@@ -153,7 +306,7 @@ impl<T: SessionStore> WechatMpClient<T> {
     /// </pre>
     #[inline]
     pub async fn fetch_shorten(&self, short_key: &str) -> LabradorResult<WechatMpShortKeyResponse> {
-        let res = self.post(WechatMpMethod::GenShortenUrl, vec![], json!({"short_key": short_key}), RequestType::Json).await?.json::<Value>()?;
+        let res = self.post(WechatMpMethod::GenShortenUrl, vec![], json!({"short_key": short_key})).await?.json::<Value>()?;
         WechatCommonResponse::parse::<WechatMpShortKeyResponse>(res)
     }
 
@@ -176,18 +329,19 @@ impl<T: SessionStore> WechatMpClient<T> {
         let session = self.client.session();
         let key = format!("{}_{}_ticket", self.appid, &ticket_type.to_string());
         let expires_key = format!("{}_{}_ticket_expires_at", self.appid, &ticket_type.to_string());
-        let ticket: String = session.get(&key, Some("".to_owned()))?.unwrap_or_default();
+        let ticket: String = session.get(&key, Some("".to_owned())).await?.unwrap_or_default();
         let timestamp = current_timestamp();
-        let expires_at: i64 = session.get(&expires_key, Some(timestamp))?.unwrap_or_default();
+        let expires_at: i64 = session.get(&expires_key, Some(timestamp)).await?.unwrap_or_default();
         if expires_at <= timestamp || force_refresh {
-            let res = self.get(WechatMpMethod::GetTicket, vec![(TICKET_TYPE.to_string(), ticket_type.to_string())], RequestType::Json).await?.json::<Value>()?;
+            let res = self.get(WechatMpMethod::GetTicket, vec![(TICKET_TYPE.to_string(), ticket_type.to_string())]).await?.json::<Value>()?;
             let v = WechatCommonResponse::parse::<Value>(res)?;
             let ticket = v["ticket"].as_str().unwrap_or_default();
             let expires_in = v["expires_in"].as_i64().unwrap_or_default();
             // 预留200秒的时间
             let expires_at = current_timestamp() + expires_in - 200;
-            session.set(&key, ticket.to_string(), Some(expires_in as usize));
-            session.set(&expires_key, expires_at, Some(expires_in as usize));
+            session.set(&key, ticket.to_string(), Some(expires_in as usize)).await?;
+            session.set(&expires_key, expires_at, Some(expires_in as usize)).await?;
+            telemetry::record_token_refresh(self.client.platform, &(ticket_type.to_string() + "_ticket"));
             Ok(ticket.to_string())
         } else {
             Ok(ticket)
@@ -232,7 +386,7 @@ impl<T: SessionStore> WechatMpClient<T> {
     /// [文档](http://mp.weixin.qq.com/wiki/0/2ad4b6bfd29f30f71d39616c2a0fcedc.html)
     /// </pre>
     pub async fn get_callback_ip(&self, force_refresh: bool) -> LabradorResult<Vec<String>> {
-        let v = self.get(WechatMpMethod::GetCallbackIp, vec![], RequestType::Json).await?.json::<Value>()?;
+        let v = self.get(WechatMpMethod::GetCallbackIp, vec![]).await?.json::<Value>()?;
         let v = WechatCommonResponse::parse::<Value>(v)?;
         let ip_list = v["ip_list"].as_array().unwrap_or(&vec![]).iter().map(|v| v.as_str().unwrap_or_default().to_string()).collect::<Vec<String>>();
         Ok(ip_list)
@@ -263,39 +417,118 @@ impl<T: SessionStore> WechatMpClient<T> {
     }
 
     /// 发送POST请求
-    async fn post<D: Serialize>(&self, method: WechatMpMethod, mut querys: Vec<(String, String)>, data: D, request_type: RequestType) -> LabradorResult<LabraResponse> {
+    async fn post<D: Serialize>(&self, method: WechatMpMethod, querys: Vec<(String, String)>, data: D) -> LabradorResult<LabraResponse> {
+        let send = |access_token: String| {
+            let mut querys = querys.clone();
+            if !access_token.is_empty() && method.need_token() {
+                querys.push((ACCESS_TOKEN.to_string(), access_token));
+            }
+            self.client.post(method.clone(), querys, &data)
+        };
         let access_token = self.access_token(false).await?;
-        if !access_token.is_empty() && method.need_token() {
-            querys.push((ACCESS_TOKEN.to_string(), access_token));
+        let response = send(access_token.to_owned()).await?;
+        if method.need_token() && is_invalid_token(&response) {
+            // access_token已失效，强制刷新后重试一次
+            return send(self.access_token_replacing(&access_token).await?).await;
         }
-        self.client.post(method, querys, data, request_type).await
+        Ok(response)
     }
 
     ///<pre>
     /// Service没有实现某个API的时候，可以用这个，
     /// 比 get 和 post 方法更灵活，可以自己构造用来处理不同的参数和不同的返回类型。
     /// </pre>
-    async fn execute<D: WechatRequest, B: Serialize>(&self, request: D) -> LabradorResult<LabraResponse> {
-        let mut querys = request.get_query_params();
-        if request.is_need_token() {
-            let access_token = self.access_token(false).await?;
-            if !access_token.is_empty() {
+    async fn execute<D: WechatRequest>(&self, request: D) -> LabradorResult<LabraResponse> {
+        let send = |access_token: Option<String>| {
+            let mut querys = request.get_query_params();
+            if let Some(access_token) = access_token.filter(|v| !v.is_empty()) {
                 querys.insert(ACCESS_TOKEN.to_string(), access_token);
             }
+            let params = querys.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<(String, String)>>();
+            let req = LabraRequest::post_multipart(request.get_api_method_name(), request.get_request_body())
+                .params(params);
+            self.client.request(req)
+        };
+        if !request.is_need_token() {
+            return send(None).await;
+        }
+        let access_token = self.access_token(false).await?;
+        let response = send(Some(access_token.to_owned())).await?;
+        if is_invalid_token(&response) {
+            // access_token已失效，强制刷新后重试一次
+            return send(Some(self.access_token_replacing(&access_token).await?)).await;
+        }
+        Ok(response)
+    }
+
+    /// 发送POST请求，流式读取响应，用于下载文件
+    async fn post_stream<D: Serialize>(&self, method: WechatMpMethod, querys: Vec<(String, String)>, data: D) -> LabradorResult<LabraStreamResponse> {
+        let send = |access_token: String| {
+            let mut querys = querys.clone();
+            if !access_token.is_empty() && method.need_token() {
+                querys.push((ACCESS_TOKEN.to_string(), access_token));
+            }
+            self.client.post_stream(method.clone(), querys, &data)
+        };
+        let access_token = self.access_token(false).await?;
+        let response = send(access_token.to_owned()).await?;
+        if method.need_token() && response.is_json() {
+            let response = response.into_response().await?;
+            if is_invalid_token(&response) {
+                // access_token已失效，强制刷新后重试一次
+                return check_stream(send(self.access_token_replacing(&access_token).await?).await?).await;
+            }
+            return check_stream(response.into()).await;
         }
-        let params = querys.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<(String, String)>>();
-        let mut req = LabraRequest::<B>::new().url(request.get_api_method_name())
-            .params(params).method(request.get_request_method()).req_type(request.get_request_type()).body(request.get_request_body::<B>());
-        self.client.request(req).await
+        Ok(response)
+    }
+
+    /// 上传multipart表单，表单无法重复发送，access_token失效时不会重试
+    async fn post_multipart(&self, method: WechatMpMethod, mut querys: Vec<(String, String)>, form: Form) -> LabradorResult<LabraResponse> {
+        if method.need_token() {
+            querys.push((ACCESS_TOKEN.to_string(), self.access_token(false).await?));
+        }
+        self.client.post_multipart(method, querys, form).await
+    }
+
+    /// 上传本地文件，access_token失效时重新打开文件后重试一次
+    async fn post_file(&self, method: WechatMpMethod, querys: Vec<(String, String)>, name: &str, file_path: &str) -> LabradorResult<LabraResponse> {
+        let send = |access_token: String| {
+            let mut querys = querys.clone();
+            if !access_token.is_empty() && method.need_token() {
+                querys.push((ACCESS_TOKEN.to_string(), access_token));
+            }
+            let method = method.clone();
+            async move {
+                let form = Form::new().part(name.to_string(), file_part(file_path).await?);
+                self.client.post_multipart(method, querys, form).await
+            }
+        };
+        let access_token = self.access_token(false).await?;
+        let response = send(access_token.to_owned()).await?;
+        if method.need_token() && is_invalid_token(&response) {
+            // access_token已失效，重新打开文件后重试一次
+            return send(self.access_token_replacing(&access_token).await?).await;
+        }
+        Ok(response)
     }
 
     /// 发送GET请求
-    async fn get(&self, method: WechatMpMethod, mut params: Vec<(String, String)>, request_type: RequestType) -> LabradorResult<LabraResponse> {
+    async fn get(&self, method: WechatMpMethod, params: Vec<(String, String)>) -> LabradorResult<LabraResponse> {
+        let send = |access_token: String| {
+            let mut params = params.clone();
+            if !access_token.is_empty() && method.need_token() {
+                params.push((ACCESS_TOKEN.to_string(), access_token));
+            }
+            self.client.get(method.clone(), params)
+        };
         let access_token = self.access_token(false).await?;
-        if !access_token.is_empty() && method.need_token() {
-            params.push((ACCESS_TOKEN.to_string(), access_token));
+        let response = send(access_token.to_owned()).await?;
+        if method.need_token() && is_invalid_token(&response) {
+            // access_token已失效，强制刷新后重试一次
+            return send(self.access_token_replacing(&access_token).await?).await;
         }
-        self.client.get(method, params, request_type).await
+        Ok(response)
     }
 
     /// 用户相关服务
@@ -349,3 +582,107 @@ impl<T: SessionStore> WechatMpClient<T> {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use async_trait::async_trait;
+    use crate::{HttpRequest, HttpTransport, LabraHttpClient, LabraHttpConfig, LabradorRegistry, LabraResponse, MockResponse, MockTransport, SimpleStorage, TransportError};
+    use super::{WechatMpClient, WechatMpConfig, WechatMpMethod};
+
+    /// 第一次获取的凭证为token1，之后为token2，接口只接受token2
+    #[derive(Debug, Default)]
+    struct TokenTransport {
+        token_requests: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl HttpTransport for TokenTransport {
+        async fn execute(&self, request: HttpRequest) -> Result<LabraResponse, TransportError> {
+            let body = if request.url.path() == "/cgi-bin/token" {
+                let token = if self.token_requests.fetch_add(1, Ordering::SeqCst) == 0 { "token1" } else { "token2" };
+                format!(r#"{{"access_token":"{}","expires_in":7200}}"#, token)
+            } else {
+                // 保证两个请求都使用token1发出
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                if request.url.as_str().contains("access_token=token2") {
+                    r#"{"ip_list":["127.0.0.1"]}"#.to_string()
+                } else {
+                    r#"{"errcode":40001,"errmsg":"invalid credential"}"#.to_string()
+                }
+            };
+            Ok(LabraResponse::new(request.url, reqwest::StatusCode::OK, None, Default::default(), body.into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_token_retry() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, r#"{"access_token":"token1","expires_in":7200}"#))
+            .push_response(MockResponse::new(200, r#"{"errcode":40001,"errmsg":"invalid credential"}"#))
+            .push_response(MockResponse::new(200, r#"{"access_token":"token2","expires_in":7200}"#))
+            .push_response(MockResponse::new(200, r#"{"ip_list":["127.0.0.1"]}"#));
+        let client = WechatMpClient::<SimpleStorage>::new("appid", "secret").transport(transport.clone());
+        let res = client.get(WechatMpMethod::GetCallbackIp, vec![]).await.unwrap();
+        assert_eq!(r#"{"ip_list":["127.0.0.1"]}"#, res.text().unwrap());
+        let requests = transport.requests();
+        assert_eq!(4, requests.len());
+        assert!(requests[1].url.as_str().contains("access_token=token1"));
+        assert!(requests[3].url.as_str().contains("access_token=token2"));
+    }
+
+    #[tokio::test]
+    async fn test_upload_file_retry() {
+        let path = std::env::temp_dir().join(format!("labrador_mp_upload_{}.jpg", std::process::id()));
+        std::fs::write(&path, b"image").unwrap();
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, r#"{"access_token":"token1","expires_in":7200}"#))
+            .push_response(MockResponse::new(200, r#"{"errcode":40001,"errmsg":"invalid credential"}"#))
+            .push_response(MockResponse::new(200, r#"{"access_token":"token2","expires_in":7200}"#))
+            .push_response(MockResponse::new(200, r#"{"type":"image","media_id":"media1","created_at":1}"#));
+        let client = WechatMpClient::<SimpleStorage>::new("appid", "secret").transport(transport.clone());
+        // 凭证失效后重新打开文件再上传一次
+        let res = client.media().upload_media_with_file("image", path.to_str().unwrap()).await;
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(Some("media1".to_string()), res.unwrap().media_id);
+        let requests = transport.requests();
+        assert_eq!(4, requests.len());
+        assert!(requests[3].url.as_str().contains("access_token=token2"));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_invalid_token() {
+        let transport = Arc::new(TokenTransport::default());
+        let client = WechatMpClient::<SimpleStorage>::new("appid", "secret").transport(transport.clone());
+        assert_eq!("token1", client.access_token(false).await.unwrap());
+        // 两个请求同时遇到凭证失效，只刷新一次
+        let (a, b) = tokio::join!(
+            client.get(WechatMpMethod::GetCallbackIp, vec![]),
+            client.get(WechatMpMethod::GetCallbackIp, vec![]),
+        );
+        assert_eq!(r#"{"ip_list":["127.0.0.1"]}"#, a.unwrap().text().unwrap());
+        assert_eq!(r#"{"ip_list":["127.0.0.1"]}"#, b.unwrap().text().unwrap());
+        assert_eq!(2, transport.token_requests.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_registry_proxy() {
+        let http_client = LabraHttpClient::new(LabraHttpConfig::new().timeout(Duration::from_secs(3))).unwrap();
+        let registry = LabradorRegistry::<WechatMpClient<SimpleStorage>, SimpleStorage>::new(SimpleStorage::new()).transport(http_client);
+        let mut config = WechatMpConfig::new("wx1", "secret");
+        registry.add_tenant(config.clone());
+        config.appid = "wx2".to_string();
+        config.proxy = Some("http://127.0.0.1:8080".to_string());
+        registry.add_tenant(config);
+        // 没有代理的租户使用共用的传输层，配置了代理的租户在共用配置上加代理
+        let shared = registry.get("wx1").await.unwrap();
+        let shared = shared.client.transport.http_config().unwrap();
+        assert_eq!(None, shared.proxy);
+        let proxied = registry.get("wx2").await.unwrap();
+        let proxied = proxied.client.transport.http_config().unwrap();
+        assert_eq!(Some("http://127.0.0.1:8080"), proxied.proxy.as_deref());
+        assert_eq!(Some(Duration::from_secs(3)), proxied.timeout);
+    }
+}
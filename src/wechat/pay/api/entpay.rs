@@ -0,0 +1,278 @@
+use crate::{LabradorResult, LabraStreamResponse, AsyncSessionStore, WechatPayClient, WechatQueryTransferBatchRequestV3, WechatQueryTransferBatchResponseV3, WechatQueryTransferDetailResponseV3, WechatQueryTransferRequest, WechatQueryTransferResponse, WechatTransferBatchRequestV3, WechatTransferBatchResponseV3, WechatTransferBillReceiptResponseV3, WechatTransferDetailReceiptRequestV3, WechatTransferDetailReceiptResponseV3, WechatTransferRequest, WechatTransferResponse};
+use crate::util::get_nonce_str;
+use crate::wechat::pay::method::{EntPayMethod, WechatPayMethod};
+
+/// 企业付款
+#[derive(Debug, Clone)]
+pub struct EntPay<'a, T: AsyncSessionStore> {
+    client: &'a WechatPayClient<T>,
+}
+
+#[allow(unused)]
+impl<'a, T: AsyncSessionStore> EntPay<'a, T> {
+
+    #[inline]
+    pub fn new(client: &WechatPayClient<T>) -> EntPay<'_, T> {
+        EntPay {
+            client,
+        }
+    }
+
+    ///
+    /// # 企业付款到零钱
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/api/tools/mch_pay.php?chapter=14_2)
+    /// <pre>
+    /// 用于企业向微信用户个人付款，目前支持向指定微信用户的openid付款。
+    /// 请求需要双向证书。
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/mmpaymkttransfers/promotion/transfers
+    /// </pre>
+    pub async fn transfer(&self, mut params: WechatTransferRequest) -> LabradorResult<WechatTransferResponse> {
+        if params.mchid.is_empty() {
+            params.mchid = self.client.mch_id.to_owned().unwrap_or_default();
+        }
+        if params.mch_appid.is_none() {
+            params.mch_appid = self.client.appid.to_owned().into();
+        }
+        if params.nonce_str.is_none() {
+            params.nonce_str = get_nonce_str().into();
+        }
+        params.get_sign(&self.client.sign_key_v2().await?);
+        let response = self.client.post(WechatPayMethod::EntPay(EntPayMethod::Transfer), params.parse_xml()).await?;
+        WechatTransferResponse::parse_xml(response.text()?).map_err(|err| err.with_response(&response))
+    }
+
+    ///
+    /// # 查询企业付款到零钱
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/api/tools/mch_pay.php?chapter=14_3)
+    /// <pre>
+    /// 用于商户的企业付款操作进行结果查询，返回付款操作详细结果。
+    /// 查询企业付款API只支持查询30天内的订单，30天之前的订单请登录商户平台查询。
+    /// 请求需要双向证书。
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/mmpaymkttransfers/gettransferinfo
+    /// </pre>
+    pub async fn query_transfer(&self, mut params: WechatQueryTransferRequest) -> LabradorResult<WechatQueryTransferResponse> {
+        if params.mch_id.is_empty() {
+            params.mch_id = self.client.mch_id.to_owned().unwrap_or_default();
+        }
+        if params.appid.is_none() {
+            params.appid = self.client.appid.to_owned().into();
+        }
+        if params.nonce_str.is_none() {
+            params.nonce_str = get_nonce_str().into();
+        }
+        params.get_sign(&self.client.sign_key_v2().await?);
+        let response = self.client.post(WechatPayMethod::EntPay(EntPayMethod::QueryTransfer), params.parse_xml()).await?;
+        WechatQueryTransferResponse::parse_xml(response.text()?).map_err(|err| err.with_response(&response))
+    }
+
+    ///
+    /// # 发起商家转账 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter4_3_1.shtml)
+    /// <pre>
+    /// 商户可以通过该接口同时向多个用户微信零钱进行转账操作。
+    /// 传入的收款用户姓名会使用平台证书加密，并在请求头中带上平台证书序列号。
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/v3/transfer/batches
+    /// </pre>
+    pub async fn transfer_batches_v3(&self, mut params: WechatTransferBatchRequestV3) -> LabradorResult<WechatTransferBatchResponseV3> {
+        if params.appid.is_none() {
+            params.appid = self.client.appid.to_owned().into();
+        }
+        let mut serial_no = None;
+        if params.transfer_detail_list.iter().any(|v| v.user_name.is_some()) {
+            let cert = self.client.encryption_cert().await?;
+            for detail in params.transfer_detail_list.iter_mut() {
+                if let Some(user_name) = detail.user_name.as_ref() {
+                    detail.user_name = WechatPayClient::<T>::encrypt_with_cert(user_name, &cert)?.into();
+                }
+            }
+            serial_no = cert.serial_no.into();
+        }
+        self.client.post_v3_with_serial(None, WechatPayMethod::EntPay(EntPayMethod::TransferBatchesV3), vec![], params, serial_no)
+            .await?.json::<WechatTransferBatchResponseV3>()
+    }
+
+    ///
+    /// # 通过微信批次单号查询批次单 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter4_3_2.shtml)
+    pub async fn query_transfer_batch_by_id_v3(&self, batch_id: &str, params: &WechatQueryTransferBatchRequestV3) -> LabradorResult<WechatQueryTransferBatchResponseV3> {
+        let querys = params.get_querys();
+        let querys = querys.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect::<Vec<_>>();
+        self.client.get_v3(WechatPayMethod::EntPay(EntPayMethod::QueryBatchByIdV3(batch_id.to_string())), querys)
+            .await?.json::<WechatQueryTransferBatchResponseV3>()
+    }
+
+    ///
+    /// # 通过商家批次单号查询批次单 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter4_3_5.shtml)
+    pub async fn query_transfer_batch_by_out_no_v3(&self, out_batch_no: &str, params: &WechatQueryTransferBatchRequestV3) -> LabradorResult<WechatQueryTransferBatchResponseV3> {
+        let querys = params.get_querys();
+        let querys = querys.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect::<Vec<_>>();
+        self.client.get_v3(WechatPayMethod::EntPay(EntPayMethod::QueryBatchByOutNoV3(out_batch_no.to_string())), querys)
+            .await?.json::<WechatQueryTransferBatchResponseV3>()
+    }
+
+    ///
+    /// # 通过微信明细单号查询明细单 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter4_3_3.shtml)
+    /// <pre>
+    /// 返回的收款用户姓名会使用商户私钥解密。
+    /// </pre>
+    pub async fn query_transfer_detail_by_id_v3(&self, batch_id: &str, detail_id: &str) -> LabradorResult<WechatQueryTransferDetailResponseV3> {
+        let method = EntPayMethod::QueryDetailByIdV3((batch_id.to_string(), detail_id.to_string()));
+        let detail = self.client.get_v3(WechatPayMethod::EntPay(method), vec![])
+            .await?.json::<WechatQueryTransferDetailResponseV3>()?;
+        self.decrypt_detail(detail)
+    }
+
+    ///
+    /// # 通过商家明细单号查询明细单 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter4_3_6.shtml)
+    /// <pre>
+    /// 返回的收款用户姓名会使用商户私钥解密。
+    /// </pre>
+    pub async fn query_transfer_detail_by_out_no_v3(&self, out_batch_no: &str, out_detail_no: &str) -> LabradorResult<WechatQueryTransferDetailResponseV3> {
+        let method = EntPayMethod::QueryDetailByOutNoV3((out_batch_no.to_string(), out_detail_no.to_string()));
+        let detail = self.client.get_v3(WechatPayMethod::EntPay(method), vec![])
+            .await?.json::<WechatQueryTransferDetailResponseV3>()?;
+        self.decrypt_detail(detail)
+    }
+
+    ///
+    /// # 转账账单电子回单申请受理 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter4_3_7.shtml)
+    pub async fn apply_bill_receipt_v3(&self, out_batch_no: &str) -> LabradorResult<WechatTransferBillReceiptResponseV3> {
+        let data = serde_json::json!({ "out_batch_no": out_batch_no });
+        self.client.post_v3(None, WechatPayMethod::EntPay(EntPayMethod::BillReceiptV3), vec![], data)
+            .await?.json::<WechatTransferBillReceiptResponseV3>()
+    }
+
+    ///
+    /// # 查询转账账单电子回单 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter4_3_8.shtml)
+    pub async fn query_bill_receipt_v3(&self, out_batch_no: &str) -> LabradorResult<WechatTransferBillReceiptResponseV3> {
+        self.client.get_v3(WechatPayMethod::EntPay(EntPayMethod::QueryBillReceiptV3(out_batch_no.to_string())), vec![])
+            .await?.json::<WechatTransferBillReceiptResponseV3>()
+    }
+
+    ///
+    /// # 转账明细电子回单受理 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter4_3_9.shtml)
+    pub async fn apply_detail_receipt_v3(&self, params: &WechatTransferDetailReceiptRequestV3) -> LabradorResult<WechatTransferDetailReceiptResponseV3> {
+        self.client.post_v3(None, WechatPayMethod::EntPay(EntPayMethod::DetailReceiptV3), vec![], params)
+            .await?.json::<WechatTransferDetailReceiptResponseV3>()
+    }
+
+    ///
+    /// # 查询转账明细电子回单受理结果 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter4_3_10.shtml)
+    pub async fn query_detail_receipt_v3(&self, params: &WechatTransferDetailReceiptRequestV3) -> LabradorResult<WechatTransferDetailReceiptResponseV3> {
+        self.client.get_v3(WechatPayMethod::EntPay(EntPayMethod::DetailReceiptV3), params.get_querys())
+            .await?.json::<WechatTransferDetailReceiptResponseV3>()
+    }
+
+    /// # 下载电子回单 - V3
+    /// 下载地址为查询电子回单返回的`download_url`，可通过`save_to`直接写入文件
+    pub async fn download_receipt_v3(&self, download_url: &str) -> LabradorResult<LabraStreamResponse> {
+        self.client.download_v3(download_url).await
+    }
+
+    /// 解密明细单中的收款用户姓名
+    fn decrypt_detail(&self, mut detail: WechatQueryTransferDetailResponseV3) -> LabradorResult<WechatQueryTransferDetailResponseV3> {
+        if let Some(user_name) = detail.user_name.as_ref() {
+            detail.user_name = self.client.decrypt_sensitive(user_name)?.into();
+        }
+        Ok(detail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LabraCertificate, MockResponse, MockTransport, SimpleStorage, TransferDetailInput, WechatPayClient, WechatTransferBatchRequestV3, WechatTransferRequest};
+    use crate::wechat::pay::tests::{signed_response, PLATFORM_CERT, PRIVATE_KEY};
+
+    #[tokio::test]
+    async fn test_transfer() {
+        let transport = MockTransport::new();
+        transport.push_response(MockResponse::new(200, "<xml><return_code>SUCCESS</return_code><return_msg></return_msg><mch_appid>appid</mch_appid><mchid>10000100</mchid><nonce_str>nonce</nonce_str><result_code>SUCCESS</result_code><partner_trade_no>T001</partner_trade_no><payment_no>1000018301201505190181489473</payment_no><payment_time>2024-01-01 12:00:00</payment_time></xml>"))
+            .push_response(MockResponse::new(200, "<xml><return_code>SUCCESS</return_code><return_msg></return_msg><result_code>FAIL</result_code><err_code>NOTENOUGH</err_code><err_code_des>余额不足</err_code_des></xml>"));
+        let client = WechatPayClient::<SimpleStorage>::new("appid", "secret")
+            .mch_id("10000100".to_string())
+            .key("key".to_string())
+            .transport(transport.clone());
+        let params = WechatTransferRequest {
+            partner_trade_no: "T001".to_string(),
+            openid: "openid".to_string(),
+            check_name: "NO_CHECK".to_string(),
+            amount: 100,
+            desc: "佣金".to_string(),
+            ..Default::default()
+        };
+        let response = client.entpay().transfer(params.clone()).await.unwrap();
+        assert_eq!("1000018301201505190181489473", response.payment_no);
+        let err = client.entpay().transfer(params).await.unwrap_err();
+        assert_eq!("NOTENOUGH", err.platform_error().unwrap().sub_code.as_deref().unwrap_or_default());
+        let requests = transport.requests();
+        assert_eq!("https://api.mch.weixin.qq.com/mmpaymkttransfers/promotion/transfers", requests[0].url.as_str());
+        assert!(requests[0].body_text().contains("<mch_appid>appid</mch_appid>"));
+        assert!(requests[0].body_text().contains("<mchid>10000100</mchid>"));
+        assert!(requests[0].body_text().contains("<desc><![CDATA[佣金]]></desc>"));
+    }
+
+    #[tokio::test]
+    async fn test_transfer_batches_v3() {
+        let transport = MockTransport::new();
+        let cert = LabraCertificate::from_pem(PLATFORM_CERT.as_bytes().to_vec()).unwrap();
+        let serial_no = cert.serial_no.to_string();
+        let client = WechatPayClient::<SimpleStorage>::new("appid", "secret")
+            .mch_id("10000100".to_string())
+            .serial_no("serial_no".to_string())
+            .private_key(PRIVATE_KEY.to_string())
+            .platform_cert(cert)
+            .transport(transport.clone());
+        let (user_name, _) = client.encrypt_sensitive("张三").await.unwrap();
+        transport.push_response(signed_response(200, r#"{"out_batch_no":"B001","batch_id":"1030000071100999991182020050700019480001","create_time":"2024-01-01T12:00:00+08:00"}"#))
+            .push_response(signed_response(200, format!(r#"{{"mchid":"10000100","out_batch_no":"B001","batch_id":"1030000071100999991182020050700019480001","appid":"appid","out_detail_no":"D001","detail_id":"1040000071100999991182020050700019500100","detail_status":"SUCCESS","transfer_amount":100,"transfer_remark":"佣金","openid":"openid","user_name":"{}","initiate_time":"2024-01-01T12:00:00+08:00","update_time":"2024-01-01T12:00:01+08:00"}}"#, user_name)));
+        let params = WechatTransferBatchRequestV3 {
+            out_batch_no: "B001".to_string(),
+            batch_name: "佣金".to_string(),
+            batch_remark: "佣金".to_string(),
+            total_amount: 200,
+            total_num: 2,
+            transfer_detail_list: vec![TransferDetailInput {
+                out_detail_no: "D001".to_string(),
+                transfer_amount: 100,
+                transfer_remark: "佣金".to_string(),
+                openid: "openid".to_string(),
+                user_name: "张三".to_string().into(),
+            }, TransferDetailInput {
+                out_detail_no: "D002".to_string(),
+                transfer_amount: 100,
+                transfer_remark: "佣金".to_string(),
+                openid: "openid2".to_string(),
+                user_name: "李四".to_string().into(),
+            }],
+            ..Default::default()
+        };
+        let response = client.entpay().transfer_batches_v3(params).await.unwrap();
+        assert_eq!("1030000071100999991182020050700019480001", response.batch_id);
+        let detail = client.entpay().query_transfer_detail_by_out_no_v3("B001", "D001").await.unwrap();
+        assert_eq!(Some("张三".to_string()), detail.user_name);
+
+        let requests = transport.requests();
+        assert_eq!(serial_no, requests[0].headers["Wechatpay-Serial"].to_str().unwrap());
+        let body = serde_json::from_str::<serde_json::Value>(&requests[0].body_text()).unwrap();
+        assert_eq!("appid", body["appid"]);
+        let user_name = body["transfer_detail_list"][0]["user_name"].as_str().unwrap();
+        assert_ne!("张三", user_name);
+        assert_eq!("张三", client.decrypt_sensitive(user_name).unwrap());
+        // 所有明细使用同一张平台证书加密
+        assert_eq!("李四", client.decrypt_sensitive(body["transfer_detail_list"][1]["user_name"].as_str().unwrap()).unwrap());
+        assert_eq!("https://api.mch.weixin.qq.com/v3/transfer/batches/out-batch-no/B001/details/out-detail-no/D001", requests[1].url.as_str());
+    }
+}
@@ -1,3 +1,7 @@
 mod wxpay;
+mod entpay;
+mod profit_sharing;
 
-pub use self::wxpay::*;
\ No newline at end of file
+pub use self::wxpay::*;
+pub use self::entpay::*;
+pub use self::profit_sharing::*;
@@ -0,0 +1,291 @@
+use crate::{DecryptProfitSharingNotifyResult, LabradorResult, LabraError, OriginNotifyResponse, AsyncSessionStore, WechatPayClient, WechatProfitSharingAmountResponseV3, WechatProfitSharingNotifyResponseV3, WechatProfitSharingOrderRequestV3, WechatProfitSharingOrderResponseV3, WechatProfitSharingReceiverRequestV3, WechatProfitSharingReceiverResponseV3, WechatProfitSharingRemoveReceiverRequestV3, WechatProfitSharingReturnRequestV3, WechatProfitSharingReturnResponseV3, WechatProfitSharingUnfreezeRequestV3, WechatQueryProfitSharingOrderRequestV3, WechatQueryProfitSharingReturnRequestV3};
+use crate::wechat::cryptos::{SignatureHeader, WechatCryptoV3};
+use crate::wechat::pay::method::{ProfitSharingMethod, WechatPayMethod};
+
+/// 分账
+///
+/// 直连商户与服务商模式共用，服务商模式下在请求中传入`sub_mchid`即可。
+#[derive(Debug, Clone)]
+pub struct ProfitSharing<'a, T: AsyncSessionStore> {
+    client: &'a WechatPayClient<T>,
+}
+
+#[allow(unused)]
+impl<'a, T: AsyncSessionStore> ProfitSharing<'a, T> {
+
+    #[inline]
+    pub fn new(client: &WechatPayClient<T>) -> ProfitSharing<'_, T> {
+        ProfitSharing {
+            client,
+        }
+    }
+
+    ///
+    /// # 添加分账接收方 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter8_1_8.shtml)
+    /// <pre>
+    /// 商户发起添加分账接收方请求，建立分账接收方列表。后续可通过发起分账请求，将分账方商户结算后的资金，分到该分账接收方。
+    /// 传入的接收方名称会使用平台证书加密，并在请求头中带上平台证书序列号。
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/v3/profitsharing/receivers/add
+    /// </pre>
+    pub async fn add_receiver_v3(&self, mut params: WechatProfitSharingReceiverRequestV3) -> LabradorResult<WechatProfitSharingReceiverResponseV3> {
+        if params.appid.is_none() {
+            params.appid = self.client.appid.to_owned().into();
+        }
+        let mut serial_no = None;
+        if let Some(name) = params.name.as_ref() {
+            let (ciphertext, serial) = self.client.encrypt_sensitive(name).await?;
+            params.name = ciphertext.into();
+            serial_no = serial.into();
+        }
+        let mut receiver = self.client.post_v3_with_serial(None, WechatPayMethod::ProfitSharing(ProfitSharingMethod::AddReceiverV3), vec![], params, serial_no)
+            .await?.json::<WechatProfitSharingReceiverResponseV3>()?;
+        if let Some(name) = receiver.name.as_ref() {
+            receiver.name = self.client.decrypt_sensitive(name)?.into();
+        }
+        Ok(receiver)
+    }
+
+    ///
+    /// # 删除分账接收方 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter8_1_9.shtml)
+    /// <pre>
+    /// 商户发起删除分账接收方请求。删除后，不支持将分账方商户结算后的资金，分到该分账接收方。
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/v3/profitsharing/receivers/delete
+    /// </pre>
+    pub async fn remove_receiver_v3(&self, mut params: WechatProfitSharingRemoveReceiverRequestV3) -> LabradorResult<WechatProfitSharingReceiverResponseV3> {
+        if params.appid.is_none() {
+            params.appid = self.client.appid.to_owned().into();
+        }
+        self.client.post_v3(None, WechatPayMethod::ProfitSharing(ProfitSharingMethod::DeleteReceiverV3), vec![], params)
+            .await?.json::<WechatProfitSharingReceiverResponseV3>()
+    }
+
+    ///
+    /// # 请求分账 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter8_1_1.shtml)
+    /// <pre>
+    /// 微信订单支付成功后，商户发起分账请求，将结算后的资金分到分账接收方。
+    /// 对同一笔订单最多能发起50次分账请求，每次请求最多分给50个接收方。
+    /// 传入的接收方名称会使用平台证书加密，并在请求头中带上平台证书序列号。
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/v3/profitsharing/orders
+    /// </pre>
+    pub async fn create_order_v3(&self, mut params: WechatProfitSharingOrderRequestV3) -> LabradorResult<WechatProfitSharingOrderResponseV3> {
+        if params.appid.is_none() {
+            params.appid = self.client.appid.to_owned().into();
+        }
+        let mut serial_no = None;
+        if params.receivers.iter().any(|v| v.name.is_some()) {
+            let cert = self.client.encryption_cert().await?;
+            for receiver in params.receivers.iter_mut() {
+                if let Some(name) = receiver.name.as_ref() {
+                    receiver.name = WechatPayClient::<T>::encrypt_with_cert(name, &cert)?.into();
+                }
+            }
+            serial_no = cert.serial_no.into();
+        }
+        self.client.post_v3_with_serial(None, WechatPayMethod::ProfitSharing(ProfitSharingMethod::CreateOrderV3), vec![], params, serial_no)
+            .await?.json::<WechatProfitSharingOrderResponseV3>()
+    }
+
+    ///
+    /// # 查询分账结果 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter8_1_2.shtml)
+    /// <pre>
+    /// 发起分账请求后，可调用此接口查询分账结果。
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/v3/profitsharing/orders/{out_order_no}
+    /// </pre>
+    pub async fn query_order_v3(&self, params: &WechatQueryProfitSharingOrderRequestV3) -> LabradorResult<WechatProfitSharingOrderResponseV3> {
+        self.client.get_v3(WechatPayMethod::ProfitSharing(ProfitSharingMethod::QueryOrderV3(params.out_order_no.to_string())), params.get_querys())
+            .await?.json::<WechatProfitSharingOrderResponseV3>()
+    }
+
+    ///
+    /// # 解冻剩余资金 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter8_1_5.shtml)
+    /// <pre>
+    /// 不需要进行分账的订单，可直接调用本接口将订单的金额全部解冻给特约商户。
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/v3/profitsharing/orders/unfreeze
+    /// </pre>
+    pub async fn unfreeze_v3(&self, params: WechatProfitSharingUnfreezeRequestV3) -> LabradorResult<WechatProfitSharingOrderResponseV3> {
+        self.client.post_v3(None, WechatPayMethod::ProfitSharing(ProfitSharingMethod::UnfreezeV3), vec![], params)
+            .await?.json::<WechatProfitSharingOrderResponseV3>()
+    }
+
+    ///
+    /// # 请求分账回退 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter8_1_3.shtml)
+    /// <pre>
+    /// 如果订单已经分账，在退款时，可以先调此接口，将已分账的资金从分账接收方的账户回退给分账方，再发起退款。
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/v3/profitsharing/return-orders
+    /// </pre>
+    pub async fn create_return_order_v3(&self, params: WechatProfitSharingReturnRequestV3) -> LabradorResult<WechatProfitSharingReturnResponseV3> {
+        self.client.post_v3(None, WechatPayMethod::ProfitSharing(ProfitSharingMethod::CreateReturnOrderV3), vec![], params)
+            .await?.json::<WechatProfitSharingReturnResponseV3>()
+    }
+
+    ///
+    /// # 查询分账回退结果 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter8_1_4.shtml)
+    /// <pre>
+    /// 商户需要核实回退结果，可调用此接口查询回退结果。
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/v3/profitsharing/return-orders/{out_return_no}
+    /// </pre>
+    pub async fn query_return_order_v3(&self, params: &WechatQueryProfitSharingReturnRequestV3) -> LabradorResult<WechatProfitSharingReturnResponseV3> {
+        self.client.get_v3(WechatPayMethod::ProfitSharing(ProfitSharingMethod::QueryReturnOrderV3(params.out_return_no.to_string())), params.get_querys())
+            .await?.json::<WechatProfitSharingReturnResponseV3>()
+    }
+
+    ///
+    /// # 查询剩余待分金额 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter8_1_6.shtml)
+    pub async fn query_amounts_v3(&self, transaction_id: &str) -> LabradorResult<WechatProfitSharingAmountResponseV3> {
+        self.client.get_v3(WechatPayMethod::ProfitSharing(ProfitSharingMethod::QueryAmountsV3(transaction_id.to_string())), vec![])
+            .await?.json::<WechatProfitSharingAmountResponseV3>()
+    }
+
+    /// # 解析分账动账通知 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter8_1_10.shtml)
+    pub async fn parse_notify_v3(&self, notify_data: &str, header: &Option<SignatureHeader>) -> LabradorResult<WechatProfitSharingNotifyResponseV3> {
+        if header.is_none() {
+            return Err(LabraError::RequestError("非法请求，头部信息验证为空".to_string()));
+        }
+        let header = header.to_owned().unwrap();
+        if !self.client.verify_notify_sign(&header, notify_data).await {
+            return Err(LabraError::RequestError("非法请求，头部信息验证失败".to_string()));
+        }
+        let origin = serde_json::from_str::<OriginNotifyResponse>(notify_data)?;
+        let resource = origin.resource.to_owned();
+        let v3_key = self.client.api_key_v3.to_owned().unwrap_or_default();
+        let crypto = WechatCryptoV3::new(&v3_key);
+        let decrypted = crypto.decrypt_data_v3(&resource)?;
+        let decrypt_notify_result = serde_json::from_slice::<DecryptProfitSharingNotifyResult>(&decrypted)?;
+        Ok(WechatProfitSharingNotifyResponseV3 {
+            raw_data: origin.into(),
+            result: decrypt_notify_result.into()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LabraCertificate, MockTransport, ProfitSharingReceiver, SimpleStorage, WechatPayClient, WechatProfitSharingOrderRequestV3, WechatProfitSharingReceiverRequestV3, WechatQueryProfitSharingOrderRequestV3};
+    use crate::util::current_timestamp;
+    use crate::wechat::cryptos::{SignatureHeader, WechatCryptoV3};
+    use crate::wechat::pay::tests::{signed_response, PLATFORM_CERT, PRIVATE_KEY};
+
+    fn client(transport: &MockTransport) -> WechatPayClient<SimpleStorage> {
+        WechatPayClient::<SimpleStorage>::new("appid", "secret")
+            .mch_id("1900000100".to_string())
+            .serial_no("serial_no".to_string())
+            .private_key(PRIVATE_KEY.to_string())
+            .key_v3("0123456789abcdef0123456789abcdef".to_string())
+            .platform_cert(LabraCertificate::from_pem(PLATFORM_CERT.as_bytes().to_vec()).unwrap())
+            .transport(transport.clone())
+    }
+
+    #[tokio::test]
+    async fn test_profit_sharing_order_v3() {
+        let transport = MockTransport::new();
+        let client = client(&transport);
+        let (name, serial_no) = client.encrypt_sensitive("示例商户全称").await.unwrap();
+        transport.push_response(signed_response(200, format!(r#"{{"sub_mchid":"1900000109","type":"MERCHANT_ID","account":"1900000110","name":"{}","relation_type":"STORE"}}"#, name)))
+            .push_response(signed_response(200, r#"{"sub_mchid":"1900000109","transaction_id":"4200000000000000000000000000","out_order_no":"P20150806125346","order_id":"3008450740201411110007820472","state":"PROCESSING","receivers":[{"amount":888,"description":"分给商户","type":"MERCHANT_ID","account":"1900000110","result":"PENDING","create_time":"2024-01-01T12:00:00+08:00","finish_time":"2024-01-01T12:00:00+08:00","detail_id":"36011111111111111111111"}]}"#))
+            .push_response(signed_response(200, r#"{"sub_mchid":"1900000109","transaction_id":"4200000000000000000000000000","out_order_no":"P20150806125346","order_id":"3008450740201411110007820472","state":"FINISHED"}"#));
+        let params = WechatProfitSharingReceiverRequestV3 {
+            sub_mchid: "1900000109".to_string().into(),
+            receiver_type: "MERCHANT_ID".to_string(),
+            account: "1900000110".to_string(),
+            name: "示例商户全称".to_string().into(),
+            relation_type: "STORE".to_string().into(),
+            ..Default::default()
+        };
+        let receiver = client.profit_sharing().add_receiver_v3(params).await.unwrap();
+        assert_eq!(Some("示例商户全称".to_string()), receiver.name);
+        let params = WechatProfitSharingOrderRequestV3 {
+            sub_mchid: "1900000109".to_string().into(),
+            transaction_id: "4200000000000000000000000000".to_string(),
+            out_order_no: "P20150806125346".to_string(),
+            receivers: vec![ProfitSharingReceiver {
+                receiver_type: "MERCHANT_ID".to_string(),
+                account: "1900000110".to_string(),
+                name: "示例商户全称".to_string().into(),
+                amount: 888,
+                description: "分给商户".to_string(),
+            }, ProfitSharingReceiver {
+                receiver_type: "MERCHANT_ID".to_string(),
+                account: "1900000111".to_string(),
+                name: "示例商户二".to_string().into(),
+                amount: 100,
+                description: "分给商户".to_string(),
+            }],
+            unfreeze_unsplit: true,
+            ..Default::default()
+        };
+        let order = client.profit_sharing().create_order_v3(params).await.unwrap();
+        assert_eq!("PENDING", order.receivers.unwrap()[0].result);
+        let params = WechatQueryProfitSharingOrderRequestV3 {
+            sub_mchid: "1900000109".to_string().into(),
+            transaction_id: "4200000000000000000000000000".to_string(),
+            out_order_no: "P20150806125346".to_string(),
+        };
+        let order = client.profit_sharing().query_order_v3(&params).await.unwrap();
+        assert_eq!("FINISHED", order.state);
+
+        let requests = transport.requests();
+        assert_eq!("https://api.mch.weixin.qq.com/v3/profitsharing/receivers/add", requests[0].url.as_str());
+        assert_eq!(serial_no, requests[0].headers["Wechatpay-Serial"].to_str().unwrap());
+        let body = serde_json::from_str::<serde_json::Value>(&requests[1].body_text()).unwrap();
+        assert_eq!("appid", body["appid"]);
+        assert_eq!("1900000109", body["sub_mchid"]);
+        assert_eq!("MERCHANT_ID", body["receivers"][0]["type"]);
+        assert_eq!("示例商户全称", client.decrypt_sensitive(body["receivers"][0]["name"].as_str().unwrap()).unwrap());
+        assert_eq!("示例商户二", client.decrypt_sensitive(body["receivers"][1]["name"].as_str().unwrap()).unwrap());
+        assert_eq!(serial_no, requests[1].headers["Wechatpay-Serial"].to_str().unwrap());
+        assert_eq!("https://api.mch.weixin.qq.com/v3/profitsharing/orders/P20150806125346?sub_mchid=1900000109&transaction_id=4200000000000000000000000000", requests[2].url.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_parse_notify_v3() {
+        let transport = MockTransport::new();
+        let client = client(&transport);
+        let cert = LabraCertificate::from_pem(PLATFORM_CERT.as_bytes().to_vec()).unwrap();
+        let notify = format!(r#"{{"id":"EV-2018022511223320873","create_time":"2024-01-01T12:00:00+08:00","resource_type":"encrypt-resource","event_type":"PROFITSHARING.SUCCESS","summary":"分账成功","resource":{{"original_type":"profitsharing","algorithm":"AEAD_AES_256_GCM","ciphertext":"{}","associated_data":"profitsharing","nonce":"0123456789ab"}}}}"#, NOTIFY_CIPHERTEXT);
+        let timestamp = current_timestamp().to_string();
+        let signature = WechatCryptoV3::sign(&format!("{}\nnonce\n{}\n", timestamp, notify), &PRIVATE_KEY.to_string()).unwrap();
+        let header = SignatureHeader {
+            time_stamp: timestamp,
+            nonce: "nonce".to_string(),
+            signature,
+            serial: cert.serial_no.to_string(),
+        };
+        let response = client.profit_sharing().parse_notify_v3(&notify, &header.clone().into()).await.unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(Some("1900000109".to_string()), result.sub_mchid);
+        assert_eq!("P20150806125346", result.out_order_no);
+        assert_eq!(888, result.receiver.amount);
+        assert_eq!("MERCHANT_ID", result.receiver.receiver_type);
+        let tampered = SignatureHeader { nonce: "other".to_string(), ..header.clone() };
+        assert!(client.profit_sharing().parse_notify_v3(&notify, &tampered.into()).await.is_err());
+        // 过期的通知即使签名正确也拒绝
+        let signature = WechatCryptoV3::sign(&format!("1704081600\nnonce\n{}\n", notify), &PRIVATE_KEY.to_string()).unwrap();
+        let replayed = SignatureHeader { time_stamp: "1704081600".to_string(), signature, ..header };
+        assert!(client.profit_sharing().parse_notify_v3(&notify, &replayed.into()).await.is_err());
+    }
+
+    const NOTIFY_CIPHERTEXT: &str = "4ouUShyn+3OcYQGHkTVFF3K25P8m+FpJCK28bKun9MpCFgNbyNAU/FDeT1H7uFvnnEC+3NBwF1cSvIYhYxdL8ASENE7dqKnewrxv6mEpPVOtmHf9buhl+Sch1Lf/TiT5XTFoVABOdJBmGjoX5LRHzpWJOWHWBNZYtoLzcZfA0yrBha3LuGs3IRxVT2wvRK7hS66Jc8Jw5PYrFOazCc/ZNeGE+SghnNCgoXy7JXpRLYOgyGA6s3HipXMKdXwSTGJNxpKSWXndIShBI30A641bml6Sv7HIMJj2Q8+QJ6ijdlZpLOTyZs4FvueUDVNOYQcOKLlHcIiYKjg+NbbAtb4+yBYyaKRc1BnEQdbHPgbrH54Py9hHMUJ8vHAXKDkss98E7Ly5DF8NhDMOoESPOwVUxKp9V6Fy1n4P5umINvqEKtKsIT/LkHiOKenbAf3iKswshCs=";
+}
@@ -1,17 +1,26 @@
 use serde_json::Value;
-use crate::{DecryptNotifyResult, DecryptRefundNotifyResult, IsvWechatPayRequestV3, LabradorResult, LabraError, OriginNotifyResponse, RequestType, SessionStore, WechatCloseOrderRequest, WechatCloseOrderRequestV3, WechatCloseOrderResponse, WechatDecryptRefundNotifyResponse, WechatOrderReverseRequest, WechatOrderReverseResponse, WechatPayClient, WechatPayNotifyResponse, WechatPayNotifyResponseV3, WechatPayRequestV3, WechatPayResponse, WechatPayResponseV3, WechatQueryOrderRequest, WechatQueryOrderRequestV3, WechatQueryOrderResponse, WechatQueryOrderResponseV3, WechatQueryRefundOrderRequest, WechatQueryRefundResponse, WechatQueryRefundResponseV3, WechatRefundNotifyResponse, WechatRefundNotifyResponseV3, WechatRefundRequest, WechatRefundRequestV3, WechatRefundResponse, WechatRefundResponseV3, WxPayShorturlRequest, WxPayShortUrlResponse, WxScanPayNotifyResponse};
+use sha1::{Digest, Sha1};
+use crate::{DecryptNotifyResult, DecryptRefundNotifyResult, IsvWechatPayRequestV3, LabradorResult, LabraError, OriginNotifyResponse, AsyncSessionStore, WechatCloseOrderRequest, WechatCloseOrderRequestV3, WechatCloseOrderResponse, WechatDecryptRefundNotifyResponse, WechatOrderReverseRequest, WechatOrderReverseResponse, WechatPayClient, WechatPayNotifyResponse, WechatPayNotifyResponseV3, WechatPayRequestV3, WechatPayResponse, WechatPayResponseV3, WechatQueryOrderRequest, WechatQueryOrderRequestV3, WechatQueryOrderResponse, WechatQueryOrderResponseV3, WechatQueryRefundOrderRequest, WechatQueryRefundResponse, WechatQueryRefundResponseV3, WechatRefundNotifyResponse, WechatRefundNotifyResponseV3, WechatRefundRequest, WechatRefundRequestV3, WechatRefundResponse, WechatRefundResponseV3, WechatTradeBillRequestV3, WechatFundFlowBillRequestV3, WechatSubMerchantFundFlowBillRequestV3, WechatBillResponseV3, WechatSubMerchantFundFlowBillResponseV3, WechatTradeBillV3, WechatFundFlowBillV3, WxPayShorturlRequest, WxPayShortUrlResponse, WxScanPayNotifyResponse};
 use crate::wechat::cryptos::{SignatureHeader, WechatCryptoV3};
 use crate::wechat::pay::method::{WechatPayMethod, WxPayMethod};
 use crate::wechat::pay::{TradeType};
 use crate::wechat::pay::request::WechatPayRequest;
+use crate::prp::PrpCrypto;
+use std::io::{self, Write};
+use flate2::write::MultiGzDecoder;
+use futures_util::StreamExt;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// 账单读入内存时解压后的最大长度
+const MAX_BILL_SIZE: u64 = 100 << 20;
 
 #[derive(Debug, Clone)]
-pub struct WxPay<'a, T: SessionStore> {
+pub struct WxPay<'a, T: AsyncSessionStore> {
     client: &'a WechatPayClient<T>,
 }
 
 #[allow(unused)]
-impl<'a, T: SessionStore> WxPay<'a, T> {
+impl<'a, T: AsyncSessionStore> WxPay<'a, T> {
 
     #[inline]
     pub fn new(client: &WechatPayClient<T>) -> WxPay<T> {
@@ -73,9 +82,9 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
             // 將通知url置空
             params.notify_url = None;
         }
-        params.get_sign(&self.client.secret);
-        let res = self.client.post(WechatPayMethod::WxPay(method), &params.parse_xml(), RequestType::Xml).await?.text()?;
-        WechatPayResponse::parse_xml(res)
+        params.get_sign(&self.client.sign_key_v2().await?);
+        let response = self.client.post(WechatPayMethod::WxPay(method), params.parse_xml()).await?;
+        WechatPayResponse::parse_xml(response.text()?).map_err(|err| err.with_response(&response))
     }
 
     ///
@@ -127,12 +136,12 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
         if params.appid.is_none() {
             params.appid = self.client.appid.to_owned().into();
         }
-        let res = self.client.post_v3(params.mch_id.to_owned().into(), WechatPayMethod::WxPay(WxPayMethod::UnifiedOrderV3(trade_type)), vec![],&params, RequestType::Json).await?.json::<serde_json::Value>()?;
+        let res = self.client.post_v3(params.mch_id.to_owned().into(), WechatPayMethod::WxPay(WxPayMethod::UnifiedOrderV3(trade_type)), vec![],&params).await?.json::<serde_json::Value>()?;
         serde_json::from_value::<WechatPayResponseV3>(res).map_err(LabraError::from)
     }
 
     pub async fn isv_unified_order_v3(&self, trade_type: TradeType, mut params: IsvWechatPayRequestV3) -> LabradorResult<WechatPayResponseV3> {
-        let res = self.client.post_v3(None, WechatPayMethod::WxPay(WxPayMethod::IsvUnifiedOrderV3(trade_type)), vec![],&params, RequestType::Json).await?.json::<serde_json::Value>()?;
+        let res = self.client.post_v3(None, WechatPayMethod::WxPay(WxPayMethod::IsvUnifiedOrderV3(trade_type)), vec![],&params).await?.json::<serde_json::Value>()?;
         serde_json::from_value::<WechatPayResponseV3>(res).map_err(LabraError::from)
     }
 
@@ -186,9 +195,9 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
     pub async fn close_order(&self,
                              mut params: WechatCloseOrderRequest) -> LabradorResult<WechatCloseOrderResponse> {
         params.appid = self.client.appid.to_owned().into();
-        params.get_sign(&self.client.api_key.to_owned().unwrap_or_default());
-        let res = self.client.post(WechatPayMethod::WxPay(WxPayMethod::CloseOrder), &params.parse_xml(), RequestType::Xml).await?.text()?;
-        WechatCloseOrderResponse::parse_xml(res)
+        params.get_sign(&self.client.sign_key_v2().await?);
+        let response = self.client.post(WechatPayMethod::WxPay(WxPayMethod::CloseOrder), params.parse_xml()).await?;
+        WechatCloseOrderResponse::parse_xml(response.text()?).map_err(|err| err.with_response(&response))
     }
 
     ///
@@ -226,7 +235,7 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
     pub async fn close_order_v3(&self, mut params: WechatCloseOrderRequestV3) -> LabradorResult<()> {
         let out_trade_no = params.out_trade_no.to_owned().unwrap_or_default();
         params.out_trade_no = None;
-        let res = self.client.post_v3(params.mchid.to_owned().into(), WechatPayMethod::WxPay(WxPayMethod::CloseOrderV3(out_trade_no)), vec![], &params, RequestType::Json).await?;
+        let res = self.client.post_v3(params.mchid.to_owned().into(), WechatPayMethod::WxPay(WxPayMethod::CloseOrderV3(out_trade_no)), vec![], &params).await?;
         let _ = res.text()?;
         // let s = res.json::<serde_json::Value>().await?;
         Ok(())
@@ -272,7 +281,7 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
     /// ```
     ///
     pub async fn query_order(&self, mut params: WechatQueryOrderRequest) -> LabradorResult<WechatQueryOrderResponse> {
-        let res = self.client.post(WechatPayMethod::WxPay(WxPayMethod::QueryOrder), &params, RequestType::Xml).await?;
+        let res = self.client.post(WechatPayMethod::WxPay(WxPayMethod::QueryOrder), params.parse_xml()).await?;
         let result = res.text()?;
         WechatQueryOrderResponse::parse_xml(result)
     }
@@ -319,7 +328,7 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
     pub async fn query_order_v3(&self, params: WechatQueryOrderRequestV3) -> LabradorResult<WechatQueryOrderResponseV3> {
         let mchid = params.mchid.clone();
         let mchid_vec = vec![("mchid",mchid.as_str())];
-        self.client.get_v3(WechatPayMethod::WxPay(WxPayMethod::QueryOrderV3((params.out_trade_no.to_owned(), params.out_trade_no.to_owned()))), mchid_vec, RequestType::Json)
+        self.client.get_v3(WechatPayMethod::WxPay(WxPayMethod::QueryOrderV3((params.out_trade_no.to_owned(), params.out_trade_no.to_owned()))), mchid_vec)
             .await?.json::<WechatQueryOrderResponseV3>()
     }
 
@@ -339,10 +348,10 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
     /// </pre>
     pub async fn query_refund_order(&self, mut params: WechatQueryRefundOrderRequest) -> LabradorResult<WechatQueryRefundResponse> {
         params.appid = self.client.appid.to_owned().into();
-        params.get_sign(&self.client.api_key.to_owned().unwrap_or_default());
-        let res = self.client.post(WechatPayMethod::WxPay(WxPayMethod::QueryRefundOrder), params, RequestType::Xml)
-            .await?.text()?;
-        WechatQueryRefundResponse::parse_xml(res)
+        params.get_sign(&self.client.sign_key_v2().await?);
+        let response = self.client.post(WechatPayMethod::WxPay(WxPayMethod::QueryRefundOrder), params.parse_xml())
+            .await?;
+        WechatQueryRefundResponse::parse_xml(response.text()?).map_err(|err| err.with_response(&response))
     }
 
 
@@ -365,9 +374,9 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
     ///
     /// </pre>
     pub async fn query_refund_order_v2(&self, params: WechatQueryRefundOrderRequest) -> LabradorResult<WechatQueryRefundResponse> {
-        let res = self.client.post(WechatPayMethod::WxPay(WxPayMethod::QueryRefundOrderV2), params, RequestType::Json)
-            .await?.text()?;
-        WechatQueryRefundResponse::parse_xml(res)
+        let response = self.client.post(WechatPayMethod::WxPay(WxPayMethod::QueryRefundOrderV2), params.parse_xml())
+            .await?;
+        WechatQueryRefundResponse::parse_xml(response.text()?).map_err(|err| err.with_response(&response))
     }
 
     /// 
@@ -381,12 +390,12 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
     /// 接口链接：https://api.mch.weixin.qq.com/v3/refund/domestic/refunds/{out_refund_no}
     /// </pre>
     pub async fn query_refund_order_v3(&self, out_refund_no: String) -> LabradorResult<WechatQueryRefundResponseV3> {
-        self.client.post_v3(None, WechatPayMethod::WxPay(WxPayMethod::QueryRefundOrderV3(out_refund_no)), vec![], "", RequestType::Json)
+        self.client.post_v3(None, WechatPayMethod::WxPay(WxPayMethod::QueryRefundOrderV3(out_refund_no)), vec![], "")
             .await?.json::<WechatQueryRefundResponseV3>()
     }
 
     pub async fn isv_query_refund_order_v3(&self, out_refund_no: String, sub_mch_id: String) -> LabradorResult<WechatQueryRefundResponseV3> {
-        self.client.post_v3(None, WechatPayMethod::WxPay(WxPayMethod::QueryRefundOrderV3(out_refund_no)), vec![("sub_mchid".to_string(), sub_mch_id)], "", RequestType::Json)
+        self.client.post_v3(None, WechatPayMethod::WxPay(WxPayMethod::QueryRefundOrderV3(out_refund_no)), vec![("sub_mchid".to_string(), sub_mch_id)], "")
             .await?.json::<WechatQueryRefundResponseV3>()
     }
 
@@ -508,9 +517,9 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
     ) -> LabradorResult<WechatRefundResponse> {
         params.appid = self.client.appid.to_owned().into();
         let mch_id = params.mch_id.as_str();
-        params.get_sign(&self.client.api_key.to_owned().unwrap_or_default());
-        let res = self.client.post(WechatPayMethod::WxPay(WxPayMethod::Refund), &params.parse_xml(), RequestType::Xml).await?.text()?;
-        WechatRefundResponse::parse_xml(res)
+        params.get_sign(&self.client.sign_key_v2().await?);
+        let response = self.client.post(WechatPayMethod::WxPay(WxPayMethod::Refund), params.parse_xml()).await?;
+        WechatRefundResponse::parse_xml(response.text()?).map_err(|err| err.with_response(&response))
     }
 
     ///
@@ -534,8 +543,8 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
     ) -> LabradorResult<WechatOrderReverseResponse> {
         params.appid = self.client.appid.to_owned().into();
         let mch_id = params.mch_id.as_str();
-        let res = self.client.post(WechatPayMethod::WxPay(WxPayMethod::ReverseOrder), &params.parse_xml(), RequestType::Xml).await?.text()?;
-        WechatOrderReverseResponse::parse_xml(res)
+        let response = self.client.post(WechatPayMethod::WxPay(WxPayMethod::ReverseOrder), params.parse_xml()).await?;
+        WechatOrderReverseResponse::parse_xml(response.text()?).map_err(|err| err.with_response(&response))
     }
 
     ///
@@ -555,8 +564,8 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
     ) -> LabradorResult<WxPayShortUrlResponse> {
         params.appid = self.client.appid.to_owned().into();
         let mch_id = params.mch_id.as_str();
-        let res = self.client.post(WechatPayMethod::WxPay(WxPayMethod::ShortUrl), &params.parse_xml(), RequestType::Xml).await?.text()?;
-        WxPayShortUrlResponse::parse_xml(res)
+        let response = self.client.post(WechatPayMethod::WxPay(WxPayMethod::ShortUrl), params.parse_xml()).await?;
+        WxPayShortUrlResponse::parse_xml(response.text()?).map_err(|err| err.with_response(&response))
     }
 
     ///
@@ -584,9 +593,262 @@ impl<'a, T: SessionStore> WxPay<'a, T> {
         &self,
         mut params: WechatRefundRequestV3
     ) -> LabradorResult<WechatRefundResponseV3> {
-       self.client.post_v3(None, WechatPayMethod::WxPay(WxPayMethod::RefundV3), vec![],params, RequestType::Json).await?
+       self.client.post_v3(None, WechatPayMethod::WxPay(WxPayMethod::RefundV3), vec![],params).await?
             .json::<WechatRefundResponseV3>()
     }
+
+    ///
+    /// # 申请交易账单 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter3_1_6.shtml)
+    /// <pre>
+    /// 微信支付按天提供交易账单文件，商户可以通过该接口获取账单文件的下载地址。
+    /// 1、次日9点启动生成前一天的对账单，建议10点后再获取
+    /// 2、仅支持三个月内的账单下载申请
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/v3/bill/tradebill
+    /// </pre>
+    pub async fn apply_trade_bill_v3(&self, params: &WechatTradeBillRequestV3) -> LabradorResult<WechatBillResponseV3> {
+        self.client.get_v3(WechatPayMethod::WxPay(WxPayMethod::TradeBillV3), params.get_querys())
+            .await?.json::<WechatBillResponseV3>()
+    }
+
+    ///
+    /// # 申请资金账单 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3/apis/chapter3_1_7.shtml)
+    /// <pre>
+    /// 微信支付按天提供微信支付账户的资金流水账单文件，商户可以通过该接口获取账单文件的下载地址。
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/v3/bill/fundflowbill
+    /// </pre>
+    pub async fn apply_fund_flow_bill_v3(&self, params: &WechatFundFlowBillRequestV3) -> LabradorResult<WechatBillResponseV3> {
+        self.client.get_v3(WechatPayMethod::WxPay(WxPayMethod::FundFlowBillV3), params.get_querys())
+            .await?.json::<WechatBillResponseV3>()
+    }
+
+    ///
+    /// # 申请单个子商户资金账单 - V3
+    /// 详见 [文档](https://pay.weixin.qq.com/wiki/doc/apiv3_partner/apis/chapter4_1_12.shtml)
+    /// <pre>
+    /// 服务商获取单个子商户的资金账单，账单文件经过加密，可能分为多个文件。
+    ///
+    /// 接口地址
+    /// https://api.mch.weixin.qq.com/v3/bill/sub-merchant-fundflowbill
+    /// </pre>
+    pub async fn apply_sub_merchant_fund_flow_bill_v3(&self, params: &WechatSubMerchantFundFlowBillRequestV3) -> LabradorResult<WechatSubMerchantFundFlowBillResponseV3> {
+        self.client.get_v3(WechatPayMethod::WxPay(WxPayMethod::SubMerchantFundFlowBillV3), params.get_querys())
+            .await?.json::<WechatSubMerchantFundFlowBillResponseV3>()
+    }
+
+    /// # 下载账单 - V3
+    /// 边下载边解压压缩的账单，并校验账单的摘要值。账单会读入内存，解压后超过100MB时返回错误，
+    /// 较大的账单请使用`download_bill_v3_to`写入文件
+    pub async fn download_bill_v3(&self, bill: &WechatBillResponseV3) -> LabradorResult<String> {
+        let mut content = Vec::new();
+        self.download_bill(bill, &mut content, Some(MAX_BILL_SIZE), |_, _| {}).await?;
+        Ok(String::from_utf8(content)?)
+    }
+
+    /// # 下载账单并写入`writer` - V3
+    /// 边下载边解压写入，不会将整个账单读入内存，每下载一块调用一次`progress(已下载字节数, 总字节数)`，返回解压后写入的字节数。
+    /// 摘要值在全部写入后才能校验，返回错误时需要丢弃已写入的内容
+    pub async fn download_bill_v3_to<W, F>(&self, bill: &WechatBillResponseV3, writer: &mut W, progress: F) -> LabradorResult<u64>
+        where W: AsyncWrite + Unpin, F: FnMut(u64, Option<u64>) {
+        self.download_bill(bill, writer, None, progress).await
+    }
+
+    async fn download_bill<W, F>(&self, bill: &WechatBillResponseV3, writer: &mut W, limit: Option<u64>, mut progress: F) -> LabradorResult<u64>
+        where W: AsyncWrite + Unpin, F: FnMut(u64, Option<u64>) {
+        let mut decoder = BillDecoder::new(&bill.hash_type, limit)?;
+        let response = self.client.download_v3(&bill.download_url).await?;
+        let total = response.content_length();
+        let mut stream = response.into_stream();
+        let mut downloaded = 0u64;
+        let mut written = 0u64;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            written += write_bill(writer, &decoder.write(&chunk)?).await?;
+            downloaded += chunk.len() as u64;
+            progress(downloaded, total);
+        }
+        written += write_bill(writer, &decoder.finish(&bill.hash_value)?).await?;
+        writer.flush().await?;
+        Ok(written)
+    }
+
+    /// # 下载单个子商户资金账单 - V3
+    /// 按序号解密并拼接所有账单文件，使用商户私钥解密账单文件的密钥。
+    /// 账单会读入内存，解压后超过100MB时返回错误，较大的账单请使用`download_sub_merchant_bill_v3_to`写入文件
+    pub async fn download_sub_merchant_bill_v3(&self, bill: &WechatSubMerchantFundFlowBillResponseV3) -> LabradorResult<String> {
+        let mut content = Vec::new();
+        self.download_sub_merchant_bill(bill, &mut content, Some(MAX_BILL_SIZE), |_, _| {}).await?;
+        Ok(String::from_utf8(content)?)
+    }
+
+    /// # 下载单个子商户资金账单并写入`writer` - V3
+    /// AES-GCM需要读取完整的密文后才能校验，单个加密文件会先读入内存再解密、解压后写入，
+    /// 每下载完一个文件调用一次`progress(已下载字节数, None)`，返回解压后写入的字节数。
+    /// 摘要值在每个文件写入后才能校验，返回错误时需要丢弃已写入的内容
+    pub async fn download_sub_merchant_bill_v3_to<W, F>(&self, bill: &WechatSubMerchantFundFlowBillResponseV3, writer: &mut W, progress: F) -> LabradorResult<u64>
+        where W: AsyncWrite + Unpin, F: FnMut(u64, Option<u64>) {
+        self.download_sub_merchant_bill(bill, writer, None, progress).await
+    }
+
+    async fn download_sub_merchant_bill<W, F>(&self, bill: &WechatSubMerchantFundFlowBillResponseV3, writer: &mut W, limit: Option<u64>, mut progress: F) -> LabradorResult<u64>
+        where W: AsyncWrite + Unpin, F: FnMut(u64, Option<u64>) {
+        let private_key = self.client.private_key.to_owned().unwrap_or_default();
+        let mut files = bill.download_bill_list.iter().collect::<Vec<_>>();
+        files.sort_by_key(|v| v.bill_sequence);
+        let mut downloaded = 0u64;
+        let mut written = 0u64;
+        for file in files {
+            // 多个文件共用大小限制
+            let mut decoder = BillDecoder::new(&file.hash_type, limit.map(|v| v.saturating_sub(written)))?;
+            let content = self.client.download_v3(&file.download_url).await?.into_response().await?.bytes()?;
+            if content.len() < 16 {
+                return Err(LabraError::ApiError("账单文件内容有误".to_string()));
+            }
+            downloaded += content.len() as u64;
+            progress(downloaded, None);
+            let key = PrpCrypto::rsa_oaep_decrypt_with_pem(&file.encrypt_key, &private_key)?;
+            let (ciphertext, tag) = content.split_at(content.len() - 16);
+            written += write_bill(writer, &decoder.write(&PrpCrypto::new(key).aes_256_gcm_decrypt(b"", file.nonce.as_bytes(), ciphertext, tag)?)?).await?;
+            written += write_bill(writer, &decoder.finish(&file.hash_value)?).await?;
+        }
+        writer.flush().await?;
+        Ok(written)
+    }
+
+    /// # 获取交易账单 - V3
+    /// 申请并下载交易账单，解析为明细数据与汇总数据
+    pub async fn trade_bill_v3(&self, params: &WechatTradeBillRequestV3) -> LabradorResult<WechatTradeBillV3> {
+        let bill = self.apply_trade_bill_v3(params).await?;
+        let mut content = Vec::new();
+        self.download_bill(&bill, &mut content, Some(MAX_BILL_SIZE), |_, _| {}).await?;
+        WechatTradeBillV3::parse_reader(content.as_slice())
+    }
+
+    /// # 获取资金账单 - V3
+    /// 申请并下载资金账单，解析为明细数据与汇总数据
+    pub async fn fund_flow_bill_v3(&self, params: &WechatFundFlowBillRequestV3) -> LabradorResult<WechatFundFlowBillV3> {
+        let bill = self.apply_fund_flow_bill_v3(params).await?;
+        let mut content = Vec::new();
+        self.download_bill(&bill, &mut content, Some(MAX_BILL_SIZE), |_, _| {}).await?;
+        WechatFundFlowBillV3::parse_reader(content.as_slice())
+    }
+
+    /// # 获取单个子商户资金账单 - V3
+    /// 申请、下载并解密子商户资金账单，解析为明细数据与汇总数据
+    pub async fn sub_merchant_fund_flow_bill_v3(&self, params: &WechatSubMerchantFundFlowBillRequestV3) -> LabradorResult<WechatFundFlowBillV3> {
+        let bill = self.apply_sub_merchant_fund_flow_bill_v3(params).await?;
+        let mut content = Vec::new();
+        self.download_sub_merchant_bill(&bill, &mut content, Some(MAX_BILL_SIZE), |_, _| {}).await?;
+        WechatFundFlowBillV3::parse_reader(content.as_slice())
+    }
+
+}
+
+/// 写入一块解压后的账单内容，返回写入的字节数
+async fn write_bill<W: AsyncWrite + Unpin>(writer: &mut W, data: &[u8]) -> LabradorResult<u64> {
+    writer.write_all(data).await?;
+    Ok(data.len() as u64)
+}
+
+/// 账单解码，逐块写入下载的内容，压缩的账单边写入边解压，同时计算解压后内容的摘要值
+enum BillDecoder {
+    Plain(BillSink),
+    Gzip(MultiGzDecoder<BillSink>),
+    /// 还没有读到足够判断是否压缩的内容
+    Pending(Vec<u8>, Option<u64>),
+}
+
+impl BillDecoder {
+    /// 只支持SHA1摘要，`limit`为解压后的最大长度
+    fn new(hash_type: &str, limit: Option<u64>) -> LabradorResult<Self> {
+        if !hash_type.eq_ignore_ascii_case("SHA1") {
+            return Err(LabraError::InvalidSignature(format!("不支持的账单摘要算法：{}", hash_type)));
+        }
+        Ok(BillDecoder::Pending(Vec::new(), limit))
+    }
+
+    /// 写入一块下载的内容，返回目前解压出的内容
+    fn write(&mut self, chunk: &[u8]) -> LabradorResult<Vec<u8>> {
+        match self {
+            BillDecoder::Plain(sink) => sink.write_all(chunk)?,
+            BillDecoder::Gzip(decoder) => decoder.write_all(chunk)?,
+            BillDecoder::Pending(head, limit) => {
+                head.extend_from_slice(chunk);
+                if head.len() >= 2 {
+                    let head = std::mem::take(head);
+                    let sink = BillSink::new(*limit);
+                    *self = if head.starts_with(&[0x1f, 0x8b]) {
+                        BillDecoder::Gzip(MultiGzDecoder::new(sink))
+                    } else {
+                        BillDecoder::Plain(sink)
+                    };
+                    return self.write(&head);
+                }
+            }
+        }
+        Ok(match self {
+            BillDecoder::Plain(sink) => std::mem::take(&mut sink.buf),
+            BillDecoder::Gzip(decoder) => std::mem::take(&mut decoder.get_mut().buf),
+            BillDecoder::Pending(..) => Vec::new(),
+        })
+    }
+
+    /// 结束写入，校验摘要值并返回剩余的内容
+    fn finish(self, hash_value: &str) -> LabradorResult<Vec<u8>> {
+        let sink = match self {
+            BillDecoder::Plain(sink) => sink,
+            BillDecoder::Gzip(decoder) => decoder.finish()?,
+            BillDecoder::Pending(head, limit) => {
+                let mut sink = BillSink::new(limit);
+                sink.write_all(&head)?;
+                sink
+            }
+        };
+        if !hex::encode(sink.hasher.finalize()).eq_ignore_ascii_case(hash_value) {
+            return Err(LabraError::InvalidSignature("账单文件摘要校验失败".to_string()));
+        }
+        Ok(sink.buf)
+    }
+}
+
+/// 暂存解压后的账单内容并计算摘要值
+struct BillSink {
+    hasher: Sha1,
+    buf: Vec<u8>,
+    size: u64,
+    limit: Option<u64>,
+}
+
+impl BillSink {
+    fn new(limit: Option<u64>) -> Self {
+        Self {
+            hasher: Sha1::default(),
+            buf: Vec::new(),
+            size: 0,
+            limit,
+        }
+    }
+}
+
+impl Write for BillSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.size += buf.len() as u64;
+        if self.limit.map(|v| self.size > v).unwrap_or(false) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "账单文件超过大小限制"));
+        }
+        self.hasher.update(buf);
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 
@@ -732,4 +994,99 @@ mod tests {
         rt.block_on(r);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_bill_decoder() {
+        use super::BillDecoder;
+        assert!(BillDecoder::new("MD5", None).is_err());
+        // 逐字节写入，跨块解压
+        let mut decoder = BillDecoder::new("SHA1", None).unwrap();
+        let mut content = Vec::new();
+        for byte in base64::decode(TRADE_BILL_GZIP).unwrap() {
+            content.extend(decoder.write(&[byte]).unwrap());
+        }
+        content.extend(decoder.finish(TRADE_BILL_SHA1).unwrap());
+        assert!(String::from_utf8(content).unwrap().starts_with("交易时间"));
+        let mut decoder = BillDecoder::new("sha1", None).unwrap();
+        assert!(decoder.write(b"a").unwrap().is_empty());
+        assert_eq!(b"a".to_vec(), decoder.finish("86f7e437faa5a7fce15d1ddcb9eaeaea377667b8").unwrap());
+        // 超过大小限制
+        let mut decoder = BillDecoder::new("SHA1", Some(4)).unwrap();
+        assert!(decoder.write(b"abcde").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_download_bill_v3_to() {
+        use crate::{MockResponse, MockTransport, WechatBillResponseV3};
+        use crate::wechat::pay::tests::PRIVATE_KEY;
+        let transport = MockTransport::new();
+        let gzip = base64::decode(TRADE_BILL_GZIP).unwrap();
+        transport.push_response(MockResponse::new(200, gzip.clone()));
+        let client = WechatPayClient::<SimpleStorage>::new("appid", "secret")
+            .mch_id("10000100".to_string())
+            .serial_no("serial_no".to_string())
+            .private_key(PRIVATE_KEY.to_string())
+            .transport(transport);
+        let bill = WechatBillResponseV3 {
+            hash_type: "SHA1".to_string(),
+            hash_value: TRADE_BILL_SHA1.to_string(),
+            download_url: "https://api.mch.weixin.qq.com/v3/billdownload/file?token=token".to_string(),
+        };
+        let mut content = Vec::new();
+        let mut downloaded = 0;
+        let written = client.wxpay().download_bill_v3_to(&bill, &mut content, |v, _| downloaded = v).await.unwrap();
+        assert_eq!(gzip.len() as u64, downloaded);
+        assert_eq!(content.len() as u64, written);
+        let bill = crate::WechatTradeBillV3::parse_reader(content.as_slice()).unwrap();
+        assert_eq!(2, bill.records.len());
+    }
+
+    #[tokio::test]
+    async fn test_bill_v3() {
+        use crate::{LabraCertificate, MockResponse, MockTransport, WechatSubMerchantFundFlowBillRequestV3, WechatTradeBillRequestV3};
+        use crate::wechat::pay::tests::{signed_response, PLATFORM_CERT, PRIVATE_KEY};
+        let transport = MockTransport::new();
+        let download_url = "https://api.mch.weixin.qq.com/v3/billdownload/file?token=6XIv5TUPto7pByrTQKhd6kwvyKLG2uY2wMMR8cNXqaA_Cv_isgaUtBzp4QtiozLO";
+        transport.push_response(signed_response(200, format!(r#"{{"hash_type":"SHA1","hash_value":"{}","download_url":"{}"}}"#, TRADE_BILL_SHA1, download_url)))
+            .push_response(MockResponse::new(200, base64::decode(TRADE_BILL_GZIP).unwrap()))
+            .push_response(signed_response(200, format!(r#"{{"hash_type":"SHA1","hash_value":"0000","download_url":"{}"}}"#, download_url)))
+            .push_response(MockResponse::new(200, base64::decode(TRADE_BILL_GZIP).unwrap()))
+            .push_response(signed_response(200, format!(r#"{{"download_bill_count":1,"download_bill_list":[{{"bill_sequence":1,"download_url":"{}","encrypt_key":"{}","hash_type":"SHA1","hash_value":"{}","nonce":"0123456789ab"}}]}}"#, download_url, FUND_FLOW_BILL_KEY, FUND_FLOW_BILL_SHA1)))
+            .push_response(MockResponse::new(200, base64::decode(FUND_FLOW_BILL).unwrap()));
+        let client = WechatPayClient::<SimpleStorage>::new("appid", "secret")
+            .mch_id("10000100".to_string())
+            .serial_no("serial_no".to_string())
+            .private_key(PRIVATE_KEY.to_string())
+            .platform_cert(LabraCertificate::from_pem(PLATFORM_CERT.as_bytes().to_vec()).unwrap())
+            .transport(transport.clone());
+        let params = WechatTradeBillRequestV3 { bill_date: "2024-01-01".to_string(), tar_type: "GZIP".to_string().into(), ..Default::default() };
+        let bill = client.wxpay().trade_bill_v3(&params).await.unwrap();
+        assert_eq!(2, bill.records.len());
+        assert_eq!("T20240101001", bill.records[0].out_trade_no);
+        assert_eq!("测试商品,1件", bill.records[0].body);
+        assert_eq!("0.60%", bill.records[0].rate);
+        assert_eq!("R20240101001", bill.records[1].out_refund_no);
+        assert_eq!(2, bill.summary.total_record);
+        assert_eq!("0.03", bill.summary.total_fee);
+        // 摘要值不一致
+        assert!(client.wxpay().trade_bill_v3(&params).await.is_err());
+
+        let params = WechatSubMerchantFundFlowBillRequestV3 { sub_mchid: "1900000109".to_string(), bill_date: "2024-01-01".to_string(), ..Default::default() };
+        let bill = client.wxpay().sub_merchant_fund_flow_bill_v3(&params).await.unwrap();
+        assert_eq!(1, bill.records.len());
+        assert_eq!("0.01", bill.records[0].amount);
+        assert_eq!("100.01", bill.records[0].balance);
+        assert_eq!(1, bill.summary.income_record);
+
+        let requests = transport.requests();
+        assert_eq!("https://api.mch.weixin.qq.com/v3/bill/tradebill?bill_date=2024-01-01&tar_type=GZIP", requests[0].url.as_str());
+        assert_eq!(download_url, requests[1].url.as_str());
+        assert!(requests[1].headers["Authorization"].to_str().unwrap().starts_with("WECHATPAY2-SHA256-RSA2048 mchid=\"10000100\""));
+        assert_eq!("https://api.mch.weixin.qq.com/v3/bill/sub-merchant-fundflowbill?sub_mchid=1900000109&bill_date=2024-01-01&account_type=BASIC&algorithm=AEAD_AES_256_GCM", requests[4].url.as_str());
+    }
+
+    const TRADE_BILL_GZIP: &str = "H4sIAAAAAAACA61TT2/aMBS/I/EddtnNm2yT0nY3ROlGtcJGiqadFjH1skt7qVbtFA6UdpT9ESB6WdV2Vemhg05lUEjFvkxsJ99iLzghMdukHeZYjn/vPf/eHz/b43N+1ODtgdvuI1a5su/aTv+CfRxmVxBr7fH9IeyROBiJ8UWIne6EnVe9HZt07Z+nTveM1VtTPLUJsWheAuYnVae3h+ypM/HdYse1ALwfcLOMbOuIX03cxsA5PUROv8Nuy6JTB0vExk1hNSShW/3snn0B46+wY/u3PpYxuKYJFEoYikiC4Eilwsw7jyIqlcCPzwcyPuBjjTL7VBeda5+ct655vcsOK4gf1IT1zemPIPKR+FBFSrSieeP0hoofaQYV5DeX8ZhBMdUeYALzHqGPMIaJjLe7VKOkRF5riUW8CWsJGQTDIJ4WJjI0iuUgHgMoCKEJbSG5uLQM+o1ACHpkbBW3V5d21ndSa/lX63R7t0SeL7/LvXmxqSe3kLGmp55lkaEX0+mMriMjv/EkU4B/OvcSfD30CGCVfvFsP129j/+oOb2WrBEitjXwhJ4aS7skvq/SzCWd+D9JEyVp+o9JFzKrxdzK7znPMlzAf/MIh5Uigz0NTuUL2cfZXOpppKpKmf5cIRqs8Rg3LflEoJWg15SHADqlZUM819ihYtakoWieK9qpM6l3VZHLo9Ha+MHjhFTFY78AhKeBiE4EAAA=";
+    const TRADE_BILL_SHA1: &str = "6504B53D8A4C23FAC23FBD46A3AD0F9D834AF137";
+    const FUND_FLOW_BILL: &str = "37/im+KBb5Ah1sQPhI40o69NpemDcFwDajQRn/k23XeWTAGO4E1qsTj28mj7KqNe/UXw1ZtIOF+ExDGNlG26xhmJWaAH8bS5Sa2/t87ZY7I2zz+StGe1/I6h5NLOStuHLoxuUKrkoBka9Fn3CwjKbgC+h+qc1O3KtnoFnjorVqNV5iN5c3Ie7RZkFTTVN4GtBvDmdE10Tc4AqLE9CGBo9bjAiUKX/lgNNkZqqNLVJv099JE+S1y3j/CeoQ511eIqli4rpXbkfqz5LayOb0iugzaaHZXNdtNaarFcgehCTZPRzJfgSI26XzjerEG4VzrmiwNkz5tJI0TQqUbIi9C/hDgWNioo5QXSKiUPaFdzI6taPmq7snhHOKHnaa9uQkXW2Uib4klymb2G7EhgguBv0XgkeSwlP7NhlA6WsroGfMM1Ae124SoI2Ey4N/6+yUgX0aNFTsSu9lG6G97fXFZWw1hTheAozl9rVeixi9IjNJI3zaXS0t1nECkRWjuE0cYbW6xciT6X81KlUl2zTCm7AXmsZr4cJK1Nz+1MYz1rcZ08T2vwOurQvphIX72Pwd2NHXreuhmoetBZ";
+    const FUND_FLOW_BILL_SHA1: &str = "0ee5d769e83a901a7bd446964b7256f50812250c";
+    const FUND_FLOW_BILL_KEY: &str = "USnfHyLYxCYIWHO1U07x8I6kEsaUor4c3l4Vch2I4hzafwDYHPMX4dPqCnLrhEtYK9K4g1vCDNqb2a4D28q2jreLtxmwmgqMgMS++IfMw2qxStcTsIegNOH+mk6EyHDaScbVSJLfaXC7RX/fd7LNWOF5lFBOgZSXr9qpLDoqJfM=";
+}
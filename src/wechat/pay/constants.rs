@@ -12,3 +12,7 @@ pub static ACCESS_TOKEN: &str = "access_token";
 pub static ACCEPT: &str = "Accept";
 pub static AUTHORIZATION: &str = "Authorization";
 pub static CONTENT_TYPE_JSON: &str = "application/json";
+/// 沙箱环境V2接口前缀
+pub static SANDBOX_PATH: &str = "/sandboxnew";
+/// 平台证书序列号，请求中包含加密的敏感信息时需要传入
+pub static WECHATPAY_SERIAL: &str = "Wechatpay-Serial";
@@ -7,6 +7,8 @@ pub enum WechatPayMethod {
     WxPay(WxPayMethod),
     /// 企业支付
     EntPay(EntPayMethod),
+    /// 分账
+    ProfitSharing(ProfitSharingMethod),
     /// 证书下载
     Certificate,
     /// 自定义方法
@@ -16,7 +18,47 @@ pub enum WechatPayMethod {
 #[allow(unused)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum EntPayMethod {
+    /// 企业付款到零钱
+    Transfer,
+    /// 查询企业付款到零钱
+    QueryTransfer,
+    /// 发起商家转账 - V3
+    TransferBatchesV3,
+    /// 通过微信批次单号查询批次单 - V3
+    QueryBatchByIdV3(String),
+    /// 通过商家批次单号查询批次单 - V3
+    QueryBatchByOutNoV3(String),
+    /// 通过微信明细单号查询明细单 - V3
+    QueryDetailByIdV3((String, String)),
+    /// 通过商家明细单号查询明细单 - V3
+    QueryDetailByOutNoV3((String, String)),
+    /// 申请转账账单电子回单 - V3
+    BillReceiptV3,
+    /// 查询转账账单电子回单 - V3
+    QueryBillReceiptV3(String),
+    /// 申请、查询转账明细电子回单 - V3
+    DetailReceiptV3,
+}
 
+#[allow(unused)]
+#[derive(Debug, PartialEq, Clone)]
+pub enum ProfitSharingMethod {
+    /// 添加分账接收方 - V3
+    AddReceiverV3,
+    /// 删除分账接收方 - V3
+    DeleteReceiverV3,
+    /// 请求分账 - V3
+    CreateOrderV3,
+    /// 查询分账结果 - V3
+    QueryOrderV3(String),
+    /// 解冻剩余资金 - V3
+    UnfreezeV3,
+    /// 请求分账回退 - V3
+    CreateReturnOrderV3,
+    /// 查询分账回退结果 - V3
+    QueryReturnOrderV3(String),
+    /// 查询剩余待分金额 - V3
+    QueryAmountsV3(String),
 }
 
 #[allow(unused)]
@@ -51,6 +93,12 @@ pub enum WxPayMethod {
     ReverseOrder,
     /// 转换短链接
     ShortUrl,
+    /// 申请交易账单 - V3
+    TradeBillV3,
+    /// 申请资金账单 - V3
+    FundFlowBillV3,
+    /// 申请单个子商户资金账单 - V3
+    SubMerchantFundFlowBillV3,
 }
 
 
@@ -59,13 +107,33 @@ impl RequestMethod for WechatPayMethod {
     fn get_method(&self) -> String {
         match self {
             WechatPayMethod::WxPay(v) => v.get_method(),
-            WechatPayMethod::EntPay(_) => {
-                String::default()
-            }
+            WechatPayMethod::EntPay(v) => v.get_method(),
+            WechatPayMethod::ProfitSharing(v) => v.get_method(),
             WechatPayMethod::Certificate => String::from("/v3/certificates"),
             WechatPayMethod::Custom(v) => v.to_string()
         }
     }
+
+    fn get_name(&self) -> String {
+        match self {
+            WechatPayMethod::WxPay(v) => v.get_name(),
+            WechatPayMethod::EntPay(v) => v.get_name(),
+            WechatPayMethod::ProfitSharing(v) => v.get_name(),
+            _ => self.get_method(),
+        }
+    }
+
+    fn is_idempotent(&self) -> bool {
+        match self {
+            WechatPayMethod::WxPay(v) => matches!(v, WxPayMethod::QueryOrder | WxPayMethod::QueryOrderV3(_) | WxPayMethod::QueryRefundOrder
+                | WxPayMethod::QueryRefundOrderV2 | WxPayMethod::QueryRefundOrderV3(_) | WxPayMethod::ShortUrl),
+            WechatPayMethod::EntPay(v) => matches!(v, EntPayMethod::QueryTransfer | EntPayMethod::QueryBatchByIdV3(_) | EntPayMethod::QueryBatchByOutNoV3(_)
+                | EntPayMethod::QueryDetailByIdV3(_) | EntPayMethod::QueryDetailByOutNoV3(_) | EntPayMethod::QueryBillReceiptV3(_)),
+            WechatPayMethod::ProfitSharing(v) => matches!(v, ProfitSharingMethod::QueryOrderV3(_) | ProfitSharingMethod::QueryReturnOrderV3(_) | ProfitSharingMethod::QueryAmountsV3(_)),
+            WechatPayMethod::Certificate => true,
+            WechatPayMethod::Custom(_) => false,
+        }
+    }
 }
 
 #[allow(unused)]
@@ -110,6 +178,76 @@ impl WxPayMethod {
             WxPayMethod::QueryRefundOrderV2 => String::from("/pay/refundqueryv2"),
             WxPayMethod::QueryRefundOrderV3(v) => format!("/v3/refund/domestic/refunds/{}", v),
             WxPayMethod::ReverseOrder => String::from("/secapi/pay/reverse"),
+            WxPayMethod::TradeBillV3 => String::from("/v3/bill/tradebill"),
+            WxPayMethod::FundFlowBillV3 => String::from("/v3/bill/fundflowbill"),
+            WxPayMethod::SubMerchantFundFlowBillV3 => String::from("/v3/bill/sub-merchant-fundflowbill"),
+        }
+    }
+
+    /// 接口名称，路径中的单号以占位符表示
+    pub fn get_name(&self) -> String {
+        match self {
+            WxPayMethod::QueryOrderV3((Some(_), _)) => String::from("/v3/pay/transactions/out-trade-no/{out_trade_no}"),
+            WxPayMethod::QueryOrderV3(_) => String::from("/v3/pay/transactions/id/{transaction_id}"),
+            WxPayMethod::CloseOrderV3(_) => String::from("/v3/pay/transactions/out-trade-no/{out_trade_no}/close"),
+            WxPayMethod::QueryRefundOrderV3(_) => String::from("/v3/refund/domestic/refunds/{out_refund_no}"),
+            _ => self.get_method(),
+        }
+    }
+}
+
+#[allow(unused)]
+impl EntPayMethod {
+    pub fn get_method(&self) -> String {
+        match self {
+            EntPayMethod::Transfer => String::from("/mmpaymkttransfers/promotion/transfers"),
+            EntPayMethod::QueryTransfer => String::from("/mmpaymkttransfers/gettransferinfo"),
+            EntPayMethod::TransferBatchesV3 => String::from("/v3/transfer/batches"),
+            EntPayMethod::QueryBatchByIdV3(v) => format!("/v3/transfer/batches/batch-id/{}", v),
+            EntPayMethod::QueryBatchByOutNoV3(v) => format!("/v3/transfer/batches/out-batch-no/{}", v),
+            EntPayMethod::QueryDetailByIdV3((batch_id, detail_id)) => format!("/v3/transfer/batches/batch-id/{}/details/detail-id/{}", batch_id, detail_id),
+            EntPayMethod::QueryDetailByOutNoV3((out_batch_no, out_detail_no)) => format!("/v3/transfer/batches/out-batch-no/{}/details/out-detail-no/{}", out_batch_no, out_detail_no),
+            EntPayMethod::BillReceiptV3 => String::from("/v3/transfer/bill-receipt"),
+            EntPayMethod::QueryBillReceiptV3(v) => format!("/v3/transfer/bill-receipt/{}", v),
+            EntPayMethod::DetailReceiptV3 => String::from("/v3/transfer-detail/electronic-receipts"),
+        }
+    }
+
+    /// 接口名称，路径中的单号以占位符表示
+    pub fn get_name(&self) -> String {
+        match self {
+            EntPayMethod::QueryBatchByIdV3(_) => String::from("/v3/transfer/batches/batch-id/{batch_id}"),
+            EntPayMethod::QueryBatchByOutNoV3(_) => String::from("/v3/transfer/batches/out-batch-no/{out_batch_no}"),
+            EntPayMethod::QueryDetailByIdV3(_) => String::from("/v3/transfer/batches/batch-id/{batch_id}/details/detail-id/{detail_id}"),
+            EntPayMethod::QueryDetailByOutNoV3(_) => String::from("/v3/transfer/batches/out-batch-no/{out_batch_no}/details/out-detail-no/{out_detail_no}"),
+            EntPayMethod::QueryBillReceiptV3(_) => String::from("/v3/transfer/bill-receipt/{out_batch_no}"),
+            _ => self.get_method(),
+        }
+    }
+}
+
+#[allow(unused)]
+impl ProfitSharingMethod {
+    pub fn get_method(&self) -> String {
+        match self {
+            ProfitSharingMethod::AddReceiverV3 => String::from("/v3/profitsharing/receivers/add"),
+            ProfitSharingMethod::DeleteReceiverV3 => String::from("/v3/profitsharing/receivers/delete"),
+            ProfitSharingMethod::CreateOrderV3 => String::from("/v3/profitsharing/orders"),
+            ProfitSharingMethod::QueryOrderV3(v) => format!("/v3/profitsharing/orders/{}", v),
+            ProfitSharingMethod::UnfreezeV3 => String::from("/v3/profitsharing/orders/unfreeze"),
+            ProfitSharingMethod::CreateReturnOrderV3 => String::from("/v3/profitsharing/return-orders"),
+            ProfitSharingMethod::QueryReturnOrderV3(v) => format!("/v3/profitsharing/return-orders/{}", v),
+            ProfitSharingMethod::QueryAmountsV3(v) => format!("/v3/profitsharing/transactions/{}/amounts", v),
+        }
+    }
+
+    /// 接口名称，路径中的单号以占位符表示
+    pub fn get_name(&self) -> String {
+        match self {
+            ProfitSharingMethod::QueryOrderV3(_) => String::from("/v3/profitsharing/orders/{out_order_no}"),
+            ProfitSharingMethod::QueryReturnOrderV3(_) => String::from("/v3/profitsharing/return-orders/{out_return_no}"),
+            ProfitSharingMethod::QueryAmountsV3(_) => String::from("/v3/profitsharing/transactions/{transaction_id}/amounts"),
+            _ => self.get_method(),
         }
     }
 }
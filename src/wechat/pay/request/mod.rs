@@ -773,3 +773,521 @@ impl WxPayShorturlRequest {
         self.sign = get_sign(&pairs, appkey);
     }
 }
+
+
+/// 申请交易账单
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatTradeBillRequestV3 {
+    /// 账单日期，格式yyyy-MM-DD，仅支持三个月内的账单下载申请
+    pub bill_date: String,
+    /// 子商户号，服务商申请单个子商户的账单时传入，不传则返回服务商及所有子商户的账单
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_mchid: Option<String>,
+    /// 账单类型，不传时默认为ALL
+    /// ALL：返回当日所有订单信息（不含充值退款订单）
+    /// SUCCESS：返回当日成功支付的订单（不含充值退款订单）
+    /// REFUND：返回当日退款订单（不含充值退款订单）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bill_type: Option<String>,
+    /// 压缩类型，不传时默认为数据流，GZIP：返回格式为.gzip的压缩包账单
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tar_type: Option<String>,
+}
+
+#[allow(unused)]
+impl WechatTradeBillRequestV3 {
+    pub fn get_querys(&self) -> Vec<(&str, &str)> {
+        let mut querys = vec![("bill_date", self.bill_date.as_str())];
+        if let Some(sub_mchid) = &self.sub_mchid {
+            querys.push(("sub_mchid", sub_mchid.as_str()));
+        }
+        if let Some(bill_type) = &self.bill_type {
+            querys.push(("bill_type", bill_type.as_str()));
+        }
+        if let Some(tar_type) = &self.tar_type {
+            querys.push(("tar_type", tar_type.as_str()));
+        }
+        querys
+    }
+}
+
+/// 申请资金账单
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatFundFlowBillRequestV3 {
+    /// 账单日期，格式yyyy-MM-DD，仅支持三个月内的账单下载申请
+    pub bill_date: String,
+    /// 资金账户类型，不传时默认为BASIC
+    /// BASIC：基本账户
+    /// OPERATION：运营账户
+    /// FEES：手续费账户
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_type: Option<String>,
+    /// 压缩类型，不传时默认为数据流，GZIP：返回格式为.gzip的压缩包账单
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tar_type: Option<String>,
+}
+
+#[allow(unused)]
+impl WechatFundFlowBillRequestV3 {
+    pub fn get_querys(&self) -> Vec<(&str, &str)> {
+        let mut querys = vec![("bill_date", self.bill_date.as_str())];
+        if let Some(account_type) = &self.account_type {
+            querys.push(("account_type", account_type.as_str()));
+        }
+        if let Some(tar_type) = &self.tar_type {
+            querys.push(("tar_type", tar_type.as_str()));
+        }
+        querys
+    }
+}
+
+/// 申请单个子商户资金账单（服务商）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatSubMerchantFundFlowBillRequestV3 {
+    /// 子商户号
+    pub sub_mchid: String,
+    /// 账单日期，格式yyyy-MM-DD，仅支持三个月内的账单下载申请
+    pub bill_date: String,
+    /// 资金账户类型，BASIC：基本账户，OPERATION：运营账户，FEES：手续费账户
+    pub account_type: String,
+    /// 加密算法，目前只支持AEAD_AES_256_GCM
+    pub algorithm: String,
+    /// 压缩类型，不传时默认为数据流，GZIP：返回格式为.gzip的压缩包账单
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tar_type: Option<String>,
+}
+
+impl Default for WechatSubMerchantFundFlowBillRequestV3 {
+    fn default() -> Self {
+        Self {
+            sub_mchid: String::default(),
+            bill_date: String::default(),
+            account_type: String::from("BASIC"),
+            algorithm: String::from("AEAD_AES_256_GCM"),
+            tar_type: None,
+        }
+    }
+}
+
+#[allow(unused)]
+impl WechatSubMerchantFundFlowBillRequestV3 {
+    pub fn get_querys(&self) -> Vec<(&str, &str)> {
+        let mut querys = vec![("sub_mchid", self.sub_mchid.as_str()), ("bill_date", self.bill_date.as_str()),
+                              ("account_type", self.account_type.as_str()), ("algorithm", self.algorithm.as_str())];
+        if let Some(tar_type) = &self.tar_type {
+            querys.push(("tar_type", tar_type.as_str()));
+        }
+        querys
+    }
+}
+
+//----------------------------------------------------------------------------------------------------------------------------
+
+// 企业付款 ↓
+
+
+/// 企业付款到零钱请求类
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatTransferRequest {
+    /// 商户账号appid，不传时使用客户端的appid
+    pub mch_appid: Option<String>,
+    /// 商户号，为空时使用客户端的商户号
+    pub mchid: String,
+    /// 设备号
+    pub device_info: Option<String>,
+    /// 随机字符串，不传时自动生成
+    pub nonce_str: Option<String>,
+    /// 商户订单号
+    pub partner_trade_no: String,
+    /// 用户openid
+    pub openid: String,
+    /// 校验用户姓名选项，NO_CHECK：不校验真实姓名，FORCE_CHECK：强校验真实姓名
+    pub check_name: String,
+    /// 收款用户姓名，check_name为FORCE_CHECK时必填
+    pub re_user_name: Option<String>,
+    /// 付款金额，单位为分
+    pub amount: i64,
+    /// 付款备注
+    pub desc: String,
+    /// 调用接口的机器Ip地址
+    pub spbill_create_ip: Option<String>,
+    /// 签名
+    pub sign: String,
+}
+
+#[allow(unused)]
+impl WechatTransferRequest {
+    pub fn parse_xml(&self) -> String {
+        let msg = format!(
+            "<xml>\n\
+                <mch_appid>{mch_appid}</mch_appid>\n\
+                <mchid>{mchid}</mchid>\n\
+                <device_info>{device_info}</device_info>\n\
+                <nonce_str>{nonce_str}</nonce_str>\n\
+                <partner_trade_no>{partner_trade_no}</partner_trade_no>\n\
+                <openid>{openid}</openid>\n\
+                <check_name>{check_name}</check_name>\n\
+                <re_user_name><![CDATA[{re_user_name}]]></re_user_name>\n\
+                <amount>{amount}</amount>\n\
+                <desc><![CDATA[{desc}]]></desc>\n\
+                <spbill_create_ip>{spbill_create_ip}</spbill_create_ip>\n\
+                <sign>{sign}</sign>\n\
+            </xml>",
+            mch_appid=self.mch_appid.to_owned().unwrap_or_default(),
+            mchid=self.mchid,
+            device_info=self.device_info.to_owned().unwrap_or_default(),
+            nonce_str=self.nonce_str.to_owned().unwrap_or_default(),
+            partner_trade_no=self.partner_trade_no,
+            openid=self.openid,
+            check_name=self.check_name,
+            re_user_name=self.re_user_name.to_owned().unwrap_or_default(),
+            amount=self.amount,
+            desc=self.desc,
+            spbill_create_ip=self.spbill_create_ip.to_owned().unwrap_or_default(),
+            sign=self.sign,
+        );
+        msg
+    }
+
+    pub fn get_sign(&mut self, appkey: &str) {
+        let mut pairs = BTreeMap::new();
+        if let Some(mch_appid) = self.mch_appid.to_owned() {
+            pairs.insert("mch_appid".to_string(), mch_appid);
+        }
+        pairs.insert("mchid".to_string(), self.mchid.to_owned());
+        if let Some(device_info) = self.device_info.to_owned() {
+            pairs.insert("device_info".to_string(), device_info);
+        }
+        if let Some(nonce_str) = self.nonce_str.to_owned() {
+            pairs.insert("nonce_str".to_string(), nonce_str);
+        }
+        pairs.insert("partner_trade_no".to_string(), self.partner_trade_no.to_owned());
+        pairs.insert("openid".to_string(), self.openid.to_owned());
+        pairs.insert("check_name".to_string(), self.check_name.to_owned());
+        if let Some(re_user_name) = self.re_user_name.to_owned() {
+            pairs.insert("re_user_name".to_string(), re_user_name);
+        }
+        pairs.insert("amount".to_string(), self.amount.to_string());
+        pairs.insert("desc".to_string(), self.desc.to_owned());
+        if let Some(spbill_create_ip) = self.spbill_create_ip.to_owned() {
+            pairs.insert("spbill_create_ip".to_string(), spbill_create_ip);
+        }
+        self.sign = get_sign(&pairs, appkey);
+    }
+}
+
+/// 查询企业付款到零钱请求类
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatQueryTransferRequest {
+    /// 商户账号appid，不传时使用客户端的appid
+    pub appid: Option<String>,
+    /// 商户号，为空时使用客户端的商户号
+    pub mch_id: String,
+    /// 商户订单号
+    pub partner_trade_no: String,
+    /// 随机字符串，不传时自动生成
+    pub nonce_str: Option<String>,
+    /// 签名
+    pub sign: String,
+}
+
+#[allow(unused)]
+impl WechatQueryTransferRequest {
+    pub fn parse_xml(&self) -> String {
+        let msg = format!(
+            "<xml>\n\
+                <appid>{appid}</appid>\n\
+                <mch_id>{mch_id}</mch_id>\n\
+                <nonce_str>{nonce_str}</nonce_str>\n\
+                <partner_trade_no>{partner_trade_no}</partner_trade_no>\n\
+                <sign>{sign}</sign>\n\
+            </xml>",
+            appid=self.appid.to_owned().unwrap_or_default(),
+            mch_id=self.mch_id,
+            nonce_str=self.nonce_str.to_owned().unwrap_or_default(),
+            partner_trade_no=self.partner_trade_no,
+            sign=self.sign,
+        );
+        msg
+    }
+
+    pub fn get_sign(&mut self, appkey: &str) {
+        let mut pairs = BTreeMap::new();
+        if let Some(appid) = self.appid.to_owned() {
+            pairs.insert("appid".to_string(), appid);
+        }
+        pairs.insert("mch_id".to_string(), self.mch_id.to_owned());
+        pairs.insert("partner_trade_no".to_string(), self.partner_trade_no.to_owned());
+        if let Some(nonce_str) = self.nonce_str.to_owned() {
+            pairs.insert("nonce_str".to_string(), nonce_str);
+        }
+        self.sign = get_sign(&pairs, appkey);
+    }
+}
+
+/// 发起商家转账请求类
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatTransferBatchRequestV3 {
+    /// 商户appid，不传时使用客户端的appid
+    pub appid: Option<String>,
+    /// 商家批次单号
+    pub out_batch_no: String,
+    /// 批次名称
+    pub batch_name: String,
+    /// 批次备注
+    pub batch_remark: String,
+    /// 转账总金额，单位为分
+    pub total_amount: i64,
+    /// 转账总笔数
+    pub total_num: i32,
+    /// 转账明细列表
+    pub transfer_detail_list: Vec<TransferDetailInput>,
+    /// 转账场景ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_scene_id: Option<String>,
+}
+
+/// 转账明细
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransferDetailInput {
+    /// 商家明细单号
+    pub out_detail_no: String,
+    /// 转账金额，单位为分
+    pub transfer_amount: i64,
+    /// 转账备注
+    pub transfer_remark: String,
+    /// 用户在直连商户应用下的用户标示
+    pub openid: String,
+    /// 收款用户姓名，传入明文，发起转账时自动使用平台证书加密
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_name: Option<String>,
+}
+
+/// 查询批次单请求类
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatQueryTransferBatchRequestV3 {
+    /// 是否查询转账明细单
+    pub need_query_detail: bool,
+    /// 请求资源起始位置，默认为0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    /// 最大资源条数，默认为20
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// 明细状态，ALL：全部，SUCCESS：转账成功，FAIL：转账失败，need_query_detail为true时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail_status: Option<String>,
+}
+
+#[allow(unused)]
+impl WechatQueryTransferBatchRequestV3 {
+    pub fn get_querys(&self) -> Vec<(String, String)> {
+        let mut querys = vec![("need_query_detail".to_string(), self.need_query_detail.to_string())];
+        if let Some(offset) = self.offset {
+            querys.push(("offset".to_string(), offset.to_string()));
+        }
+        if let Some(limit) = self.limit {
+            querys.push(("limit".to_string(), limit.to_string()));
+        }
+        if let Some(detail_status) = &self.detail_status {
+            querys.push(("detail_status".to_string(), detail_status.to_string()));
+        }
+        querys
+    }
+}
+
+/// 转账明细电子回单请求类
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatTransferDetailReceiptRequestV3 {
+    /// 受理类型，BATCH_TRANSFER：批量转账明细电子回单，TRANSFER_TO_POCKET：企业付款至零钱电子回单，TRANSFER_TO_BANK：企业付款至银行卡电子回单
+    pub accept_type: String,
+    /// 商家转账批次单号，受理类型为BATCH_TRANSFER时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_batch_no: Option<String>,
+    /// 商家转账明细单号
+    pub out_detail_no: String,
+}
+
+#[allow(unused)]
+impl WechatTransferDetailReceiptRequestV3 {
+    pub fn get_querys(&self) -> Vec<(&str, &str)> {
+        let mut querys = vec![("accept_type", self.accept_type.as_str())];
+        if let Some(out_batch_no) = &self.out_batch_no {
+            querys.push(("out_batch_no", out_batch_no.as_str()));
+        }
+        querys.push(("out_detail_no", self.out_detail_no.as_str()));
+        querys
+    }
+}
+
+//----------------------------------------------------------------------------------------------------------------------------
+
+// 分账 ↓
+
+
+/// 添加分账接收方请求类
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatProfitSharingReceiverRequestV3 {
+    /// 子商户号，服务商模式下必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_mchid: Option<String>,
+    /// 应用ID，不传时使用客户端的appid
+    pub appid: Option<String>,
+    /// 子商户应用ID，服务商模式下接收方类型为PERSONAL_SUB_OPENID时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_appid: Option<String>,
+    /// 接收方类型，MERCHANT_ID：商户号，PERSONAL_OPENID：个人openid，PERSONAL_SUB_OPENID：个人sub_openid
+    #[serde(rename = "type")]
+    pub receiver_type: String,
+    /// 接收方账号
+    pub account: String,
+    /// 接收方名称，传入明文，请求时自动使用平台证书加密
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 与分账方的关系类型，如SERVICE_PROVIDER、STORE、STAFF、PARTNER、CUSTOM等
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation_type: Option<String>,
+    /// 自定义的分账关系，relation_type为CUSTOM时必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_relation: Option<String>,
+}
+
+/// 删除分账接收方请求类
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatProfitSharingRemoveReceiverRequestV3 {
+    /// 子商户号，服务商模式下必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_mchid: Option<String>,
+    /// 应用ID，不传时使用客户端的appid
+    pub appid: Option<String>,
+    /// 子商户应用ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_appid: Option<String>,
+    /// 接收方类型
+    #[serde(rename = "type")]
+    pub receiver_type: String,
+    /// 接收方账号
+    pub account: String,
+}
+
+/// 请求分账请求类
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatProfitSharingOrderRequestV3 {
+    /// 子商户号，服务商模式下必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_mchid: Option<String>,
+    /// 应用ID，不传时使用客户端的appid
+    pub appid: Option<String>,
+    /// 子商户应用ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_appid: Option<String>,
+    /// 微信订单号
+    pub transaction_id: String,
+    /// 商户分账单号
+    pub out_order_no: String,
+    /// 分账接收方列表
+    pub receivers: Vec<ProfitSharingReceiver>,
+    /// 是否解冻剩余未分资金
+    pub unfreeze_unsplit: bool,
+}
+
+/// 分账接收方
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfitSharingReceiver {
+    /// 接收方类型
+    #[serde(rename = "type")]
+    pub receiver_type: String,
+    /// 接收方账号
+    pub account: String,
+    /// 接收方名称，传入明文，请求时自动使用平台证书加密
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 分账金额，单位为分
+    pub amount: i64,
+    /// 分账描述
+    pub description: String,
+}
+
+/// 查询分账结果请求类
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatQueryProfitSharingOrderRequestV3 {
+    /// 子商户号，服务商模式下必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_mchid: Option<String>,
+    /// 微信订单号
+    pub transaction_id: String,
+    /// 商户分账单号
+    pub out_order_no: String,
+}
+
+#[allow(unused)]
+impl WechatQueryProfitSharingOrderRequestV3 {
+    pub fn get_querys(&self) -> Vec<(&str, &str)> {
+        let mut querys = Vec::new();
+        if let Some(sub_mchid) = &self.sub_mchid {
+            querys.push(("sub_mchid", sub_mchid.as_str()));
+        }
+        querys.push(("transaction_id", self.transaction_id.as_str()));
+        querys
+    }
+}
+
+/// 解冻剩余资金请求类
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatProfitSharingUnfreezeRequestV3 {
+    /// 子商户号，服务商模式下必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_mchid: Option<String>,
+    /// 微信订单号
+    pub transaction_id: String,
+    /// 商户分账单号
+    pub out_order_no: String,
+    /// 分账描述
+    pub description: String,
+}
+
+/// 请求分账回退请求类
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatProfitSharingReturnRequestV3 {
+    /// 子商户号，服务商模式下必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_mchid: Option<String>,
+    /// 微信分账单号，与商户分账单号二选一
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_id: Option<String>,
+    /// 商户分账单号，与微信分账单号二选一
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_order_no: Option<String>,
+    /// 商户回退单号
+    pub out_return_no: String,
+    /// 回退商户号
+    pub return_mchid: String,
+    /// 回退金额，单位为分
+    pub amount: i64,
+    /// 回退描述
+    pub description: String,
+}
+
+/// 查询分账回退结果请求类
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatQueryProfitSharingReturnRequestV3 {
+    /// 子商户号，服务商模式下必填
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_mchid: Option<String>,
+    /// 商户回退单号
+    pub out_return_no: String,
+    /// 商户分账单号
+    pub out_order_no: String,
+}
+
+#[allow(unused)]
+impl WechatQueryProfitSharingReturnRequestV3 {
+    pub fn get_querys(&self) -> Vec<(&str, &str)> {
+        let mut querys = Vec::new();
+        if let Some(sub_mchid) = &self.sub_mchid {
+            querys.push(("sub_mchid", sub_mchid.as_str()));
+        }
+        querys.push(("out_order_no", self.out_order_no.as_str()));
+        querys
+    }
+}
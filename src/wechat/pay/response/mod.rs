@@ -1,7 +1,8 @@
+use std::io::BufRead;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value};
 
-use crate::{Amount, errors::LabraError, GoodsDetail, LabradorResult, Payer, RefundAmount, SceneInfo, TradeType};
+use crate::{Amount, errors::{LabraError, Platform, PlatformError}, GoodsDetail, LabradorResult, Payer, RefundAmount, SceneInfo, TradeType};
 use crate::util::{get_nonce_str, get_timestamp, xmlutil};
 use crate::wechat::cryptos::{EncryptV3, WechatCrypto, WechatCryptoV3};
 
@@ -181,7 +182,7 @@ impl WechatPayResponse {
                 transaction_id: transaction_id.into(),
             })
         } else {
-            Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: return_msg})
+            Err(PlatformError::new(Platform::WechatPay, -1, return_msg).into())
         }
 
 
@@ -250,7 +251,7 @@ impl WechatCloseOrderResponse {
                 result_code,
             })
         } else {
-            Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: return_msg})
+            Err(PlatformError::new(Platform::WechatPay, -1, return_msg).into())
         }
 
     }
@@ -502,7 +503,7 @@ impl WechatQueryOrderResponse {
                 cash_fee_type: cash_fee_type.into()
             })
         } else {
-            Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: return_msg})
+            Err(PlatformError::new(Platform::WechatPay, -1, return_msg).into())
         }
 
     }
@@ -652,11 +653,11 @@ impl WechatOrderReverseResponse {
                     recall
                 })
             } else {
-                Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: err_code_des})
+                Err(PlatformError::new(Platform::WechatPay, -1, err_code_des).sub_code(err_code).into())
             }
 
         } else {
-            Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: return_msg})
+            Err(PlatformError::new(Platform::WechatPay, -1, return_msg).into())
         }
     }
 }
@@ -719,11 +720,11 @@ impl WechatRefundResponse {
 
                 })
             } else {
-                Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: err_code_des})
+                Err(PlatformError::new(Platform::WechatPay, -1, err_code_des).sub_code(err_code).into())
             }
 
         } else {
-            Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: return_msg})
+            Err(PlatformError::new(Platform::WechatPay, -1, return_msg).into())
         }
 
     }
@@ -814,11 +815,11 @@ impl WechatQueryRefundResponse {
                     promotion_detail: None
                 })
             } else {
-                Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: err_code_des})
+                Err(PlatformError::new(Platform::WechatPay, -1, err_code_des).sub_code(err_code).into())
             }
 
         } else {
-            Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: return_msg})
+            Err(PlatformError::new(Platform::WechatPay, -1, return_msg).into())
         }
 
     }
@@ -1034,7 +1035,7 @@ impl WxScanPayNotifyResponse {
             let result_code = xmlutil::evaluate(&doc, "//xml/result_code/text()").string();
             let err_code_des = xmlutil::evaluate(&doc, "//xml/err_code_des/text()").string();
             let _sign = xmlutil::evaluate(&doc, "//xml/sign/text()").string();
-            let _err_code = xmlutil::evaluate(&doc, "//xml/err_code/text()").string();
+            let err_code = xmlutil::evaluate(&doc, "//xml/err_code/text()").string();
             if result_code.eq("SUCCESS") {
                 Ok(WxScanPayNotifyResponse {
                     openid,
@@ -1042,11 +1043,11 @@ impl WxScanPayNotifyResponse {
                     product_id
                 })
             } else {
-                Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: err_code_des})
+                Err(PlatformError::new(Platform::WechatPay, -1, err_code_des).sub_code(err_code).into())
             }
 
         } else {
-            Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: return_msg})
+            Err(PlatformError::new(Platform::WechatPay, -1, return_msg).into())
         }
 
     }
@@ -1233,7 +1234,7 @@ impl WechatPayNotifyResponse {
                 cash_fee,
             })
         } else {
-            Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: return_msg})
+            Err(PlatformError::new(Platform::WechatPay, -1, return_msg).into())
         }
 
     }
@@ -1359,7 +1360,7 @@ impl WechatRefundNotifyResponse {
                 transaction_id,
             })
         } else {
-            Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: return_msg})
+            Err(PlatformError::new(Platform::WechatPay, -1, return_msg).into())
         }
 
     }
@@ -1393,8 +1394,765 @@ impl WxPayShortUrlResponse {
                 short_url,
             })
         } else {
-            Err(LabraError::ClientError{ errcode: "-1".to_string(), errmsg: return_msg})
+            Err(PlatformError::new(Platform::WechatPay, -1, return_msg).into())
+        }
+
+    }
+}
+
+/// 申请账单结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatBillResponseV3 {
+    /// 哈希类型，目前只有SHA1
+    pub hash_type: String,
+    /// 原始账单（gzip需要解压缩）的摘要值，用于校验文件的完整性
+    pub hash_value: String,
+    /// 账单下载地址，5分钟内有效
+    pub download_url: String,
+}
+
+/// 申请单个子商户资金账单结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatSubMerchantFundFlowBillResponseV3 {
+    /// 下载信息总数
+    pub download_bill_count: u32,
+    /// 下载信息明细
+    pub download_bill_list: Vec<WechatBillFileV3>,
+}
+
+/// 加密的账单文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatBillFileV3 {
+    /// 账单文件序号，商户需要按照序号将多个文件的内容依次拼接
+    pub bill_sequence: u32,
+    /// 账单下载地址，5分钟内有效
+    pub download_url: String,
+    /// 加密账单文件的密钥，使用商户证书公钥加密后base64编码
+    pub encrypt_key: String,
+    /// 哈希类型，目前只有SHA1
+    pub hash_type: String,
+    /// 原始账单（gzip需要解压缩）的摘要值
+    pub hash_value: String,
+    /// 加密账单文件使用的随机字符串
+    pub nonce: String,
+}
+
+/// 账单文本
+///
+/// 第一行为表头，之后是以`开头的明细数据，最后两行为汇总表头与汇总数据
+struct BillTable {
+    headers: Vec<String>,
+    summary_headers: Vec<String>,
+    summary: Vec<String>,
+}
+
+impl BillTable {
+    /// 逐行读取账单，每读到一条明细数据调用一次`record`
+    fn read<R: BufRead, F: FnMut(&Self, &[String])>(reader: R, mut record: F) -> LabradorResult<Self> {
+        let mut lines = reader.lines();
+        let mut next_line = || -> LabradorResult<Option<String>> {
+            for line in lines.by_ref() {
+                let line = line?;
+                let line = line.trim_start_matches('\u{feff}').trim_end_matches('\r');
+                if !line.is_empty() {
+                    return Ok(Some(line.to_string()));
+                }
+            }
+            Ok(None)
+        };
+        let headers = next_line()?.ok_or_else(|| LabraError::ApiError("账单内容为空".to_string()))?.split(',').map(String::from).collect();
+        let mut table = Self { headers, summary_headers: Vec::new(), summary: Vec::new() };
+        while let Some(line) = next_line()? {
+            if line.starts_with('`') {
+                record(&table, &Self::fields(&line));
+            } else {
+                table.summary_headers = line.split(',').map(String::from).collect();
+                table.summary = next_line()?.map(|v| Self::fields(&v)).unwrap_or_default();
+                break;
+            }
+        }
+        if table.summary_headers.is_empty() {
+            return Err(LabraError::ApiError("账单缺少汇总数据".to_string()));
+        }
+        Ok(table)
+    }
+
+    /// 数据字段以`开头，字段内容中可能包含逗号
+    fn fields(line: &str) -> Vec<String> {
+        line.trim_start_matches('`').split(",`").map(String::from).collect()
+    }
+
+    /// 按表头名称取值，表头中带有单位时只需要传入名称，如“收支金额”
+    fn value(headers: &[String], fields: &[String], name: &str) -> String {
+        headers.iter().position(|v| v == name)
+            .or_else(|| headers.iter().position(|v| v.starts_with(name)))
+            .and_then(|i| fields.get(i))
+            .map(|v| v.to_string())
+            .unwrap_or_default()
+    }
+
+    fn summary_value(&self, name: &str) -> String {
+        Self::value(&self.summary_headers, &self.summary, name)
+    }
+}
+
+/// 交易账单，金额单位为元
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatTradeBillV3 {
+    /// 明细数据
+    pub records: Vec<WechatTradeBillRecord>,
+    /// 汇总数据
+    pub summary: WechatTradeBillSummary,
+}
+
+/// 交易账单明细，不同账单类型的字段不同，账单中没有的字段为空
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatTradeBillRecord {
+    /// 交易时间
+    pub trade_time: String,
+    /// 公众账号ID
+    pub appid: String,
+    /// 商户号
+    pub mch_id: String,
+    /// 特约商户号
+    pub sub_mch_id: String,
+    /// 设备号
+    pub device_info: String,
+    /// 微信订单号
+    pub transaction_id: String,
+    /// 商户订单号
+    pub out_trade_no: String,
+    /// 用户标识
+    pub openid: String,
+    /// 交易类型
+    pub trade_type: String,
+    /// 交易状态
+    pub trade_state: String,
+    /// 付款银行
+    pub bank_type: String,
+    /// 货币种类
+    pub fee_type: String,
+    /// 应结订单金额
+    pub settlement_total_fee: String,
+    /// 代金券金额
+    pub coupon_fee: String,
+    /// 退款申请时间
+    pub refund_create_time: String,
+    /// 退款成功时间
+    pub refund_success_time: String,
+    /// 微信退款单号
+    pub refund_id: String,
+    /// 商户退款单号
+    pub out_refund_no: String,
+    /// 退款金额
+    pub refund_fee: String,
+    /// 充值券退款金额
+    pub coupon_refund_fee: String,
+    /// 退款类型
+    pub refund_type: String,
+    /// 退款状态
+    pub refund_status: String,
+    /// 商品名称
+    pub body: String,
+    /// 商户数据包
+    pub attach: String,
+    /// 手续费
+    pub service_charge: String,
+    /// 费率
+    pub rate: String,
+    /// 订单金额
+    pub total_fee: String,
+    /// 申请退款金额
+    pub apply_refund_fee: String,
+    /// 费率备注
+    pub rate_remark: String,
+}
+
+/// 交易账单汇总
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatTradeBillSummary {
+    /// 总交易单数
+    pub total_record: u64,
+    /// 应结订单总金额
+    pub settlement_total_fee: String,
+    /// 退款总金额
+    pub refund_total_fee: String,
+    /// 充值券退款总金额
+    pub coupon_refund_total_fee: String,
+    /// 手续费总金额
+    pub service_charge_total: String,
+    /// 订单总金额
+    pub total_fee: String,
+    /// 申请退款总金额
+    pub apply_refund_total_fee: String,
+}
+
+#[allow(unused)]
+impl WechatTradeBillV3 {
+    /// 解析账单文本
+    pub fn parse(text: &str) -> LabradorResult<WechatTradeBillV3> {
+        Self::parse_reader(text.as_bytes())
+    }
+
+    /// 逐行读取并解析账单，可以直接读取下载到文件中的账单
+    pub fn parse_reader<R: BufRead>(reader: R) -> LabradorResult<WechatTradeBillV3> {
+        let mut records = Vec::new();
+        let table = BillTable::read(reader, |table, fields| {
+            let value = |name: &str| BillTable::value(&table.headers, fields, name);
+            records.push(WechatTradeBillRecord {
+                trade_time: value("交易时间"),
+                appid: value("公众账号ID"),
+                mch_id: value("商户号"),
+                sub_mch_id: value("特约商户号"),
+                device_info: value("设备号"),
+                transaction_id: value("微信订单号"),
+                out_trade_no: value("商户订单号"),
+                openid: value("用户标识"),
+                trade_type: value("交易类型"),
+                trade_state: value("交易状态"),
+                bank_type: value("付款银行"),
+                fee_type: value("货币种类"),
+                settlement_total_fee: value("应结订单金额"),
+                coupon_fee: value("代金券金额"),
+                refund_create_time: value("退款申请时间"),
+                refund_success_time: value("退款成功时间"),
+                refund_id: value("微信退款单号"),
+                out_refund_no: value("商户退款单号"),
+                refund_fee: value("退款金额"),
+                coupon_refund_fee: value("充值券退款金额"),
+                refund_type: value("退款类型"),
+                refund_status: value("退款状态"),
+                body: value("商品名称"),
+                attach: value("商户数据包"),
+                service_charge: value("手续费"),
+                rate: value("费率"),
+                total_fee: value("订单金额"),
+                apply_refund_fee: value("申请退款金额"),
+                rate_remark: value("费率备注"),
+            });
+        })?;
+        let summary = WechatTradeBillSummary {
+            total_record: table.summary_value("总交易单数").parse().unwrap_or_default(),
+            settlement_total_fee: table.summary_value("应结订单总金额"),
+            refund_total_fee: table.summary_value("退款总金额"),
+            coupon_refund_total_fee: table.summary_value("充值券退款总金额"),
+            service_charge_total: table.summary_value("手续费总金额"),
+            total_fee: table.summary_value("订单总金额"),
+            apply_refund_total_fee: table.summary_value("申请退款总金额"),
+        };
+        Ok(WechatTradeBillV3 { records, summary })
+    }
+}
+
+/// 资金账单，金额单位为元
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatFundFlowBillV3 {
+    /// 明细数据
+    pub records: Vec<WechatFundFlowBillRecord>,
+    /// 汇总数据
+    pub summary: WechatFundFlowBillSummary,
+}
+
+/// 资金账单明细
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatFundFlowBillRecord {
+    /// 记账时间
+    pub bill_time: String,
+    /// 微信支付业务单号
+    pub transaction_id: String,
+    /// 资金流水单号
+    pub fund_flow_id: String,
+    /// 业务名称
+    pub business_name: String,
+    /// 业务类型
+    pub business_type: String,
+    /// 收支类型
+    pub income_type: String,
+    /// 收支金额
+    pub amount: String,
+    /// 账户结余
+    pub balance: String,
+    /// 资金变更提交申请人
+    pub applicant: String,
+    /// 备注
+    pub remark: String,
+    /// 业务凭证号
+    pub voucher_no: String,
+}
+
+/// 资金账单汇总
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WechatFundFlowBillSummary {
+    /// 资金流水总笔数
+    pub total_record: u64,
+    /// 收入笔数
+    pub income_record: u64,
+    /// 收入金额
+    pub income_amount: String,
+    /// 支出笔数
+    pub expense_record: u64,
+    /// 支出金额
+    pub expense_amount: String,
+}
+
+#[allow(unused)]
+impl WechatFundFlowBillV3 {
+    /// 解析账单文本
+    pub fn parse(text: &str) -> LabradorResult<WechatFundFlowBillV3> {
+        Self::parse_reader(text.as_bytes())
+    }
+
+    /// 逐行读取并解析账单，可以直接读取下载到文件中的账单
+    pub fn parse_reader<R: BufRead>(reader: R) -> LabradorResult<WechatFundFlowBillV3> {
+        let mut records = Vec::new();
+        let table = BillTable::read(reader, |table, fields| {
+            let value = |name: &str| BillTable::value(&table.headers, fields, name);
+            records.push(WechatFundFlowBillRecord {
+                bill_time: value("记账时间"),
+                transaction_id: value("微信支付业务单号"),
+                fund_flow_id: value("资金流水单号"),
+                business_name: value("业务名称"),
+                business_type: value("业务类型"),
+                income_type: value("收支类型"),
+                amount: value("收支金额"),
+                balance: value("账户结余"),
+                applicant: value("资金变更提交申请人"),
+                remark: value("备注"),
+                voucher_no: value("业务凭证号"),
+            });
+        })?;
+        let summary = WechatFundFlowBillSummary {
+            total_record: table.summary_value("资金流水总笔数").parse().unwrap_or_default(),
+            income_record: table.summary_value("收入笔数").parse().unwrap_or_default(),
+            income_amount: table.summary_value("收入金额"),
+            expense_record: table.summary_value("支出笔数").parse().unwrap_or_default(),
+            expense_amount: table.summary_value("支出金额"),
+        };
+        Ok(WechatFundFlowBillV3 { records, summary })
+    }
+}
+
+//----------------------------------------------------------------------------------------------------------------------------
+
+// 企业付款 ↓
+
+
+/// 企业付款到零钱结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatTransferResponse {
+    /// 商户appid
+    pub mch_appid: String,
+    /// 商户号
+    pub mchid: String,
+    /// 设备号
+    pub device_info: Option<String>,
+    /// 随机字符串
+    pub nonce_str: String,
+    pub return_code: String,
+    /// 返回结果
+    pub return_msg: String,
+    /// 业务结果
+    pub result_code: String,
+    /// 商户订单号
+    pub partner_trade_no: String,
+    /// 微信付款单号
+    pub payment_no: String,
+    /// 付款成功时间
+    pub payment_time: String,
+}
+
+#[allow(unused)]
+impl WechatTransferResponse {
+    pub fn parse_xml(xml: String) -> LabradorResult<Self> {
+        let package = xmlutil::parse(&xml);
+        let doc = package.as_document();
+        let return_code = xmlutil::evaluate(&doc, "//xml/return_code/text()").string();
+        let return_msg = xmlutil::evaluate(&doc, "//xml/return_msg/text()").string();
+        if return_code.eq(&"SUCCESS") {
+            let result_code = xmlutil::evaluate(&doc, "//xml/result_code/text()").string();
+            let err_code = xmlutil::evaluate(&doc, "//xml/err_code/text()").string();
+            let err_code_des = xmlutil::evaluate(&doc, "//xml/err_code_des/text()").string();
+            if result_code.eq("SUCCESS") {
+                let device_info = xmlutil::evaluate(&doc, "//xml/device_info/text()").string();
+                Ok(WechatTransferResponse {
+                    mch_appid: xmlutil::evaluate(&doc, "//xml/mch_appid/text()").string(),
+                    mchid: xmlutil::evaluate(&doc, "//xml/mchid/text()").string(),
+                    device_info: if device_info.is_empty() { None } else { device_info.into() },
+                    nonce_str: xmlutil::evaluate(&doc, "//xml/nonce_str/text()").string(),
+                    return_code,
+                    return_msg,
+                    result_code,
+                    partner_trade_no: xmlutil::evaluate(&doc, "//xml/partner_trade_no/text()").string(),
+                    payment_no: xmlutil::evaluate(&doc, "//xml/payment_no/text()").string(),
+                    payment_time: xmlutil::evaluate(&doc, "//xml/payment_time/text()").string(),
+                })
+            } else {
+                Err(PlatformError::new(Platform::WechatPay, -1, err_code_des).sub_code(err_code).into())
+            }
+        } else {
+            Err(PlatformError::new(Platform::WechatPay, -1, return_msg).into())
         }
+    }
+}
+
+/// 查询企业付款到零钱结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatQueryTransferResponse {
+    /// 商户单号
+    pub partner_trade_no: String,
+    /// 商户号的appid
+    pub appid: String,
+    /// 商户号
+    pub mch_id: String,
+    /// 付款单号
+    pub detail_id: String,
+    /// 转账状态，SUCCESS：转账成功，FAILED：转账失败，PROCESSING：处理中
+    pub status: String,
+    /// 失败原因
+    pub reason: Option<String>,
+    /// 收款用户openid
+    pub openid: String,
+    /// 收款用户姓名
+    pub transfer_name: Option<String>,
+    /// 付款金额，单位为分
+    pub payment_amount: i64,
+    /// 转账时间
+    pub transfer_time: String,
+    /// 付款成功时间
+    pub payment_time: Option<String>,
+    /// 付款备注
+    pub desc: String,
+}
 
+#[allow(unused)]
+impl WechatQueryTransferResponse {
+    pub fn parse_xml(xml: String) -> LabradorResult<Self> {
+        let package = xmlutil::parse(&xml);
+        let doc = package.as_document();
+        let return_code = xmlutil::evaluate(&doc, "//xml/return_code/text()").string();
+        let return_msg = xmlutil::evaluate(&doc, "//xml/return_msg/text()").string();
+        if return_code.eq(&"SUCCESS") {
+            let result_code = xmlutil::evaluate(&doc, "//xml/result_code/text()").string();
+            let err_code = xmlutil::evaluate(&doc, "//xml/err_code/text()").string();
+            let err_code_des = xmlutil::evaluate(&doc, "//xml/err_code_des/text()").string();
+            if result_code.eq("SUCCESS") {
+                let optional = |name: &str| {
+                    let value = xmlutil::evaluate(&doc, format!("//xml/{}/text()", name)).string();
+                    if value.is_empty() { None } else { Some(value) }
+                };
+                Ok(WechatQueryTransferResponse {
+                    partner_trade_no: xmlutil::evaluate(&doc, "//xml/partner_trade_no/text()").string(),
+                    appid: xmlutil::evaluate(&doc, "//xml/appid/text()").string(),
+                    mch_id: xmlutil::evaluate(&doc, "//xml/mch_id/text()").string(),
+                    detail_id: xmlutil::evaluate(&doc, "//xml/detail_id/text()").string(),
+                    status: xmlutil::evaluate(&doc, "//xml/status/text()").string(),
+                    reason: optional("reason"),
+                    openid: xmlutil::evaluate(&doc, "//xml/openid/text()").string(),
+                    transfer_name: optional("transfer_name"),
+                    payment_amount: xmlutil::evaluate(&doc, "//xml/payment_amount/text()").string().parse().unwrap_or_default(),
+                    transfer_time: xmlutil::evaluate(&doc, "//xml/transfer_time/text()").string(),
+                    payment_time: optional("payment_time"),
+                    desc: xmlutil::evaluate(&doc, "//xml/desc/text()").string(),
+                })
+            } else {
+                Err(PlatformError::new(Platform::WechatPay, -1, err_code_des).sub_code(err_code).into())
+            }
+        } else {
+            Err(PlatformError::new(Platform::WechatPay, -1, return_msg).into())
+        }
     }
-}
\ No newline at end of file
+}
+
+/// 发起商家转账结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatTransferBatchResponseV3 {
+    /// 商家批次单号
+    pub out_batch_no: String,
+    /// 微信批次单号
+    pub batch_id: String,
+    /// 批次创建时间
+    pub create_time: String,
+    /// 批次状态
+    pub batch_status: Option<String>,
+}
+
+/// 查询批次单结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatQueryTransferBatchResponseV3 {
+    /// 转账批次单
+    pub transfer_batch: TransferBatch,
+    /// 转账明细单列表
+    pub transfer_detail_list: Option<Vec<TransferDetailCompact>>,
+}
+
+/// 转账批次单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferBatch {
+    /// 商户号
+    pub mchid: String,
+    /// 商家批次单号
+    pub out_batch_no: String,
+    /// 微信批次单号
+    pub batch_id: String,
+    /// 商户appid
+    pub appid: String,
+    /// 批次状态，WAIT_PAY：待付款，ACCEPTED：已受理，PROCESSING：转账中，FINISHED：已完成，CLOSED：已关闭
+    pub batch_status: String,
+    /// 批次类型，API：API方式发起，WEB：页面方式发起
+    pub batch_type: String,
+    /// 批次名称
+    pub batch_name: String,
+    /// 批次备注
+    pub batch_remark: String,
+    /// 批次关闭原因
+    pub close_reason: Option<String>,
+    /// 转账总金额，单位为分
+    pub total_amount: i64,
+    /// 转账总笔数
+    pub total_num: i32,
+    /// 批次创建时间
+    pub create_time: Option<String>,
+    /// 批次更新时间
+    pub update_time: Option<String>,
+    /// 转账成功金额
+    pub success_amount: Option<i64>,
+    /// 转账成功笔数
+    pub success_num: Option<i32>,
+    /// 转账失败金额
+    pub fail_amount: Option<i64>,
+    /// 转账失败笔数
+    pub fail_num: Option<i32>,
+    /// 转账场景ID
+    pub transfer_scene_id: Option<String>,
+}
+
+/// 转账明细单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferDetailCompact {
+    /// 微信明细单号
+    pub detail_id: String,
+    /// 商家明细单号
+    pub out_detail_no: String,
+    /// 明细状态，INIT：初始态，WAIT_PAY：待确认，PROCESSING：转账中，SUCCESS：转账成功，FAIL：转账失败
+    pub detail_status: String,
+}
+
+/// 查询明细单结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatQueryTransferDetailResponseV3 {
+    /// 商户号
+    pub mchid: String,
+    /// 商家批次单号
+    pub out_batch_no: String,
+    /// 微信批次单号
+    pub batch_id: String,
+    /// 商户appid
+    pub appid: String,
+    /// 商家明细单号
+    pub out_detail_no: String,
+    /// 微信明细单号
+    pub detail_id: String,
+    /// 明细状态
+    pub detail_status: String,
+    /// 转账金额，单位为分
+    pub transfer_amount: i64,
+    /// 转账备注
+    pub transfer_remark: String,
+    /// 失败原因
+    pub fail_reason: Option<String>,
+    /// 收款用户openid
+    pub openid: String,
+    /// 收款用户姓名，已使用商户私钥解密
+    pub user_name: Option<String>,
+    /// 转账发起时间
+    pub initiate_time: String,
+    /// 明细更新时间
+    pub update_time: String,
+}
+
+/// 转账账单电子回单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatTransferBillReceiptResponseV3 {
+    /// 商家批次单号
+    pub out_batch_no: String,
+    /// 电子回单申请单号
+    pub signature_no: Option<String>,
+    /// 电子回单状态，ACCEPTED：已受理，FINISHED：已完成
+    pub signature_status: Option<String>,
+    /// 电子回单文件的哈希类型
+    pub hash_type: Option<String>,
+    /// 电子回单文件的哈希值
+    pub hash_value: Option<String>,
+    /// 电子回单文件的下载地址
+    pub download_url: Option<String>,
+    /// 创建时间
+    pub create_time: Option<String>,
+    /// 更新时间
+    pub update_time: Option<String>,
+}
+
+/// 转账明细电子回单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatTransferDetailReceiptResponseV3 {
+    /// 受理类型
+    pub accept_type: String,
+    /// 商家转账批次单号
+    pub out_batch_no: Option<String>,
+    /// 商家转账明细单号
+    pub out_detail_no: String,
+    /// 电子回单受理单号
+    pub signature_no: String,
+    /// 电子回单状态，ACCEPTED：已受理，FINISHED：已完成
+    pub signature_status: Option<String>,
+    /// 电子回单文件的哈希类型
+    pub hash_type: Option<String>,
+    /// 电子回单文件的哈希值
+    pub hash_value: Option<String>,
+    /// 电子回单文件的下载地址
+    pub download_url: Option<String>,
+}
+
+//----------------------------------------------------------------------------------------------------------------------------
+
+// 分账 ↓
+
+
+/// 分账接收方
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatProfitSharingReceiverResponseV3 {
+    /// 子商户号
+    pub sub_mchid: Option<String>,
+    /// 接收方类型
+    #[serde(rename = "type")]
+    pub receiver_type: String,
+    /// 接收方账号
+    pub account: String,
+    /// 接收方名称，已使用商户私钥解密
+    pub name: Option<String>,
+    /// 与分账方的关系类型
+    pub relation_type: Option<String>,
+    /// 自定义的分账关系
+    pub custom_relation: Option<String>,
+}
+
+/// 分账单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatProfitSharingOrderResponseV3 {
+    /// 子商户号
+    pub sub_mchid: Option<String>,
+    /// 微信订单号
+    pub transaction_id: String,
+    /// 商户分账单号
+    pub out_order_no: String,
+    /// 微信分账单号
+    pub order_id: String,
+    /// 分账单状态，PROCESSING：处理中，FINISHED：分账完成
+    pub state: String,
+    /// 分账接收方列表
+    pub receivers: Option<Vec<ProfitSharingReceiverResult>>,
+}
+
+/// 分账接收方的分账结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfitSharingReceiverResult {
+    /// 分账金额，单位为分
+    pub amount: i64,
+    /// 分账描述
+    pub description: String,
+    /// 接收方类型
+    #[serde(rename = "type")]
+    pub receiver_type: String,
+    /// 接收方账号
+    pub account: String,
+    /// 分账结果，PENDING：待分账，SUCCESS：分账成功，CLOSED：已关闭
+    pub result: String,
+    /// 分账失败原因
+    pub fail_reason: Option<String>,
+    /// 分账创建时间
+    pub create_time: String,
+    /// 分账完成时间
+    pub finish_time: String,
+    /// 分账明细单号
+    pub detail_id: String,
+}
+
+/// 分账回退单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatProfitSharingReturnResponseV3 {
+    /// 子商户号
+    pub sub_mchid: Option<String>,
+    /// 微信分账单号
+    pub order_id: String,
+    /// 商户分账单号
+    pub out_order_no: String,
+    /// 商户回退单号
+    pub out_return_no: String,
+    /// 微信回退单号
+    pub return_id: String,
+    /// 回退商户号
+    pub return_mchid: String,
+    /// 回退金额，单位为分
+    pub amount: i64,
+    /// 回退描述
+    pub description: String,
+    /// 回退结果，PROCESSING：处理中，SUCCESS：已成功，FAILED：已失败
+    pub result: String,
+    /// 失败原因
+    pub fail_reason: Option<String>,
+    /// 创建时间
+    pub create_time: String,
+    /// 完成时间
+    pub finish_time: Option<String>,
+}
+
+/// 剩余待分金额
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WechatProfitSharingAmountResponseV3 {
+    /// 微信订单号
+    pub transaction_id: String,
+    /// 订单剩余待分金额，单位为分
+    pub unsplit_amount: i64,
+}
+
+/// 分账动账通知
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WechatProfitSharingNotifyResponseV3 {
+    /// 源数据
+    pub raw_data: Option<OriginNotifyResponse>,
+    /// 解密后的数据
+    pub result: Option<DecryptProfitSharingNotifyResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DecryptProfitSharingNotifyResult {
+    /// 服务商商户号，服务商模式下返回
+    pub sp_mchid: Option<String>,
+    /// 子商户号，服务商模式下返回
+    pub sub_mchid: Option<String>,
+    /// 直连商户号，直连模式下返回
+    pub mchid: Option<String>,
+    /// 微信订单号
+    pub transaction_id: String,
+    /// 微信分账/回退单号
+    pub order_id: String,
+    /// 商户分账/回退单号
+    pub out_order_no: String,
+    /// 分账接收方
+    pub receiver: ProfitSharingNotifyReceiver,
+    /// 成功时间
+    pub success_time: String,
+}
+
+/// 分账动账通知中的接收方
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProfitSharingNotifyReceiver {
+    /// 分账接收方类型
+    #[serde(rename = "type")]
+    pub receiver_type: String,
+    /// 分账接收方账号
+    pub account: String,
+    /// 分账动账金额，单位为分
+    pub amount: i64,
+    /// 分账/回退描述
+    pub description: String,
+}